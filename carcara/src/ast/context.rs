@@ -77,6 +77,36 @@ impl ContextStack {
         self.len() == 0
     }
 
+    /// The ids of the contexts in the stack. If every context was added using `ContextStack::push`,
+    /// these identify the subproofs that are currently open.
+    pub fn ids(&self) -> &[usize] {
+        &self.stack
+    }
+
+    /// Returns `true` if the variable is declared or assigned by any of the contexts in the stack.
+    pub fn binds(&self, var: &SortedVar) -> bool {
+        self.stack.iter().any(|&id| {
+            let guard = self.context_vec[id].1.read().unwrap();
+            guard.as_ref().is_some_and(|context| {
+                context.args.iter().any(|arg| match arg {
+                    AnchorArg::Variable(v) | AnchorArg::Assign(v, _) => v == var,
+                })
+            })
+        })
+    }
+
+    /// The number of contexts in the stack, not counting the contexts at the top of the stack whose
+    /// anchors have no arguments.
+    pub fn len_with_args(&self) -> usize {
+        let has_args = |&id: &usize| {
+            let guard = self.context_vec[id].1.read().unwrap();
+            guard
+                .as_ref()
+                .is_some_and(|context| !context.args.is_empty())
+        };
+        self.stack.iter().rposition(has_args).map_or(0, |i| i + 1)
+    }
+
     pub fn last(&self) -> Option<RwLockReadGuard<Option<Context>>> {
         self.stack
             .last()
@@ -174,6 +174,9 @@ macro_rules! match_term {
     (@GET_VARIANT <=)       => { $crate::ast::Operator::LessEq };
     (@GET_VARIANT >=)       => { $crate::ast::Operator::GreaterEq };
 
+    (@GET_VARIANT select)   => { $crate::ast::Operator::Select };
+    (@GET_VARIANT store)    => { $crate::ast::Operator::Store };
+
     (@GET_VARIANT cl)    => { $crate::ast::Operator::Cl };
     (@GET_VARIANT delete)    => { $crate::ast::Operator::Delete };
 
@@ -189,12 +192,19 @@ macro_rules! match_term {
     (@GET_VARIANT bvxor)    => { $crate::ast::Operator::BvXor };
     (@GET_VARIANT bvxnor)   => { $crate::ast::Operator::BvXNor };
     (@GET_VARIANT bvcomp)   => { $crate::ast::Operator::BvComp };
+    (@GET_VARIANT bvnand)   => { $crate::ast::Operator::BvNAnd };
+    (@GET_VARIANT bvnor)    => { $crate::ast::Operator::BvNOr };
     (@GET_VARIANT bvadd)    => { $crate::ast::Operator::BvAdd };
+    (@GET_VARIANT bvsub)    => { $crate::ast::Operator::BvSub };
     (@GET_VARIANT bvmul)    => { $crate::ast::Operator::BvMul };
     (@GET_VARIANT bvudiv)   => { $crate::ast::Operator::BvUDiv };
     (@GET_VARIANT bvurem)   => { $crate::ast::Operator::BvURem };
+    (@GET_VARIANT bvsdiv)   => { $crate::ast::Operator::BvSDiv };
+    (@GET_VARIANT bvsrem)   => { $crate::ast::Operator::BvSRem };
+    (@GET_VARIANT bvsmod)   => { $crate::ast::Operator::BvSMod };
     (@GET_VARIANT bvshl)    => { $crate::ast::Operator::BvShl };
     (@GET_VARIANT bvlshr)   => { $crate::ast::Operator::BvLShr };
+    (@GET_VARIANT bvashr)   => { $crate::ast::Operator::BvAShr };
     (@GET_VARIANT concat)   => { $crate::ast::Operator::BvConcat };
 
     (@GET_VARIANT bvuge)    => { $crate::ast::Operator::BvUGe };
@@ -20,15 +20,16 @@ mod tests;
 
 pub use context::{Context, ContextStack};
 pub use iter::ProofIter;
-pub use node::{ProofNode, StepNode, SubproofNode};
+pub use node::{compare_nodes, ProofNode, StepNode, SubproofNode};
 pub use polyeq::{alpha_equiv, polyeq, Polyeq, PolyeqComparable, PolyeqConfig};
+pub(crate) use pool::number_term;
 pub use pool::{PrimitivePool, TermPool};
 pub use printer::{print_proof, write_asserts, write_proof_to_dest, USE_SHARING_IN_TERM_DISPLAY};
 pub use problem::*;
 pub use proof::*;
 pub use rc::Rc;
 pub use substitution::{Substitution, SubstitutionError};
-pub use term::{Binder, BindingList, Constant, Operator, ParamOperator, Sort, SortedVar, Term};
-
-#[cfg(test)]
-pub(crate) use node::compare_nodes;
+pub use term::{
+    Binder, BindingList, Constant, ConstructorDef, DatatypeDef, MatchCase, Operator, ParamOperator,
+    Sort, SortedVar, Term,
+};
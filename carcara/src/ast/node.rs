@@ -522,7 +522,8 @@ fn proof_node_to_list(root: &Rc<ProofNode>) -> Vec<ProofCommand> {
     }
 }
 
-#[cfg(test)]
+/// Returns `true` if the two nodes are structurally equal, that is, if they have the same ids,
+/// clauses and arguments, and their premises are also structurally equal.
 pub fn compare_nodes(a: &Rc<ProofNode>, b: &Rc<ProofNode>) -> bool {
     if a == b {
         return true;
@@ -541,6 +541,20 @@ impl PolyeqComparable for Term {
             (Term::Let(binds_a, a), Term::Let(binds_b, b)) => {
                 comp.compare_binder(binds_a, binds_b, a, b)
             }
+            (Term::Match(a, cases_a), Term::Match(b, cases_b)) => {
+                comp.eq(a, b)
+                    && cases_a.len() == cases_b.len()
+                    && cases_a.iter().zip(cases_b).all(|(a, b)| {
+                        let same_constructor = match (&a.constructor, &b.constructor) {
+                            (Some(c_a), Some(c_b)) => c_a == c_b,
+                            (None, None) => true,
+                            _ => false,
+                        };
+                        same_constructor
+                            && a.bindings.len() == b.bindings.len()
+                            && comp.compare_binder(&a.bindings, &b.bindings, &a.body, &b.body)
+                    })
+            }
             (Term::Const(Constant::Real(r)), Term::Op(Operator::RealDiv, args)) => {
                 // if a is a rational and b a division literal, check
                 // if they are the same
@@ -644,7 +658,10 @@ impl PolyeqComparable for Sort {
     fn eq(comp: &mut Polyeq, a: &Self, b: &Self) -> bool {
         match (a, b) {
             (Sort::Function(sorts_a), Sort::Function(sorts_b)) => comp.eq(sorts_a, sorts_b),
-            (Sort::Atom(a, sorts_a), Sort::Atom(b, sorts_b)) => a == b && comp.eq(sorts_a, sorts_b),
+            (Sort::Atom(a, sorts_a), Sort::Atom(b, sorts_b))
+            | (Sort::Datatype(a, sorts_a), Sort::Datatype(b, sorts_b)) => {
+                a == b && comp.eq(sorts_a, sorts_b)
+            }
             (Sort::Bool, Sort::Bool)
             | (Sort::Int, Sort::Int)
             | (Sort::Real, Sort::Real)
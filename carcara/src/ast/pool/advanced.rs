@@ -1,4 +1,4 @@
-use super::super::{Rc, Term};
+use super::super::{DatatypeDef, Rc, Term};
 use super::{PrimitivePool, TermPool};
 use indexmap::IndexSet;
 use std::sync::{Arc, RwLock};
@@ -65,6 +65,10 @@ impl TermPool for ContextPool {
             .unwrap()
             .free_vars_with_priorities(term, [&self.global_pool])
     }
+
+    fn datatype(&self, name: &str) -> Option<&DatatypeDef> {
+        self.global_pool.datatype(name)
+    }
 }
 
 // =========================================================================
@@ -133,4 +137,8 @@ impl TermPool for LocalPool {
             ],
         )
     }
+
+    fn datatype(&self, name: &str) -> Option<&DatatypeDef> {
+        self.ctx_pool.datatype(name)
+    }
 }
@@ -3,10 +3,11 @@
 pub mod advanced;
 mod storage;
 
-use super::{Binder, Operator, Rc, Sort, Substitution, Term};
+use super::{Binder, BindingList, DatatypeDef, MatchCase, Operator, Rc, Sort, Substitution, Term};
 use crate::ast::{Constant, ParamOperator};
 use indexmap::{IndexMap, IndexSet};
-use rug::Integer;
+use rug::{Integer, Rational};
+use std::collections::HashMap;
 use storage::Storage;
 
 pub trait TermPool {
@@ -45,6 +46,24 @@ pub trait TermPool {
     /// This method uses a cache, so there is no additional cost to computing the free variables of
     /// a term multiple times.
     fn free_vars(&mut self, term: &Rc<Term>) -> IndexSet<Rc<Term>>;
+    /// Returns the definition of the datatype with the given name, if it was declared.
+    fn datatype(&self, name: &str) -> Option<&DatatypeDef>;
+}
+
+/// Builds a numerical constant term, using a unary `-` application if it is negative.
+pub(crate) fn number_term(pool: &mut dyn TermPool, value: Rational, is_int: bool) -> Rc<Term> {
+    let is_negative = value < 0;
+    let value = value.abs();
+    let term = if is_int {
+        pool.add(Term::new_int(value.numer().clone()))
+    } else {
+        pool.add(Term::new_real(value))
+    };
+    if is_negative {
+        pool.add(Term::Op(Operator::Sub, vec![term]))
+    } else {
+        term
+    }
 }
 
 /// A structure to store and manage all allocated terms.
@@ -61,6 +80,7 @@ pub struct PrimitivePool {
     pub(crate) storage: Storage,
     pub(crate) free_vars_cache: IndexMap<Rc<Term>, IndexSet<Rc<Term>>>,
     pub(crate) sorts_cache: IndexMap<Rc<Term>, Rc<Term>>,
+    pub(crate) datatypes: IndexMap<String, DatatypeDef>,
 }
 
 impl PrimitivePool {
@@ -70,6 +90,133 @@ impl PrimitivePool {
         Self::default()
     }
 
+    /// Returns the number of terms currently stored in the pool.
+    pub fn num_terms(&self) -> usize {
+        self.storage.len()
+    }
+
+    /// Removes from the pool every term that is not referenced anywhere outside of it, and returns
+    /// the number of removed terms. Since no one holds these terms anymore, removing them doesn't
+    /// break hash consing: if an equal term is added later, it will simply be allocated again.
+    ///
+    /// The pool caches (for sorts and free variables) also hold references to terms, so a term is
+    /// considered unreferenced if all of its references come from the pool itself.
+    pub fn collect_garbage(&mut self) -> usize {
+        let mut total = 0;
+        loop {
+            // Removing the cache entries of a term may release the last reference to another term
+            // that was already visited (for example, its sort), so we repeat until nothing changes
+            let sorts_cache = &mut self.sorts_cache;
+            let free_vars_cache = &mut self.free_vars_cache;
+            let removed = self.storage.retain_rev(|term| {
+                let sort = sorts_cache.get(term);
+                let free_vars = free_vars_cache.get(term);
+                let internal_refs = 1
+                    + usize::from(sort.is_some())
+                    + usize::from(sort == Some(term))
+                    + usize::from(free_vars.is_some())
+                    + usize::from(free_vars.is_some_and(|set| set.contains(term)));
+                if Rc::strong_count(term) > internal_refs {
+                    return true;
+                }
+                sorts_cache.swap_remove(term);
+                free_vars_cache.swap_remove(term);
+                false
+            });
+            if removed == 0 {
+                return total;
+            }
+            total += removed;
+        }
+    }
+
+    /// Adds to this pool a term that may have been created by a different pool, returning the
+    /// equivalent term in this pool. Any subterms that are not already in this pool are also added.
+    ///
+    /// This is used to bring back terms created by other threads using a `LocalPool`, which may
+    /// contain duplicates of terms that are not in this pool. The `cache` maps the terms that were
+    /// already imported to their equivalents in this pool, and may be reused between calls.
+    pub fn import(&mut self, term: &Rc<Term>, cache: &mut HashMap<Rc<Term>, Rc<Term>>) -> Rc<Term> {
+        if let Some(t) = cache.get(term) {
+            return t.clone();
+        }
+        if self.storage.get(term).is_some_and(|t| t == term) {
+            return term.clone();
+        }
+
+        let result = match term.as_ref() {
+            Term::Const(_) => term.as_ref().clone(),
+            Term::Var(name, sort) => Term::Var(name.clone(), self.import(sort, cache)),
+            Term::App(f, args) => Term::App(self.import(f, cache), self.import_all(args, cache)),
+            Term::Op(op, args) => Term::Op(*op, self.import_all(args, cache)),
+            Term::Sort(sort) => Term::Sort(match sort {
+                Sort::Function(sorts) => Sort::Function(self.import_all(sorts, cache)),
+                Sort::Atom(name, args) => Sort::Atom(name.clone(), self.import_all(args, cache)),
+                Sort::Array(x, y) => Sort::Array(self.import(x, cache), self.import(y, cache)),
+                Sort::ParamSort(vars, sort) => {
+                    Sort::ParamSort(self.import_all(vars, cache), self.import(sort, cache))
+                }
+                Sort::Datatype(name, args) => {
+                    Sort::Datatype(name.clone(), self.import_all(args, cache))
+                }
+                other => other.clone(),
+            }),
+            Term::Binder(binder, bindings, inner) => {
+                let bindings = self.import_bindings(bindings, cache);
+                Term::Binder(*binder, bindings, self.import(inner, cache))
+            }
+            Term::Let(bindings, inner) => {
+                let bindings = self.import_bindings(bindings, cache);
+                Term::Let(bindings, self.import(inner, cache))
+            }
+            Term::ParamOp { op, op_args, args } => Term::ParamOp {
+                op: *op,
+                op_args: self.import_all(op_args, cache),
+                args: self.import_all(args, cache),
+            },
+            Term::Match(scrutinee, cases) => {
+                let scrutinee = self.import(scrutinee, cache);
+                let cases = cases
+                    .iter()
+                    .map(|c| MatchCase {
+                        constructor: c.constructor.as_ref().map(|t| self.import(t, cache)),
+                        bindings: self.import_bindings(&c.bindings, cache),
+                        body: self.import(&c.body, cache),
+                    })
+                    .collect();
+                Term::Match(scrutinee, cases)
+            }
+        };
+        let result = self.add(result);
+        cache.insert(term.clone(), result.clone());
+        result
+    }
+
+    fn import_all(
+        &mut self,
+        terms: &[Rc<Term>],
+        cache: &mut HashMap<Rc<Term>, Rc<Term>>,
+    ) -> Vec<Rc<Term>> {
+        terms.iter().map(|t| self.import(t, cache)).collect()
+    }
+
+    fn import_bindings(
+        &mut self,
+        bindings: &BindingList,
+        cache: &mut HashMap<Rc<Term>, Rc<Term>>,
+    ) -> BindingList {
+        let vars = bindings.iter();
+        BindingList(
+            vars.map(|(name, value)| (name.clone(), self.import(value, cache)))
+                .collect(),
+        )
+    }
+
+    /// Registers a datatype definition, so it can later be retrieved with [`TermPool::datatype`].
+    pub(crate) fn add_datatype(&mut self, def: DatatypeDef) {
+        self.datatypes.insert(def.name.clone(), def);
+    }
+
     /// Computes the sort of a term and adds it to the sort cache.
     fn compute_sort(&mut self, term: &Rc<Term>) -> Rc<Term> {
         if let Some(sort) = self.sorts_cache.get(term) {
@@ -255,6 +402,8 @@ impl PrimitivePool {
                 Sort::Function(result)
             }
             Term::Let(_, inner) => self.compute_sort(inner).as_sort().unwrap().clone(),
+            // We assume that all cases have the same sort, which is checked by the parser
+            Term::Match(_, cases) => self.compute_sort(&cases[0].body).as_sort().unwrap().clone(),
             Term::ParamOp { op, op_args, args } => {
                 let sort = match op {
                     ParamOperator::BvExtract => {
@@ -291,7 +440,7 @@ impl PrimitivePool {
                         let bvsize = op_args[0].as_integer().unwrap();
                         Sort::BitVec(bvsize)
                     }
-                    ParamOperator::BvBitOf => Sort::Bool,
+                    ParamOperator::BvBitOf | ParamOperator::DtTester => Sort::Bool,
                     ParamOperator::BvIntOf => Sort::Int,
                     ParamOperator::RePower | ParamOperator::ReLoop => Sort::RegLan,
                     ParamOperator::ArrayConst => op_args[0].as_sort().unwrap().clone(),
@@ -379,6 +528,18 @@ impl PrimitivePool {
                 }
                 vars
             }
+            Term::Match(scrutinee, cases) => {
+                let mut set = self.free_vars_with_priorities(scrutinee, prior_pools);
+                for case in cases {
+                    let mut vars = self.free_vars_with_priorities(&case.body, prior_pools);
+                    for bound_var in &case.bindings {
+                        let term = self.add_with_priorities(bound_var.clone().into(), prior_pools);
+                        vars.swap_remove(&term);
+                    }
+                    set.extend(vars);
+                }
+                set
+            }
             Term::Var(..) => {
                 let mut set = IndexSet::with_capacity(1);
                 set.insert(term.clone());
@@ -412,4 +573,8 @@ impl TermPool for PrimitivePool {
     fn free_vars(&mut self, term: &Rc<Term>) -> IndexSet<Rc<Term>> {
         self.free_vars_with_priorities(term, [])
     }
+
+    fn datatype(&self, name: &str) -> Option<&DatatypeDef> {
+        self.datatypes.get(name)
+    }
 }
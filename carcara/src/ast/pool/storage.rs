@@ -55,6 +55,27 @@ impl Storage {
         self.0.get(term).map(|t| &t.0)
     }
 
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    /// Removes every term for which `keep` returns `false`. The terms are visited in the reverse
+    /// order of insertion, and each removed term is dropped before visiting the next one. Since a
+    /// term is always added after its subterms, this means that when a term is visited, all terms
+    /// that contained it have already been visited. Returns the number of removed terms.
+    pub fn retain_rev(&mut self, mut keep: impl FnMut(&Rc<Term>) -> bool) -> usize {
+        let old_len = self.0.len();
+        let mut kept = Vec::with_capacity(old_len);
+        let mut terms = std::mem::take(&mut self.0).into_iter().collect::<Vec<_>>();
+        while let Some(term) = terms.pop() {
+            if keep(&term.0) {
+                kept.push(term);
+            }
+        }
+        self.0 = kept.into_iter().rev().collect();
+        old_len - self.0.len()
+    }
+
     // This method is only necessary for the hash consing tests
     #[cfg(test)]
     pub fn into_vec(self) -> Vec<Rc<Term>> {
@@ -365,6 +365,33 @@ impl<'a> AlethePrinter<'a> {
                 }
                 Ok(())
             }
+            Term::Match(scrutinee, cases) => {
+                write!(self.inner, "(match ")?;
+                scrutinee.print_with_sharing(self)?;
+                write!(self.inner, " (")?;
+                for (i, case) in cases.iter().enumerate() {
+                    if i > 0 {
+                        write!(self.inner, " ")?;
+                    }
+                    write!(self.inner, "(")?;
+                    match &case.constructor {
+                        Some(c) if case.bindings.is_empty() => c.print_with_sharing(self)?,
+                        Some(c) => {
+                            write!(self.inner, "(")?;
+                            c.print_with_sharing(self)?;
+                            for (name, _) in &case.bindings {
+                                write!(self.inner, " {}", quote_symbol(name))?;
+                            }
+                            write!(self.inner, ")")?;
+                        }
+                        None => write!(self.inner, "{}", quote_symbol(&case.bindings[0].0))?,
+                    }
+                    write!(self.inner, " ")?;
+                    case.body.print_with_sharing(self)?;
+                    write!(self.inner, ")")?;
+                }
+                write!(self.inner, "))")
+            }
         }
     }
 
@@ -550,6 +577,10 @@ impl fmt::Display for Sort {
                 0 => write!(f, "{}", quote_symbol(name)),
                 _ => write_s_expr(f, quote_symbol(name), args),
             },
+            Sort::Datatype(name, args) => match args.len() {
+                0 => write!(f, "{}", quote_symbol(name)),
+                _ => write_s_expr(f, quote_symbol(name), args),
+            },
             Sort::Bool => write!(f, "Bool"),
             Sort::Int => write!(f, "Int"),
             Sort::Real => write!(f, "Real"),
@@ -592,6 +623,50 @@ impl fmt::Display for ProblemPrelude {
             writeln!(f, "(declare-sort {} {})", quote_symbol(name), arity)?;
         }
 
+        // All datatypes are declared in a single command, so mutually recursive datatypes can
+        // reference each other
+        if !self.datatype_declarations.is_empty() {
+            write!(f, "(declare-datatypes (")?;
+            for (i, dt) in self.datatype_declarations.iter().enumerate() {
+                if i > 0 {
+                    write!(f, " ")?;
+                }
+                write!(f, "({} {})", quote_symbol(&dt.name), dt.params.len())?;
+            }
+            write!(f, ") (")?;
+            for (i, dt) in self.datatype_declarations.iter().enumerate() {
+                if i > 0 {
+                    write!(f, " ")?;
+                }
+                if !dt.params.is_empty() {
+                    write!(f, "(par (")?;
+                    for (j, p) in dt.params.iter().enumerate() {
+                        if j > 0 {
+                            write!(f, " ")?;
+                        }
+                        write!(f, "{}", quote_symbol(p))?;
+                    }
+                    write!(f, ") ")?;
+                }
+                write!(f, "(")?;
+                for (j, c) in dt.constructors.iter().enumerate() {
+                    if j > 0 {
+                        write!(f, " ")?;
+                    }
+                    write!(f, "({}", quote_symbol(&c.name))?;
+                    for (sel, sort) in &c.selectors {
+                        write!(f, " ({} {})", quote_symbol(sel), sort)?;
+                    }
+                    write!(f, ")")?;
+                }
+                write!(f, ")")?;
+                if !dt.params.is_empty() {
+                    write!(f, ")")?;
+                }
+            }
+            writeln!(f, "))")?;
+        }
+
         for (name, sort) in &self.function_declarations {
             write!(f, "(declare-fun {} ", quote_symbol(name))?;
             if let Sort::Function(sorts) = sort.as_sort().unwrap() {
@@ -1,4 +1,4 @@
-use super::{Rc, Term};
+use super::{DatatypeDef, Rc, Term};
 use indexmap::IndexSet;
 
 /// An SMT problem in the SMT-LIB format.
@@ -21,12 +21,16 @@ impl Problem {
 
 /// The prelude of an SMT-LIB problem instance.
 ///
-/// This stores the sort declarations, function declarations and the problem's logic string.
+/// This stores the sort declarations, datatype declarations, function declarations and the
+/// problem's logic string.
 #[derive(Debug, Clone, Default)]
 pub struct ProblemPrelude {
     /// The sort declarations, each represented by its name and arity.
     pub(crate) sort_declarations: Vec<(String, usize)>,
 
+    /// The datatype declarations, in the order they were declared.
+    pub(crate) datatype_declarations: Vec<DatatypeDef>,
+
     /// The function declarations, each represented by its name and body.
     pub(crate) function_declarations: Vec<(String, Rc<Term>)>,
 
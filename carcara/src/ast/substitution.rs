@@ -1,6 +1,6 @@
 //! Algorithms for creating and applying capture-avoiding substitutions over terms.
 
-use super::{Binder, BindingList, Rc, Sort, SortedVar, Term, TermPool};
+use super::{Binder, BindingList, MatchCase, Rc, Sort, SortedVar, Term, TermPool};
 use indexmap::{IndexMap, IndexSet};
 use thiserror::Error;
 
@@ -16,6 +16,24 @@ pub enum SubstitutionError {
     DifferentSorts(Rc<Term>, Rc<Term>),
 }
 
+impl SubstitutionError {
+    /// Returns a stable identifier for the kind of this error.
+    pub fn code(&self) -> &'static str {
+        match self {
+            SubstitutionError::NotAVariable(_) => "substitution.not_a_variable",
+            SubstitutionError::DifferentSorts(..) => "substitution.different_sorts",
+        }
+    }
+
+    /// Returns the terms carried by this error, in the order they appear in the error message.
+    pub fn terms(&self) -> Vec<&Rc<Term>> {
+        match self {
+            SubstitutionError::NotAVariable(t) => vec![t],
+            SubstitutionError::DifferentSorts(a, b) => vec![a, b],
+        }
+    }
+}
+
 type SubstitutionResult<T> = Result<T, SubstitutionError>;
 
 /// Represents a capture-avoiding substitution over terms.
@@ -223,6 +241,29 @@ impl Substitution {
                 };
                 pool.add(Term::Let(new_bindings, new_term))
             }
+            Term::Match(scrutinee, cases) => {
+                self.compute_should_be_renamed(pool);
+                let new_scrutinee = self.apply(pool, scrutinee);
+                let new_cases = cases
+                    .iter()
+                    .map(|case| {
+                        let (bindings, mut renaming) =
+                            self.rename_binding_list(pool, &case.bindings, false);
+                        let body = if renaming.is_empty() {
+                            self.apply(pool, &case.body)
+                        } else {
+                            let renamed = renaming.apply(pool, &case.body);
+                            self.apply(pool, &renamed)
+                        };
+                        MatchCase {
+                            constructor: case.constructor.clone(),
+                            bindings,
+                            body,
+                        }
+                    })
+                    .collect();
+                pool.add(Term::Match(new_scrutinee, new_cases))
+            }
             Term::Const(_) | Term::Var(..) => term.clone(),
             Term::ParamOp { op, op_args, args } => {
                 let new_args = apply_to_sequence!(args);
@@ -236,6 +277,10 @@ impl Substitution {
                 let new_args = apply_to_sequence!(args);
                 pool.add(Term::Sort(Sort::Atom(sort.clone(), new_args)))
             }
+            Term::Sort(Sort::Datatype(name, args)) => {
+                let new_args = apply_to_sequence!(args);
+                pool.add(Term::Sort(Sort::Datatype(name.clone(), new_args)))
+            }
             Term::Sort(Sort::Function(args)) => {
                 let new_args = apply_to_sequence!(args);
                 pool.add(Term::Sort(Sort::Function(new_args)))
@@ -44,6 +44,49 @@ pub enum Term {
         op_args: Vec<Rc<Term>>,
         args: Vec<Rc<Term>>,
     },
+
+    /// A `match` term over a datatype value, consisting of the matched term and the match cases.
+    Match(Rc<Term>, Vec<MatchCase>),
+}
+
+/// A case in a `match` term.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct MatchCase {
+    /// The constructor matched by this case. If this is `None`, the pattern is a single variable,
+    /// which matches any value.
+    pub constructor: Option<Rc<Term>>,
+
+    /// The variables bound by the pattern. If the pattern is a constructor, these are bound to the
+    /// constructor's fields, in order. Otherwise, this contains only the pattern variable.
+    pub bindings: BindingList,
+
+    /// The term this case evaluates to.
+    pub body: Rc<Term>,
+}
+
+/// A datatype declaration, from a `declare-datatype` or `declare-datatypes` command.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct DatatypeDef {
+    /// The datatype name.
+    pub name: String,
+
+    /// The sort parameters of the datatype. In the selector sorts, these are represented as
+    /// `Sort::Var` sorts.
+    pub params: Vec<String>,
+
+    /// The datatype constructors, in the order they were declared.
+    pub constructors: Vec<ConstructorDef>,
+}
+
+/// A datatype constructor declaration.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct ConstructorDef {
+    /// The constructor name.
+    pub name: String,
+
+    /// The selectors of this constructor, each represented by its name and the sort of the
+    /// corresponding field.
+    pub selectors: Vec<SortedVar>,
 }
 
 /// The sort of a term.
@@ -91,6 +134,12 @@ pub enum Sort {
     /// A parametric sort, with a set of sort variables that can appear in the second argument.
     ParamSort(Vec<Rc<Term>>, Rc<Term>),
 
+    /// A datatype sort, from a `declare-datatype` or `declare-datatypes` command.
+    ///
+    /// The associated string is the datatype name, and the associated terms are the sort arguments
+    /// for this sort, if the datatype is parametric.
+    Datatype(String, Vec<Rc<Term>>),
+
     /// The sort of RARE lists.
     RareList,
 
@@ -396,6 +445,9 @@ pub enum ParamOperator {
     RePower,
     ReLoop,
 
+    // Datatype testers, whose single parameter is the constructor being tested
+    DtTester,
+
     // Qualified operators
     ArrayConst,
 }
@@ -527,6 +579,8 @@ impl_str_conversion_traits!(ParamOperator {
     RePower: "re.^",
     ReLoop: "re.loop",
 
+    DtTester: "is",
+
     ArrayConst: "const",
 });
 
@@ -612,6 +666,14 @@ impl Sort {
                     a_s.match_with(b_s, map)
                 })
             }
+            (Sort::Datatype(a, sorts_a), Sort::Datatype(b, sorts_b)) => {
+                a == b
+                    && sorts_a.iter().zip(sorts_b.iter()).all(|(t_a, t_b)| {
+                        let s_a = t_a.as_sort().unwrap();
+                        let s_b = t_b.as_sort().unwrap();
+                        s_a.match_with(s_b, map)
+                    })
+            }
             (Sort::Bool, Sort::Bool)
             | (Sort::Int, Sort::Int)
             | (Sort::Real, Sort::Real)
@@ -808,6 +870,7 @@ impl Term {
     /// Returns `true` if the term is a user defined sort with arity zero, or a sort variable.
     pub fn is_sort_var(&self) -> bool {
         matches!(self, Term::Sort(Sort::Atom(_, args)) if args.is_empty())
+            || matches!(self, Term::Sort(Sort::Var(_)))
     }
 
     /// Returns `true` if the term is a user defined parametric sort
@@ -920,12 +983,9 @@ impl Rc<Term> {
 
     /// Similar to `Term::as_integer_err`, but also checks if non-negative.
     pub fn as_usize_err(&self) -> Result<usize, CheckerError> {
-        if let Some(i) = self.as_integer() {
-            if i >= 0 {
-                return Ok(i.to_usize().unwrap());
-            }
-        }
-        Err(CheckerError::ExpectedNonnegInteger(self.clone()))
+        self.as_integer()
+            .and_then(|i| i.to_usize())
+            .ok_or_else(|| CheckerError::ExpectedNonnegInteger(self.clone()))
     }
 
     /// Similar to `Term::as_signed_number`, but returns a `CheckerError` on failure.
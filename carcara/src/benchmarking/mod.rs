@@ -79,6 +79,16 @@ pub struct OnlineBenchmarkResults {
     pub num_assumes: usize,
     pub num_easy_assumes: usize,
 
+    /// For each rule, the steps (as `(file, step id)` pairs) that were considered holes.
+    pub holes: IndexMap<String, Vec<(String, String)>>,
+
+    /// The errors encountered, as `(file, error code, error message)` triples. When checking a
+    /// file fails on several steps, each failure is recorded separately.
+    pub errors: Vec<(String, &'static str, String)>,
+
+    /// For each compression step, the total number of commands in the proofs before and after it.
+    pub compression: IndexMap<String, (usize, usize)>,
+
     pub is_holey: bool,
     pub had_error: bool,
 }
@@ -138,6 +148,13 @@ impl OnlineBenchmarkResults {
         &self.step_time_by_rule
     }
 
+    /// Records the number of commands in a proof before and after a compression step.
+    pub fn add_compression(&mut self, step: &str, size_before: usize, size_after: usize) {
+        let entry = self.compression.entry(step.to_owned()).or_default();
+        entry.0 += size_before;
+        entry.1 += size_after;
+    }
+
     /// Prints the benchmark results
     pub fn print(&self, sort_by_total: bool) {
         let [parsing, checking, elaborating, scheduling, accounted_for, total, assume_time, assume_core_time, polyeq_time] =
@@ -181,6 +198,17 @@ impl OnlineBenchmarkResults {
         );
         println!("polyeq ratio:        {}", self.polyeq_time_ratio);
 
+        if !self.compression.is_empty() {
+            println!("compression:");
+            for (step, (before, after)) in &self.compression {
+                let reduction = 100.0 * (*before as f64 - *after as f64) / (*before).max(1) as f64;
+                println!(
+                    "    {: <18}{} -> {} commands ({:.02}% smaller)",
+                    step, before, after, reduction
+                );
+            }
+        }
+
         println!("total accounted for: {}", accounted_for);
         println!("total:               {}", total);
 
@@ -384,8 +412,9 @@ pub trait CollectResults {
     fn add_assume_measurement(&mut self, file: &str, id: &str, is_easy: bool, time: Duration);
     fn add_polyeq_depth(&mut self, depth: usize);
     fn add_run_measurement(&mut self, id: &RunId, measurement: RunMeasurement);
+    fn add_hole(&mut self, file: &str, step_id: &str, rule: &str);
     fn register_holey(&mut self);
-    fn register_error(&mut self, error: &crate::Error);
+    fn register_error(&mut self, file: &str, error: &crate::Error);
 
     fn combine(a: Self, b: Self) -> Self
     where
@@ -474,16 +503,49 @@ impl CollectResults for OnlineBenchmarkResults {
             polyeq_depths: a.polyeq_depths.combine(b.polyeq_depths),
             num_assumes: a.num_assumes + b.num_assumes,
             num_easy_assumes: a.num_easy_assumes + b.num_easy_assumes,
+            holes: {
+                let mut holes = a.holes;
+                for (rule, steps) in b.holes {
+                    holes.entry(rule).or_default().extend(steps);
+                }
+                holes
+            },
+            errors: a.errors.into_iter().chain(b.errors).collect(),
+            compression: {
+                let mut compression = a.compression;
+                for (step, (before, after)) in b.compression {
+                    let entry = compression.entry(step).or_default();
+                    entry.0 += before;
+                    entry.1 += after;
+                }
+                compression
+            },
             is_holey: a.is_holey || b.is_holey,
             had_error: a.had_error || b.had_error,
         }
     }
 
+    fn add_hole(&mut self, file: &str, step_id: &str, rule: &str) {
+        self.holes
+            .entry(rule.to_owned())
+            .or_default()
+            .push((file.to_owned(), step_id.to_owned()));
+    }
+
     fn register_holey(&mut self) {
         self.is_holey = true;
     }
 
-    fn register_error(&mut self, _: &crate::Error) {
+    fn register_error(&mut self, file: &str, error: &crate::Error) {
+        if let crate::Error::CheckerFailures(failures) = error {
+            for f in failures {
+                self.errors
+                    .push((file.to_owned(), f.inner.code(), f.to_string()));
+            }
+        } else {
+            self.errors
+                .push((file.to_owned(), error.code(), error.to_string()));
+        }
         self.had_error = true;
     }
 }
@@ -505,11 +567,13 @@ impl CollectResults for CsvBenchmarkResults {
         self.runs.insert(id, measurement);
     }
 
+    fn add_hole(&mut self, _: &str, _: &str, _: &str) {}
+
     fn register_holey(&mut self) {
         self.is_holey = true;
     }
 
-    fn register_error(&mut self, _: &crate::Error) {
+    fn register_error(&mut self, _: &str, _: &crate::Error) {
         self.num_errors += 1;
     }
 
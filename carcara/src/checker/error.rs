@@ -40,6 +40,21 @@ pub enum CheckerError {
     #[error(transparent)]
     Subproof(#[from] SubproofError),
 
+    #[error(transparent)]
+    CuttingPlanes(#[from] CuttingPlanesError),
+
+    #[error(transparent)]
+    PbBlasting(#[from] PbBlastingError),
+
+    #[error(transparent)]
+    Datatype(#[from] DatatypeError),
+
+    #[error(transparent)]
+    Array(#[from] ArrayError),
+
+    #[error(transparent)]
+    Rare(#[from] RareError),
+
     #[error("reflexivity failed with terms '{0}' and '{1}'")]
     ReflexivityFailed(Rc<Term>, Rc<Term>),
 
@@ -94,6 +109,9 @@ pub enum CheckerError {
     CannotApplyReUnfoldPosComponentDifferentArgNum(Rc<Term>, Rc<Term>),
 
     // General errors
+    #[error("premise at index {1} of depth {0} was already discarded")]
+    DiscardedPremise(usize, usize),
+
     #[error("expected {0} premises, got {1}")]
     WrongNumberOfPremises(Range, usize),
 
@@ -139,6 +157,12 @@ pub enum CheckerError {
     #[error("expected term '{0}' to be an integer constant")]
     ExpectedAnyInteger(Rc<Term>),
 
+    #[error("expected term '{0}' to be a bit-vector constant")]
+    ExpectedAnyBvConstant(Rc<Term>),
+
+    #[error("expected term '{0}' to have a bit-vector sort")]
+    ExpectedBvTerm(Rc<Term>),
+
     #[error("expected term '{0}' to be an non-negative integer constant")]
     ExpectedNonnegInteger(Rc<Term>),
 
@@ -186,6 +210,163 @@ pub enum CheckerError {
     UnknownRule,
 }
 
+impl CheckerError {
+    /// Returns a stable identifier for the kind of this error. Unlike the error message, this does
+    /// not depend on the terms involved, so it can be used to group failures by their cause. Codes
+    /// of errors from nested error types are prefixed by a category, e.g. `cong.missing_premise`.
+    pub fn code(&self) -> &'static str {
+        use EqualityError::*;
+        match self {
+            CheckerError::Unspecified => "unspecified",
+            CheckerError::Explanation(_) => "explanation",
+            CheckerError::Substitution(e) => e.code(),
+            CheckerError::Assume(_) => "assume",
+            CheckerError::Resolution(e) => e.code(),
+            CheckerError::DrupFormatError(e) => e.code(),
+            CheckerError::Cong(e) => e.code(),
+            CheckerError::Quant(e) => e.code(),
+            CheckerError::LinearArithmetic(e) => e.code(),
+            CheckerError::Subproof(e) => e.code(),
+            CheckerError::CuttingPlanes(e) => e.code(),
+            CheckerError::PbBlasting(e) => e.code(),
+            CheckerError::Datatype(e) => e.code(),
+            CheckerError::Array(e) => e.code(),
+            CheckerError::Rare(e) => e.code(),
+            CheckerError::ReflexivityFailed(..) => "reflexivity_failed",
+            CheckerError::SimplificationFailed { .. } => "simplification_failed",
+            CheckerError::CycleInSimplification(_) => "cycle_in_simplification",
+            CheckerError::SumProdSimplifyInvalidConclusion(_) => {
+                "sum_prod_simplify_invalid_conclusion"
+            }
+            CheckerError::TermIsNotConnective(_) => "term_is_not_connective",
+            CheckerError::IsNotValidIteIntro(_) => "is_not_valid_ite_intro",
+            CheckerError::BrokenTransitivityChain(..) => "broken_transitivity_chain",
+            CheckerError::ContractionMissingTerm(_) => "contraction_missing_term",
+            CheckerError::ContractionExtraTerm(_) => "contraction_extra_term",
+            CheckerError::NotValidNaryTerm(_) => "not_valid_nary_term",
+            CheckerError::LengthCannotBeEvaluated(_) => "length_cannot_be_evaluated",
+            CheckerError::NoIthChildInTerm(..) => "no_ith_child_in_term",
+            CheckerError::CannotApplyReUnfoldPos(_) => "cannot_apply_re_unfold_pos",
+            CheckerError::CannotApplyReUnfoldPosComponent(_) => {
+                "cannot_apply_re_unfold_pos_component"
+            }
+            CheckerError::CannotApplyReUnfoldPosComponentDifferentArgNum(..) => {
+                "cannot_apply_re_unfold_pos_component_different_arg_num"
+            }
+            CheckerError::DiscardedPremise(..) => "discarded_premise",
+            CheckerError::WrongNumberOfPremises(..) => "wrong_number_of_premises",
+            CheckerError::WrongLengthOfClause(..) => "wrong_length_of_clause",
+            CheckerError::WrongNumberOfArgs(..) => "wrong_number_of_args",
+            CheckerError::WrongNumberOfTermsInOp(..) => "wrong_number_of_terms_in_op",
+            CheckerError::TermDoesntAppearInOp(..) => "term_doesnt_appear_in_op",
+            CheckerError::WrongLengthOfPremiseClause(..) => "wrong_length_of_premise_clause",
+            CheckerError::TermOfWrongForm(..) => "term_of_wrong_form",
+            CheckerError::ExpectedBoolConstant(..) => "expected_bool_constant",
+            CheckerError::ExpectedAnyBoolConstant(_) => "expected_any_bool_constant",
+            CheckerError::ExpectedStringConstantOfLengthOne(_) => {
+                "expected_string_constant_of_length_one"
+            }
+            CheckerError::ExpectedDifferentConstantPrefixes(..) => {
+                "expected_different_constant_prefixes"
+            }
+            CheckerError::ExpectedNumber(..) => "expected_number",
+            CheckerError::ExpectedInteger(..) => "expected_integer",
+            CheckerError::ExpectedAnyNumber(_) => "expected_any_number",
+            CheckerError::ExpectedAnyInteger(_) => "expected_any_integer",
+            CheckerError::ExpectedAnyBvConstant(_) => "expected_any_bv_constant",
+            CheckerError::ExpectedBvTerm(_) => "expected_bv_term",
+            CheckerError::ExpectedNonnegInteger(_) => "expected_nonneg_integer",
+            CheckerError::ExpectedOperationTerm(_) => "expected_operation_term",
+            CheckerError::ExpectedQuantifierTerm(_) => "expected_quantifier_term",
+            CheckerError::ExpectedBinderTerm(_) => "expected_binder_term",
+            CheckerError::ExpectedLetTerm(_) => "expected_let_term",
+            CheckerError::ExpectedToBePrefix(..) => "expected_to_be_prefix",
+            CheckerError::ExpectedToBeSuffix(..) => "expected_to_be_suffix",
+            CheckerError::ExpectedToNotBeEmpty(_) => "expected_to_not_be_empty",
+            CheckerError::MustBeLastStepInSubproof => "must_be_last_step_in_subproof",
+            CheckerError::DivOrModByZero => "div_or_mod_by_zero",
+            CheckerError::TermEquality(ExpectedEqual(..)) => "term_equality.expected_equal",
+            CheckerError::TermEquality(ExpectedToBe { .. }) => "term_equality.expected_to_be",
+            CheckerError::QuantifierEquality(ExpectedEqual(..)) => {
+                "quantifier_equality.expected_equal"
+            }
+            CheckerError::QuantifierEquality(ExpectedToBe { .. }) => {
+                "quantifier_equality.expected_to_be"
+            }
+            CheckerError::BindingListEquality(ExpectedEqual(..)) => {
+                "binding_list_equality.expected_equal"
+            }
+            CheckerError::BindingListEquality(ExpectedToBe { .. }) => {
+                "binding_list_equality.expected_to_be"
+            }
+            CheckerError::IntegerEquality(ExpectedEqual(..)) => "integer_equality.expected_equal",
+            CheckerError::IntegerEquality(ExpectedToBe { .. }) => "integer_equality.expected_to_be",
+            CheckerError::UnknownRule => "unknown_rule",
+        }
+    }
+
+    /// Returns the terms carried by this error, in the order they appear in the error message.
+    pub fn terms(&self) -> Vec<&Rc<Term>> {
+        match self {
+            CheckerError::Substitution(e) => e.terms(),
+            CheckerError::Resolution(e) => e.terms(),
+            CheckerError::Cong(e) => e.terms(),
+            CheckerError::Quant(e) => e.terms(),
+            CheckerError::LinearArithmetic(e) => e.terms(),
+            CheckerError::Subproof(e) => e.terms(),
+            CheckerError::CuttingPlanes(e) => e.terms(),
+            CheckerError::PbBlasting(e) => e.terms(),
+            CheckerError::Datatype(e) => e.terms(),
+            CheckerError::Array(e) => e.terms(),
+            CheckerError::Rare(e) => e.terms(),
+            CheckerError::TermEquality(e) => e.values(),
+
+            CheckerError::SimplificationFailed { original, result, target } => {
+                vec![original, result, target]
+            }
+
+            CheckerError::Assume(t)
+            | CheckerError::CycleInSimplification(t)
+            | CheckerError::SumProdSimplifyInvalidConclusion(t)
+            | CheckerError::TermIsNotConnective(t)
+            | CheckerError::IsNotValidIteIntro(t)
+            | CheckerError::ContractionMissingTerm(t)
+            | CheckerError::ContractionExtraTerm(t)
+            | CheckerError::NotValidNaryTerm(t)
+            | CheckerError::LengthCannotBeEvaluated(t)
+            | CheckerError::NoIthChildInTerm(_, t)
+            | CheckerError::CannotApplyReUnfoldPos(t)
+            | CheckerError::CannotApplyReUnfoldPosComponent(t)
+            | CheckerError::TermDoesntAppearInOp(_, t)
+            | CheckerError::TermOfWrongForm(_, t)
+            | CheckerError::ExpectedBoolConstant(_, t)
+            | CheckerError::ExpectedAnyBoolConstant(t)
+            | CheckerError::ExpectedStringConstantOfLengthOne(t)
+            | CheckerError::ExpectedNumber(_, t)
+            | CheckerError::ExpectedInteger(_, t)
+            | CheckerError::ExpectedAnyNumber(t)
+            | CheckerError::ExpectedAnyInteger(t)
+            | CheckerError::ExpectedAnyBvConstant(t)
+            | CheckerError::ExpectedBvTerm(t)
+            | CheckerError::ExpectedNonnegInteger(t)
+            | CheckerError::ExpectedOperationTerm(t)
+            | CheckerError::ExpectedQuantifierTerm(t)
+            | CheckerError::ExpectedBinderTerm(t)
+            | CheckerError::ExpectedLetTerm(t)
+            | CheckerError::ExpectedToNotBeEmpty(t) => vec![t],
+
+            CheckerError::ReflexivityFailed(a, b)
+            | CheckerError::BrokenTransitivityChain(a, b)
+            | CheckerError::CannotApplyReUnfoldPosComponentDifferentArgNum(a, b)
+            | CheckerError::ExpectedDifferentConstantPrefixes(a, b)
+            | CheckerError::ExpectedToBePrefix(a, b)
+            | CheckerError::ExpectedToBeSuffix(a, b) => vec![a, b],
+
+            _ => Vec::new(),
+        }
+    }
+}
+
 /// Errors in which we expected two things to be equal but they weren't.
 #[derive(Debug, Error)]
 pub enum EqualityError<T: TypeName> {
@@ -196,6 +377,17 @@ pub enum EqualityError<T: TypeName> {
     ExpectedToBe { expected: T, got: T },
 }
 
+impl<T: TypeName> EqualityError<T> {
+    /// Returns the two values that were expected to be equal. In the case of
+    /// `EqualityError::ExpectedToBe`, the expected value comes first.
+    pub fn values(&self) -> Vec<&T> {
+        match self {
+            EqualityError::ExpectedEqual(a, b) => vec![a, b],
+            EqualityError::ExpectedToBe { expected, got } => vec![expected, got],
+        }
+    }
+}
+
 struct DisplayIndexedOp<'a>(&'a ParamOperator, &'a Vec<Rc<Term>>);
 
 impl fmt::Display for DisplayIndexedOp<'_> {
@@ -247,6 +439,39 @@ pub enum CongruenceError {
     ),
 }
 
+impl CongruenceError {
+    /// Returns a stable identifier for the kind of this error.
+    pub fn code(&self) -> &'static str {
+        match self {
+            CongruenceError::TooManyPremises => "cong.too_many_premises",
+            CongruenceError::MissingPremise(..) => "cong.missing_premise",
+            CongruenceError::PremiseDoesntJustifyArgs { .. } => "cong.premise_doesnt_justify_args",
+            CongruenceError::DifferentFunctions(..) => "cong.different_functions",
+            CongruenceError::DifferentOperators(..) => "cong.different_operators",
+            CongruenceError::DifferentNumberOfArguments(..) => "cong.different_number_of_arguments",
+            CongruenceError::NotApplicationOrOperation(_) => "cong.not_application_or_operation",
+            CongruenceError::DifferentIndexedOperators(..) => "cong.different_indexed_operators",
+        }
+    }
+
+    /// Returns the terms carried by this error, in the order they appear in the error message.
+    pub fn terms(&self) -> Vec<&Rc<Term>> {
+        match self {
+            CongruenceError::MissingPremise(a, b) | CongruenceError::DifferentFunctions(a, b) => {
+                vec![a, b]
+            }
+            CongruenceError::PremiseDoesntJustifyArgs { args, premise } => {
+                vec![&premise.0, &premise.1, &args.0, &args.1]
+            }
+            CongruenceError::NotApplicationOrOperation(t) => vec![t],
+            CongruenceError::DifferentIndexedOperators((_, a), (_, b)) => {
+                a.iter().chain(b).collect()
+            }
+            _ => Vec::new(),
+        }
+    }
+}
+
 /// Errors relevant to the rules dealing with quantifiers.
 #[derive(Debug, Error)]
 pub enum QuantifierError {
@@ -273,6 +498,28 @@ pub enum QuantifierError {
     ClauseDoesntAppearInCnf(Rc<Term>),
 }
 
+impl QuantifierError {
+    /// Returns a stable identifier for the kind of this error.
+    pub fn code(&self) -> &'static str {
+        match self {
+            QuantifierError::NoBindingMatchesArg(_) => "quant.no_binding_matches_arg",
+            QuantifierError::NoArgGivenForBinding(_) => "quant.no_arg_given_for_binding",
+            QuantifierError::JoinFailed { .. } => "quant.join_failed",
+            QuantifierError::CnfNewBindingIntroduced(_) => "quant.cnf_new_binding_introduced",
+            QuantifierError::CnfBindingIsMissing(_) => "quant.cnf_binding_is_missing",
+            QuantifierError::ClauseDoesntAppearInCnf(_) => "quant.clause_doesnt_appear_in_cnf",
+        }
+    }
+
+    /// Returns the terms carried by this error, in the order they appear in the error message.
+    pub fn terms(&self) -> Vec<&Rc<Term>> {
+        match self {
+            QuantifierError::ClauseDoesntAppearInCnf(t) => vec![t],
+            _ => Vec::new(),
+        }
+    }
+}
+
 /// Errors relevant to the linear arithmetic rules.
 #[derive(Debug, Error)]
 pub enum LinearArithmeticError {
@@ -296,6 +543,51 @@ pub enum LinearArithmeticError {
 
     #[error("expected term '{0}' to be less than or equal to term '{1}'")]
     ExpectedLessEq(Rc<Term>, Rc<Term>),
+
+    #[error("couldn't find coefficients for `la_generic` step: negation of clause is satisfiable")]
+    NoFarkasCoefficients,
+
+    #[error("clause is not valid: its negation has an integer solution")]
+    LiaGenericNotValid,
+}
+
+impl LinearArithmeticError {
+    /// Returns a stable identifier for the kind of this error.
+    pub fn code(&self) -> &'static str {
+        match self {
+            LinearArithmeticError::NotValidTautologyCase(_) => "la.not_valid_tautology_case",
+            LinearArithmeticError::InvalidDisequalityOp(_) => "la.invalid_disequality_op",
+            LinearArithmeticError::TooManyArgsInDisequality(_) => "la.too_many_args_in_disequality",
+            LinearArithmeticError::DisequalityIsNotContradiction(..) => {
+                "la.disequality_is_not_contradiction"
+            }
+            LinearArithmeticError::DisequalityIsNotTautology(..) => {
+                "la.disequality_is_not_tautology"
+            }
+            LinearArithmeticError::ExpectedLessThan(..) => "la.expected_less_than",
+            LinearArithmeticError::ExpectedLessEq(..) => "la.expected_less_eq",
+            LinearArithmeticError::NoFarkasCoefficients => "la.no_farkas_coefficients",
+            LinearArithmeticError::LiaGenericNotValid => "la.lia_generic_not_valid",
+        }
+    }
+
+    /// Returns the terms carried by this error, in the order they appear in the error message.
+    /// For errors that carry a linear combination, these are the variables of that combination.
+    pub fn terms(&self) -> Vec<&Rc<Term>> {
+        match self {
+            LinearArithmeticError::NotValidTautologyCase(t)
+            | LinearArithmeticError::InvalidDisequalityOp(t)
+            | LinearArithmeticError::TooManyArgsInDisequality(t) => vec![t],
+            LinearArithmeticError::DisequalityIsNotContradiction(_, LinearComb(vars, _))
+            | LinearArithmeticError::DisequalityIsNotTautology(_, LinearComb(vars, _)) => {
+                vars.keys().collect()
+            }
+            LinearArithmeticError::ExpectedLessThan(a, b)
+            | LinearArithmeticError::ExpectedLessEq(a, b) => vec![a, b],
+            LinearArithmeticError::NoFarkasCoefficients
+            | LinearArithmeticError::LiaGenericNotValid => Vec::new(),
+        }
+    }
 }
 
 /// Errors relevant to all rules that end subproofs (not just the `subproof` rule).
@@ -344,6 +636,319 @@ pub enum SubproofError {
     OnepointWrongRightBindings(BindingList),
 }
 
+impl SubproofError {
+    /// Returns a stable identifier for the kind of this error.
+    pub fn code(&self) -> &'static str {
+        match self {
+            SubproofError::DischargeMustBeAssume(_) => "subproof.discharge_must_be_assume",
+            SubproofError::LocalAssumeNotDischarged(_) => "subproof.local_assume_not_discharged",
+            SubproofError::DischargeInWrongRule => "subproof.discharge_in_wrong_rule",
+            SubproofError::BindBindingIsFreeVarInPhi(_) => {
+                "subproof.bind_binding_is_free_var_in_phi"
+            }
+            SubproofError::BindUnexpectedVarArgument(_) => "subproof.bind_unexpected_var_argument",
+            SubproofError::BindDifferentNumberOfBindings(..) => {
+                "subproof.bind_different_number_of_bindings"
+            }
+            SubproofError::BindingIsNotInContext(_) => "subproof.binding_is_not_in_context",
+            SubproofError::WrongNumberOfLetBindings(..) => "subproof.wrong_number_of_let_bindings",
+            SubproofError::PremiseDoesntJustifyLet { .. } => "subproof.premise_doesnt_justify_let",
+            SubproofError::NoPointForSubstitution(..) => "subproof.no_point_for_substitution",
+            SubproofError::OnepointWrongLeftBindings(_) => "subproof.onepoint_wrong_left_bindings",
+            SubproofError::OnepointWrongRightBindings(_) => {
+                "subproof.onepoint_wrong_right_bindings"
+            }
+        }
+    }
+
+    /// Returns the terms carried by this error, in the order they appear in the error message.
+    pub fn terms(&self) -> Vec<&Rc<Term>> {
+        match self {
+            SubproofError::PremiseDoesntJustifyLet { substitution, premise } => {
+                vec![&premise.0, &premise.1, &substitution.0, &substitution.1]
+            }
+            SubproofError::NoPointForSubstitution(_, t) => vec![t],
+            _ => Vec::new(),
+        }
+    }
+}
+
+/// Errors relevant to the cutting planes rules.
+#[derive(Debug, Error)]
+pub enum CuttingPlanesError {
+    #[error("term is neither a plain nor a negated pseudo-boolean literal: '{0}'")]
+    NotPseudoBooleanLiteral(Rc<Term>),
+
+    #[error("literal '{0}' does not appear in both pseudo-boolean sums")]
+    MissingLiteral(String),
+
+    #[error("literal of the conclusion is not present in either premise: '{0}'")]
+    LiteralNotInPremises(String),
+
+    #[error("expected {left} + {right} to be equal to {conclusion} + {slack}")]
+    ConstantsDontMatch {
+        left: Integer,
+        right: Integer,
+        conclusion: Integer,
+        slack: Integer,
+    },
+
+    #[error("operator '{0}' is not a supported relation")]
+    UnsupportedRelation(Operator),
+
+    #[error("found integer constant {0} in left-hand side of normalized term")]
+    ConstantInNormalizedSum(Integer),
+
+    #[error("expected normalized sums to have the same number of summands, got {0} and {1}")]
+    DifferentNumberOfSummands(usize, usize),
+
+    #[error("summands don't match: '{0}' and '{1}'")]
+    DifferentSummands(String, String),
+}
+
+impl CuttingPlanesError {
+    /// Returns a stable identifier for the kind of this error.
+    pub fn code(&self) -> &'static str {
+        match self {
+            CuttingPlanesError::NotPseudoBooleanLiteral(_) => "cp.not_pseudo_boolean_literal",
+            CuttingPlanesError::MissingLiteral(_) => "cp.missing_literal",
+            CuttingPlanesError::LiteralNotInPremises(_) => "cp.literal_not_in_premises",
+            CuttingPlanesError::ConstantsDontMatch { .. } => "cp.constants_dont_match",
+            CuttingPlanesError::UnsupportedRelation(_) => "cp.unsupported_relation",
+            CuttingPlanesError::ConstantInNormalizedSum(_) => "cp.constant_in_normalized_sum",
+            CuttingPlanesError::DifferentNumberOfSummands(..) => "cp.different_number_of_summands",
+            CuttingPlanesError::DifferentSummands(..) => "cp.different_summands",
+        }
+    }
+
+    /// Returns the terms carried by this error, in the order they appear in the error message.
+    pub fn terms(&self) -> Vec<&Rc<Term>> {
+        match self {
+            CuttingPlanesError::NotPseudoBooleanLiteral(t) => vec![t],
+            _ => Vec::new(),
+        }
+    }
+}
+
+/// Errors relevant to the pseudo-boolean bit-blasting rules.
+#[derive(Debug, Error)]
+pub enum PbBlastingError {
+    #[error("term '{0}' is not a bitvector")]
+    NotABitvector(Rc<Term>),
+
+    #[error("bitvector width {0} is too large")]
+    WidthTooLarge(Integer),
+
+    #[error("expected at most {bits} summands, got {summands}")]
+    TooManySummands { bits: usize, summands: usize },
+
+    #[error("summand '{0}' does not have the expected form")]
+    MalformedSummand(Rc<Term>),
+
+    #[error("expected coefficient to be 2^{exponent}, got {got}")]
+    CoefficientNotPowerOfTwo { exponent: usize, got: Integer },
+
+    #[error("expected index to be {expected}, got {got}")]
+    WrongIndex { expected: usize, got: Integer },
+
+    #[error("'{0}' has no sign bit")]
+    MissingSignBit(Rc<Term>),
+
+    #[error("expected {0} bits in '@pbbterm', got {1}")]
+    WrongNumberOfBits(usize, usize),
+
+    #[error("expected bit '{0}' to be 0 or 1")]
+    InvalidBit(Rc<Term>),
+
+    #[error("bits represent value {computed}, expected {expected}")]
+    WrongConstantValue {
+        expected: Integer,
+        computed: Integer,
+    },
+
+    #[error("expected choice variable to be named 'z', got '{0}'")]
+    WrongChoiceVariableName(String),
+
+    #[error("expected choice variable to be of sort 'Int', got '{0}'")]
+    WrongChoiceVariableSort(Rc<Term>),
+
+    #[error("expected choice variable '{0}', got '{1}'")]
+    ExpectedChoiceVariable(String, Rc<Term>),
+}
+
+impl PbBlastingError {
+    /// Returns a stable identifier for the kind of this error.
+    pub fn code(&self) -> &'static str {
+        match self {
+            PbBlastingError::NotABitvector(_) => "pbblast.not_a_bitvector",
+            PbBlastingError::WidthTooLarge(_) => "pbblast.width_too_large",
+            PbBlastingError::TooManySummands { .. } => "pbblast.too_many_summands",
+            PbBlastingError::MalformedSummand(_) => "pbblast.malformed_summand",
+            PbBlastingError::CoefficientNotPowerOfTwo { .. } => {
+                "pbblast.coefficient_not_power_of_two"
+            }
+            PbBlastingError::WrongIndex { .. } => "pbblast.wrong_index",
+            PbBlastingError::MissingSignBit(_) => "pbblast.missing_sign_bit",
+            PbBlastingError::WrongNumberOfBits(..) => "pbblast.wrong_number_of_bits",
+            PbBlastingError::InvalidBit(_) => "pbblast.invalid_bit",
+            PbBlastingError::WrongConstantValue { .. } => "pbblast.wrong_constant_value",
+            PbBlastingError::WrongChoiceVariableName(_) => "pbblast.wrong_choice_variable_name",
+            PbBlastingError::WrongChoiceVariableSort(_) => "pbblast.wrong_choice_variable_sort",
+            PbBlastingError::ExpectedChoiceVariable(..) => "pbblast.expected_choice_variable",
+        }
+    }
+
+    /// Returns the terms carried by this error, in the order they appear in the error message.
+    pub fn terms(&self) -> Vec<&Rc<Term>> {
+        match self {
+            PbBlastingError::NotABitvector(t)
+            | PbBlastingError::MalformedSummand(t)
+            | PbBlastingError::MissingSignBit(t)
+            | PbBlastingError::InvalidBit(t)
+            | PbBlastingError::WrongChoiceVariableSort(t)
+            | PbBlastingError::ExpectedChoiceVariable(_, t) => vec![t],
+            _ => Vec::new(),
+        }
+    }
+}
+
+/// Errors relevant to the datatype rules.
+#[derive(Debug, Error)]
+pub enum DatatypeError {
+    #[error("term '{0}' is not of a datatype sort")]
+    NotADatatypeTerm(Rc<Term>),
+
+    #[error("term '{0}' is not a constructor application")]
+    NotAConstructorApp(Rc<Term>),
+
+    #[error("term '{0}' is not a selector application")]
+    NotASelectorApp(Rc<Term>),
+
+    #[error("term '{0}' is not a tester application")]
+    NotATester(Rc<Term>),
+
+    #[error("expected constructor '{0}', got '{1}'")]
+    WrongConstructor(String, String),
+
+    #[error("expected different constructors, but both terms use '{0}'")]
+    SameConstructor(String),
+
+    #[error("selector '{0}' does not belong to constructor '{1}'")]
+    SelectorOfOtherConstructor(String, String),
+
+    #[error("datatype '{0}' does not have exactly one constructor")]
+    NotSingletonDatatype(String),
+
+    #[error("'{0}' is not an argument of the constructor applications in the premise")]
+    NotAnArgumentEquality(Rc<Term>),
+
+    #[error("term '{0}' does not occur in '{1}' under constructor applications")]
+    NoCycle(Rc<Term>, Rc<Term>),
+}
+
+impl DatatypeError {
+    /// Returns a stable identifier for the kind of this error.
+    pub fn code(&self) -> &'static str {
+        match self {
+            DatatypeError::NotADatatypeTerm(_) => "dt.not_a_datatype_term",
+            DatatypeError::NotAConstructorApp(_) => "dt.not_a_constructor_app",
+            DatatypeError::NotASelectorApp(_) => "dt.not_a_selector_app",
+            DatatypeError::NotATester(_) => "dt.not_a_tester",
+            DatatypeError::WrongConstructor(..) => "dt.wrong_constructor",
+            DatatypeError::SameConstructor(_) => "dt.same_constructor",
+            DatatypeError::SelectorOfOtherConstructor(..) => "dt.selector_of_other_constructor",
+            DatatypeError::NotSingletonDatatype(_) => "dt.not_singleton_datatype",
+            DatatypeError::NotAnArgumentEquality(_) => "dt.not_an_argument_equality",
+            DatatypeError::NoCycle(..) => "dt.no_cycle",
+        }
+    }
+
+    /// Returns the terms carried by this error, in the order they appear in the error message.
+    pub fn terms(&self) -> Vec<&Rc<Term>> {
+        match self {
+            DatatypeError::NotADatatypeTerm(t)
+            | DatatypeError::NotAConstructorApp(t)
+            | DatatypeError::NotASelectorApp(t)
+            | DatatypeError::NotATester(t)
+            | DatatypeError::NotAnArgumentEquality(t) => vec![t],
+            DatatypeError::NoCycle(a, b) => vec![a, b],
+            _ => Vec::new(),
+        }
+    }
+}
+
+/// Errors relevant to the array rules.
+#[derive(Debug, Error)]
+pub enum ArrayError {
+    #[error("term '{0}' is not a constant array")]
+    NotAConstantArray(Rc<Term>),
+
+    #[error("term '{0}' is not a valid extensionality witness for '{1}' and '{2}'")]
+    InvalidExtWitness(Rc<Term>, Rc<Term>, Rc<Term>),
+}
+
+impl ArrayError {
+    /// Returns a stable identifier for the kind of this error.
+    pub fn code(&self) -> &'static str {
+        match self {
+            ArrayError::NotAConstantArray(_) => "array.not_a_constant_array",
+            ArrayError::InvalidExtWitness(..) => "array.invalid_ext_witness",
+        }
+    }
+
+    /// Returns the terms carried by this error, in the order they appear in the error message.
+    pub fn terms(&self) -> Vec<&Rc<Term>> {
+        match self {
+            ArrayError::NotAConstantArray(t) => vec![t],
+            ArrayError::InvalidExtWitness(k, a, b) => vec![k, a, b],
+        }
+    }
+}
+
+/// Errors relevant to the `rare_rewrite` rule.
+#[derive(Debug, Error)]
+pub enum RareError {
+    #[error("unknown RARE rule: '{0}'")]
+    UnknownRule(String),
+
+    #[error("parameter '{0}' is not a list, but was instantiated with a RARE list")]
+    UnexpectedList(String),
+
+    #[error("list parameter '{0}' must be instantiated with a single term outside of an operator")]
+    ListOutsideOperator(String),
+
+    #[error("operator '{0}' can't be applied to an empty list, since it has no neutral element")]
+    NoNeutralElement(Operator),
+
+    #[error("can't evaluate '{0}' application: its arguments don't have the expected form")]
+    CannotEvaluate(Operator),
+
+    #[error("condition '{0}' is not a premise, and can't be evaluated to `true`")]
+    UnprovenCondition(Rc<Term>),
+}
+
+impl RareError {
+    /// Returns a stable identifier for the kind of this error.
+    pub fn code(&self) -> &'static str {
+        match self {
+            RareError::UnknownRule(_) => "rare.unknown_rule",
+            RareError::UnexpectedList(_) => "rare.unexpected_list",
+            RareError::ListOutsideOperator(_) => "rare.list_outside_operator",
+            RareError::NoNeutralElement(_) => "rare.no_neutral_element",
+            RareError::CannotEvaluate(_) => "rare.cannot_evaluate",
+            RareError::UnprovenCondition(_) => "rare.unproven_condition",
+        }
+    }
+
+    /// Returns the terms carried by this error, in the order they appear in the error message.
+    pub fn terms(&self) -> Vec<&Rc<Term>> {
+        match self {
+            RareError::UnprovenCondition(t) => vec![t],
+            _ => Vec::new(),
+        }
+    }
+}
+
 /// A wrapper struct that implements `fmt::Display` for linear combinations.
 struct DisplayLinearComb<'a>(&'a Operator, &'a LinearComb);
 
@@ -1,24 +1,37 @@
 pub mod error;
 mod parallel;
+mod rewriter;
 mod rules;
+mod streaming;
 
 use crate::{
     ast::*,
     benchmarking::{CollectResults, OnlineBenchmarkResults},
+    lia_prover::{self, LiaProverResult},
+    rare::RareRules,
     CarcaraResult, Error,
 };
-use error::{CheckerError, SubproofError};
+use error::{CheckerError, LinearArithmeticError, SubproofError};
 use indexmap::IndexSet;
-pub use parallel::{scheduler::Scheduler, ParallelProofChecker};
+pub use parallel::{
+    scheduler::{Scheduler, StepWeights},
+    ParallelProofChecker,
+};
+pub use rewriter::{Rewrite, Rewriter};
 use rules::{Premise, Rule, RuleArgs, RuleResult};
 use std::{
     collections::HashSet,
     fmt,
+    sync::Arc,
     time::{Duration, Instant},
 };
+pub use streaming::StreamingChecker;
 
-// The elaborator needs to use this function to elaborate `bfun_elim` steps
+// The elaborator needs to use these functions to elaborate `bfun_elim`, `la_generic` and `qnt_cnf`
+// steps
 pub(crate) use rules::clausification::apply_bfun_elim;
+pub(crate) use rules::linear_arithmetic::{find_la_generic_args, la_generic_constraints};
+pub(crate) use rules::quantifier::qnt_cnf_clauses;
 
 #[derive(Clone)]
 pub struct CheckerStatistics<'s, CR: CollectResults + Send + Default> {
@@ -61,6 +74,24 @@ pub struct Config {
 
     /// A set of rule names that the checker will allow, considering them holes in the proof.
     pub allowed_rules: HashSet<String>,
+
+    /// If `true`, the checker will not stop at the first step that fails to check. Instead, it
+    /// will record the failure, trust the step's conclusion, and keep checking the remaining steps.
+    /// All failures are then reported together in an `Error::CheckerFailures`.
+    pub keep_going: bool,
+
+    /// If `true`, the checker will try to prove `lia_generic` steps using its built-in integer
+    /// arithmetic prover, and check the resulting proof. Steps that the prover can't prove are
+    /// still considered holes. Normally, all `lia_generic` steps are considered holes.
+    pub prove_lia_generic: bool,
+
+    /// The RARE rewrite rules used to check `rare_rewrite` steps. If this is `None`, `rare_rewrite`
+    /// is treated as an unknown rule.
+    pub rare_rules: Option<Arc<RareRules>>,
+
+    /// The rewriter used to check `all_simplify` steps. Steps that it can't prove are still
+    /// considered holes. If this is `None`, `all_simplify` is treated as an unknown rule.
+    pub all_simplify_rewriter: Option<Rewriter>,
 }
 
 impl Config {
@@ -77,6 +108,55 @@ impl Config {
         self.ignore_unknown_rules = value;
         self
     }
+
+    pub fn keep_going(mut self, value: bool) -> Self {
+        self.keep_going = value;
+        self
+    }
+
+    pub fn prove_lia_generic(mut self, value: bool) -> Self {
+        self.prove_lia_generic = value;
+        self
+    }
+
+    pub fn rare_rules(mut self, value: RareRules) -> Self {
+        self.rare_rules = Some(Arc::new(value));
+        self
+    }
+
+    pub fn all_simplify_rewriter(mut self, value: Rewriter) -> Self {
+        self.all_simplify_rewriter = Some(value);
+        self
+    }
+}
+
+/// A step that failed to check. When the checker is run with `Config::keep_going`, it collects one
+/// of these for every failing step.
+#[derive(Debug)]
+pub struct CheckerFailure {
+    pub step: String,
+    pub rule: String,
+    pub inner: CheckerError,
+}
+
+impl fmt::Display for CheckerFailure {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "step '{}' with rule '{}': {}",
+            self.step, self.rule, self.inner
+        )
+    }
+}
+
+impl From<CheckerFailure> for Error {
+    fn from(failure: CheckerFailure) -> Self {
+        Error::Checker {
+            inner: failure.inner,
+            rule: failure.rule,
+            step: failure.step,
+        }
+    }
 }
 
 pub struct ProofChecker<'c> {
@@ -85,6 +165,7 @@ pub struct ProofChecker<'c> {
     context: ContextStack,
     reached_empty_clause: bool,
     is_holey: bool,
+    failures: Vec<CheckerFailure>,
 }
 
 impl<'c> ProofChecker<'c> {
@@ -95,6 +176,7 @@ impl<'c> ProofChecker<'c> {
             context: ContextStack::new(),
             reached_empty_clause: false,
             is_holey: false,
+            failures: Vec::new(),
         }
     }
 
@@ -140,12 +222,13 @@ impl<'c> ProofChecker<'c> {
                     } else {
                         None
                     };
-                    self.check_step(step, previous_command, &iter, &mut stats)
-                        .map_err(|e| Error::Checker {
-                            inner: e,
-                            rule: step.rule.clone(),
+                    if let Err(e) = self.check_step(step, previous_command, &iter, &mut stats) {
+                        self.record_failure(CheckerFailure {
                             step: step.id.clone(),
+                            rule: step.rule.clone(),
+                            inner: e,
                         })?;
+                    }
 
                     // If this is the last command of a subproof, we have to pop the subproof
                     // commands off of the stack. The parser already ensures that the last command
@@ -181,29 +264,49 @@ impl<'c> ProofChecker<'c> {
                     }
                 }
                 ProofCommand::Assume { id, term } => {
-                    if !self.check_assume(id, term, &problem.premises, &iter, &mut stats) {
-                        return Err(Error::Checker {
-                            inner: CheckerError::Assume(term.clone()),
-                            rule: "assume".into(),
+                    if !self.check_assume(
+                        id,
+                        term,
+                        &problem.premises,
+                        iter.is_in_subproof(),
+                        &mut stats,
+                    ) {
+                        self.record_failure(CheckerFailure {
                             step: id.clone(),
-                        });
+                            rule: "assume".into(),
+                            inner: CheckerError::Assume(term.clone()),
+                        })?;
                     }
                 }
             }
         }
-        if self.reached_empty_clause {
+        if !self.failures.is_empty() {
+            Err(Error::CheckerFailures(std::mem::take(&mut self.failures)))
+        } else if self.reached_empty_clause {
             Ok(self.is_holey)
         } else {
             Err(Error::DoesNotReachEmptyClause)
         }
     }
 
-    fn check_assume<'i, CR: CollectResults + Send + Default>(
+    /// Handles a step that failed to check. Normally, this simply returns the failure as an error,
+    /// aborting the checking. If `Config::keep_going` is enabled, the failure is instead recorded,
+    /// and the step's conclusion is trusted so that later steps can still be checked.
+    fn record_failure(&mut self, failure: CheckerFailure) -> CarcaraResult<()> {
+        if self.config.keep_going {
+            self.failures.push(failure);
+            Ok(())
+        } else {
+            Err(failure.into())
+        }
+    }
+
+    fn check_assume<CR: CollectResults + Send + Default>(
         &mut self,
         id: &str,
         term: &Rc<Term>,
         premises: &IndexSet<Rc<Term>>,
-        iter: &'i ProofIter<'i>,
+        is_in_subproof: bool,
         mut stats: &mut Option<&mut CheckerStatistics<CR>>,
     ) -> bool {
         let time = Instant::now();
@@ -211,7 +314,7 @@ impl<'c> ProofChecker<'c> {
         // Some subproofs contain `assume` commands inside them. These don't refer to the original
         // problem premises, but are instead local assumptions that are discharged by the subproof's
         // final step, so we ignore the `assume` command if it is inside a subproof.
-        if iter.is_in_subproof() {
+        if is_in_subproof {
             return true;
         }
 
@@ -275,6 +378,45 @@ impl<'c> ProofChecker<'c> {
         previous_command: Option<Premise>,
         iter: &'i ProofIter<'i>,
         stats: &mut Option<&mut CheckerStatistics<CR>>,
+    ) -> RuleResult {
+        let premises: Vec<_> = step
+            .premises
+            .iter()
+            .map(|&p| {
+                let command = iter.get_premise(p);
+                Premise::new(p, command)
+            })
+            .collect();
+        let discharge: Vec<_> = step
+            .discharge
+            .iter()
+            .map(|&i| iter.get_premise(i))
+            .collect();
+        let local_assumes = iter.is_end_step().then(|| {
+            let subproof = iter.current_subproof().unwrap();
+            Self::subproof_assumes(subproof, iter.depth()).collect::<Vec<_>>()
+        });
+        self.check_step_with_premises(
+            step,
+            previous_command,
+            &premises,
+            &discharge,
+            local_assumes.as_deref(),
+            stats,
+        )
+    }
+
+    /// Checks a step whose premises were already fetched. If the step ends a subproof,
+    /// `local_assumes` should contain the index and id of each `assume` command in the subproof,
+    /// so that the checker can make sure that they are all discharged.
+    fn check_step_with_premises<CR: CollectResults + Send + Default>(
+        &mut self,
+        step: &ProofStep,
+        previous_command: Option<Premise>,
+        premises: &[Premise],
+        discharge: &[&ProofCommand],
+        local_assumes: Option<&[((usize, usize), &str)]>,
+        stats: &mut Option<&mut CheckerStatistics<CR>>,
     ) -> RuleResult {
         let time = Instant::now();
         let mut polyeq_time = Duration::ZERO;
@@ -283,51 +425,43 @@ impl<'c> ProofChecker<'c> {
             return Err(CheckerError::Subproof(SubproofError::DischargeInWrongRule));
         }
 
-        let rule = match Self::get_rule(&step.rule, self.config.elaborated) {
+        let rule = match Self::get_configured_rule(&self.config, &step.rule) {
             Some(r) => r,
             None if self.config.ignore_unknown_rules
                 || self.config.allowed_rules.contains(&step.rule) =>
             {
                 self.is_holey = true;
+                if let Some(s) = stats {
+                    s.results.add_hole(s.file_name, &step.id, &step.rule);
+                }
                 return Ok(());
             }
             None => return Err(CheckerError::UnknownRule),
         };
 
-        if step.rule == "hole" || step.rule == "lia_generic" {
+        if is_hole(self.pool, &self.config, step)? {
             self.is_holey = true;
+            if let Some(s) = stats {
+                s.results.add_hole(s.file_name, &step.id, &step.rule);
+            }
         }
 
-        let premises: Vec<_> = step
-            .premises
-            .iter()
-            .map(|&p| {
-                let command = iter.get_premise(p);
-                Premise::new(p, command)
-            })
-            .collect();
-        let discharge: Vec<_> = step
-            .discharge
-            .iter()
-            .map(|&i| iter.get_premise(i))
-            .collect();
-
         let rule_args = RuleArgs {
             conclusion: &step.clause,
-            premises: &premises,
+            premises,
             args: &step.args,
             pool: self.pool,
             context: &mut self.context,
             previous_command,
-            discharge: &discharge,
+            discharge,
+            rare_rules: self.config.rare_rules.as_deref(),
             polyeq_time: &mut polyeq_time,
         };
 
         rule(rule_args)?;
 
-        if iter.is_end_step() {
-            let subproof = iter.current_subproof().unwrap();
-            Self::check_discharge(subproof, iter.depth(), &step.discharge)?;
+        if let Some(assumes) = local_assumes {
+            Self::check_assumes_discharged(assumes.iter().copied(), &step.discharge)?;
         }
 
         if let Some(s) = stats {
@@ -340,25 +474,52 @@ impl<'c> ProofChecker<'c> {
         Ok(())
     }
 
+    /// Returns the index and id of each `assume` command in a subproof.
+    fn subproof_assumes(
+        subproof: &[ProofCommand],
+        depth: usize,
+    ) -> impl Iterator<Item = ((usize, usize), &str)> {
+        subproof
+            .iter()
+            .enumerate()
+            .filter(|(_, command)| command.is_assume())
+            .map(move |(i, command)| ((depth, i), command.id()))
+    }
+
     fn check_discharge(
         subproof: &[ProofCommand],
         depth: usize,
         discharge: &[(usize, usize)],
+    ) -> RuleResult {
+        Self::check_assumes_discharged(Self::subproof_assumes(subproof, depth), discharge)
+    }
+
+    fn check_assumes_discharged<'a>(
+        mut assumes: impl Iterator<Item = ((usize, usize), &'a str)>,
+        discharge: &[(usize, usize)],
     ) -> RuleResult {
         let discharge: IndexSet<_> = discharge.iter().collect();
-        if let Some((_, not_discharged)) = subproof
-            .iter()
-            .enumerate()
-            .find(|&(i, command)| command.is_assume() && !discharge.contains(&(depth, i)))
-        {
+        if let Some((_, not_discharged)) = assumes.find(|(i, _)| !discharge.contains(i)) {
             Err(CheckerError::Subproof(
-                SubproofError::LocalAssumeNotDischarged(not_discharged.id().to_owned()),
+                SubproofError::LocalAssumeNotDischarged(not_discharged.to_owned()),
             ))
         } else {
             Ok(())
         }
     }
 
+    /// Like `get_rule`, but also considers rules that can only be checked depending on the
+    /// configuration, like `rare_rewrite` and `all_simplify`.
+    fn get_configured_rule(config: &Config, rule_name: &str) -> Option<Rule> {
+        match rule_name {
+            "rare_rewrite" if config.rare_rules.is_some() => Some(rules::rare::rare_rewrite),
+            "all_simplify" if config.all_simplify_rewriter.is_some() => {
+                Some(rules::simplification::all_simplify)
+            }
+            _ => Self::get_rule(rule_name, config.elaborated),
+        }
+    }
+
     pub fn get_rule(rule_name: &str, elaborated: bool) -> Option<Rule> {
         use rules::*;
 
@@ -391,6 +552,7 @@ impl<'c> ProofChecker<'c> {
             "eq_congruent_pred" => congruence::eq_congruent_pred,
             "distinct_elim" => clausification::distinct_elim,
             "la_rw_eq" => linear_arithmetic::la_rw_eq,
+            "la_generic" if elaborated => linear_arithmetic::strict_la_generic,
             "la_generic" => linear_arithmetic::la_generic,
             "la_disequality" => linear_arithmetic::la_disequality,
             "la_totality" => linear_arithmetic::la_totality,
@@ -464,9 +626,45 @@ impl<'c> ProofChecker<'c> {
             "la_mult_pos" => extras::la_mult_pos,
             "la_mult_neg" => extras::la_mult_neg,
             "mod_simplify" => extras::mod_simplify,
+            "bitblast_var" => bitvectors::var,
+            "bitblast_const" => bitvectors::constant,
+            "bitblast_equal" => bitvectors::equal,
+            "bitblast_ite" => bitvectors::ite,
             "bitblast_extract" => bitvectors::extract,
+            "bitblast_concat" => bitvectors::concat,
+            "bitblast_zero_extend" => bitvectors::zero_extend,
+            "bitblast_sign_extend" => bitvectors::sign_extend,
+            "bitblast_repeat" => bitvectors::repeat,
+            "bitblast_rotate_left" => bitvectors::rotate_left,
+            "bitblast_rotate_right" => bitvectors::rotate_right,
+            "bitblast_bvnot" => bitvectors::not,
+            "bitblast_bvand" => bitvectors::and,
+            "bitblast_bvor" => bitvectors::or,
+            "bitblast_bvxor" => bitvectors::xor,
+            "bitblast_bvnand" => bitvectors::nand,
+            "bitblast_bvnor" => bitvectors::nor,
+            "bitblast_bvxnor" => bitvectors::xnor,
+            "bitblast_bvcomp" => bitvectors::comp,
+            "bitblast_bvneg" => bitvectors::neg,
             "bitblast_bvadd" => bitvectors::add,
+            "bitblast_bvsub" => bitvectors::sub,
+            "bitblast_bvmul" => bitvectors::mul,
+            "bitblast_bvudiv" => bitvectors::udiv,
+            "bitblast_bvurem" => bitvectors::urem,
+            "bitblast_bvsdiv" => bitvectors::sdiv,
+            "bitblast_bvsrem" => bitvectors::srem,
+            "bitblast_bvsmod" => bitvectors::smod,
+            "bitblast_bvshl" => bitvectors::shl,
+            "bitblast_bvlshr" => bitvectors::lshr,
+            "bitblast_bvashr" => bitvectors::ashr,
             "bitblast_ult" => bitvectors::ult,
+            "bitblast_ule" => bitvectors::ule,
+            "bitblast_ugt" => bitvectors::ugt,
+            "bitblast_uge" => bitvectors::uge,
+            "bitblast_slt" => bitvectors::slt,
+            "bitblast_sle" => bitvectors::sle,
+            "bitblast_sgt" => bitvectors::sgt,
+            "bitblast_sge" => bitvectors::sge,
 
             "concat_eq" => strings::concat_eq,
             "concat_unify" => strings::concat_unify,
@@ -496,6 +694,17 @@ impl<'c> ProofChecker<'c> {
             "pbblast_bvand" => pb_blasting::pbblast_bvand,
             "pbblast_bvxor_ith_bit" => pb_blasting::pbblast_bvxor_ith_bit,
             "pbblast_bvand_ith_bit" => pb_blasting::pbblast_bvand_ith_bit,
+            "pbblast_bvadd" => pb_blasting::pbblast_bvadd,
+            "pbblast_bvsub" => pb_blasting::pbblast_bvsub,
+            "pbblast_bvneg" => pb_blasting::pbblast_bvneg,
+            "pbblast_bvmul" => pb_blasting::pbblast_bvmul,
+            "pbblast_bvshl" => pb_blasting::pbblast_bvshl,
+            "pbblast_bvlshr" => pb_blasting::pbblast_bvlshr,
+            "pbblast_bvashr" => pb_blasting::pbblast_bvashr,
+            "pbblast_concat" => pb_blasting::pbblast_concat,
+            "pbblast_extract" => pb_blasting::pbblast_extract,
+            "pbblast_zero_extend" => pb_blasting::pbblast_zero_extend,
+            "pbblast_sign_extend" => pb_blasting::pbblast_sign_extend,
 
             // cutting planes rules
             "cp_addition" => cutting_planes::cp_addition,
@@ -505,6 +714,22 @@ impl<'c> ProofChecker<'c> {
             "cp_literal" => cutting_planes::cp_literal,
             "cp_normalize" => cutting_planes::cp_normalize,
 
+            // array rules
+            "array_read_over_write" => arrays::array_read_over_write,
+            "array_read_over_write_1" => arrays::array_read_over_write_1,
+            "array_const" => arrays::array_const,
+            "array_ext" => arrays::array_ext,
+
+            // datatype rules
+            "dt_split" => datatypes::dt_split,
+            "dt_inst" => datatypes::dt_inst,
+            "dt_collapse_selector" => datatypes::dt_collapse_selector,
+            "dt_collapse_tester" => datatypes::dt_collapse_tester,
+            "dt_collapse_tester_singleton" => datatypes::dt_collapse_tester_singleton,
+            "dt_unif" => datatypes::dt_unif,
+            "dt_clash" => datatypes::dt_clash,
+            "dt_cycle" => datatypes::dt_cycle,
+
             "string_decompose" => strings::string_decompose,
             "string_length_pos" => strings::string_length_pos,
             "string_length_non_empty" => strings::string_length_non_empty,
@@ -522,11 +747,7 @@ impl<'c> ProofChecker<'c> {
 
             // Special rules that always check as valid, and are used to indicate holes in the
             // proof.
-            "hole" => |_| Ok(()),
-            "lia_generic" => |_| {
-                log::warn!("encountered \"lia_generic\" rule, ignoring");
-                Ok(())
-            },
+            "hole" | "lia_generic" => |_| Ok(()),
 
             // The Alethe specification does not yet describe how this more strict version of the
             // resolution rule will be called. Until that is decided and added to the specification,
@@ -537,3 +758,89 @@ impl<'c> ProofChecker<'c> {
         })
     }
 }
+
+/// The rules whose steps may be holes in the proof. Depending on the configuration, the checker may
+/// still be able to prove some `lia_generic` and `all_simplify` steps.
+pub const HOLE_RULES: &[&str] = &["hole", "lia_generic", "all_simplify"];
+
+/// Returns `true` if the step is a hole. If enabled in the config, this tries to prove
+/// `lia_generic` and `all_simplify` steps, in which case they are not considered holes.
+fn is_hole(
+    pool: &mut dyn TermPool,
+    config: &Config,
+    step: &ProofStep,
+) -> Result<bool, CheckerError> {
+    match step.rule.as_str() {
+        "hole" => Ok(true),
+        "lia_generic" => {
+            let is_proved = config.prove_lia_generic && prove_lia_generic(pool, &step.clause)?;
+            if !is_proved {
+                log::warn!("encountered \"lia_generic\" rule, ignoring");
+            }
+            Ok(!is_proved)
+        }
+        "all_simplify" => {
+            let is_proved = (config.all_simplify_rewriter.as_ref())
+                .is_some_and(|r| r.proves_all_simplify(pool, &step.clause));
+            if !is_proved {
+                log::warn!(
+                    "couldn't prove \"all_simplify\" step '{}', ignoring",
+                    step.id
+                );
+            }
+            Ok(!is_proved)
+        }
+        _ => Ok(false),
+    }
+}
+
+/// Tries to prove the clause of a `lia_generic` step using the built-in prover, and checks the
+/// resulting proof. Returns `false` if the prover gave up.
+fn prove_lia_generic(pool: &mut dyn TermPool, clause: &[Rc<Term>]) -> Result<bool, CheckerError> {
+    let root = match lia_prover::prove(pool, clause, "lia_generic", 0) {
+        LiaProverResult::Proved(root) => root,
+        LiaProverResult::CounterModel => {
+            return Err(LinearArithmeticError::LiaGenericNotValid.into())
+        }
+        LiaProverResult::Unknown => return Ok(false),
+    };
+
+    // We don't trust the prover, so we check every step of the proof it found
+    let mut nodes = Vec::new();
+    root.traverse(|node| nodes.push(node.clone()));
+    for node in &nodes {
+        let ProofNode::Step(step) = node.as_ref() else {
+            unreachable!("the prover only creates steps")
+        };
+        let premises: Vec<_> = step.premises.iter().map(|p| (p.id(), p.clause())).collect();
+        check_isolated_step(pool, &step.rule, &premises, &step.args, &step.clause)?;
+    }
+    Ok(true)
+}
+
+/// Checks a single step that is not part of an Alethe proof, given the id and clause of each of its
+/// premises. This is used for steps built by Carcara itself, or translated from other proof formats.
+/// The step can't depend on any context, so rules that open or close subproofs can't be checked.
+pub(crate) fn check_isolated_step(
+    pool: &mut dyn TermPool,
+    rule: &str,
+    premises: &[(&str, &[Rc<Term>])],
+    args: &[Rc<Term>],
+    conclusion: &[Rc<Term>],
+) -> RuleResult {
+    let rule = ProofChecker::get_rule(rule, true).ok_or(CheckerError::UnknownRule)?;
+    let premises: Vec<_> = (premises.iter().enumerate())
+        .map(|(i, &(id, clause))| Premise { id, clause, index: (0, i) })
+        .collect();
+    rule(RuleArgs {
+        conclusion,
+        premises: &premises,
+        args,
+        pool,
+        context: &mut ContextStack::new(),
+        previous_command: None,
+        discharge: &[],
+        rare_rules: None,
+        polyeq_time: &mut Duration::default(),
+    })
+}
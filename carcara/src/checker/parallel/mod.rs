@@ -3,7 +3,7 @@ pub mod scheduler;
 use super::{
     error::{CheckerError, SubproofError},
     rules::{Premise, RuleArgs, RuleResult},
-    Config, ProofChecker,
+    CheckerFailure, Config, ProofChecker,
 };
 use crate::benchmarking::{CollectResults, OnlineBenchmarkResults};
 use crate::checker::CheckerStatistics;
@@ -12,7 +12,7 @@ use crate::{
     CarcaraResult, Error,
 };
 use indexmap::IndexSet;
-pub use scheduler::{Schedule, ScheduleIter, Scheduler};
+pub use scheduler::{Schedule, ScheduleIter, Scheduler, WorkQueues};
 use std::{
     ops::ControlFlow,
     sync::{atomic::AtomicBool, Arc},
@@ -27,6 +27,7 @@ pub struct ParallelProofChecker<'c> {
     context: ContextStack,
     reached_empty_clause: bool,
     is_holey: bool,
+    failures: Vec<(Vec<usize>, CheckerFailure)>,
     stack_size: usize,
 }
 
@@ -45,6 +46,7 @@ impl<'c> ParallelProofChecker<'c> {
             context: ContextStack::from_usage(context_usage),
             reached_empty_clause: false,
             is_holey: false,
+            failures: Vec::new(),
             stack_size,
         }
     }
@@ -58,6 +60,7 @@ impl<'c> ParallelProofChecker<'c> {
             context: ContextStack::from_previous(&self.context),
             reached_empty_clause: false,
             is_holey: false,
+            failures: Vec::new(),
             stack_size: self.stack_size,
         }
     }
@@ -72,13 +75,11 @@ impl<'c> ParallelProofChecker<'c> {
         // thread already found out an invalid step)
         let premature_abort = Arc::new(AtomicBool::new(false));
         let context_pool = ContextPool::from_global(&self.pool);
+        let queues = &WorkQueues::new(scheduler);
         //
         thread::scope(|s| {
-            let threads: Vec<_> = scheduler
-                .loads
-                .iter()
-                .enumerate()
-                .map(|(i, schedule)| {
+            let threads: Vec<_> = (0..scheduler.queues.len())
+                .map(|i| {
                     // Shares the self between threads
                     let mut local_self = self.share();
                     let local_pool = LocalPool::from_previous(&context_pool);
@@ -87,11 +88,11 @@ impl<'c> ParallelProofChecker<'c> {
                     thread::Builder::new()
                         .name(format!("worker-{i}"))
                         .stack_size(self.stack_size)
-                        .spawn_scoped(s, move || -> CarcaraResult<(bool, bool)> {
+                        .spawn_scoped(s, move || -> CarcaraResult<(bool, bool, _)> {
                             local_self.worker_thread_check(
                                 problem,
                                 proof,
-                                schedule,
+                                (scheduler, queues, i),
                                 local_pool,
                                 should_abort,
                                 None::<&mut CheckerStatistics<OnlineBenchmarkResults>>,
@@ -103,6 +104,7 @@ impl<'c> ParallelProofChecker<'c> {
 
             // Unify the results of all threads and generate the final result based on them
             let (mut reached, mut holey) = (false, false);
+            let mut failures = Vec::new();
             let mut err: Result<_, Error> = Ok(());
 
             // Wait until the threads finish and merge the results and statistics
@@ -111,9 +113,10 @@ impl<'c> ParallelProofChecker<'c> {
                 .map(|t| t.join().unwrap())
                 .try_for_each(|opt| {
                     match opt {
-                        Ok((local_reached, local_holey)) => {
+                        Ok((local_reached, local_holey, local_failures)) => {
                             // Mask the result booleans
                             (reached, holey) = (reached | local_reached, holey | local_holey);
+                            failures.extend(local_failures);
                             ControlFlow::Continue(())
                         }
                         Err(e) => {
@@ -126,7 +129,9 @@ impl<'c> ParallelProofChecker<'c> {
             // If an error happened
             err?;
 
-            if reached {
+            if !failures.is_empty() {
+                Err(Error::CheckerFailures(sort_failures(failures)))
+            } else if reached {
                 Ok(holey)
             } else {
                 Err(Error::DoesNotReachEmptyClause)
@@ -145,13 +150,11 @@ impl<'c> ParallelProofChecker<'c> {
         // thread already found out an invalid step)
         let premature_abort = Arc::new(AtomicBool::new(false));
         let context_pool = ContextPool::from_global(&self.pool);
+        let queues = &WorkQueues::new(scheduler);
         //
         thread::scope(|s| {
-            let threads: Vec<_> = scheduler
-                .loads
-                .iter()
-                .enumerate()
-                .map(|(i, schedule)| {
+            let threads: Vec<_> = (0..scheduler.queues.len())
+                .map(|i| {
                     let mut local_stats = CheckerStatistics {
                         file_name: "",
                         polyeq_time: Duration::ZERO,
@@ -169,17 +172,17 @@ impl<'c> ParallelProofChecker<'c> {
                         .stack_size(self.stack_size)
                         .spawn_scoped(
                             s,
-                            move || -> CarcaraResult<(bool, bool, CheckerStatistics<CR>)> {
+                            move || -> CarcaraResult<(bool, bool, _, CheckerStatistics<CR>)> {
                                 local_self
                                     .worker_thread_check(
                                         problem,
                                         proof,
-                                        schedule,
+                                        (scheduler, queues, i),
                                         local_pool,
                                         should_abort,
                                         Some(&mut local_stats),
                                     )
-                                    .map(|r| (r.0, r.1, local_stats))
+                                    .map(|r| (r.0, r.1, r.2, local_stats))
                             },
                         )
                         .unwrap()
@@ -188,6 +191,7 @@ impl<'c> ParallelProofChecker<'c> {
 
             // Unify the results of all threads and generate the final result based on them
             let (mut reached, mut holey) = (false, false);
+            let mut failures = Vec::new();
             let mut err: Result<_, Error> = Ok(());
 
             // Wait until the threads finish and merge the results and statistics
@@ -196,7 +200,7 @@ impl<'c> ParallelProofChecker<'c> {
                 .map(|t| t.join().unwrap())
                 .for_each(|opt| {
                     match opt {
-                        Ok((local_reached, local_holey, mut local_stats)) => {
+                        Ok((local_reached, local_holey, local_failures, mut local_stats)) => {
                             // Combine the statistics
                             // Takes the external and local benchmark results to local variables and combine them
                             let main = std::mem::take(&mut stats.results);
@@ -210,6 +214,7 @@ impl<'c> ParallelProofChecker<'c> {
 
                             // Mask the result booleans
                             (reached, holey) = (reached | local_reached, holey | local_holey);
+                            failures.extend(local_failures);
                         }
                         Err(e) => {
                             // Since we want the statistics of the whole run
@@ -224,7 +229,9 @@ impl<'c> ParallelProofChecker<'c> {
             // If an error happened
             err?;
 
-            if reached {
+            if !failures.is_empty() {
+                Err(Error::CheckerFailures(sort_failures(failures)))
+            } else if reached {
                 Ok(holey)
             } else {
                 Err(Error::DoesNotReachEmptyClause)
@@ -236,11 +243,47 @@ impl<'c> ParallelProofChecker<'c> {
         &mut self,
         problem: &Problem,
         proof: &Proof,
-        schedule: &Schedule,
+        (scheduler, queues, worker): (&Scheduler, &WorkQueues, usize),
         mut pool: LocalPool,
         should_abort: Arc<AtomicBool>,
         mut stats: Option<&mut CheckerStatistics<CR>>,
-    ) -> CarcaraResult<(bool, bool)> {
+    ) -> CarcaraResult<(bool, bool, Vec<(Vec<usize>, CheckerFailure)>)> {
+        use std::sync::atomic::Ordering;
+
+        while let Some(task) = queues.next_task(worker) {
+            let schedule = &scheduler.tasks[task];
+            self.check_schedule(
+                problem,
+                proof,
+                schedule,
+                &mut pool,
+                &should_abort,
+                &mut stats,
+            )?;
+            if should_abort.load(Ordering::Acquire) {
+                break;
+            }
+        }
+
+        // Returns Ok(reached empty clause, isHoley, failures)
+        let failures = std::mem::take(&mut self.failures);
+        if self.reached_empty_clause {
+            Ok((true, self.is_holey, failures))
+        } else {
+            Ok((false, self.is_holey, failures))
+        }
+    }
+
+    /// Checks the steps of a single task.
+    fn check_schedule<CR: CollectResults + Send + Default>(
+        &mut self,
+        problem: &Problem,
+        proof: &Proof,
+        schedule: &Schedule,
+        pool: &mut LocalPool,
+        should_abort: &AtomicBool,
+        stats: &mut Option<&mut CheckerStatistics<CR>>,
+    ) -> CarcaraResult<()> {
         use std::sync::atomic::Ordering;
 
         let mut iter = schedule.iter(&proof.commands[..]);
@@ -274,16 +317,17 @@ impl<'c> ParallelProofChecker<'c> {
                         None
                     };
 
-                    self.check_step(step, previous_command, &iter, &mut pool, &mut stats)
-                        .map_err(|e| {
-                            // Signalize to other threads to stop the proof checking
-                            should_abort.store(true, Ordering::Release);
-                            Error::Checker {
-                                inner: e,
-                                rule: step.rule.clone(),
+                    if let Err(e) = self.check_step(step, previous_command, &iter, pool, stats) {
+                        self.record_failure(
+                            iter.position(),
+                            CheckerFailure {
                                 step: step.id.clone(),
-                            }
-                        })?;
+                                rule: step.rule.clone(),
+                                inner: e,
+                            },
+                            should_abort,
+                        )?;
+                    }
 
                     if step.clause.is_empty() {
                         self.reached_empty_clause = true;
@@ -295,7 +339,7 @@ impl<'c> ParallelProofChecker<'c> {
 
                     self.context.push_with_id(&s.args, s.context_id);
 
-                    if let Some(stats) = &mut stats {
+                    if let Some(stats) = stats {
                         // Collects statistics
                         let rule_name = match s.commands.last() {
                             Some(ProofCommand::Step(step)) => {
@@ -312,14 +356,16 @@ impl<'c> ParallelProofChecker<'c> {
                     }
                 }
                 ProofCommand::Assume { id, term } => {
-                    if !self.check_assume(id, term, &problem.premises, &iter, &mut stats) {
-                        // Signalize to other threads to stop the proof checking
-                        should_abort.store(true, Ordering::Release);
-                        return Err(Error::Checker {
-                            inner: CheckerError::Assume(term.clone()),
-                            rule: "assume".into(),
-                            step: id.clone(),
-                        });
+                    if !self.check_assume(id, term, &problem.premises, &iter, stats) {
+                        self.record_failure(
+                            iter.position(),
+                            CheckerFailure {
+                                step: id.clone(),
+                                rule: "assume".into(),
+                                inner: CheckerError::Assume(term.clone()),
+                            },
+                            should_abort,
+                        )?;
                     }
                 }
             }
@@ -329,11 +375,33 @@ impl<'c> ParallelProofChecker<'c> {
             }
         }
 
-        // Returns Ok(reached empty clause, isHoley)
-        if self.reached_empty_clause {
-            Ok((true, self.is_holey))
+        // Each task starts at the root proof, so we pop the contexts of the subproofs that are
+        // still open
+        for _ in 0..last_depth {
+            self.context.pop();
+        }
+        Ok(())
+    }
+
+    /// Handles a step that failed to check, similarly to `ProofChecker::record_failure`. If
+    /// `Config::keep_going` is not enabled, this also signals the other threads to stop checking.
+    /// The failure is recorded along with the step's position in the proof, so the failures found
+    /// by different threads can be put back in proof order.
+    fn record_failure(
+        &mut self,
+        position: Vec<usize>,
+        failure: CheckerFailure,
+        should_abort: &AtomicBool,
+    ) -> CarcaraResult<()> {
+        use std::sync::atomic::Ordering;
+
+        if self.config.keep_going {
+            self.failures.push((position, failure));
+            Ok(())
         } else {
-            Ok((false, self.is_holey))
+            // Signalize to other threads to stop the proof checking
+            should_abort.store(true, Ordering::Release);
+            Err(failure.into())
         }
     }
 
@@ -421,17 +489,25 @@ impl<'c> ParallelProofChecker<'c> {
             return Err(CheckerError::Subproof(SubproofError::DischargeInWrongRule));
         }
 
-        let rule = match ProofChecker::get_rule(&step.rule, self.config.elaborated) {
+        let rule = match ProofChecker::get_configured_rule(&self.config, &step.rule) {
             Some(r) => r,
-            None if self.config.ignore_unknown_rules => {
+            None if self.config.ignore_unknown_rules
+                || self.config.allowed_rules.contains(&step.rule) =>
+            {
                 self.is_holey = true;
+                if let Some(s) = stats {
+                    s.results.add_hole(s.file_name, &step.id, &step.rule);
+                }
                 return Ok(());
             }
             None => return Err(CheckerError::UnknownRule),
         };
 
-        if step.rule == "hole" || step.rule == "lia_generic" {
+        if super::is_hole(pool, &self.config, step)? {
             self.is_holey = true;
+            if let Some(s) = stats {
+                s.results.add_hole(s.file_name, &step.id, &step.rule);
+            }
         }
 
         let premises: Vec<_> = step
@@ -456,6 +532,7 @@ impl<'c> ParallelProofChecker<'c> {
             context: &mut self.context,
             previous_command,
             discharge: &discharge,
+            rare_rules: self.config.rare_rules.as_deref(),
             polyeq_time: &mut polyeq_time,
         };
 
@@ -475,3 +552,10 @@ impl<'c> ParallelProofChecker<'c> {
         Ok(())
     }
 }
+
+/// Sorts the failures collected by all threads by their position in the proof, since each thread
+/// only returns the failures of the steps it checked.
+fn sort_failures(mut failures: Vec<(Vec<usize>, CheckerFailure)>) -> Vec<CheckerFailure> {
+    failures.sort_by(|(a, _), (b, _)| a.cmp(b));
+    failures.into_iter().map(|(_, f)| f).collect()
+}
@@ -1,7 +1,9 @@
-use crate::ast::{Proof, ProofCommand};
+use crate::ast::{Proof, ProofCommand, ProofStep};
 use std::{
     cmp::Ordering,
-    collections::{BinaryHeap, HashSet},
+    collections::{BinaryHeap, HashMap, VecDeque},
+    io::{self, BufRead},
+    sync::Mutex,
 };
 
 /// Struct responsible for storing a thread work schedule.
@@ -67,147 +69,339 @@ impl PartialEq for AssignedLoad {
     }
 }
 
-/// Represents a level in the proof stack. It holds the subproof itself,
-/// its prerequisite step (anchor) and which schedules used any step inside
-/// this layer
-struct StackLevel<'a> {
-    id: usize,
-    cmds: &'a [ProofCommand],
-    pre_req: Option<(usize, usize)>,
-    used_by: HashSet<usize>,
-}
+/// The number of tasks that the scheduler tries to create for each worker. Having more tasks than
+/// workers allows idle workers to steal work from busy ones, compensating for inaccurate weights.
+const TASKS_PER_WORKER: u64 = 8;
 
-impl<'a> StackLevel<'a> {
-    pub fn new(id: usize, cmds: &'a [ProofCommand], pre_req: Option<(usize, usize)>) -> Self {
-        Self {
-            id,
-            cmds,
-            pre_req,
-            used_by: HashSet::new(),
+/// The estimated cost of checking each rule, used by the scheduler to balance the work between
+/// threads. By default, the weights given by [`get_step_weight`] are used, but these can be
+/// replaced by times measured in previous runs.
+#[derive(Debug, Clone, Default)]
+pub struct StepWeights(HashMap<String, u64>);
+
+impl StepWeights {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Reads the mean checking time of each rule from a CSV file with a header and `rule,time`
+    /// rows, like the `by-rule.csv` file created by the `bench` command. Rules that do not appear
+    /// in the file use the default weights.
+    pub fn from_csv<R: BufRead>(input: R) -> io::Result<Self> {
+        let mut totals: HashMap<String, (u128, u128)> = HashMap::new();
+        for line in input.lines().skip(1) {
+            let line = line?;
+            let Some((rule, time)) = line.trim().rsplit_once(',') else {
+                continue;
+            };
+            let time: u128 = time.parse().map_err(|_| {
+                let message = format!("invalid time in step weights: '{}'", line);
+                io::Error::new(io::ErrorKind::InvalidData, message)
+            })?;
+            let (total, count) = totals.entry(rule.to_owned()).or_default();
+            *total += time;
+            *count += 1;
         }
+        let means = totals
+            .into_iter()
+            .map(|(rule, (total, count))| (rule, u64::try_from(total / count).unwrap_or(u64::MAX)))
+            .collect();
+        Ok(Self(means))
+    }
+
+    /// Returns the estimated cost of checking a command. The cost of a subproof is the sum of the
+    /// costs of its commands, which is computed separately by the scheduler.
+    pub fn weight(&self, command: &ProofCommand) -> u64 {
+        let rule = match command {
+            ProofCommand::Assume { .. } => "assume",
+            ProofCommand::Step(s) => &s.rule,
+            ProofCommand::Subproof(_) => return 0,
+        };
+        self.0
+            .get(rule)
+            .copied()
+            .unwrap_or_else(|| get_step_weight(command))
     }
 }
 
-/// Struct that stores the schedules for each thread.
+/// Struct that stores the work to be done by each thread.
+///
+/// The proof is split into tasks, each of which is checked by a single thread. Each thread starts
+/// with a queue of tasks, and when it runs out of work, it steals tasks from the queues of other
+/// threads.
 pub struct Scheduler {
-    pub loads: Vec<Schedule>,
+    /// The tasks, each represented by a schedule of the steps that it checks.
+    pub tasks: Vec<Schedule>,
+
+    /// The initial queue of tasks of each thread, as indices into `tasks`.
+    pub queues: Vec<Vec<usize>>,
 }
 
 impl Scheduler {
-    /// Creates a thread scheduler for this proof using a specific number of
-    /// workers. This scheduler is responsible for balancing the load (the
-    /// proof steps have different costs to be checked) aiming for minimum
-    /// amount of async overhead.
-    ///
-    /// Returns a scheduler itself and context usage info (a vector holding
-    /// how many threads are going to use each of the contexts. This vector maps
-    /// the contexts based in the subproof hashing value (i.e. `subproof_id`)
-    /// created in the parser).
+    /// Creates a thread scheduler for this proof using a specific number of workers, using the
+    /// default step weights. See [`Scheduler::with_weights`].
     pub fn new(num_workers: usize, proof: &Proof) -> (Self, Vec<usize>) {
-        // Initializes the control and result variables
-        let cmds = &proof.commands;
-        let mut loads = vec![Schedule::new(); num_workers];
-        let mut stack = vec![StackLevel::new(0, cmds, None)];
-        let mut pq = BinaryHeap::<AssignedLoad>::new();
-        let mut context_usage = vec![];
-        for i in 0..num_workers {
-            pq.push(AssignedLoad(0, i));
-        }
+        Self::with_weights(num_workers, proof, &StepWeights::default())
+    }
 
-        loop {
-            // Pop the finished subproofs
-            while !stack.is_empty() && {
-                let top = stack.last().unwrap();
-                top.id == top.cmds.len()
-            } {
-                for schedule_id in &stack.last().unwrap().used_by {
-                    let last = loads[*schedule_id].last().unwrap();
-                    // If it's an useless context insertion
-                    if last.0 < stack.len()
-                        && matches!(stack[last.0].cmds[last.1], ProofCommand::Subproof(_))
-                    {
-                        // Make sure this context usage count is reduced
-                        let subproof_id = match &stack[last.0].cmds[last.1] {
-                            ProofCommand::Subproof(s) => s.context_id,
-                            _ => unreachable!(),
-                        };
-                        context_usage[subproof_id] -= 1;
-
-                        loads[*schedule_id].pop();
+    /// Creates a thread scheduler for this proof using a specific number of workers.
+    ///
+    /// The proof is split into tasks of similar weight, following the premise dependencies: each
+    /// task contains whole subtrees of the premise graph, so that steps that share premises are
+    /// likely to be checked by the same thread. Subproofs are also kept whole in a single task,
+    /// unless they are too heavy, in which case their commands are split into several tasks that
+    /// share the subproof context. The tasks are then distributed between the workers, balancing
+    /// their estimated load.
+    ///
+    /// Returns a scheduler itself and context usage info (a vector holding how many tasks are going
+    /// to use each of the contexts. This vector maps the contexts based in the subproof hashing
+    /// value (i.e. `subproof_id`) created in the parser).
+    pub fn with_weights(
+        num_workers: usize,
+        proof: &Proof,
+        weights: &StepWeights,
+    ) -> (Self, Vec<usize>) {
+        let subproof_weights = compute_subproof_weights(&proof.commands, weights);
+        let weight = |command: &ProofCommand| match command {
+            ProofCommand::Subproof(s) => subproof_weights[s.context_id],
+            other => weights.weight(other),
+        };
+        let total: u64 = proof.commands.iter().map(weight).sum();
+        let target = if num_workers == 1 {
+            u64::MAX
+        } else {
+            (total / (num_workers as u64 * TASKS_PER_WORKER)).max(1)
+        };
+
+        // Each task is a list of units, each unit being the path of a command (the index of the
+        // command in each nested subproof). If the command is a subproof, the whole subproof is
+        // included in the task
+        let mut task_units: Vec<(u64, Vec<Vec<usize>>)> = Vec::new();
+        let mut pending: Vec<(Vec<usize>, &[ProofCommand])> = vec![(Vec::new(), &proof.commands)];
+        while let Some((prefix, commands)) = pending.pop() {
+            let depth = prefix.len();
+            let path = |i: usize| {
+                let mut path = prefix.clone();
+                path.push(i);
+                path
+            };
+
+            // We group the commands into subtrees of the premise graph by visiting them from last to
+            // first. Each command joins the group of one of the commands that use it as a premise,
+            // as long as that group is not already too heavy
+            let mut consumer_group: Vec<Option<usize>> = vec![None; commands.len()];
+            let mut groups: Vec<(u64, Vec<usize>)> = Vec::new();
+            for (i, command) in commands.iter().enumerate().rev() {
+                let w = weight(command);
+                if let ProofCommand::Subproof(s) = command {
+                    if w > target {
+                        pending.push((path(i), &s.commands));
+                        continue;
                     }
-                    // Creates a closing step for each schedule that used this subproof
-                    else {
-                        loads[*schedule_id].push((stack.len() - 1, usize::MAX));
+                }
+                let g = match consumer_group[i] {
+                    Some(g) if groups[g].0.saturating_add(w) <= target => g,
+                    _ => {
+                        groups.push((0, Vec::new()));
+                        groups.len() - 1
                     }
+                };
+                groups[g].0 = groups[g].0.saturating_add(w);
+                groups[g].1.push(i);
+                for p in premises_at_depth(command, depth) {
+                    consumer_group[p].get_or_insert(g);
                 }
-                stack.pop();
             }
-            if stack.is_empty() {
-                break;
+
+            // Small groups are then packed together into tasks
+            let mut current: (u64, Vec<Vec<usize>>) = (0, Vec::new());
+            for (w, group) in groups {
+                if !current.1.is_empty() && current.0.saturating_add(w) > target {
+                    task_units.push(std::mem::take(&mut current));
+                }
+                current.0 = current.0.saturating_add(w);
+                current.1.extend(group.into_iter().map(path));
             }
-            //
-            let AssignedLoad(mut load, load_index) = pq.pop().unwrap();
-            {
-                let top = stack.last().unwrap();
-                let step_weight = get_step_weight(&top.cmds[top.id]);
-                load = load
-                    .checked_add(step_weight)
-                    .expect("Weight balancing overflow!");
-                pq.push(AssignedLoad(load, load_index));
+            if !current.1.is_empty() {
+                task_units.push(current);
             }
+        }
 
-            let depth = stack.len() - 1;
-            let mut i = 1;
-            let initial_layer = {
-                let tmp = loads[load_index].last().unwrap_or(&(0, 0));
-                if tmp.1 == usize::MAX {
-                    tmp.0 - 1
-                } else {
-                    tmp.0
-                }
-            };
-            // If this step needs the context of the subproof oppening step
-            // but it was not assigned to this schedule yet
-            while initial_layer + i <= depth {
-                let subproof_oppening = stack[initial_layer + i].pre_req.unwrap();
-                let last_inserted = *loads[load_index].last().unwrap_or(&(usize::MAX, 0));
-
-                if last_inserted != subproof_oppening {
-                    loads[load_index].push(subproof_oppening);
-                    stack[subproof_oppening.0].used_by.insert(load_index);
-
-                    // Now this subproof is used by another schedule
-                    let subproof_id = match &stack[subproof_oppening.0].cmds[subproof_oppening.1] {
-                        ProofCommand::Subproof(s) => s.context_id,
-                        _ => unreachable!(),
-                    };
-                    context_usage[subproof_id] += 1;
+        let num_contexts = subproof_weights.len();
+        let mut context_usage = vec![0; num_contexts];
+        let mut tasks = Vec::with_capacity(task_units.len());
+        let mut task_weights = Vec::with_capacity(task_units.len());
+        for (w, mut units) in task_units {
+            units.sort_unstable();
+            tasks.push(build_schedule(&proof.commands, &units, &mut context_usage));
+            task_weights.push(w);
+        }
+
+        // The heaviest tasks are assigned first, each to the least loaded worker. Since each worker
+        // checks its queue in order, the heaviest tasks are also checked first
+        let mut order: Vec<usize> = (0..tasks.len()).collect();
+        order.sort_by_key(|&i| std::cmp::Reverse(task_weights[i]));
+        let mut queues = vec![Vec::new(); num_workers];
+        let mut pq: BinaryHeap<_> = (0..num_workers).map(|i| AssignedLoad(0, i)).collect();
+        for i in order {
+            let AssignedLoad(load, worker) = pq.pop().unwrap();
+            queues[worker].push(i);
+            pq.push(AssignedLoad(load.saturating_add(task_weights[i]), worker));
+        }
+        (Scheduler { tasks, queues }, context_usage)
+    }
+}
+
+/// The task queues of the workers, shared between threads. Each worker takes tasks from the front
+/// of its own queue, and when it is empty, steals tasks from the back of the queues of other
+/// workers.
+pub struct WorkQueues(Vec<Mutex<VecDeque<usize>>>);
+
+impl WorkQueues {
+    pub fn new(scheduler: &Scheduler) -> Self {
+        let queues = scheduler.queues.iter();
+        Self(
+            queues
+                .map(|q| Mutex::new(q.iter().copied().collect()))
+                .collect(),
+        )
+    }
+
+    /// Returns the next task to be checked by a worker, or `None` if there is no work left.
+    pub fn next_task(&self, worker: usize) -> Option<usize> {
+        if let Some(task) = self.0[worker].lock().unwrap().pop_front() {
+            return Some(task);
+        }
+        let n = self.0.len();
+        (1..n).find_map(|offset| self.0[(worker + offset) % n].lock().unwrap().pop_back())
+    }
+}
+
+/// Computes the weight of every subproof in the proof, indexed by their context id.
+fn compute_subproof_weights(commands: &[ProofCommand], weights: &StepWeights) -> Vec<u64> {
+    let mut result = Vec::new();
+    // Similarly to the checker, we traverse the proof iteratively to avoid stack overflows. Each
+    // frame holds the commands of a subproof, the position in it, its context id and its weight
+    let mut stack = vec![(commands, 0, usize::MAX, 0u64)];
+    while let Some((commands, i, _, _)) = stack.last_mut() {
+        let Some(command) = commands.get(*i) else {
+            let (_, _, context_id, w) = stack.pop().unwrap();
+            if let Some(parent) = stack.last_mut() {
+                parent.3 = parent.3.saturating_add(w);
+                if result.len() <= context_id {
+                    result.resize(context_id + 1, 0);
                 }
-                i += 1;
+                result[context_id] = w;
             }
+            continue;
+        };
+        *i += 1;
+        let w = weights.weight(command);
+        let top = stack.last_mut().unwrap();
+        top.3 = top.3.saturating_add(w);
+        if let ProofCommand::Subproof(s) = command {
+            stack.push((&s.commands, 0, s.context_id, 0));
+        }
+    }
+    result
+}
 
-            let top = stack.last_mut().unwrap();
-            // Assign a step to some Schedule
-            loads[load_index].push((depth, top.id));
-            top.used_by.insert(load_index);
-
-            // Go to next step
-            let last_id = top.id;
-            top.id += 1;
-            if let ProofCommand::Subproof(s) = &top.cmds[last_id] {
-                stack.push(StackLevel::new(0, &s.commands, Some((depth, last_id))));
-                stack.last_mut().unwrap().used_by.insert(load_index);
-                // First schedule using this subproof
-                context_usage.push(1);
+/// Returns the indices of the commands in the subproof at the given depth that are used as premises
+/// by a command in that subproof. For subproofs, this considers the premises of the commands
+/// directly inside them. This is only used to group commands, so it does not need to be exact.
+fn premises_at_depth(command: &ProofCommand, depth: usize) -> Vec<usize> {
+    let steps: Vec<&ProofStep> = match command {
+        ProofCommand::Assume { .. } => Vec::new(),
+        ProofCommand::Step(s) => vec![s],
+        ProofCommand::Subproof(s) => s
+            .commands
+            .iter()
+            .filter_map(|c| match c {
+                ProofCommand::Step(s) => Some(s),
+                _ => None,
+            })
+            .collect(),
+    };
+    steps
+        .into_iter()
+        .flat_map(|s| s.premises.iter().chain(&s.discharge))
+        .filter(|(d, _)| *d == depth)
+        .map(|&(_, i)| i)
+        .collect()
+}
+
+/// Builds the schedule of a task, given the sorted paths of its units. The subproofs that contain
+/// each unit are opened before it, and closed when the next unit is outside of them. Every time a
+/// subproof is opened, its entry in `context_usage` is incremented.
+fn build_schedule(
+    proof: &[ProofCommand],
+    units: &[Vec<usize>],
+    context_usage: &mut [usize],
+) -> Schedule {
+    fn subproof_at<'a>(proof: &'a [ProofCommand], path: &[usize]) -> &'a [ProofCommand] {
+        path.iter().fold(proof, |commands, &i| match &commands[i] {
+            ProofCommand::Subproof(s) => &s.commands,
+            _ => unreachable!(),
+        })
+    }
+
+    let mut schedule = Schedule::new();
+    let mut open: Vec<usize> = Vec::new();
+    for unit in units {
+        let (&index, parents) = unit.split_last().unwrap();
+        let common = open.iter().zip(parents).take_while(|(a, b)| a == b).count();
+        while open.len() > common {
+            open.pop();
+            schedule.push((open.len(), usize::MAX));
+        }
+        while open.len() < parents.len() {
+            let i = parents[open.len()];
+            if let ProofCommand::Subproof(s) = &subproof_at(proof, &open)[i] {
+                context_usage[s.context_id] += 1;
+            }
+            schedule.push((open.len(), i));
+            open.push(i);
+        }
+
+        let commands = subproof_at(proof, &open);
+        schedule.push((open.len(), index));
+        let ProofCommand::Subproof(s) = &commands[index] else {
+            continue;
+        };
+
+        // If the unit is a subproof, all of its commands are included. The subproof itself is
+        // left open, and is closed by the next unit
+        let base_depth = open.len() + 1;
+        open.push(index);
+        context_usage[s.context_id] += 1;
+        let mut stack: Vec<(&[ProofCommand], usize)> = vec![(&s.commands, 0)];
+        while let Some(&(commands, i)) = stack.last() {
+            if i == commands.len() {
+                stack.pop();
+                if !stack.is_empty() {
+                    schedule.push((base_depth + stack.len() - 1, usize::MAX));
+                }
+                continue;
+            }
+            stack.last_mut().unwrap().1 += 1;
+            schedule.push((base_depth + stack.len() - 1, i));
+            if let ProofCommand::Subproof(inner) = &commands[i] {
+                context_usage[inner.context_id] += 1;
+                stack.push((&inner.commands, 0));
             }
         }
-        (Scheduler { loads }, context_usage)
     }
+    while !open.is_empty() {
+        open.pop();
+        schedule.push((open.len(), usize::MAX));
+    }
+    schedule
 }
 
 /// Iterates through schedule steps
 pub struct ScheduleIter<'a> {
     proof_stack: Vec<&'a [ProofCommand]>,
+    subproof_indices: Vec<usize>,
     steps: &'a Vec<(usize, usize)>,
     step_id: usize,
 }
@@ -216,6 +410,7 @@ impl<'a> ScheduleIter<'a> {
     pub fn new(proof_commands: &'a [ProofCommand], steps: &'a Vec<(usize, usize)>) -> Self {
         Self {
             proof_stack: vec![proof_commands],
+            subproof_indices: Vec::new(),
             steps,
             step_id: 0,
         }
@@ -252,6 +447,16 @@ impl<'a> ScheduleIter<'a> {
     pub fn get_premise(&self, (depth, index): (usize, usize)) -> &ProofCommand {
         &self.proof_stack[depth][index]
     }
+
+    /// Returns the position of the most recently returned step in the proof, that is, the indices
+    /// of the subproofs that contain it, followed by its index in the inner-most one. Sorting
+    /// positions puts the steps in the order they appear in the proof.
+    pub fn position(&self) -> Vec<usize> {
+        let (_, index) = self.steps[self.step_id - 1];
+        let mut position = self.subproof_indices.clone();
+        position.push(index);
+        position
+    }
 }
 
 impl<'a> Iterator for ScheduleIter<'a> {
@@ -266,6 +471,7 @@ impl<'a> Iterator for ScheduleIter<'a> {
         // If current step is an closing subproof step
         while let (_, usize::MAX) = self.steps[self.step_id] {
             self.proof_stack.pop();
+            self.subproof_indices.pop();
             self.step_id += 1;
             // If reached the last closing step of the whole proof
             if self.step_id == self.steps.len() {
@@ -280,6 +486,7 @@ impl<'a> Iterator for ScheduleIter<'a> {
         // Opens a new subproof
         if let ProofCommand::Subproof(subproof) = command {
             self.proof_stack.push(&subproof.commands);
+            self.subproof_indices.push(cur_step.1);
         }
         Some(command)
     }
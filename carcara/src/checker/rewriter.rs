@@ -0,0 +1,302 @@
+//! A normalizing rewriter, used to check `all_simplify` steps.
+//!
+//! The rewriter is built from the transformations of the Alethe simplification rules, like
+//! `ite_simplify` and `sum_simplify`. To check an `all_simplify` step, both sides of its equality
+//! are rewritten bottom-up, applying the enabled transformations until none of them matches, and
+//! the resulting normal forms are compared.
+
+use super::rules::simplification::{
+    flatten_and_or, simplify_and_or, simplify_bool, simplify_comp, simplify_div, simplify_eq,
+    simplify_equiv, simplify_implies, simplify_ite, simplify_minus, simplify_not, simplify_qnt,
+    simplify_sum_prod,
+};
+use crate::{ast::*, utils::DedupIterator};
+use std::collections::HashMap;
+
+/// A transformation that the rewriter may apply. Each one corresponds to one of the Alethe
+/// simplification rules, and is named after it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Rewrite {
+    IteSimplify,
+    EqSimplify,
+    AndSimplify,
+    OrSimplify,
+    NotSimplify,
+    ImpliesSimplify,
+    EquivSimplify,
+    BoolSimplify,
+    QntSimplify,
+    DivSimplify,
+    ProdSimplify,
+    MinusSimplify,
+    SumSimplify,
+    CompSimplify,
+    AcSimp,
+}
+
+impl_str_conversion_traits!(Rewrite {
+    IteSimplify: "ite_simplify",
+    EqSimplify: "eq_simplify",
+    AndSimplify: "and_simplify",
+    OrSimplify: "or_simplify",
+    NotSimplify: "not_simplify",
+    ImpliesSimplify: "implies_simplify",
+    EquivSimplify: "equiv_simplify",
+    BoolSimplify: "bool_simplify",
+    QntSimplify: "qnt_simplify",
+    DivSimplify: "div_simplify",
+    ProdSimplify: "prod_simplify",
+    MinusSimplify: "minus_simplify",
+    SumSimplify: "sum_simplify",
+    CompSimplify: "comp_simplify",
+    AcSimp: "ac_simp",
+});
+
+impl Rewrite {
+    pub const ALL: [Rewrite; 15] = [
+        Rewrite::IteSimplify,
+        Rewrite::EqSimplify,
+        Rewrite::AndSimplify,
+        Rewrite::OrSimplify,
+        Rewrite::NotSimplify,
+        Rewrite::ImpliesSimplify,
+        Rewrite::EquivSimplify,
+        Rewrite::BoolSimplify,
+        Rewrite::QntSimplify,
+        Rewrite::DivSimplify,
+        Rewrite::ProdSimplify,
+        Rewrite::MinusSimplify,
+        Rewrite::SumSimplify,
+        Rewrite::CompSimplify,
+        Rewrite::AcSimp,
+    ];
+
+    /// Applies this transformation to the root of the term. Returns `None` if it doesn't match.
+    fn apply(self, pool: &mut dyn TermPool, term: &Rc<Term>) -> Option<Rc<Term>> {
+        match self {
+            Rewrite::IteSimplify => simplify_ite(term, pool),
+            Rewrite::EqSimplify => simplify_eq(term, pool),
+            Rewrite::AndSimplify => simplify_and_or(term, pool, Operator::And),
+            Rewrite::OrSimplify => simplify_and_or(term, pool, Operator::Or),
+            Rewrite::NotSimplify => simplify_not(term, pool),
+            Rewrite::ImpliesSimplify => simplify_implies(term, pool),
+            Rewrite::EquivSimplify => simplify_equiv(term, pool),
+            Rewrite::BoolSimplify => simplify_bool(term, pool),
+            Rewrite::QntSimplify => simplify_qnt(term),
+            Rewrite::DivSimplify => simplify_div(term, pool),
+            Rewrite::ProdSimplify => simplify_sum_prod(term, pool, Operator::Mult),
+            Rewrite::MinusSimplify => simplify_minus(term, pool),
+            Rewrite::SumSimplify => simplify_sum_prod(term, pool, Operator::Add),
+            Rewrite::CompSimplify => simplify_comp(term, pool),
+            Rewrite::AcSimp => flatten_and_or(term, pool),
+        }
+    }
+}
+
+/// The maximum number of transformations applied while normalizing a term. This guards against
+/// sets of transformations that don't terminate.
+const MAX_REWRITES: usize = 100_000;
+
+/// A rewriter that normalizes terms using a configurable set of transformations.
+#[derive(Debug, Clone)]
+pub struct Rewriter {
+    rewrites: Vec<Rewrite>,
+}
+
+impl Default for Rewriter {
+    fn default() -> Self {
+        Self::new(Rewrite::ALL)
+    }
+}
+
+impl Rewriter {
+    /// Creates a rewriter that uses the given transformations. At each term, they are tried in
+    /// the order they were given.
+    pub fn new(rewrites: impl IntoIterator<Item = Rewrite>) -> Self {
+        Self {
+            rewrites: rewrites.into_iter().dedup().collect(),
+        }
+    }
+
+    pub fn rewrites(&self) -> &[Rewrite] {
+        &self.rewrites
+    }
+
+    /// Rewrites a term to its normal form. Returns `None` if the rewriter gave up.
+    pub fn normalize(&self, pool: &mut dyn TermPool, term: &Rc<Term>) -> Option<Rc<Term>> {
+        Normalizer {
+            rewrites: &self.rewrites,
+            pool,
+            cache: HashMap::new(),
+            budget: MAX_REWRITES,
+        }
+        .normalize(term)
+    }
+
+    /// Returns `true` if the rewriter can show that the conclusion of an `all_simplify` step holds,
+    /// that is, if both sides of its equality have the same normal form.
+    pub fn proves_all_simplify(&self, pool: &mut dyn TermPool, conclusion: &[Rc<Term>]) -> bool {
+        let [conclusion] = conclusion else {
+            return false;
+        };
+        let Some((left, right)) = match_term!((= l r) = conclusion) else {
+            return false;
+        };
+        let mut normalizer = Normalizer {
+            rewrites: &self.rewrites,
+            pool,
+            cache: HashMap::new(),
+            budget: MAX_REWRITES,
+        };
+        let (Some(left), Some(right)) = (normalizer.normalize(left), normalizer.normalize(right))
+        else {
+            return false;
+        };
+        if Polyeq::new().mod_reordering(true).eq(&left, &right) {
+            return true;
+        }
+
+        // The normal forms may still be equivalent if the transformations can reduce the equality
+        // between them to `true`, for example, if the two sides are numerical constants
+        let equality = build_term!(normalizer.pool, (= {left} {right}));
+        normalizer
+            .normalize(&equality)
+            .is_some_and(|t| t.is_bool_true())
+    }
+}
+
+struct Normalizer<'a> {
+    rewrites: &'a [Rewrite],
+    pool: &'a mut dyn TermPool,
+    cache: HashMap<Rc<Term>, Rc<Term>>,
+    budget: usize,
+}
+
+impl Normalizer<'_> {
+    fn normalize(&mut self, term: &Rc<Term>) -> Option<Rc<Term>> {
+        if let Some(t) = self.cache.get(term) {
+            return Some(t.clone());
+        }
+        let mut current = self.normalize_children(term)?;
+        'outer: loop {
+            for &rewrite in self.rewrites {
+                if let Some(next) = rewrite.apply(self.pool, &current) {
+                    if next == current {
+                        continue;
+                    }
+                    self.budget = self.budget.checked_sub(1)?;
+
+                    // The result of a transformation may contain new terms that are not yet
+                    // normalized, like the negation introduced by `comp_simplify`
+                    current = self.normalize_children(&next)?;
+                    continue 'outer;
+                }
+            }
+            break;
+        }
+        self.cache.insert(term.clone(), current.clone());
+        Some(current)
+    }
+
+    fn normalize_children(&mut self, term: &Rc<Term>) -> Option<Rc<Term>> {
+        let result = match term.as_ref() {
+            Term::Op(op, args) => Term::Op(*op, self.normalize_all(args)?),
+            Term::App(func, args) => Term::App(func.clone(), self.normalize_all(args)?),
+            Term::Binder(binder, bindings, inner) => {
+                Term::Binder(*binder, bindings.clone(), self.normalize(inner)?)
+            }
+            Term::Let(bindings, inner) => Term::Let(bindings.clone(), self.normalize(inner)?),
+            _ => return Some(term.clone()),
+        };
+        Some(self.pool.add(result))
+    }
+
+    fn normalize_all(&mut self, terms: &[Rc<Term>]) -> Option<Vec<Rc<Term>>> {
+        terms.iter().map(|t| self.normalize(t)).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{checker, parser, parser::tests::parse_terms};
+
+    #[test]
+    fn test_normalize() {
+        let definitions = "
+            (declare-fun p () Bool)
+            (declare-fun q () Bool)
+            (declare-fun x () Int)
+            (declare-fun y () Real)
+        ";
+        let cases = [
+            ("(not (not (and p true)))", "p"),
+            ("(ite (not p) x (+ x 0))", "x"),
+            ("(and p (and q p) (not (not q)))", "(and p q)"),
+            ("(or p (not p) q)", "true"),
+            ("(+ 1 x 2 (* 3 0 x))", "(+ 3 x)"),
+            ("(* 2.0 y 0.5)", "y"),
+            ("(- 5 (- 2 3))", "6"),
+            ("(- (- x))", "x"),
+            ("(div (- 7) 2)", "(- 4)"),
+            ("(/ y 1.0)", "y"),
+            ("(> x (+ x 0))", "false"),
+            ("(>= 2 1)", "true"),
+            ("(=> p (=> q p))", "(=> (and p q) p)"),
+            ("(forall ((z Int)) (= z z))", "true"),
+            ("(= (- 2 1) 1)", "true"),
+        ];
+        let mut pool = PrimitivePool::new();
+        let rewriter = Rewriter::default();
+        for (term, expected) in cases {
+            let [term, expected] = parse_terms(&mut pool, definitions, [term, expected]);
+            let got = rewriter.normalize(&mut pool, &term).unwrap();
+            assert_eq!(expected, got, "normalizing {}", term);
+        }
+    }
+
+    #[test]
+    fn test_all_simplify() {
+        // Returns `Some(is_holey)` if the proof checked
+        fn check(rewriter: Option<Rewriter>, conclusion: &str) -> Option<bool> {
+            let problem = format!(
+                "(declare-fun p () Bool)
+                 (declare-fun q () Bool)
+                 (declare-fun x () Int)
+                 (assert (not {conclusion}))"
+            );
+            let proof = format!(
+                "(assume h1 (not {conclusion}))
+                 (step t1 (cl {conclusion}) :rule all_simplify)
+                 (step t2 (cl) :rule resolution :premises (h1 t1))"
+            );
+            let (problem, proof, mut pool) =
+                parser::parse_instance(problem.as_bytes(), proof.as_bytes(), parser::Config::new())
+                    .unwrap();
+            let mut config = checker::Config::new();
+            config.all_simplify_rewriter = rewriter;
+            checker::ProofChecker::new(&mut pool, config)
+                .check(&problem, &proof)
+                .ok()
+        }
+
+        let rewriter = || Some(Rewriter::default());
+        assert_eq!(
+            check(rewriter(), "(= (ite (< x 0) p p) (not (not p)))"),
+            Some(false)
+        );
+        assert_eq!(check(rewriter(), "(= (+ x 1 1) (+ 2 x))"), Some(false));
+        assert_eq!(
+            check(rewriter(), "(= (=> p false) (not (or p false)))"),
+            Some(false)
+        );
+
+        // Steps that the rewriter can't prove are still holes
+        assert_eq!(check(rewriter(), "(= (and p q) (and q p))"), Some(true));
+        let only_ite = Some(Rewriter::new([Rewrite::IteSimplify]));
+        assert_eq!(check(only_ite, "(= (+ x 1 1) (+ 2 x))"), Some(true));
+
+        // Without a rewriter, `all_simplify` is an unknown rule
+        assert_eq!(check(None, "(= (+ x 1 1) (+ 2 x))"), None);
+    }
+}
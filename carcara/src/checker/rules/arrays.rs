@@ -0,0 +1,69 @@
+//! Rules for the theory of arrays.
+
+use super::{assert_clause_len, assert_eq, RuleArgs, RuleResult};
+use crate::{
+    ast::*,
+    checker::error::{ArrayError, CheckerError},
+};
+
+pub fn array_read_over_write(RuleArgs { conclusion, .. }: RuleArgs) -> RuleResult {
+    assert_clause_len(conclusion, 2)?;
+
+    let (i, j) = match_term_err!((= i j) = &conclusion[0])?;
+    let (((a, i_2, _), j_2), (a_2, j_3)) =
+        match_term_err!((= (select (store a i e) j) (select a j)) = &conclusion[1])?;
+
+    assert_eq(i, i_2)?;
+    assert_eq(j, j_2)?;
+    assert_eq(j, j_3)?;
+    assert_eq(a, a_2)
+}
+
+pub fn array_read_over_write_1(RuleArgs { conclusion, .. }: RuleArgs) -> RuleResult {
+    assert_clause_len(conclusion, 1)?;
+
+    let (((_, i, e), j), result) =
+        match_term_err!((= (select (store a i e) j) e) = &conclusion[0])?;
+    assert_eq(i, j)?;
+    assert_eq(e, result)
+}
+
+pub fn array_const(RuleArgs { conclusion, .. }: RuleArgs) -> RuleResult {
+    assert_clause_len(conclusion, 1)?;
+
+    let ((array, _), v) = match_term_err!((= (select k i) v) = &conclusion[0])?;
+    match array.as_ref() {
+        Term::ParamOp {
+            op: ParamOperator::ArrayConst, args, ..
+        } => assert_eq(&args[0], v),
+        _ => Err(ArrayError::NotAConstantArray(array.clone()).into()),
+    }
+}
+
+pub fn array_ext(RuleArgs { conclusion, .. }: RuleArgs) -> RuleResult {
+    assert_clause_len(conclusion, 2)?;
+
+    let (a, b) = match_term_err!((= a b) = &conclusion[0])?;
+    let ((a_2, k), (b_2, k_2)) =
+        match_term_err!((not (= (select a k) (select b k))) = &conclusion[1])?;
+    assert_eq(a, a_2)?;
+    assert_eq(b, b_2)?;
+    assert_eq(k, k_2)?;
+
+    // The index `k` must be the witness of the arrays' disequality, that is, the term
+    // `(choice ((x I)) (not (= (select a x) (select b x))))`
+    let invalid_witness = || -> CheckerError {
+        ArrayError::InvalidExtWitness(k.clone(), a.clone(), b.clone()).into()
+    };
+    let (bindings, ((a_3, x), (b_3, x_2))) =
+        match_term!((choice ... (not (= (select a x) (select b x)))) = k)
+            .ok_or_else(invalid_witness)?;
+    let [(var, _)] = bindings.as_ref() else {
+        return Err(invalid_witness());
+    };
+    let is_bound_var = |t: &Rc<Term>| t.as_var() == Some(var.as_str());
+    if a_3 != a || b_3 != b || !is_bound_var(x) || !is_bound_var(x_2) {
+        return Err(invalid_witness());
+    }
+    Ok(())
+}
@@ -1,6 +1,13 @@
+//! Rules for bit-blasting bit-vector terms.
+//!
+//! A bit-blasted term is represented as a `@bbterm` application whose arguments are the boolean
+//! formulas for each bit of the bit-vector, starting from the least significant bit. Each rule
+//! recomputes the expected circuit for the operation from the bits of its arguments, and checks
+//! that it is syntactically equal to the one given in the conclusion.
+
 use crate::{
     ast::{pool::TermPool, Operator, ParamOperator, Rc, Sort, Term},
-    checker::rules::assert_clause_len,
+    checker::{error::CheckerError, rules::assert_clause_len},
 };
 
 use super::{assert_eq, RuleArgs, RuleResult};
@@ -23,49 +30,97 @@ fn build_term_vec(term: &Rc<Term>, size: usize, pool: &mut dyn TermPool) -> Vec<
     term
 }
 
-pub fn ult(RuleArgs { conclusion, pool, .. }: RuleArgs) -> RuleResult {
-    assert_clause_len(conclusion, 1)?;
-    let ((x, y), res) = match_term_err!((= (bvult x y) res) = &conclusion[0])?;
-
-    let Sort::BitVec(size) = pool.sort(x).as_sort().cloned().unwrap() else {
-        unreachable!();
-    };
-
-    let size = size.to_usize().unwrap();
+/// Returns the width of a bit-vector term.
+fn bv_width(pool: &mut dyn TermPool, term: &Rc<Term>) -> Result<usize, CheckerError> {
+    match pool.sort(term).as_sort() {
+        Some(Sort::BitVec(size)) => size
+            .to_usize()
+            .ok_or_else(|| CheckerError::ExpectedBvTerm(term.clone())),
+        _ => Err(CheckerError::ExpectedBvTerm(term.clone())),
+    }
+}
 
-    let x = build_term_vec(x, size, pool);
-    let y = build_term_vec(y, size, pool);
+/// Returns the bits of a bit-vector term, starting from the least significant bit.
+fn bits_of(pool: &mut dyn TermPool, term: &Rc<Term>) -> Result<Vec<Rc<Term>>, CheckerError> {
+    let size = bv_width(pool, term)?;
+    Ok(build_term_vec(term, size, pool))
+}
 
-    let mut expected_res = build_term!(pool, (and (not {x[0].clone()}) {y[0].clone()}));
+/// Checks that `res` is the `@bbterm` whose arguments are the bits in `expected`.
+fn assert_bits(pool: &mut dyn TermPool, expected: Vec<Rc<Term>>, res: &Rc<Term>) -> RuleResult {
+    let expected = pool.add(Term::Op(Operator::BvBbTerm, expected));
+    assert_eq(&expected, res)
+}
 
-    for i in 1..size {
-        let new_res = build_term!(
-            pool,
-            (or (and (= {x[i].clone()} {y[i].clone()}) {expected_res.clone()})
-                (and (not {x[i].clone()}) {y[i].clone()}))
-        );
-        expected_res = new_res;
+/// Builds the conjunction of `terms`. If there is only one term, it is returned as is.
+fn and_all(pool: &mut dyn TermPool, mut terms: Vec<Rc<Term>>) -> Rc<Term> {
+    if terms.len() == 1 {
+        terms.pop().unwrap()
+    } else {
+        pool.add(Term::Op(Operator::And, terms))
     }
+}
 
-    assert_eq(&expected_res, res)
+/// Builds the disjunction of `terms`. If there is only one term, it is returned as is.
+fn or_all(pool: &mut dyn TermPool, mut terms: Vec<Rc<Term>>) -> Rc<Term> {
+    if terms.len() == 1 {
+        terms.pop().unwrap()
+    } else {
+        pool.add(Term::Op(Operator::Or, terms))
+    }
 }
 
-pub fn add(RuleArgs { conclusion, pool, .. }: RuleArgs) -> RuleResult {
-    assert_clause_len(conclusion, 1)?;
-    let ((x, y), res) = match_term_err!((= (bvadd x y) res) = &conclusion[0])?;
+/// Applies the boolean operator `op` to the corresponding bits of all `args`.
+fn bitwise(pool: &mut dyn TermPool, op: Operator, args: &[Vec<Rc<Term>>]) -> Vec<Rc<Term>> {
+    (0..args[0].len())
+        .map(|i| {
+            let bits = args.iter().map(|x| x[i].clone()).collect();
+            pool.add(Term::Op(op, bits))
+        })
+        .collect()
+}
 
-    let Sort::BitVec(size) = pool.sort(x).as_sort().cloned().unwrap() else {
-        unreachable!();
-    };
+fn negate_bits(pool: &mut dyn TermPool, x: &[Rc<Term>]) -> Vec<Rc<Term>> {
+    x.iter()
+        .map(|b| build_term!(pool, (not {b.clone()})))
+        .collect()
+}
 
-    let size = size.to_usize().unwrap();
+/// Selects, bit by bit, between `x` and `y` according to `cond`.
+fn ite_bits(
+    pool: &mut dyn TermPool,
+    cond: &Rc<Term>,
+    x: &[Rc<Term>],
+    y: &[Rc<Term>],
+) -> Vec<Rc<Term>> {
+    x.iter()
+        .zip(y)
+        .map(|(a, b)| build_term!(pool, (ite {cond.clone()} {a.clone()} {b.clone()})))
+        .collect()
+}
 
-    let x = build_term_vec(x, size, pool);
-    let y = build_term_vec(y, size, pool);
+/// Builds the conjunction of the equalities between the corresponding bits of `x` and `y`.
+fn equal_bits(pool: &mut dyn TermPool, x: &[Rc<Term>], y: &[Rc<Term>]) -> Rc<Term> {
+    let eqs = x
+        .iter()
+        .zip(y)
+        .map(|(a, b)| build_term!(pool, (= {a.clone()} {b.clone()})))
+        .collect();
+    and_all(pool, eqs)
+}
 
-    let mut carries = vec![pool.bool_false()];
+/// Builds a ripple-carry adder for `x` and `y`, with the given carry in. Returns the bits of the
+/// sum and the carry out.
+fn ripple_carry_adder(
+    pool: &mut dyn TermPool,
+    x: &[Rc<Term>],
+    y: &[Rc<Term>],
+    carry_in: Rc<Term>,
+) -> (Vec<Rc<Term>>, Rc<Term>) {
+    let size = x.len();
+    let mut carries = vec![carry_in];
 
-    for i in 1..size {
+    for i in 1..=size {
         let carry_i = build_term!(
           pool,
           (or (and {x[i - 1].clone()} {y[i - 1].clone()}) (and (xor {x[i - 1].clone()} {y[i - 1].clone()}) {carries[i - 1].clone()}))
@@ -73,7 +128,7 @@ pub fn add(RuleArgs { conclusion, pool, .. }: RuleArgs) -> RuleResult {
         carries.push(carry_i);
     }
 
-    let res_args: Vec<_> = (0..size)
+    let sum = (0..size)
         .map(|i| {
             build_term!(
               pool,
@@ -82,23 +137,578 @@ pub fn add(RuleArgs { conclusion, pool, .. }: RuleArgs) -> RuleResult {
         })
         .collect();
 
-    let expected_res = pool.add(Term::Op(Operator::BvBbTerm, res_args));
+    (sum, carries.pop().unwrap())
+}
+
+fn adder(pool: &mut dyn TermPool, x: &[Rc<Term>], y: &[Rc<Term>]) -> Vec<Rc<Term>> {
+    let carry_in = pool.bool_false();
+    ripple_carry_adder(pool, x, y, carry_in).0
+}
+
+/// Builds the two's complement negation of `x`, as `(not x) + 0` with a carry in of `true`.
+fn negation(pool: &mut dyn TermPool, x: &[Rc<Term>]) -> Vec<Rc<Term>> {
+    let not_x = negate_bits(pool, x);
+    let zeros = vec![pool.bool_false(); x.len()];
+    let carry_in = pool.bool_true();
+    ripple_carry_adder(pool, &not_x, &zeros, carry_in).0
+}
+
+/// Builds the subtraction of `y` from `x`, as `x + (not y)` with a carry in of `true`.
+fn subtractor(pool: &mut dyn TermPool, x: &[Rc<Term>], y: &[Rc<Term>]) -> Vec<Rc<Term>> {
+    let not_y = negate_bits(pool, y);
+    let carry_in = pool.bool_true();
+    ripple_carry_adder(pool, x, &not_y, carry_in).0
+}
+
+/// Builds a shift-and-add multiplier. Each partial product `x * y[k]` is added to the bits of the
+/// result starting at bit `k`, and bits past the width of the result are discarded.
+fn multiplier(pool: &mut dyn TermPool, x: &[Rc<Term>], y: &[Rc<Term>]) -> Vec<Rc<Term>> {
+    let size = x.len();
+    let mut res: Vec<_> = x
+        .iter()
+        .map(|b| build_term!(pool, (and {b.clone()} {y[0].clone()})))
+        .collect();
+
+    for k in 1..size {
+        let mut carry = pool.bool_false();
+        for j in 0..size - k {
+            let r = res[j + k].clone();
+            let p = build_term!(pool, (and {x[j].clone()} {y[k].clone()}));
+            res[j + k] = build_term!(pool, (xor (xor {r.clone()} {p.clone()}) {carry.clone()}));
+            carry = build_term!(
+                pool,
+                (or (and {r.clone()} {p.clone()}) (and (xor {r} {p}) {carry}))
+            );
+        }
+    }
+    res
+}
+
+/// Builds a restoring divider for `x` and `y`, returning the bits of the quotient and of the
+/// remainder. At each step, from the most significant bit of `x` down, the partial remainder is
+/// shifted left, the next bit of `x` is shifted in, and `y` is subtracted from it if that doesn't
+/// underflow. Dividing by zero results in a quotient of all ones and a remainder equal to `x`, as
+/// required by the SMT-LIB semantics.
+fn divider(
+    pool: &mut dyn TermPool,
+    x: &[Rc<Term>],
+    y: &[Rc<Term>],
+) -> (Vec<Rc<Term>>, Vec<Rc<Term>>) {
+    let size = x.len();
+    let mut quotient = vec![pool.bool_false(); size];
+    let mut remainder = vec![pool.bool_false(); size];
+
+    // The partial remainder needs an extra bit, since shifting it may overflow
+    let mut not_y = negate_bits(pool, y);
+    not_y.push(pool.bool_true());
+
+    for i in (0..size).rev() {
+        let shifted: Vec<_> = std::iter::once(x[i].clone())
+            .chain(remainder.iter().cloned())
+            .collect();
+        let carry_in = pool.bool_true();
+        let (diff, no_underflow) = ripple_carry_adder(pool, &shifted, &not_y, carry_in);
+        remainder = ite_bits(pool, &no_underflow, &diff[..size], &shifted[..size]);
+        quotient[i] = no_underflow;
+    }
+    (quotient, remainder)
+}
+
+/// Returns the absolute value of a signed bit-vector `x`.
+fn absolute_value(pool: &mut dyn TermPool, x: &[Rc<Term>]) -> Vec<Rc<Term>> {
+    let neg_x = negation(pool, x);
+    ite_bits(pool, x.last().unwrap(), &neg_x, x)
+}
 
+fn signed_division(pool: &mut dyn TermPool, x: &[Rc<Term>], y: &[Rc<Term>]) -> Vec<Rc<Term>> {
+    let (abs_x, abs_y) = (absolute_value(pool, x), absolute_value(pool, y));
+    let (quotient, _) = divider(pool, &abs_x, &abs_y);
+    let neg_quotient = negation(pool, &quotient);
+    let (msb_x, msb_y) = (x.last().unwrap().clone(), y.last().unwrap().clone());
+    let signs_differ = build_term!(pool, (xor {msb_x} {msb_y}));
+    ite_bits(pool, &signs_differ, &neg_quotient, &quotient)
+}
+
+fn signed_remainder(pool: &mut dyn TermPool, x: &[Rc<Term>], y: &[Rc<Term>]) -> Vec<Rc<Term>> {
+    let (abs_x, abs_y) = (absolute_value(pool, x), absolute_value(pool, y));
+    let (_, remainder) = divider(pool, &abs_x, &abs_y);
+    let neg_remainder = negation(pool, &remainder);
+    ite_bits(pool, x.last().unwrap(), &neg_remainder, &remainder)
+}
+
+/// Builds the signed modulo of `x` and `y`, whose result follows the sign of `y`.
+fn signed_modulo(pool: &mut dyn TermPool, x: &[Rc<Term>], y: &[Rc<Term>]) -> Vec<Rc<Term>> {
+    let (abs_x, abs_y) = (absolute_value(pool, x), absolute_value(pool, y));
+    let (_, u) = divider(pool, &abs_x, &abs_y);
+    let neg_u = negation(pool, &u);
+    let neg_u_plus_y = adder(pool, &neg_u, y);
+    let u_plus_y = adder(pool, &u, y);
+
+    let (msb_x, msb_y) = (x.last().unwrap(), y.last().unwrap());
+    let if_x_negative = ite_bits(pool, msb_y, &neg_u, &neg_u_plus_y);
+    let if_x_positive = ite_bits(pool, msb_y, &u_plus_y, &u);
+    let nonzero_result = ite_bits(pool, msb_x, &if_x_negative, &if_x_positive);
+
+    let u_is_zero = {
+        let not_u = negate_bits(pool, &u);
+        and_all(pool, not_u)
+    };
+    ite_bits(pool, &u_is_zero, &u, &nonzero_result)
+}
+
+#[derive(Clone, Copy)]
+enum ShiftKind {
+    Left,
+    LogicalRight,
+    ArithmeticRight,
+}
+
+/// Builds a barrel shifter. In stage `s`, the partial result is shifted by `2^s` if bit `s` of
+/// `y` is set. If any of the remaining bits of `y` is set, the shift amount is at least the width
+/// of `x`, and every bit of the result is the fill bit.
+fn barrel_shifter(
+    pool: &mut dyn TermPool,
+    kind: ShiftKind,
+    x: &[Rc<Term>],
+    y: &[Rc<Term>],
+) -> Vec<Rc<Term>> {
+    let size = x.len();
+    let fill = match kind {
+        ShiftKind::Left | ShiftKind::LogicalRight => pool.bool_false(),
+        ShiftKind::ArithmeticRight => x[size - 1].clone(),
+    };
+
+    let mut res = x.to_vec();
+    let mut stage = 0;
+    while (1 << stage) < size {
+        let amount = 1 << stage;
+        res = (0..size)
+            .map(|i| {
+                let shifted = match kind {
+                    ShiftKind::Left if i >= amount => res[i - amount].clone(),
+                    ShiftKind::LogicalRight | ShiftKind::ArithmeticRight if i + amount < size => {
+                        res[i + amount].clone()
+                    }
+                    _ => fill.clone(),
+                };
+                build_term!(pool, (ite {y[stage].clone()} {shifted} {res[i].clone()}))
+            })
+            .collect();
+        stage += 1;
+    }
+
+    let overflow = or_all(pool, y[stage..].to_vec());
+    res.iter()
+        .map(|b| build_term!(pool, (ite {overflow.clone()} {fill.clone()} {b.clone()})))
+        .collect()
+}
+
+/// Builds a comparator for `x` and `y`, starting from the least significant bit. If `signed` is
+/// true, the roles of `x` and `y` are swapped for the most significant bit, which is the sign bit.
+fn comparator(
+    pool: &mut dyn TermPool,
+    x: &[Rc<Term>],
+    y: &[Rc<Term>],
+    strict: bool,
+    signed: bool,
+) -> Rc<Term> {
+    let size = x.len();
+    let less_than = |pool: &mut dyn TermPool, i: usize| {
+        let (a, b) = (x[i].clone(), y[i].clone());
+        if signed && i == size - 1 {
+            build_term!(pool, (and { a }(not { b })))
+        } else {
+            build_term!(pool, (and (not {a}) {b}))
+        }
+    };
+
+    let mut res = if strict {
+        less_than(pool, 0)
+    } else if signed && size == 1 {
+        build_term!(pool, (or {x[0].clone()} (not {y[0].clone()})))
+    } else {
+        build_term!(pool, (or (not {x[0].clone()}) {y[0].clone()}))
+    };
+
+    for i in 1..size {
+        let lt = less_than(pool, i);
+        res = build_term!(
+            pool,
+            (or (and (= {x[i].clone()} {y[i].clone()}) {res}) {lt})
+        );
+    }
+    res
+}
+
+pub fn ult(RuleArgs { conclusion, pool, .. }: RuleArgs) -> RuleResult {
+    assert_clause_len(conclusion, 1)?;
+    let ((x, y), res) = match_term_err!((= (bvult x y) res) = &conclusion[0])?;
+    let (x, y) = (bits_of(pool, x)?, bits_of(pool, y)?);
+    let expected_res = comparator(pool, &x, &y, true, false);
+    assert_eq(&expected_res, res)
+}
+
+pub fn ule(RuleArgs { conclusion, pool, .. }: RuleArgs) -> RuleResult {
+    assert_clause_len(conclusion, 1)?;
+    let ((x, y), res) = match_term_err!((= (bvule x y) res) = &conclusion[0])?;
+    let (x, y) = (bits_of(pool, x)?, bits_of(pool, y)?);
+    let expected_res = comparator(pool, &x, &y, false, false);
+    assert_eq(&expected_res, res)
+}
+
+pub fn ugt(RuleArgs { conclusion, pool, .. }: RuleArgs) -> RuleResult {
+    assert_clause_len(conclusion, 1)?;
+    let ((x, y), res) = match_term_err!((= (bvugt x y) res) = &conclusion[0])?;
+    let (x, y) = (bits_of(pool, x)?, bits_of(pool, y)?);
+    let expected_res = comparator(pool, &y, &x, true, false);
+    assert_eq(&expected_res, res)
+}
+
+pub fn uge(RuleArgs { conclusion, pool, .. }: RuleArgs) -> RuleResult {
+    assert_clause_len(conclusion, 1)?;
+    let ((x, y), res) = match_term_err!((= (bvuge x y) res) = &conclusion[0])?;
+    let (x, y) = (bits_of(pool, x)?, bits_of(pool, y)?);
+    let expected_res = comparator(pool, &y, &x, false, false);
     assert_eq(&expected_res, res)
 }
 
+pub fn slt(RuleArgs { conclusion, pool, .. }: RuleArgs) -> RuleResult {
+    assert_clause_len(conclusion, 1)?;
+    let ((x, y), res) = match_term_err!((= (bvslt x y) res) = &conclusion[0])?;
+    let (x, y) = (bits_of(pool, x)?, bits_of(pool, y)?);
+    let expected_res = comparator(pool, &x, &y, true, true);
+    assert_eq(&expected_res, res)
+}
+
+pub fn sle(RuleArgs { conclusion, pool, .. }: RuleArgs) -> RuleResult {
+    assert_clause_len(conclusion, 1)?;
+    let ((x, y), res) = match_term_err!((= (bvsle x y) res) = &conclusion[0])?;
+    let (x, y) = (bits_of(pool, x)?, bits_of(pool, y)?);
+    let expected_res = comparator(pool, &x, &y, false, true);
+    assert_eq(&expected_res, res)
+}
+
+pub fn sgt(RuleArgs { conclusion, pool, .. }: RuleArgs) -> RuleResult {
+    assert_clause_len(conclusion, 1)?;
+    let ((x, y), res) = match_term_err!((= (bvsgt x y) res) = &conclusion[0])?;
+    let (x, y) = (bits_of(pool, x)?, bits_of(pool, y)?);
+    let expected_res = comparator(pool, &y, &x, true, true);
+    assert_eq(&expected_res, res)
+}
+
+pub fn sge(RuleArgs { conclusion, pool, .. }: RuleArgs) -> RuleResult {
+    assert_clause_len(conclusion, 1)?;
+    let ((x, y), res) = match_term_err!((= (bvsge x y) res) = &conclusion[0])?;
+    let (x, y) = (bits_of(pool, x)?, bits_of(pool, y)?);
+    let expected_res = comparator(pool, &y, &x, false, true);
+    assert_eq(&expected_res, res)
+}
+
+pub fn equal(RuleArgs { conclusion, pool, .. }: RuleArgs) -> RuleResult {
+    assert_clause_len(conclusion, 1)?;
+    let ((x, y), res) = match_term_err!((= (= x y) res) = &conclusion[0])?;
+    let (x, y) = (bits_of(pool, x)?, bits_of(pool, y)?);
+    let expected_res = equal_bits(pool, &x, &y);
+    assert_eq(&expected_res, res)
+}
+
+pub fn var(RuleArgs { conclusion, pool, .. }: RuleArgs) -> RuleResult {
+    assert_clause_len(conclusion, 1)?;
+    let (x, res) = match_term_err!((= x res) = &conclusion[0])?;
+    let size = bv_width(pool, x)?;
+    let expected = build_term_vec(x, size, pool);
+    assert_bits(pool, expected, res)
+}
+
+pub fn constant(RuleArgs { conclusion, pool, .. }: RuleArgs) -> RuleResult {
+    assert_clause_len(conclusion, 1)?;
+    let (c, res) = match_term_err!((= c res) = &conclusion[0])?;
+
+    let (value, width) = match c.as_ref() {
+        Term::Const(_) => c.as_bitvector(),
+        Term::ParamOp {
+            op: ParamOperator::BvConst, op_args, ..
+        } => op_args[0].as_integer().zip(op_args[1].as_integer()),
+        _ => None,
+    }
+    .ok_or_else(|| CheckerError::ExpectedAnyBvConstant(c.clone()))?;
+
+    let width = width
+        .to_u32()
+        .ok_or_else(|| CheckerError::ExpectedAnyBvConstant(c.clone()))?;
+    let expected = (0..width)
+        .map(|i| pool.bool_constant(value.get_bit(i)))
+        .collect();
+    assert_bits(pool, expected, res)
+}
+
+pub fn ite(RuleArgs { conclusion, pool, .. }: RuleArgs) -> RuleResult {
+    assert_clause_len(conclusion, 1)?;
+    let ((cond, x, y), res) = match_term_err!((= (ite c x y) res) = &conclusion[0])?;
+    let (x, y) = (bits_of(pool, x)?, bits_of(pool, y)?);
+    let expected = ite_bits(pool, cond, &x, &y);
+    assert_bits(pool, expected, res)
+}
+
+pub fn not(RuleArgs { conclusion, pool, .. }: RuleArgs) -> RuleResult {
+    assert_clause_len(conclusion, 1)?;
+    let (x, res) = match_term_err!((= (bvnot x) res) = &conclusion[0])?;
+    let x = bits_of(pool, x)?;
+    let expected = negate_bits(pool, &x);
+    assert_bits(pool, expected, res)
+}
+
+pub fn neg(RuleArgs { conclusion, pool, .. }: RuleArgs) -> RuleResult {
+    assert_clause_len(conclusion, 1)?;
+    let (x, res) = match_term_err!((= (bvneg x) res) = &conclusion[0])?;
+    let x = bits_of(pool, x)?;
+    let expected = negation(pool, &x);
+    assert_bits(pool, expected, res)
+}
+
+/// Checks the bit-blasting of the bitwise operators `bvand`, `bvor` and `bvxor`, which may be
+/// applied to any number of arguments.
+fn check_bitwise(
+    pool: &mut dyn TermPool,
+    bv_op: Operator,
+    bool_op: Operator,
+    app: &Rc<Term>,
+    res: &Rc<Term>,
+) -> RuleResult {
+    let args = match app.as_op() {
+        Some((op, args)) if op == bv_op => args,
+        _ => return Err(CheckerError::TermOfWrongForm("(bvop x y ...)", app.clone())),
+    };
+    let args = args
+        .iter()
+        .map(|a| bits_of(pool, a))
+        .collect::<Result<Vec<_>, _>>()?;
+    let expected = bitwise(pool, bool_op, &args);
+    assert_bits(pool, expected, res)
+}
+
+pub fn and(RuleArgs { conclusion, pool, .. }: RuleArgs) -> RuleResult {
+    assert_clause_len(conclusion, 1)?;
+    let (app, res) = match_term_err!((= app res) = &conclusion[0])?;
+    check_bitwise(pool, Operator::BvAnd, Operator::And, app, res)
+}
+
+pub fn or(RuleArgs { conclusion, pool, .. }: RuleArgs) -> RuleResult {
+    assert_clause_len(conclusion, 1)?;
+    let (app, res) = match_term_err!((= app res) = &conclusion[0])?;
+    check_bitwise(pool, Operator::BvOr, Operator::Or, app, res)
+}
+
+pub fn xor(RuleArgs { conclusion, pool, .. }: RuleArgs) -> RuleResult {
+    assert_clause_len(conclusion, 1)?;
+    let (app, res) = match_term_err!((= app res) = &conclusion[0])?;
+    check_bitwise(pool, Operator::BvXor, Operator::Xor, app, res)
+}
+
+pub fn nand(RuleArgs { conclusion, pool, .. }: RuleArgs) -> RuleResult {
+    assert_clause_len(conclusion, 1)?;
+    let ((x, y), res) = match_term_err!((= (bvnand x y) res) = &conclusion[0])?;
+    let args = [bits_of(pool, x)?, bits_of(pool, y)?];
+    let and = bitwise(pool, Operator::And, &args);
+    let expected = negate_bits(pool, &and);
+    assert_bits(pool, expected, res)
+}
+
+pub fn nor(RuleArgs { conclusion, pool, .. }: RuleArgs) -> RuleResult {
+    assert_clause_len(conclusion, 1)?;
+    let ((x, y), res) = match_term_err!((= (bvnor x y) res) = &conclusion[0])?;
+    let args = [bits_of(pool, x)?, bits_of(pool, y)?];
+    let or = bitwise(pool, Operator::Or, &args);
+    let expected = negate_bits(pool, &or);
+    assert_bits(pool, expected, res)
+}
+
+pub fn xnor(RuleArgs { conclusion, pool, .. }: RuleArgs) -> RuleResult {
+    assert_clause_len(conclusion, 1)?;
+    let ((x, y), res) = match_term_err!((= (bvxnor x y) res) = &conclusion[0])?;
+    let args = [bits_of(pool, x)?, bits_of(pool, y)?];
+    let expected = bitwise(pool, Operator::Equals, &args);
+    assert_bits(pool, expected, res)
+}
+
+pub fn comp(RuleArgs { conclusion, pool, .. }: RuleArgs) -> RuleResult {
+    assert_clause_len(conclusion, 1)?;
+    let ((x, y), res) = match_term_err!((= (bvcomp x y) res) = &conclusion[0])?;
+    let (x, y) = (bits_of(pool, x)?, bits_of(pool, y)?);
+    let expected = equal_bits(pool, &x, &y);
+    assert_bits(pool, vec![expected], res)
+}
+
+pub fn add(RuleArgs { conclusion, pool, .. }: RuleArgs) -> RuleResult {
+    assert_clause_len(conclusion, 1)?;
+    let (args, res) = match_term_err!((= (bvadd ...) res) = &conclusion[0])?;
+
+    // `bvadd` is left-associative, so we add each argument to the sum of the previous ones
+    let mut expected = bits_of(pool, &args[0])?;
+    for arg in &args[1..] {
+        let y = bits_of(pool, arg)?;
+        expected = adder(pool, &expected, &y);
+    }
+    assert_bits(pool, expected, res)
+}
+
+pub fn sub(RuleArgs { conclusion, pool, .. }: RuleArgs) -> RuleResult {
+    assert_clause_len(conclusion, 1)?;
+    let ((x, y), res) = match_term_err!((= (bvsub x y) res) = &conclusion[0])?;
+    let (x, y) = (bits_of(pool, x)?, bits_of(pool, y)?);
+    let expected = subtractor(pool, &x, &y);
+    assert_bits(pool, expected, res)
+}
+
+pub fn mul(RuleArgs { conclusion, pool, .. }: RuleArgs) -> RuleResult {
+    assert_clause_len(conclusion, 1)?;
+    let (args, res) = match_term_err!((= (bvmul ...) res) = &conclusion[0])?;
+
+    let mut expected = bits_of(pool, &args[0])?;
+    for arg in &args[1..] {
+        let y = bits_of(pool, arg)?;
+        expected = multiplier(pool, &expected, &y);
+    }
+    assert_bits(pool, expected, res)
+}
+
+pub fn udiv(RuleArgs { conclusion, pool, .. }: RuleArgs) -> RuleResult {
+    assert_clause_len(conclusion, 1)?;
+    let ((x, y), res) = match_term_err!((= (bvudiv x y) res) = &conclusion[0])?;
+    let (x, y) = (bits_of(pool, x)?, bits_of(pool, y)?);
+    let (expected, _) = divider(pool, &x, &y);
+    assert_bits(pool, expected, res)
+}
+
+pub fn urem(RuleArgs { conclusion, pool, .. }: RuleArgs) -> RuleResult {
+    assert_clause_len(conclusion, 1)?;
+    let ((x, y), res) = match_term_err!((= (bvurem x y) res) = &conclusion[0])?;
+    let (x, y) = (bits_of(pool, x)?, bits_of(pool, y)?);
+    let (_, expected) = divider(pool, &x, &y);
+    assert_bits(pool, expected, res)
+}
+
+pub fn sdiv(RuleArgs { conclusion, pool, .. }: RuleArgs) -> RuleResult {
+    assert_clause_len(conclusion, 1)?;
+    let ((x, y), res) = match_term_err!((= (bvsdiv x y) res) = &conclusion[0])?;
+    let (x, y) = (bits_of(pool, x)?, bits_of(pool, y)?);
+    let expected = signed_division(pool, &x, &y);
+    assert_bits(pool, expected, res)
+}
+
+pub fn srem(RuleArgs { conclusion, pool, .. }: RuleArgs) -> RuleResult {
+    assert_clause_len(conclusion, 1)?;
+    let ((x, y), res) = match_term_err!((= (bvsrem x y) res) = &conclusion[0])?;
+    let (x, y) = (bits_of(pool, x)?, bits_of(pool, y)?);
+    let expected = signed_remainder(pool, &x, &y);
+    assert_bits(pool, expected, res)
+}
+
+pub fn smod(RuleArgs { conclusion, pool, .. }: RuleArgs) -> RuleResult {
+    assert_clause_len(conclusion, 1)?;
+    let ((x, y), res) = match_term_err!((= (bvsmod x y) res) = &conclusion[0])?;
+    let (x, y) = (bits_of(pool, x)?, bits_of(pool, y)?);
+    let expected = signed_modulo(pool, &x, &y);
+    assert_bits(pool, expected, res)
+}
+
+pub fn shl(RuleArgs { conclusion, pool, .. }: RuleArgs) -> RuleResult {
+    assert_clause_len(conclusion, 1)?;
+    let ((x, y), res) = match_term_err!((= (bvshl x y) res) = &conclusion[0])?;
+    let (x, y) = (bits_of(pool, x)?, bits_of(pool, y)?);
+    let expected = barrel_shifter(pool, ShiftKind::Left, &x, &y);
+    assert_bits(pool, expected, res)
+}
+
+pub fn lshr(RuleArgs { conclusion, pool, .. }: RuleArgs) -> RuleResult {
+    assert_clause_len(conclusion, 1)?;
+    let ((x, y), res) = match_term_err!((= (bvlshr x y) res) = &conclusion[0])?;
+    let (x, y) = (bits_of(pool, x)?, bits_of(pool, y)?);
+    let expected = barrel_shifter(pool, ShiftKind::LogicalRight, &x, &y);
+    assert_bits(pool, expected, res)
+}
+
+pub fn ashr(RuleArgs { conclusion, pool, .. }: RuleArgs) -> RuleResult {
+    assert_clause_len(conclusion, 1)?;
+    let ((x, y), res) = match_term_err!((= (bvashr x y) res) = &conclusion[0])?;
+    let (x, y) = (bits_of(pool, x)?, bits_of(pool, y)?);
+    let expected = barrel_shifter(pool, ShiftKind::ArithmeticRight, &x, &y);
+    assert_bits(pool, expected, res)
+}
+
+pub fn concat(RuleArgs { conclusion, pool, .. }: RuleArgs) -> RuleResult {
+    assert_clause_len(conclusion, 1)?;
+    let (args, res) = match_term_err!((= (concat ...) res) = &conclusion[0])?;
+
+    // The first argument holds the most significant bits, so the bits of the result start with
+    // the bits of the last argument
+    let mut expected = Vec::new();
+    for arg in args.iter().rev() {
+        expected.extend(bits_of(pool, arg)?);
+    }
+    assert_bits(pool, expected, res)
+}
+
+pub fn zero_extend(RuleArgs { conclusion, pool, .. }: RuleArgs) -> RuleResult {
+    assert_clause_len(conclusion, 1)?;
+    let ((i, x), res) = match_term_err!((= ((_ zero_extend i) x) res) = &conclusion[0])?;
+    let i = i.as_usize_err()?;
+    let mut expected = bits_of(pool, x)?;
+    expected.extend(std::iter::repeat_n(pool.bool_false(), i));
+    assert_bits(pool, expected, res)
+}
+
+pub fn sign_extend(RuleArgs { conclusion, pool, .. }: RuleArgs) -> RuleResult {
+    assert_clause_len(conclusion, 1)?;
+    let ((i, x), res) = match_term_err!((= ((_ sign_extend i) x) res) = &conclusion[0])?;
+    let i = i.as_usize_err()?;
+    let mut expected = bits_of(pool, x)?;
+    if let Some(sign) = expected.last().cloned() {
+        expected.extend(std::iter::repeat_n(sign, i));
+    }
+    assert_bits(pool, expected, res)
+}
+
+pub fn repeat(RuleArgs { conclusion, pool, .. }: RuleArgs) -> RuleResult {
+    assert_clause_len(conclusion, 1)?;
+    let ((i, x), res) = match_term_err!((= ((_ repeat i) x) res) = &conclusion[0])?;
+    let i = i.as_usize_err()?;
+    let x = bits_of(pool, x)?;
+    let expected = x.iter().cycle().take(i * x.len()).cloned().collect();
+    assert_bits(pool, expected, res)
+}
+
+pub fn rotate_left(RuleArgs { conclusion, pool, .. }: RuleArgs) -> RuleResult {
+    assert_clause_len(conclusion, 1)?;
+    let ((i, x), res) = match_term_err!((= ((_ rotate_left i) x) res) = &conclusion[0])?;
+    let mut expected = bits_of(pool, x)?;
+    let i = i.as_usize_err()?.checked_rem(expected.len()).unwrap_or(0);
+    expected.rotate_right(i);
+    assert_bits(pool, expected, res)
+}
+
+pub fn rotate_right(RuleArgs { conclusion, pool, .. }: RuleArgs) -> RuleResult {
+    assert_clause_len(conclusion, 1)?;
+    let ((i, x), res) = match_term_err!((= ((_ rotate_right i) x) res) = &conclusion[0])?;
+    let mut expected = bits_of(pool, x)?;
+    let i = i.as_usize_err()?.checked_rem(expected.len()).unwrap_or(0);
+    expected.rotate_left(i);
+    assert_bits(pool, expected, res)
+}
+
 pub fn extract(RuleArgs { conclusion, pool, .. }: RuleArgs) -> RuleResult {
     assert_clause_len(conclusion, 1)?;
     let (((_, left_j), left_x), right) =
         match_term_err!((= ((_ extract i j) x) (bbterm ...)) = &conclusion[0])?;
 
-    let left_j = left_j.as_integer().unwrap();
-    let mut index = left_j;
+    let mut index = left_j.as_usize_err()?;
 
     if let Some((Operator::BvBbTerm, args)) = left_x.as_op() {
-        let mut index = index.to_usize().unwrap();
         for arg in right {
-            assert_eq(&args[index], arg)?;
+            let bit = args
+                .get(index)
+                .ok_or_else(|| CheckerError::NoIthChildInTerm(index, left_x.clone()))?;
+            assert_eq(bit, arg)?;
             index += 1;
         }
         return Ok(());
@@ -107,7 +717,7 @@ pub fn extract(RuleArgs { conclusion, pool, .. }: RuleArgs) -> RuleResult {
     for arg in right {
         let expected_arg = Term::ParamOp {
             op: ParamOperator::BvBitOf,
-            op_args: vec![pool.add(Term::new_int(index.clone()))],
+            op_args: vec![pool.add(Term::new_int(index))],
             args: vec![left_x.clone()],
         };
         let new_arg = pool.add(expected_arg);
@@ -116,3 +726,218 @@ pub fn extract(RuleArgs { conclusion, pool, .. }: RuleArgs) -> RuleResult {
     }
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ast::pool::PrimitivePool;
+    use std::collections::HashMap;
+
+    /// Evaluates a boolean circuit whose leaves are all boolean constants.
+    fn eval(term: &Rc<Term>, cache: &mut HashMap<Rc<Term>, bool>) -> bool {
+        if let Some(&value) = cache.get(term) {
+            return value;
+        }
+        let value = if term.is_bool_true() {
+            true
+        } else if term.is_bool_false() {
+            false
+        } else {
+            let (op, args) = term.as_op().unwrap();
+            let args: Vec<_> = args.iter().map(|a| eval(a, cache)).collect();
+            match op {
+                Operator::Not => !args[0],
+                Operator::And => args.iter().all(|&a| a),
+                Operator::Or => args.iter().any(|&a| a),
+                Operator::Xor => args.iter().fold(false, |acc, &a| acc ^ a),
+                Operator::Equals => args[0] == args[1],
+                Operator::Ite => {
+                    if args[0] {
+                        args[1]
+                    } else {
+                        args[2]
+                    }
+                }
+                _ => panic!("unexpected operator in circuit: {}", op),
+            }
+        };
+        cache.insert(term.clone(), value);
+        value
+    }
+
+    fn to_bits(pool: &mut PrimitivePool, value: u64, size: usize) -> Vec<Rc<Term>> {
+        (0..size)
+            .map(|i| pool.bool_constant(value >> i & 1 == 1))
+            .collect()
+    }
+
+    fn from_bits(bits: &[Rc<Term>]) -> u64 {
+        let cache = &mut HashMap::new();
+        bits.iter()
+            .enumerate()
+            .map(|(i, b)| u64::from(eval(b, cache)) << i)
+            .sum()
+    }
+
+    fn to_signed(value: u64, size: usize) -> i64 {
+        let value = value as i64;
+        if value >> (size - 1) & 1 == 1 {
+            value - (1 << size)
+        } else {
+            value
+        }
+    }
+
+    type Circuit = fn(&mut dyn TermPool, &[Rc<Term>], &[Rc<Term>]) -> Vec<Rc<Term>>;
+
+    /// Checks `circuit` against `reference` for every pair of inputs of every width up to
+    /// `max_size`. The reference receives the inputs and the width, and its result is truncated to
+    /// the width.
+    fn check_exhaustively(
+        circuit: Circuit,
+        reference: fn(u64, u64, usize) -> u64,
+        max_size: usize,
+    ) {
+        let pool = &mut PrimitivePool::new();
+        for size in 1..=max_size {
+            let mask = (1u64 << size) - 1;
+            for x in 0..=mask {
+                for y in 0..=mask {
+                    let (x_bits, y_bits) = (to_bits(pool, x, size), to_bits(pool, y, size));
+                    let got = from_bits(&circuit(pool, &x_bits, &y_bits));
+                    let expected = reference(x, y, size) & mask;
+                    assert_eq!(expected, got, "x = {x}, y = {y}, size = {size}");
+                }
+            }
+        }
+    }
+
+    fn check_predicate_exhaustively(
+        strict: bool,
+        signed: bool,
+        reference: fn(u64, u64, usize) -> bool,
+    ) {
+        let pool = &mut PrimitivePool::new();
+        for size in 1..=4 {
+            for x in 0..(1 << size) {
+                for y in 0..(1 << size) {
+                    let (x_bits, y_bits) = (to_bits(pool, x, size), to_bits(pool, y, size));
+                    let circuit = comparator(pool, &x_bits, &y_bits, strict, signed);
+                    let got = eval(&circuit, &mut HashMap::new());
+                    let expected = reference(x, y, size);
+                    assert_eq!(expected, got, "x = {x}, y = {y}, size = {size}");
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn arithmetic_circuits() {
+        check_exhaustively(adder, |x, y, _| x + y, 4);
+        check_exhaustively(subtractor, |x, y, _| x.wrapping_sub(y), 4);
+        check_exhaustively(
+            |pool, x, _| negation(pool, x),
+            |x, _, _| x.wrapping_neg(),
+            4,
+        );
+        check_exhaustively(multiplier, |x, y, _| x * y, 4);
+    }
+
+    #[test]
+    fn unsigned_division_circuits() {
+        check_exhaustively(
+            |pool, x, y| divider(pool, x, y).0,
+            |x, y, size| x.checked_div(y).unwrap_or((1 << size) - 1),
+            4,
+        );
+        check_exhaustively(
+            |pool, x, y| divider(pool, x, y).1,
+            |x, y, _| x.checked_rem(y).unwrap_or(x),
+            4,
+        );
+    }
+
+    #[test]
+    fn signed_division_circuits() {
+        // The results are computed following the SMT-LIB definitions of these operators, in terms
+        // of the unsigned ones
+        fn udiv(x: u64, y: u64, size: usize) -> u64 {
+            x.checked_div(y).unwrap_or((1 << size) - 1)
+        }
+        fn urem(x: u64, y: u64) -> u64 {
+            x.checked_rem(y).unwrap_or(x)
+        }
+        fn abs(x: u64, size: usize) -> u64 {
+            to_signed(x, size).unsigned_abs()
+        }
+        check_exhaustively(
+            signed_division,
+            |x, y, size| {
+                let q = udiv(abs(x, size), abs(y, size), size);
+                if (to_signed(x, size) < 0) != (to_signed(y, size) < 0) {
+                    q.wrapping_neg()
+                } else {
+                    q
+                }
+            },
+            4,
+        );
+        check_exhaustively(
+            signed_remainder,
+            |x, y, size| {
+                let r = urem(abs(x, size), abs(y, size));
+                if to_signed(x, size) < 0 {
+                    r.wrapping_neg()
+                } else {
+                    r
+                }
+            },
+            4,
+        );
+        check_exhaustively(
+            signed_modulo,
+            |x, y, size| {
+                let u = urem(abs(x, size), abs(y, size)) & ((1 << size) - 1);
+                match (to_signed(x, size) < 0, to_signed(y, size) < 0) {
+                    _ if u == 0 => u,
+                    (false, false) => u,
+                    (true, false) => u.wrapping_neg().wrapping_add(y),
+                    (false, true) => u + y,
+                    (true, true) => u.wrapping_neg(),
+                }
+            },
+            4,
+        );
+    }
+
+    #[test]
+    fn shift_circuits() {
+        check_exhaustively(
+            |pool, x, y| barrel_shifter(pool, ShiftKind::Left, x, y),
+            |x, y, size| if y >= size as u64 { 0 } else { x << y },
+            4,
+        );
+        check_exhaustively(
+            |pool, x, y| barrel_shifter(pool, ShiftKind::LogicalRight, x, y),
+            |x, y, size| if y >= size as u64 { 0 } else { x >> y },
+            4,
+        );
+        check_exhaustively(
+            |pool, x, y| barrel_shifter(pool, ShiftKind::ArithmeticRight, x, y),
+            |x, y, size| (to_signed(x, size) >> y.min(size as u64 - 1)) as u64,
+            4,
+        );
+    }
+
+    #[test]
+    fn comparison_circuits() {
+        check_predicate_exhaustively(true, false, |x, y, _| x < y);
+        check_predicate_exhaustively(false, false, |x, y, _| x <= y);
+        check_predicate_exhaustively(true, true, |x, y, size| {
+            to_signed(x, size) < to_signed(y, size)
+        });
+        check_predicate_exhaustively(false, true, |x, y, size| {
+            to_signed(x, size) <= to_signed(y, size)
+        });
+    }
+}
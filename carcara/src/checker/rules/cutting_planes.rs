@@ -2,7 +2,7 @@ use super::{
     assert_clause_len, assert_eq, assert_num_args, assert_num_premises, RuleArgs, RuleResult, Term,
 };
 use crate::ast::{Constant, Operator};
-use crate::checker::error::{CheckerError, EqualityError};
+use crate::checker::error::{CheckerError, CuttingPlanesError, EqualityError};
 use crate::checker::Rc;
 use rug::Integer;
 use std::collections::HashMap;
@@ -44,7 +44,7 @@ fn get_pb_hashmap(pbsum: &Rc<Term>) -> Result<PbHash, CheckerError> {
             } else if let Some((coeff, literal)) = match_term!((* coeff literal) = term) {
                 (coeff, format!("{}",literal))
             } else {
-                return Err(CheckerError::Explanation(format!("Term is neither plain nor negated: {}",term)));
+                return Err(CuttingPlanesError::NotPseudoBooleanLiteral(term.clone()).into());
             };
 
         let coeff = coeff.as_integer_err()?;
@@ -55,7 +55,10 @@ fn get_pb_hashmap(pbsum: &Rc<Term>) -> Result<PbHash, CheckerError> {
 
 fn unwrap_pseudoboolean_inequality(clause: &Rc<Term>) -> Result<(PbHash, Integer), CheckerError> {
     let (pbsum, constant) = match_term_err!((>= pbsum constant) = clause)?;
-    let constant = constant.as_integer_err()?;
+    // The degree may be negative, for example after adding a literal and its negation
+    let constant = constant
+        .as_signed_integer()
+        .ok_or_else(|| CheckerError::ExpectedAnyInteger(constant.clone()))?;
     let pbsum = get_pb_hashmap(pbsum)?;
     Ok((pbsum, constant))
 }
@@ -140,10 +143,7 @@ fn assert_pbsum_subset_keys(pbsum_a: &PbHash, pbsum_b: &PbHash) -> Result<(), Ch
         }
 
         if pbsum_b.get(key).is_none() {
-            return Err(CheckerError::Explanation(format!(
-                "Key {} of {:?} not found in {:?}",
-                key, pbsum_b, pbsum_a
-            )));
+            return Err(CuttingPlanesError::MissingLiteral(key.clone()).into());
         }
     }
     Ok(())
@@ -192,13 +192,12 @@ pub fn cp_addition(RuleArgs { premises, args, conclusion, .. }: RuleArgs) -> Rul
     // Verify constants match (with slack)
     rassert!(
         constant_l.clone() + constant_r.clone() == constant_c.clone() + slack.clone(),
-        CheckerError::Explanation(format!(
-            "Expected {} + {} == {} + {} ",
-            constant_l.clone(),
-            constant_r.clone(),
-            constant_c.clone(),
-            slack.clone()
-        ))
+        CuttingPlanesError::ConstantsDontMatch {
+            left: constant_l,
+            right: constant_r,
+            conclusion: constant_c,
+            slack,
+        }
     );
 
     // Verify premise and conclusion share same keys
@@ -218,10 +217,7 @@ pub fn cp_addition(RuleArgs { premises, args, conclusion, .. }: RuleArgs) -> Rul
             }
             // ¬∃ x, (x ∈ C) ∧ ¬(x ∈ L) ∧ ¬(x ∈ R)
             _ => {
-                return Err(CheckerError::Explanation(format!(
-                    "Literal of the conclusion not present in either premises: {}",
-                    literal
-                )));
+                return Err(CuttingPlanesError::LiteralNotInPremises(literal.clone()).into());
             }
         }
     }
@@ -385,8 +381,9 @@ pub fn cp_literal(RuleArgs { pool, args, conclusion, .. }: RuleArgs) -> RuleResu
     if let Some((l, _)) = match_term!((>= l 0) = &conclusion[0]) {
         return assert_eq(l, &args[0]);
     }
-    Err(CheckerError::Explanation(
-        "No valid pattern was found".into(),
+    Err(CheckerError::TermOfWrongForm(
+        "(>= (* c l) 0)",
+        conclusion[0].clone(),
     ))
 }
 
@@ -409,12 +406,8 @@ fn match_supported_relation_err(
                 Err(CheckerError::WrongNumberOfArgs(2.into(), args.len()))
             }
         }
-        Term::Op(op, _) => Err(CheckerError::Explanation(format!(
-            "Operator {op} is not a valid relation"
-        ))),
-        _ => Err(CheckerError::Explanation(
-            "Expected relation operator".into(),
-        )),
+        Term::Op(op, _) => Err(CuttingPlanesError::UnsupportedRelation(*op).into()),
+        _ => Err(CheckerError::ExpectedOperationTerm(term.clone())),
     }
 }
 
@@ -517,11 +510,7 @@ fn term_to_ctv(term: &Rc<Term>) -> Result<CoeffTimesVar, Integer> {
 fn collect_addition_list(term: &Rc<Term>) -> Result<Vec<CoeffTimesVar>, CheckerError> {
     let mut add_list = vec![];
     for t in split_summation(term) {
-        let ctv = term_to_ctv(t).map_err(|i| {
-            CheckerError::Explanation(format!(
-                "Found integer constant {i} in LHS of normalized term"
-            ))
-        })?;
+        let ctv = term_to_ctv(t).map_err(CuttingPlanesError::ConstantInNormalizedSum)?;
         add_list.push(ctv);
     }
     Ok(add_list)
@@ -599,17 +588,13 @@ fn check_pb_inequalities(
 ) -> RuleResult {
     rassert!(
         vars_l.len() == vars_r.len(),
-        CheckerError::Explanation(format!(
-            "List of variables should have same length, got {} and {}",
-            vars_l.len(),
-            vars_r.len()
-        ))
+        CuttingPlanesError::DifferentNumberOfSummands(vars_l.len(), vars_r.len())
     );
 
     for (var_l, var_r) in vars_l.iter().zip(vars_r) {
         rassert!(
             var_l == var_r,
-            CheckerError::Explanation(format!("{var_l:?} != {var_r:?}"))
+            CuttingPlanesError::DifferentSummands(format!("{var_l:?}"), format!("{var_r:?}"))
         );
     }
 
@@ -685,9 +670,7 @@ pub fn cp_normalize(RuleArgs { conclusion, .. }: RuleArgs) -> RuleResult {
             Operator::GreaterEq => (), /* Nothing to be done */
             _ => {
                 // Should be impossible to get here
-                Err(CheckerError::Explanation(format!(
-                    "Invalid relation operator: {relation_operator}"
-                )))?;
+                Err(CuttingPlanesError::UnsupportedRelation(*relation_operator))?;
             }
         }
 
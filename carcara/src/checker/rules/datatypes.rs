@@ -0,0 +1,268 @@
+//! Rules for the theory of algebraic datatypes.
+
+use super::{assert_clause_len, assert_eq, assert_is_bool_constant, RuleArgs, RuleResult};
+use crate::{
+    ast::*,
+    checker::error::{CheckerError, DatatypeError},
+};
+
+/// Returns the definition of the datatype of `term`, or an error if `term` is not of a datatype
+/// sort.
+fn get_datatype<'a>(
+    pool: &'a dyn TermPool,
+    term: &Rc<Term>,
+) -> Result<&'a DatatypeDef, CheckerError> {
+    let sort = pool.sort(term);
+    match sort.as_sort() {
+        Some(Sort::Datatype(name, _)) => pool
+            .datatype(name)
+            .ok_or_else(|| DatatypeError::NotADatatypeTerm(term.clone()).into()),
+        _ => Err(DatatypeError::NotADatatypeTerm(term.clone()).into()),
+    }
+}
+
+/// Returns `true` if `sort` is the sort of the constructor `c` of `def`, as given by the datatype
+/// declaration. If the datatype is parametric, the sort may be wrapped in a `Sort::ParamSort`.
+fn has_constructor_sort(def: &DatatypeDef, c: &ConstructorDef, sort: &Rc<Term>) -> bool {
+    let sort = match sort.as_sort() {
+        Some(Sort::ParamSort(_, inner)) => inner,
+        _ => sort,
+    };
+    let is_datatype = |sort: &Rc<Term>| match sort.as_sort() {
+        Some(Sort::Datatype(name, _)) => *name == def.name,
+        _ => false,
+    };
+    match sort.as_sort() {
+        Some(Sort::Function(sorts)) => match sorts.split_last() {
+            Some((result, fields)) => {
+                is_datatype(result)
+                    && fields.len() == c.selectors.len()
+                    && fields.iter().zip(&c.selectors).all(|(a, (_, b))| a == b)
+            }
+            None => false,
+        },
+        _ => c.selectors.is_empty() && is_datatype(sort),
+    }
+}
+
+/// If `term` is an application of a constructor of `def`, returns the index of the constructor and
+/// the arguments it was applied to. Nullary constructors are represented by the constructor symbol
+/// itself, so they have no arguments.
+///
+/// Constructors are represented as variables, so a symbol is only considered a constructor if its
+/// sort is the one given in the datatype declaration, and if it is not shadowed by a variable bound
+/// in the context.
+fn as_constructor_app<'a>(
+    def: &DatatypeDef,
+    context: &ContextStack,
+    term: &'a Rc<Term>,
+) -> Option<(usize, &'a [Rc<Term>])> {
+    let (symbol, args) = match term.as_ref() {
+        Term::Var(..) => (term, [].as_slice()),
+        Term::App(f, args) => (f, args.as_slice()),
+        _ => return None,
+    };
+    let Term::Var(name, sort) = symbol.as_ref() else {
+        return None;
+    };
+    let i = def.constructors.iter().position(|c| c.name == *name)?;
+    let constructor = &def.constructors[i];
+    let is_constructor = constructor.selectors.len() == args.len()
+        && has_constructor_sort(def, constructor, sort)
+        && !context.binds(&(name.clone(), sort.clone()));
+    is_constructor.then_some((i, args))
+}
+
+/// Returns the datatype definition of `term`, together with the index of its constructor and its
+/// arguments. Returns an error if `term` is not a constructor application.
+fn expect_constructor_app<'a>(
+    pool: &'a dyn TermPool,
+    context: &ContextStack,
+    term: &'a Rc<Term>,
+) -> Result<(&'a DatatypeDef, usize, &'a [Rc<Term>]), CheckerError> {
+    let def = get_datatype(pool, term)?;
+    let (i, args) = as_constructor_app(def, context, term)
+        .ok_or_else(|| DatatypeError::NotAConstructorApp(term.clone()))?;
+    Ok((def, i, args))
+}
+
+/// If `term` is a tester application, of the form `((_ is C) t)`, returns the constructor name `C`
+/// and the tested term `t`.
+fn expect_tester(term: &Rc<Term>) -> Result<(&str, &Rc<Term>), CheckerError> {
+    match term.as_ref() {
+        Term::ParamOp {
+            op: ParamOperator::DtTester,
+            op_args,
+            args,
+        } => match (op_args[0].as_var(), args.as_slice()) {
+            (Some(name), [t]) => Ok((name, t)),
+            _ => Err(DatatypeError::NotATester(term.clone()).into()),
+        },
+        _ => Err(DatatypeError::NotATester(term.clone()).into()),
+    }
+}
+
+/// If `term` is the application of a unary function symbol, which may be a selector, returns the
+/// symbol name and the argument.
+fn expect_selector_app(term: &Rc<Term>) -> Result<(&str, &Rc<Term>), CheckerError> {
+    match term.as_ref() {
+        Term::App(f, args) => match (f.as_var(), args.as_slice()) {
+            (Some(name), [t]) => Ok((name, t)),
+            _ => Err(DatatypeError::NotASelectorApp(term.clone()).into()),
+        },
+        _ => Err(DatatypeError::NotASelectorApp(term.clone()).into()),
+    }
+}
+
+/// Returns `true` if `needle` occurs in `haystack` as an argument of a (possibly nested) constructor
+/// application.
+fn occurs_under_constructors(
+    pool: &dyn TermPool,
+    context: &ContextStack,
+    needle: &Rc<Term>,
+    haystack: &Rc<Term>,
+) -> bool {
+    let Ok(def) = get_datatype(pool, haystack) else {
+        return false;
+    };
+    let Some((_, args)) = as_constructor_app(def, context, haystack) else {
+        return false;
+    };
+    args.iter()
+        .any(|a| a == needle || occurs_under_constructors(pool, context, needle, a))
+}
+
+pub fn dt_split(RuleArgs { conclusion, pool, .. }: RuleArgs) -> RuleResult {
+    assert_clause_len(conclusion, 1..)?;
+
+    let (_, t) = expect_tester(&conclusion[0])?;
+    let def = get_datatype(pool, t)?;
+    assert_clause_len(conclusion, def.constructors.len())?;
+
+    for (constructor, literal) in def.constructors.iter().zip(conclusion) {
+        let (name, u) = expect_tester(literal)?;
+        assert_eq(t, u)?;
+        rassert!(
+            name == constructor.name,
+            DatatypeError::WrongConstructor(constructor.name.clone(), name.to_owned())
+        );
+    }
+    Ok(())
+}
+
+pub fn dt_inst(RuleArgs { conclusion, pool, context, .. }: RuleArgs) -> RuleResult {
+    assert_clause_len(conclusion, 1)?;
+
+    let (tester, (t, app)) = match_term_err!((= tester (= t app)) = &conclusion[0])?;
+    let (name, tested) = expect_tester(tester)?;
+    assert_eq(tested, t)?;
+
+    let (def, i, args) = expect_constructor_app(pool, context, app)?;
+    let constructor = &def.constructors[i];
+    rassert!(
+        constructor.name == name,
+        DatatypeError::WrongConstructor(name.to_owned(), constructor.name.clone())
+    );
+
+    // Each argument of the constructor application must be the corresponding selector applied to
+    // the tested term
+    for ((expected, _), arg) in constructor.selectors.iter().zip(args) {
+        let (selector, u) = expect_selector_app(arg)?;
+        rassert!(
+            selector == expected,
+            DatatypeError::SelectorOfOtherConstructor(
+                selector.to_owned(),
+                constructor.name.clone()
+            )
+        );
+        assert_eq(t, u)?;
+    }
+    Ok(())
+}
+
+pub fn dt_collapse_selector(RuleArgs { conclusion, pool, context, .. }: RuleArgs) -> RuleResult {
+    assert_clause_len(conclusion, 1)?;
+
+    let (selector_app, result) = match_term_err!((= s r) = &conclusion[0])?;
+    let (selector, app) = expect_selector_app(selector_app)?;
+    let (def, i, args) = expect_constructor_app(pool, context, app)?;
+    let constructor = &def.constructors[i];
+
+    let j = constructor
+        .selectors
+        .iter()
+        .position(|(name, _)| name == selector)
+        .ok_or_else(|| {
+            DatatypeError::SelectorOfOtherConstructor(selector.to_owned(), constructor.name.clone())
+        })?;
+    assert_eq(&args[j], result)
+}
+
+pub fn dt_collapse_tester(RuleArgs { conclusion, pool, context, .. }: RuleArgs) -> RuleResult {
+    assert_clause_len(conclusion, 1)?;
+
+    let (tester, result) = match_term_err!((= t b) = &conclusion[0])?;
+    let (name, app) = expect_tester(tester)?;
+    let (def, i, _) = expect_constructor_app(pool, context, app)?;
+    assert_is_bool_constant(result, def.constructors[i].name == name)
+}
+
+pub fn dt_collapse_tester_singleton(RuleArgs { conclusion, pool, .. }: RuleArgs) -> RuleResult {
+    assert_clause_len(conclusion, 1)?;
+
+    let (tester, result) = match_term_err!((= t b) = &conclusion[0])?;
+    let (_, t) = expect_tester(tester)?;
+    let def = get_datatype(pool, t)?;
+    rassert!(
+        def.constructors.len() == 1,
+        DatatypeError::NotSingletonDatatype(def.name.clone())
+    );
+    assert_is_bool_constant(result, true)
+}
+
+pub fn dt_unif(RuleArgs { conclusion, pool, context, .. }: RuleArgs) -> RuleResult {
+    assert_clause_len(conclusion, 2)?;
+
+    let (a, b) = match_term_err!((not (= a b)) = &conclusion[0])?;
+    let (t, s) = match_term_err!((= t s) = &conclusion[1])?;
+
+    let (def, i, a_args) = expect_constructor_app(pool, context, a)?;
+    let (_, j, b_args) = expect_constructor_app(pool, context, b)?;
+    rassert!(
+        i == j,
+        DatatypeError::WrongConstructor(
+            def.constructors[i].name.clone(),
+            def.constructors[j].name.clone()
+        )
+    );
+    rassert!(
+        a_args.iter().zip(b_args).any(|(x, y)| x == t && y == s),
+        DatatypeError::NotAnArgumentEquality(conclusion[1].clone())
+    );
+    Ok(())
+}
+
+pub fn dt_clash(RuleArgs { conclusion, pool, context, .. }: RuleArgs) -> RuleResult {
+    assert_clause_len(conclusion, 1)?;
+
+    let (a, b) = match_term_err!((not (= a b)) = &conclusion[0])?;
+    let (def, i, _) = expect_constructor_app(pool, context, a)?;
+    let (_, j, _) = expect_constructor_app(pool, context, b)?;
+    rassert!(
+        i != j,
+        DatatypeError::SameConstructor(def.constructors[i].name.clone())
+    );
+    Ok(())
+}
+
+pub fn dt_cycle(RuleArgs { conclusion, pool, context, .. }: RuleArgs) -> RuleResult {
+    assert_clause_len(conclusion, 1)?;
+
+    let (a, b) = match_term_err!((not (= a b)) = &conclusion[0])?;
+    rassert!(
+        occurs_under_constructors(pool, context, a, b)
+            || occurs_under_constructors(pool, context, b, a),
+        DatatypeError::NoCycle(a.clone(), b.clone())
+    );
+    Ok(())
+}
@@ -2,8 +2,9 @@ use super::{assert_clause_len, assert_eq, assert_num_args, RuleArgs, RuleResult}
 use crate::{
     ast::*,
     checker::error::{CheckerError, LinearArithmeticError},
+    simplex::{self, Constraint, Relation, Solution},
 };
-use indexmap::{map::Entry, IndexMap};
+use indexmap::{map::Entry, IndexMap, IndexSet};
 use rug::{ops::NegAssign, Integer, Rational};
 
 pub fn la_rw_eq(RuleArgs { conclusion, .. }: RuleArgs) -> RuleResult {
@@ -257,37 +258,101 @@ fn strengthen(op: Operator, disequality: &mut LinearComb, a: &Rational) -> Opera
     }
 }
 
-pub fn la_generic(RuleArgs { conclusion, args, .. }: RuleArgs) -> RuleResult {
-    assert_num_args(args, conclusion.len())?;
+/// Negates a disequality and moves all non-constant terms to the left side, and the constant
+/// terms to the right side. The resulting operator is always `>`, `>=` or `=`. These are the
+/// steps 1 to 3 of the `la_generic` rule.
+fn negate_and_flip(phi: &Rc<Term>) -> Result<(Operator, LinearComb), CheckerError> {
+    // Steps 1 and 2: Negate the disequality
+    let (mut op, s1, s2) = negate_disequality(phi)?;
+
+    // Step 3: Move all non constant terms to the left side, and the d terms to the right.
+    // We move everything to the left side by subtracting s2 from s1
+    let mut disequality = s1.sub(s2);
+    disequality.1 = -disequality.1; // We negate d to move it to the other side
+
+    // If the operator is < or <=, we flip the disequality so it is > or >=
+    if op == Operator::LessThan {
+        disequality.neg();
+        op = Operator::GreaterThan;
+    } else if op == Operator::LessEq {
+        disequality.neg();
+        op = Operator::GreaterEq;
+    }
+    Ok((op, disequality))
+}
 
-    let args: Vec<_> = args
+/// Converts the negation of each literal in a clause into a linear constraint, in the same way
+/// as the `la_generic` rule does before the strengthening step. The non-constant terms are added
+/// to `vars`, and represented in the constraints by their indices in it.
+pub fn la_generic_constraints(
+    clause: &[Rc<Term>],
+    vars: &mut IndexSet<Rc<Term>>,
+) -> Result<Vec<Constraint>, CheckerError> {
+    clause
         .iter()
+        .map(|phi| {
+            let (op, LinearComb(left_side, constant)) = negate_and_flip(phi)?;
+            let coefficients = left_side
+                .into_iter()
+                .map(|(var, coeff)| (vars.insert_full(var).0, coeff))
+                .collect();
+            let relation = match op {
+                Operator::GreaterThan => Relation::Greater,
+                Operator::GreaterEq => Relation::GreaterEq,
+                _ => Relation::Equal,
+            };
+            Ok(Constraint { coefficients, relation, constant })
+        })
+        .collect()
+}
+
+/// Searches for coefficients that make a `la_generic` step with the given clause valid, using the
+/// simplex method. Since the strengthening rules only make the final disequality stronger, the
+/// search ignores them, and considers all variables as reals. Returns `None` if the negation of
+/// the clause is satisfiable over the reals.
+pub fn find_la_generic_args(clause: &[Rc<Term>]) -> Result<Option<Vec<Integer>>, CheckerError> {
+    let mut vars = IndexSet::new();
+    let constraints = la_generic_constraints(clause, &mut vars)?;
+    match simplex::solve(vars.len(), &constraints) {
+        Solution::Unsat(coefficients) => Ok(Some(coefficients)),
+        Solution::Sat(_) => Ok(None),
+    }
+}
+
+pub fn la_generic(RuleArgs { conclusion, args, .. }: RuleArgs) -> RuleResult {
+    // If the coefficients are omitted, we try to find them ourselves
+    if args.is_empty() {
+        let args =
+            find_la_generic_args(conclusion)?.ok_or(LinearArithmeticError::NoFarkasCoefficients)?;
+        return check_la_generic(conclusion, args.into_iter().map(Rational::from).collect());
+    }
+    check_la_generic(conclusion, parse_la_generic_args(conclusion, args)?)
+}
+
+/// A version of the `la_generic` rule that requires the coefficients to be given as arguments.
+pub fn strict_la_generic(RuleArgs { conclusion, args, .. }: RuleArgs) -> RuleResult {
+    check_la_generic(conclusion, parse_la_generic_args(conclusion, args)?)
+}
+
+fn parse_la_generic_args(
+    conclusion: &[Rc<Term>],
+    args: &[Rc<Term>],
+) -> Result<Vec<Rational>, CheckerError> {
+    assert_num_args(args, conclusion.len())?;
+    args.iter()
         .map(|a| {
             a.as_fraction()
                 .ok_or_else(|| CheckerError::ExpectedAnyNumber(a.clone()))
         })
-        .collect::<Result<_, _>>()?;
+        .collect()
+}
 
+fn check_la_generic(conclusion: &[Rc<Term>], args: Vec<Rational>) -> RuleResult {
     let final_disequality = conclusion
         .iter()
         .zip(args)
         .map(|(phi, a)| -> Result<_, CheckerError> {
-            // Steps 1 and 2: Negate the disequality
-            let (mut op, s1, s2) = negate_disequality(phi)?;
-
-            // Step 3: Move all non constant terms to the left side, and the d terms to the right.
-            // We move everything to the left side by subtracting s2 from s1
-            let mut disequality = s1.sub(s2);
-            disequality.1 = -disequality.1; // We negate d to move it to the other side
-
-            // If the operator is < or <=, we flip the disequality so it is > or >=
-            if op == Operator::LessThan {
-                disequality.neg();
-                op = Operator::GreaterThan;
-            } else if op == Operator::LessEq {
-                disequality.neg();
-                op = Operator::GreaterEq;
-            }
+            let (op, mut disequality) = negate_and_flip(phi)?;
 
             // Step 4: Apply strengthening rules
             let op = strengthen(op, &mut disequality, &a);
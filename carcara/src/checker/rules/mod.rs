@@ -4,6 +4,7 @@ use super::{
 };
 use crate::{
     ast::*,
+    rare::RareRules,
     utils::{Range, TypeName},
 };
 use std::time::Duration;
@@ -25,6 +26,9 @@ pub struct RuleArgs<'a> {
     pub(super) previous_command: Option<Premise<'a>>,
     pub(super) discharge: &'a [&'a ProofCommand],
 
+    // The RARE rules used to check `rare_rewrite` steps, if any were loaded
+    pub(super) rare_rules: Option<&'a RareRules>,
+
     pub(super) polyeq_time: &'a mut Duration,
 }
 
@@ -157,15 +161,18 @@ fn assert_is_bool_constant(got: &Rc<Term>, expected: bool) -> RuleResult {
 
 // Since the rule submodules use the `rassert!` macro, we have to declare them here, after the
 // macro is declared
+pub(super) mod arrays;
 pub(super) mod bitvectors;
 pub(super) mod clausification;
 pub(super) mod congruence;
 pub(super) mod cutting_planes;
+pub(super) mod datatypes;
 pub(super) mod drup;
 pub(super) mod extras;
 pub(super) mod linear_arithmetic;
 pub(super) mod pb_blasting;
 pub(super) mod quantifier;
+pub(super) mod rare;
 pub(super) mod reflexivity;
 pub(super) mod resolution;
 pub(super) mod simplification;
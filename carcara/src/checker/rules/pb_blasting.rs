@@ -1,7 +1,10 @@
-use super::{assert_eq, assert_num_args, RuleArgs, RuleResult};
+use super::{assert_clause_len, assert_eq, assert_num_args, RuleArgs, RuleResult};
 use crate::{
     ast::{Binder, Rc, Sort, Term, TermPool},
-    checker::{error::CheckerError, rules::cutting_planes::split_summation},
+    checker::{
+        error::{CheckerError, EqualityError, PbBlastingError},
+        rules::cutting_planes::split_summation,
+    },
 };
 use rug::Integer;
 
@@ -9,13 +12,10 @@ use rug::Integer;
 fn get_bit_width(x: &Rc<Term>, pool: &mut dyn TermPool) -> Result<usize, CheckerError> {
     // Get bit width of `x`
     let Sort::BitVec(n) = pool.sort(x).as_sort().cloned().unwrap() else {
-        return Err(CheckerError::Explanation(
-            "Was not able to get the bitvector sort".into(),
-        ));
+        return Err(PbBlastingError::NotABitvector(x.clone()).into());
     };
-    n.to_usize().ok_or(CheckerError::Explanation(format!(
-        "Failed to convert value {n} to usize"
-    )))
+    n.to_usize()
+        .ok_or_else(|| PbBlastingError::WidthTooLarge(n).into())
 }
 
 // Helper to check that a summation has the expected shape
@@ -30,11 +30,7 @@ fn check_pbblast_sum(
     // The summation must have at most as many summands as the bitvector has bits.
     rassert!(
         width >= sum.len(),
-        CheckerError::Explanation(format!(
-            "Mismatched number of summands {} and bits {}",
-            width,
-            sum.len()
-        ))
+        PbBlastingError::TooManySummands { bits: width, summands: sum.len() }
     );
 
     for (i, element) in sum.iter().enumerate() {
@@ -47,15 +43,11 @@ fn check_pbblast_sum(
                     match match_term!(((_ int_of idx) bitvector) = element) {
                         Some((idx, bv)) => (Integer::from(1), idx, bv),
                         None => {
-                            return Err(CheckerError::Explanation(
-                                "Summand does not match either pattern".into(),
-                            ));
+                            return Err(PbBlastingError::MalformedSummand(element.clone()).into());
                         }
                     }
                 } else {
-                    return Err(CheckerError::Explanation(
-                        "Coefficient was not found and i != 0".into(),
-                    ));
+                    return Err(PbBlastingError::MalformedSummand(element.clone()).into());
                 }
             }
         };
@@ -65,17 +57,20 @@ fn check_pbblast_sum(
         // Check that the coefficient is 2^i.
         rassert!(
             c == (Integer::from(1) << i),
-            CheckerError::Explanation(format!("Coefficient {} is not 2^{}", c, i))
+            PbBlastingError::CoefficientNotPowerOfTwo { exponent: i, got: c }
         );
         // Check that the index is i.
         rassert!(
             idx == i,
-            CheckerError::Explanation(format!("Index {} is not {}", idx, i))
+            PbBlastingError::WrongIndex { expected: i, got: idx }
         );
         // Finally, the bitvector in the summand must be the one we expect.
         rassert!(
             *bv == *bitvector,
-            CheckerError::Explanation(format!("Wrong bitvector in blasting {} {}", bv, bitvector))
+            EqualityError::ExpectedToBe {
+                expected: bitvector.clone(),
+                got: bv.clone()
+            }
         );
     }
     Ok(())
@@ -87,11 +82,10 @@ fn check_pbblast_sum_short_circuit(pbbterm: &[Rc<Term>], sum: &[Rc<Term>]) -> Ru
     // The summation must have at most as many summands as the bitvector has bits.
     rassert!(
         pbbterm.len() >= sum.len(),
-        CheckerError::Explanation(format!(
-            "Mismatched number of summands {} and bits {}",
-            pbbterm.len(),
-            sum.len()
-        ))
+        PbBlastingError::TooManySummands {
+            bits: pbbterm.len(),
+            summands: sum.len(),
+        }
     );
 
     for (i, element) in sum.iter().enumerate() {
@@ -102,9 +96,7 @@ fn check_pbblast_sum_short_circuit(pbbterm: &[Rc<Term>], sum: &[Rc<Term>]) -> Ru
                 if i == 0 {
                     (Integer::from(1), element)
                 } else {
-                    return Err(CheckerError::Explanation(
-                        "Coefficient was not found and i != 0".into(),
-                    ));
+                    return Err(PbBlastingError::MalformedSummand(element.clone()).into());
                 }
             }
         };
@@ -112,7 +104,7 @@ fn check_pbblast_sum_short_circuit(pbbterm: &[Rc<Term>], sum: &[Rc<Term>]) -> Ru
         // Check that the coefficient is 2^i.
         rassert!(
             c == (Integer::from(1) << i),
-            CheckerError::Explanation(format!("Coefficient {} is not 2^{}", c, i))
+            PbBlastingError::CoefficientNotPowerOfTwo { exponent: i, got: c }
         );
 
         assert_eq(bv, &pbbterm[i])?;
@@ -224,14 +216,14 @@ fn check_pbblast_signed_relation(n: usize, sign: &Rc<Term>, bitvector: &Rc<Term>
     if let Some(pbb) = match_term!((pbbterm ...) = bitvector) {
         let last = pbb
             .last()
-            .ok_or(CheckerError::Explanation("No sign bit in pbbterm".into()))?;
+            .ok_or_else(|| PbBlastingError::MissingSignBit(bitvector.clone()))?;
         let (coeff, sign_bit) = match_term_err!((* coeff sign_bit) = sign)?;
         let coeff = coeff.as_integer_err()?;
 
         // Check that the coefficient is 2^(n-1)
         rassert!(
             coeff == (Integer::from(1) << (n - 1)), // 2^(n-1)
-            CheckerError::Explanation(format!("Expected coefficient 2^{} got {coeff}", (n - 1)))
+            PbBlastingError::CoefficientNotPowerOfTwo { exponent: n - 1, got: coeff }
         );
         return assert_eq(sign_bit, last);
     }
@@ -244,19 +236,22 @@ fn check_pbblast_signed_relation(n: usize, sign: &Rc<Term>, bitvector: &Rc<Term>
     // Check that the coefficient is 2^(n-1)
     rassert!(
         coeff == (Integer::from(1) << (n - 1)), // 2^(n-1)
-        CheckerError::Explanation(format!("Expected coefficient 2^{} got {coeff}", (n - 1)))
+        PbBlastingError::CoefficientNotPowerOfTwo { exponent: n - 1, got: coeff }
     );
 
     // Check that the index is n-1.
     rassert!(
         idx == n - 1,
-        CheckerError::Explanation(format!("Index {} is not {}", idx, n - 1))
+        PbBlastingError::WrongIndex { expected: n - 1, got: idx }
     );
 
     // Finally, the bitvector in the term must be the one we expect.
     rassert!(
         *bv == *bitvector,
-        CheckerError::Explanation(format!("Wrong bitvector in sign bit {} {}", bv, bitvector))
+        EqualityError::ExpectedToBe {
+            expected: bitvector.clone(),
+            got: bv.clone()
+        }
     );
 
     Ok(())
@@ -360,12 +355,15 @@ pub fn pbblast_pbbvar(RuleArgs { conclusion, .. }: RuleArgs) -> RuleResult {
         // Check that the index is `i`.
         rassert!(
             idx == i,
-            CheckerError::Explanation(format!("Index {} is not {}", idx, i))
+            PbBlastingError::WrongIndex { expected: i, got: idx }
         );
         // Finally, the bitvector in the summand must be the one we expect.
         rassert!(
             *bv == *x,
-            CheckerError::Explanation(format!("Mismatched bitvectors {} {}", bv, x))
+            EqualityError::ExpectedToBe {
+                expected: x.clone(),
+                got: bv.clone()
+            }
         );
     }
     Ok(())
@@ -373,23 +371,18 @@ pub fn pbblast_pbbvar(RuleArgs { conclusion, .. }: RuleArgs) -> RuleResult {
 
 /// Implements the blasting of a constant
 pub fn pbblast_pbbconst(RuleArgs { conclusion, .. }: RuleArgs) -> RuleResult {
-    let (bv, pbs) = match_term_err!((= bv (pbbterm ...)) = &conclusion[0])
-        .map_err(|_| CheckerError::Explanation("Malformed @pbbterm equality".into()))?;
+    let (bv, pbs) = match_term_err!((= bv (pbbterm ...)) = &conclusion[0])?;
 
-    let (m, w) = bv.as_bitvector().ok_or(CheckerError::Explanation(
-        "Expected bitvector constant".into(),
-    ))?;
+    let (m, w) = bv
+        .as_bitvector()
+        .ok_or_else(|| CheckerError::TermOfWrongForm("(_ bvN w)", bv.clone()))?;
 
     let size = w
         .to_usize()
-        .ok_or(CheckerError::Explanation("Invalid bitvector width".into()))?;
+        .ok_or_else(|| PbBlastingError::WidthTooLarge(w.clone()))?;
 
     if pbs.len() != size {
-        return Err(CheckerError::Explanation(format!(
-            "Expected {} @pbbterms, got {}",
-            size,
-            pbs.len()
-        )));
+        return Err(PbBlastingError::WrongNumberOfBits(size, pbs.len()).into());
     }
 
     let computed_value = pbs
@@ -398,10 +391,7 @@ pub fn pbblast_pbbconst(RuleArgs { conclusion, .. }: RuleArgs) -> RuleResult {
         .try_fold(Integer::new(), |acc, (i, term)| {
             let pb = term
                 .as_integer()
-                .ok_or(CheckerError::Explanation(format!(
-                    "Non-integer term at position {}",
-                    i
-                )))?
+                .ok_or_else(|| PbBlastingError::InvalidBit(term.clone()))?
                 .to_i32_wrapping();
 
             match pb {
@@ -411,18 +401,16 @@ pub fn pbblast_pbbconst(RuleArgs { conclusion, .. }: RuleArgs) -> RuleResult {
                     let increment = Integer::i_pow_u(2, exponent);
                     Ok(&acc + Integer::from(increment))
                 }
-                _ => Err(CheckerError::Explanation(format!(
-                    "Invalid value {} at position {}",
-                    pb, i
-                ))),
+                _ => Err(PbBlastingError::InvalidBit(term.clone())),
             }
         })?;
 
     if computed_value != m {
-        return Err(CheckerError::Explanation(format!(
-            "Computed value {} != declared value {}",
-            computed_value, m
-        )));
+        return Err(PbBlastingError::WrongConstantValue {
+            expected: m,
+            computed: computed_value,
+        }
+        .into());
     }
 
     Ok(())
@@ -434,20 +422,23 @@ pub fn pbblast_pbbconst(RuleArgs { conclusion, .. }: RuleArgs) -> RuleResult {
 ///
 /// Ex: `get_bitvector_terms((pbbterm @x0 @x1), 2)`
 /// >>> `[@x0, @x1]`
-fn get_bitvector_terms(bv: &Rc<Term>, pool: &mut dyn TermPool) -> Vec<Rc<Term>> {
+fn get_bitvector_terms(
+    bv: &Rc<Term>,
+    pool: &mut dyn TermPool,
+) -> Result<Vec<Rc<Term>>, CheckerError> {
     if let Some(xs) = match_term!((pbbterm ...) = bv) {
-        xs.to_vec()
+        Ok(xs.to_vec())
     } else {
         // Get bit width of `x`
-        let n = get_bit_width(bv, pool).unwrap();
-        (0..n)
+        let n = get_bit_width(bv, pool)?;
+        Ok((0..n)
             .map(|i| {
                 build_term!(
                     pool,
                     ((_ int_of { pool.add(Term::new_int(i)) }) { bv.clone() })
                 )
             })
-            .collect()
+            .collect())
     }
 }
 
@@ -456,8 +447,8 @@ pub fn pbblast_bvxor(RuleArgs { pool, conclusion, .. }: RuleArgs) -> RuleResult
     let ((x, y), bit_constraints) =
         match_term_err!((= (bvxor x y) (pbbterm ...)) = &conclusion[0])?;
 
-    let xs = get_bitvector_terms(x, pool);
-    let ys = get_bitvector_terms(y, pool);
+    let xs = get_bitvector_terms(x, pool)?;
+    let ys = get_bitvector_terms(y, pool)?;
 
     // Zip three lists into tuples
     for ((bc, xi), yi) in bit_constraints.iter().zip(xs.iter()).zip(ys.iter()) {
@@ -467,11 +458,11 @@ pub fn pbblast_bvxor(RuleArgs { pool, conclusion, .. }: RuleArgs) -> RuleResult
         let (z_name, z_type) = &bindings[0];
         rassert!(
             z_name == "z",
-            CheckerError::Explanation(format!("Expected {z_name} to be \"z\""))
+            PbBlastingError::WrongChoiceVariableName(z_name.clone())
         );
         rassert!(
             *z_type.as_sort().unwrap() == Sort::Int,
-            CheckerError::Explanation(format!("Expected {z_type} to be Sort::Int"))
+            PbBlastingError::WrongChoiceVariableSort(z_type.clone())
         );
 
         // c1 : (>= (+ xi yi) z)
@@ -480,7 +471,7 @@ pub fn pbblast_bvxor(RuleArgs { pool, conclusion, .. }: RuleArgs) -> RuleResult
         assert_eq(yic, yi)?;
         rassert!(
             zc.as_var() == Some(z_name) && pool.sort(zc) == *z_type,
-            CheckerError::Explanation(format!("Expected {z_name} but got {zc}"))
+            PbBlastingError::ExpectedChoiceVariable(z_name.clone(), zc.clone())
         );
 
         // c2 : (>= (+ z xi) yi)
@@ -489,7 +480,7 @@ pub fn pbblast_bvxor(RuleArgs { pool, conclusion, .. }: RuleArgs) -> RuleResult
         assert_eq(yic, yi)?;
         rassert!(
             zc.as_var() == Some(z_name) && pool.sort(zc) == *z_type,
-            CheckerError::Explanation(format!("Expected {z_name} but got {zc}"))
+            PbBlastingError::ExpectedChoiceVariable(z_name.clone(), zc.clone())
         );
 
         // c3 : (>= (+ z yi) xi)
@@ -498,7 +489,7 @@ pub fn pbblast_bvxor(RuleArgs { pool, conclusion, .. }: RuleArgs) -> RuleResult
         assert_eq(yic, yi)?;
         rassert!(
             zc.as_var() == Some(z_name) && pool.sort(zc) == *z_type,
-            CheckerError::Explanation(format!("Expected {z_name} but got {zc}"))
+            PbBlastingError::ExpectedChoiceVariable(z_name.clone(), zc.clone())
         );
 
         // c4 : (>= 2 (+ z xi yi)
@@ -507,7 +498,7 @@ pub fn pbblast_bvxor(RuleArgs { pool, conclusion, .. }: RuleArgs) -> RuleResult
         assert_eq(yic, yi)?;
         rassert!(
             zc.as_var() == Some(z_name) && pool.sort(zc) == *z_type,
-            CheckerError::Explanation(format!("Expected {z_name} but got {zc}"))
+            PbBlastingError::ExpectedChoiceVariable(z_name.clone(), zc.clone())
         );
     }
 
@@ -519,8 +510,8 @@ pub fn pbblast_bvand(RuleArgs { pool, conclusion, .. }: RuleArgs) -> RuleResult
     let ((x, y), bit_constraints) =
         match_term_err!((= (bvand x y) (pbbterm ...)) = &conclusion[0])?;
 
-    let xs = get_bitvector_terms(x, pool);
-    let ys = get_bitvector_terms(y, pool);
+    let xs = get_bitvector_terms(x, pool)?;
+    let ys = get_bitvector_terms(y, pool)?;
 
     // Zip three lists into tuples
     for ((bc, xi), yi) in bit_constraints.iter().zip(xs.iter()).zip(ys.iter()) {
@@ -530,11 +521,11 @@ pub fn pbblast_bvand(RuleArgs { pool, conclusion, .. }: RuleArgs) -> RuleResult
         let (z_name, z_type) = &bindings[0];
         rassert!(
             z_name == "z",
-            CheckerError::Explanation(format!("Expected {z_name} to be \"z\""))
+            PbBlastingError::WrongChoiceVariableName(z_name.clone())
         );
         rassert!(
             *z_type.as_sort().unwrap() == Sort::Int,
-            CheckerError::Explanation(format!("Expected {z_type} to be Sort::Int"))
+            PbBlastingError::WrongChoiceVariableSort(z_type.clone())
         );
 
         // c1 : (>= @x0 z)
@@ -542,7 +533,7 @@ pub fn pbblast_bvand(RuleArgs { pool, conclusion, .. }: RuleArgs) -> RuleResult
         assert_eq(xic, xi)?;
         rassert!(
             zc.as_var() == Some(z_name) && pool.sort(zc) == *z_type,
-            CheckerError::Explanation(format!("Expected {z_name} but got {zc}"))
+            PbBlastingError::ExpectedChoiceVariable(z_name.clone(), zc.clone())
         );
 
         // c2 : (>= @y0 z)
@@ -550,14 +541,14 @@ pub fn pbblast_bvand(RuleArgs { pool, conclusion, .. }: RuleArgs) -> RuleResult
         assert_eq(yic, yi)?;
         rassert!(
             zc.as_var() == Some(z_name) && pool.sort(zc) == *z_type,
-            CheckerError::Explanation(format!("Expected {z_name} but got {zc}"))
+            PbBlastingError::ExpectedChoiceVariable(z_name.clone(), zc.clone())
         );
 
         // c3 : (>= (+ z 1) (+ @x0 @y0))
         let ((zc, _), (xic, yic)) = match_term_err!((>= (+ z 1) (+ xi yi)) = c3)?;
         rassert!(
             zc.as_var() == Some(z_name) && pool.sort(zc) == *z_type,
-            CheckerError::Explanation(format!("Expected {z_name} but got {zc}"))
+            PbBlastingError::ExpectedChoiceVariable(z_name.clone(), zc.clone())
         );
         assert_eq(xic, xi)?;
         assert_eq(yic, yi)?;
@@ -648,3 +639,278 @@ pub fn pbblast_bvand_ith_bit(RuleArgs { args, pool, conclusion, .. }: RuleArgs)
 
     Ok(())
 }
+
+/// Checks that `res` is the `@pbbterm` application of the `expected` bits.
+fn assert_pbb_bits(expected: &[Rc<Term>], res: &Rc<Term>) -> RuleResult {
+    let bits = match_term_err!((pbbterm ...) = res)?;
+    rassert!(
+        bits.len() == expected.len(),
+        PbBlastingError::WrongNumberOfBits(expected.len(), bits.len())
+    );
+    for (got, expected) in bits.iter().zip(expected) {
+        assert_eq(got, expected)?;
+    }
+    Ok(())
+}
+
+/// Builds the conjunction of the bits `a` and `b`, in the same form used by `pbblast_bvand`.
+fn pb_and(pool: &mut dyn TermPool, a: &Rc<Term>, b: &Rc<Term>) -> Rc<Term> {
+    build_term!(pool, (choice (("z" Int)) (and
+        (>= {a.clone()} (let z Int))
+        (>= {b.clone()} (let z Int))
+        (>= (+ (let z Int) 1) (+ {a.clone()} {b.clone()}))
+    )))
+}
+
+/// Builds the carry out of the column sum `s`, which is the `z` such that `2z <= s <= 2z + 1`.
+fn pb_carry(pool: &mut dyn TermPool, s: &Rc<Term>) -> Rc<Term> {
+    build_term!(pool, (choice (("z" Int)) (and
+        (>= {s.clone()} (* 2 (let z Int)))
+        (>= (+ (* 2 (let z Int)) 1) {s.clone()})
+    )))
+}
+
+/// Builds the sum bit of the column sum `s`, given its carry out.
+fn pb_sum_bit(pool: &mut dyn TermPool, s: &Rc<Term>, carry: &Rc<Term>) -> Rc<Term> {
+    build_term!(pool, (choice (("z" Int)) (= {s.clone()} (+ (let z Int) (* 2 {carry.clone()})))))
+}
+
+/// Builds the bit that is equal to `a` if `sel` is 1, and to `b` otherwise.
+fn pb_mux(pool: &mut dyn TermPool, sel: &Rc<Term>, a: &Rc<Term>, b: &Rc<Term>) -> Rc<Term> {
+    let (sel, a, b) = (sel.clone(), a.clone(), b.clone());
+    build_term!(pool, (choice (("z" Int)) (and
+        (>= (+ (let z Int) 1) (+ {a.clone()} {sel.clone()}))
+        (>= (+ {a} 1) (+ (let z Int) {sel.clone()}))
+        (>= (+ (let z Int) {sel.clone()}) {b.clone()})
+        (>= (+ {b} {sel}) (let z Int))
+    )))
+}
+
+fn pb_not_bits(pool: &mut dyn TermPool, x: &[Rc<Term>]) -> Vec<Rc<Term>> {
+    x.iter()
+        .map(|b| build_term!(pool, (- 1 {b.clone()})))
+        .collect()
+}
+
+/// Builds a ripple-carry adder for `x` and `y`, with the given carry in. For each bit, the column
+/// sum `(+ x_i y_i c_i)` is split into the sum bit and the carry out.
+fn pb_adder(
+    pool: &mut dyn TermPool,
+    x: &[Rc<Term>],
+    y: &[Rc<Term>],
+    carry_in: Rc<Term>,
+) -> Vec<Rc<Term>> {
+    let mut carry = carry_in;
+    x.iter()
+        .zip(y)
+        .map(|(a, b)| {
+            let s = build_term!(pool, (+ {a.clone()} {b.clone()} {carry.clone()}));
+            carry = pb_carry(pool, &s);
+            pb_sum_bit(pool, &s, &carry)
+        })
+        .collect()
+}
+
+/// Builds a shift-and-add multiplier, similarly to the one used in bit-blasting. Each partial
+/// product `x * y[k]` is added to the bits of the result starting at bit `k`.
+fn pb_multiplier(pool: &mut dyn TermPool, x: &[Rc<Term>], y: &[Rc<Term>]) -> Vec<Rc<Term>> {
+    let size = x.len();
+    let mut res: Vec<_> = x.iter().map(|b| pb_and(pool, b, &y[0])).collect();
+    for k in 1..size {
+        let mut carry = pool.add(Term::new_int(0));
+        for j in 0..size - k {
+            let p = pb_and(pool, &x[j], &y[k]);
+            let s = build_term!(pool, (+ {res[j + k].clone()} {p} {carry}));
+            carry = pb_carry(pool, &s);
+            res[j + k] = pb_sum_bit(pool, &s, &carry);
+        }
+    }
+    res
+}
+
+#[derive(Clone, Copy)]
+enum ShiftKind {
+    Left,
+    LogicalRight,
+    ArithmeticRight,
+}
+
+/// Builds a barrel shifter, similarly to the one used in bit-blasting. At each stage, the bits are
+/// shifted by a power of two if the corresponding bit of `y` is set. If any of the remaining bits
+/// of `y` is set, every bit is replaced by the fill bit.
+fn pb_barrel_shifter(
+    pool: &mut dyn TermPool,
+    kind: ShiftKind,
+    x: &[Rc<Term>],
+    y: &[Rc<Term>],
+) -> Vec<Rc<Term>> {
+    let size = x.len();
+    let fill = match kind {
+        ShiftKind::Left | ShiftKind::LogicalRight => pool.add(Term::new_int(0)),
+        ShiftKind::ArithmeticRight => x[size - 1].clone(),
+    };
+
+    let mut res = x.to_vec();
+    let mut stage = 0;
+    while (1 << stage) < size {
+        let amount = 1 << stage;
+        res = (0..size)
+            .map(|i| {
+                let shifted = match kind {
+                    ShiftKind::Left if i >= amount => &res[i - amount],
+                    ShiftKind::LogicalRight | ShiftKind::ArithmeticRight if i + amount < size => {
+                        &res[i + amount]
+                    }
+                    _ => &fill,
+                };
+                pb_mux(pool, &y[stage], shifted, &res[i])
+            })
+            .collect();
+        stage += 1;
+    }
+    for overflow in &y[stage..] {
+        res = res
+            .iter()
+            .map(|b| pb_mux(pool, overflow, &fill, b))
+            .collect();
+    }
+    res
+}
+
+/// Implements the blasting of `bvadd`. The sum is computed by a ripple-carry adder, in which the
+/// sum bit and the carry out of each column are introduced as choice terms.
+pub fn pbblast_bvadd(RuleArgs { pool, conclusion, .. }: RuleArgs) -> RuleResult {
+    assert_clause_len(conclusion, 1)?;
+    let (args, res) = match_term_err!((= (bvadd ...) res) = &conclusion[0])?;
+
+    // `bvadd` is left-associative, so we add each argument to the sum of the previous ones
+    let mut expected = get_bitvector_terms(&args[0], pool)?;
+    for arg in &args[1..] {
+        let y = get_bitvector_terms(arg, pool)?;
+        let zero = pool.add(Term::new_int(0));
+        expected = pb_adder(pool, &expected, &y, zero);
+    }
+    assert_pbb_bits(&expected, res)
+}
+
+/// Implements the blasting of `bvsub`, as `x + (- 1 y)` with a carry in of 1.
+pub fn pbblast_bvsub(RuleArgs { pool, conclusion, .. }: RuleArgs) -> RuleResult {
+    assert_clause_len(conclusion, 1)?;
+    let ((x, y), res) = match_term_err!((= (bvsub x y) res) = &conclusion[0])?;
+    let x = get_bitvector_terms(x, pool)?;
+    let y = get_bitvector_terms(y, pool)?;
+    let not_y = pb_not_bits(pool, &y);
+    let one = pool.add(Term::new_int(1));
+    let expected = pb_adder(pool, &x, &not_y, one);
+    assert_pbb_bits(&expected, res)
+}
+
+/// Implements the blasting of `bvneg`, as `(- 1 x) + 0` with a carry in of 1.
+pub fn pbblast_bvneg(RuleArgs { pool, conclusion, .. }: RuleArgs) -> RuleResult {
+    assert_clause_len(conclusion, 1)?;
+    let (x, res) = match_term_err!((= (bvneg x) res) = &conclusion[0])?;
+    let x = get_bitvector_terms(x, pool)?;
+    let not_x = pb_not_bits(pool, &x);
+    let zeros = vec![pool.add(Term::new_int(0)); x.len()];
+    let one = pool.add(Term::new_int(1));
+    let expected = pb_adder(pool, &not_x, &zeros, one);
+    assert_pbb_bits(&expected, res)
+}
+
+/// Implements the blasting of `bvmul`. The partial products are conjunctions in the same form used
+/// by `pbblast_bvand`, and they are added using the same columns as `pbblast_bvadd`.
+pub fn pbblast_bvmul(RuleArgs { pool, conclusion, .. }: RuleArgs) -> RuleResult {
+    assert_clause_len(conclusion, 1)?;
+    let (args, res) = match_term_err!((= (bvmul ...) res) = &conclusion[0])?;
+
+    let mut expected = get_bitvector_terms(&args[0], pool)?;
+    for arg in &args[1..] {
+        let y = get_bitvector_terms(arg, pool)?;
+        expected = pb_multiplier(pool, &expected, &y);
+    }
+    assert_pbb_bits(&expected, res)
+}
+
+fn check_pbblast_shift(
+    pool: &mut dyn TermPool,
+    kind: ShiftKind,
+    x: &Rc<Term>,
+    y: &Rc<Term>,
+    res: &Rc<Term>,
+) -> RuleResult {
+    let x = get_bitvector_terms(x, pool)?;
+    let y = get_bitvector_terms(y, pool)?;
+    let expected = pb_barrel_shifter(pool, kind, &x, &y);
+    assert_pbb_bits(&expected, res)
+}
+
+/// Implements the blasting of `bvshl`, using a barrel shifter in which each bit is selected by a
+/// multiplexer.
+pub fn pbblast_bvshl(RuleArgs { pool, conclusion, .. }: RuleArgs) -> RuleResult {
+    assert_clause_len(conclusion, 1)?;
+    let ((x, y), res) = match_term_err!((= (bvshl x y) res) = &conclusion[0])?;
+    check_pbblast_shift(pool, ShiftKind::Left, x, y, res)
+}
+
+/// Implements the blasting of `bvlshr`.
+pub fn pbblast_bvlshr(RuleArgs { pool, conclusion, .. }: RuleArgs) -> RuleResult {
+    assert_clause_len(conclusion, 1)?;
+    let ((x, y), res) = match_term_err!((= (bvlshr x y) res) = &conclusion[0])?;
+    check_pbblast_shift(pool, ShiftKind::LogicalRight, x, y, res)
+}
+
+/// Implements the blasting of `bvashr`. Here, the bits shifted in are copies of the sign bit.
+pub fn pbblast_bvashr(RuleArgs { pool, conclusion, .. }: RuleArgs) -> RuleResult {
+    assert_clause_len(conclusion, 1)?;
+    let ((x, y), res) = match_term_err!((= (bvashr x y) res) = &conclusion[0])?;
+    check_pbblast_shift(pool, ShiftKind::ArithmeticRight, x, y, res)
+}
+
+/// Implements the blasting of `concat`. The first argument holds the most significant bits, so the
+/// bits of the result start with the bits of the last argument.
+pub fn pbblast_concat(RuleArgs { pool, conclusion, .. }: RuleArgs) -> RuleResult {
+    assert_clause_len(conclusion, 1)?;
+    let (args, res) = match_term_err!((= (concat ...) res) = &conclusion[0])?;
+    let mut expected = Vec::new();
+    for arg in args.iter().rev() {
+        expected.extend(get_bitvector_terms(arg, pool)?);
+    }
+    assert_pbb_bits(&expected, res)
+}
+
+/// Implements the blasting of `extract`, whose result is the bits of `x` from `j` to `i`.
+pub fn pbblast_extract(RuleArgs { pool, conclusion, .. }: RuleArgs) -> RuleResult {
+    assert_clause_len(conclusion, 1)?;
+    let (((i, j), x), res) = match_term_err!((= ((_ extract i j) x) res) = &conclusion[0])?;
+    let (i, j) = (i.as_usize_err()?, j.as_usize_err()?);
+    let x = get_bitvector_terms(x, pool)?;
+    rassert!(
+        j <= i && i < x.len(),
+        CheckerError::TermOfWrongForm("((_ extract i j) x)", conclusion[0].clone())
+    );
+    assert_pbb_bits(&x[j..=i], res)
+}
+
+/// Implements the blasting of `zero_extend`, which adds `i` bits that are always 0.
+pub fn pbblast_zero_extend(RuleArgs { pool, conclusion, .. }: RuleArgs) -> RuleResult {
+    assert_clause_len(conclusion, 1)?;
+    let ((i, x), res) = match_term_err!((= ((_ zero_extend i) x) res) = &conclusion[0])?;
+    let i = i.as_usize_err()?;
+    let mut expected = get_bitvector_terms(x, pool)?;
+    expected.extend(std::iter::repeat_n(pool.add(Term::new_int(0)), i));
+    assert_pbb_bits(&expected, res)
+}
+
+/// Implements the blasting of `sign_extend`, which adds `i` copies of the sign bit.
+pub fn pbblast_sign_extend(RuleArgs { pool, conclusion, .. }: RuleArgs) -> RuleResult {
+    assert_clause_len(conclusion, 1)?;
+    let ((i, x), res) = match_term_err!((= ((_ sign_extend i) x) res) = &conclusion[0])?;
+    let i = i.as_usize_err()?;
+    let mut expected = get_bitvector_terms(x, pool)?;
+    let sign = expected
+        .last()
+        .ok_or_else(|| PbBlastingError::MissingSignBit(x.clone()))?
+        .clone();
+    expected.extend(std::iter::repeat_n(sign, i));
+    assert_pbb_bits(&expected, res)
+}
@@ -238,6 +238,28 @@ fn conjunctive_normal_form(term: &Rc<Term>) -> CnfFormula {
     }
 }
 
+/// Computes the clauses in the conjunctive normal form of the quantified term `phi`, after
+/// prenexing its universal quantifiers. Returns the clauses and the bindings of the prenexed term,
+/// which start with the original bindings, `l_bindings`.
+pub fn qnt_cnf_clauses(
+    pool: &mut dyn TermPool,
+    l_bindings: &BindingList,
+    phi: &Rc<Term>,
+) -> (IndexSet<SortedVar>, Vec<Rc<Term>>) {
+    let mut new_bindings = l_bindings.iter().cloned().collect::<IndexSet<_>>();
+    let nnf = negation_normal_form(pool, phi, true, &mut IndexMap::new());
+    let prenexed = prenex_forall(pool, &mut new_bindings, &nnf);
+    let clauses = conjunctive_normal_form(&prenexed)
+        .into_iter()
+        .map(|c| match c.as_slice() {
+            [] => unreachable!(),
+            [term] => term.clone(),
+            _ => pool.add(Term::Op(Operator::Or, c)),
+        })
+        .collect();
+    (new_bindings, clauses)
+}
+
 pub fn qnt_cnf(RuleArgs { conclusion, pool, .. }: RuleArgs) -> RuleResult {
     assert_clause_len(conclusion, 1)?;
 
@@ -254,19 +276,7 @@ pub fn qnt_cnf(RuleArgs { conclusion, pool, .. }: RuleArgs) -> RuleResult {
     };
 
     let r_bindings = r_bindings.iter().cloned().collect::<IndexSet<_>>();
-    let mut new_bindings = l_bindings.iter().cloned().collect::<IndexSet<_>>();
-    let clauses: Vec<_> = {
-        let nnf = negation_normal_form(pool, phi, true, &mut IndexMap::new());
-        let prenexed = prenex_forall(pool, &mut new_bindings, &nnf);
-        let cnf = conjunctive_normal_form(&prenexed);
-        cnf.into_iter()
-            .map(|c| match c.as_slice() {
-                [] => unreachable!(),
-                [term] => term.clone(),
-                _ => pool.add(Term::Op(Operator::Or, c)),
-            })
-            .collect()
-    };
+    let (new_bindings, clauses) = qnt_cnf_clauses(pool, l_bindings, phi);
 
     // `new_bindings` contains all bindings that existed in the original term, plus all bindings
     // added by the prenexing step. All bindings in the right side must be in this set
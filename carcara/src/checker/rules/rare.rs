@@ -0,0 +1,61 @@
+//! The `rare_rewrite` rule, which applies a rewrite rule from a RARE file.
+
+use super::{assert_clause_len, assert_polyeq_expected, get_premise_term, RuleArgs, RuleResult};
+use crate::{
+    ast::*,
+    checker::error::{CheckerError, RareError},
+    rare,
+};
+
+pub fn rare_rewrite(
+    RuleArgs {
+        conclusion,
+        premises,
+        args,
+        pool,
+        rare_rules,
+        polyeq_time,
+        ..
+    }: RuleArgs,
+) -> RuleResult {
+    assert_clause_len(conclusion, 1)?;
+    let rules = rare_rules.ok_or(CheckerError::UnknownRule)?;
+
+    let Some((name, args)) = args.split_first() else {
+        return Err(CheckerError::WrongNumberOfArgs((1..).into(), 0));
+    };
+    let Term::Const(Constant::String(name)) = name.as_ref() else {
+        return Err(CheckerError::TermOfWrongForm(
+            "\"<rule name>\"",
+            name.clone(),
+        ));
+    };
+    let rule = rules
+        .get(name)
+        .ok_or_else(|| RareError::UnknownRule(name.clone()))?;
+    let instance = rule.instantiate(pool, args)?;
+
+    // Solvers usually print constants computed by the rule (like the width of a bitvector) already
+    // evaluated, so if the conclusion doesn't match the instance exactly, we try again after
+    // evaluating it
+    let expected = build_term!(pool, (= {instance.lhs} {instance.rhs}));
+    if !polyeq(&conclusion[0], &expected, polyeq_time) {
+        let expected = rare::evaluate(pool, &expected);
+        assert_polyeq_expected(&conclusion[0], expected, polyeq_time)?;
+    }
+
+    // Each condition must either be proved by a premise, or be evaluated to `true`
+    let premises = premises
+        .iter()
+        .map(get_premise_term)
+        .collect::<Result<Vec<_>, _>>()?;
+    for condition in instance.conditions {
+        if premises.iter().any(|p| polyeq(p, &condition, polyeq_time)) {
+            continue;
+        }
+        if !rare::evaluate(pool, &condition).is_bool_true() {
+            return Err(RareError::UnprovenCondition(condition).into());
+        }
+    }
+    Ok(())
+}
@@ -83,91 +83,105 @@ fn generic_simplify_rule(
 }
 
 pub fn ite_simplify(args: RuleArgs) -> RuleResult {
-    generic_simplify_rule(args.conclusion, args.pool, |term, pool| {
-        simplify!(term {
-            // ite true t_1 t_2 => t_1
-            (ite true t_1 t_2): (_, t_1, _) => t_1.clone(),
-
-            // ite false t_1 t_2 => t_2
-            (ite false t_1 t_2): (_, _, t_2) => t_2.clone(),
-
-            // ite phi t t => t
-            (ite phi t t): (_, t_1, t_2) if t_1 == t_2 => t_1.clone(),
-
-            // ite psi true false => psi
-            (ite psi true false): (psi, _, _) => psi.clone(),
-
-            // ite psi false true => ¬psi
-            (ite psi false true): (psi, _, _) => build_term!(pool, (not {psi.clone()})),
-
-            // ite ¬phi t_1 t_2 => ite phi t_2 t_1
-            (ite (not phi) t_1 t_2): (phi, t_1, t_2) => {
-                build_term!(pool, (ite {phi.clone()} {t_2.clone()} {t_1.clone()}))
-            },
-
-            // ite phi (ite phi t_1 t_2) t_3 => ite phi t_1 t_3
-            (ite phi (ite phi t_1 t_2) t_3): (phi_1, (phi_2, t_1, _), t_3) if phi_1 == phi_2 => {
-                build_term!(pool, (ite {phi_1.clone()} {t_1.clone()} {t_3.clone()}))
-            },
-
-            // ite phi t_1 (ite phi t_2 t_3) => ite phi t_1 t_3
-            (ite phi t_1 (ite phi t_2 t_3)): (phi_1, t_1, (phi_2, _, t_3)) if phi_1 == phi_2 => {
-                build_term!(pool, (ite {phi_1.clone()} {t_1.clone()} {t_3.clone()}))
-            },
-
-            // ite psi true phi => psi v phi
-            (ite psi true phi): (psi, _, phi) => {
-                build_term!(pool, (or {psi.clone()} {phi.clone()}))
-            },
-
-            // ite psi phi false => psi ^ phi
-            (ite psi phi false): (psi, phi, _) => {
-                build_term!(pool, (and {psi.clone()} {phi.clone()}))
-            },
-
-            // ite psi false phi => ¬psi ^ phi
-            (ite psi false phi): (psi, _, phi) => {
-                build_term!(pool, (and (not {psi.clone()}) {phi.clone()}))
-            },
-
-            // ite psi phi true => ¬psi v phi
-            (ite psi phi true): (psi, phi, _) => {
-                build_term!(pool, (or (not {psi.clone()}) {phi.clone()}))
-            },
-        })
+    generic_simplify_rule(args.conclusion, args.pool, simplify_ite)
+}
+
+/// Applies the first `ite_simplify` transformation that matches the term, if any.
+pub fn simplify_ite(term: &Term, pool: &mut dyn TermPool) -> Option<Rc<Term>> {
+    simplify!(term {
+        // ite true t_1 t_2 => t_1
+        (ite true t_1 t_2): (_, t_1, _) => t_1.clone(),
+
+        // ite false t_1 t_2 => t_2
+        (ite false t_1 t_2): (_, _, t_2) => t_2.clone(),
+
+        // ite phi t t => t
+        (ite phi t t): (_, t_1, t_2) if t_1 == t_2 => t_1.clone(),
+
+        // ite psi true false => psi
+        (ite psi true false): (psi, _, _) => psi.clone(),
+
+        // ite psi false true => ¬psi
+        (ite psi false true): (psi, _, _) => build_term!(pool, (not {psi.clone()})),
+
+        // ite ¬phi t_1 t_2 => ite phi t_2 t_1
+        (ite (not phi) t_1 t_2): (phi, t_1, t_2) => {
+            build_term!(pool, (ite {phi.clone()} {t_2.clone()} {t_1.clone()}))
+        },
+
+        // ite phi (ite phi t_1 t_2) t_3 => ite phi t_1 t_3
+        (ite phi (ite phi t_1 t_2) t_3): (phi_1, (phi_2, t_1, _), t_3) if phi_1 == phi_2 => {
+            build_term!(pool, (ite {phi_1.clone()} {t_1.clone()} {t_3.clone()}))
+        },
+
+        // ite phi t_1 (ite phi t_2 t_3) => ite phi t_1 t_3
+        (ite phi t_1 (ite phi t_2 t_3)): (phi_1, t_1, (phi_2, _, t_3)) if phi_1 == phi_2 => {
+            build_term!(pool, (ite {phi_1.clone()} {t_1.clone()} {t_3.clone()}))
+        },
+
+        // ite psi true phi => psi v phi
+        (ite psi true phi): (psi, _, phi) => {
+            build_term!(pool, (or {psi.clone()} {phi.clone()}))
+        },
+
+        // ite psi phi false => psi ^ phi
+        (ite psi phi false): (psi, phi, _) => {
+            build_term!(pool, (and {psi.clone()} {phi.clone()}))
+        },
+
+        // ite psi false phi => ¬psi ^ phi
+        (ite psi false phi): (psi, _, phi) => {
+            build_term!(pool, (and (not {psi.clone()}) {phi.clone()}))
+        },
+
+        // ite psi phi true => ¬psi v phi
+        (ite psi phi true): (psi, phi, _) => {
+            build_term!(pool, (or (not {psi.clone()}) {phi.clone()}))
+        },
     })
 }
 
 pub fn eq_simplify(args: RuleArgs) -> RuleResult {
-    generic_simplify_rule(args.conclusion, args.pool, |term, pool| {
-        simplify!(term {
-            // t = t => true
-            (= t t): (t1, t2) if t1 == t2 => pool.bool_true(),
-
-            // t_1 = t_2 => false, if t_1 and t_2 are different numerical constants
-            (= t t): (t1, t2) if {
-                let t1 = t1.as_signed_number();
-                let t2 = t2.as_signed_number();
-                t1.is_some() && t2.is_some() && t1 != t2
-            } => pool.bool_false(),
-
-            // ¬(t = t) => false, if t is a numerical constant
-            (not (= t t)): (t1, t2) if t1 == t2 && t1.is_signed_number() => pool.bool_false(),
-        })
+    generic_simplify_rule(args.conclusion, args.pool, simplify_eq)
+}
+
+/// Applies the first `eq_simplify` transformation that matches the term, if any.
+pub fn simplify_eq(term: &Term, pool: &mut dyn TermPool) -> Option<Rc<Term>> {
+    simplify!(term {
+        // t = t => true
+        (= t t): (t1, t2) if t1 == t2 => pool.bool_true(),
+
+        // t_1 = t_2 => false, if t_1 and t_2 are different numerical constants
+        (= t t): (t1, t2) if {
+            let t1 = t1.as_signed_number();
+            let t2 = t2.as_signed_number();
+            t1.is_some() && t2.is_some() && t1 != t2
+        } => pool.bool_false(),
+
+        // ¬(t = t) => false, if t is a numerical constant
+        (not (= t t)): (t1, t2) if t1 == t2 && t1.is_signed_number() => pool.bool_false(),
     })
 }
 
-/// Used for both the `and_simplify` and `or_simplify` rules, depending on `rule_kind`. `rule_kind`
-/// has to be either `Operator::And` or `Operator::Or`.
-fn generic_and_or_simplify(
-    pool: &mut dyn TermPool,
-    conclusion: &[Rc<Term>],
-    rule_kind: Operator,
-) -> RuleResult {
-    assert_clause_len(conclusion, 1)?;
+/// The arguments of a conjunction or disjunction after each step of the `and_simplify` and
+/// `or_simplify` transformations.
+struct AndOrSimplification {
+    /// The arguments, after removing all "skip term"s.
+    without_skip_terms: Vec<Rc<Term>>,
+
+    /// The arguments, after removing all "skip term"s and duplicates.
+    deduped: Vec<Rc<Term>>,
 
+    /// Whether the conjunction or disjunction was short-circuited.
+    is_short_circuited: bool,
+}
+
+/// Applies the `and_simplify` or `or_simplify` transformations to the arguments of a conjunction or
+/// disjunction, depending on `rule_kind`. `rule_kind` has to be either `Operator::And` or
+/// `Operator::Or`.
+fn and_or_simplification(args: &[Rc<Term>], rule_kind: Operator) -> AndOrSimplification {
     // The "skip term" is the term that represents the empty conjunction or disjunction, and can be
-    // skipped. This is `false` for conjunctions and `true` disjunctions
+    // skipped. This is `true` for conjunctions and `false` disjunctions
     let skip_term = match rule_kind {
         Operator::And => true,
         Operator::Or => false,
@@ -175,16 +189,75 @@ fn generic_and_or_simplify(
     };
 
     // The "short-circuit term" is the term that can short-circuit the conjunction or disjunction.
-    // This is `true` for conjunctions and `false` for disjunctions
+    // This is `false` for conjunctions and `true` for disjunctions
     let short_circuit_term = !skip_term;
 
+    let without_skip_terms: Vec<_> = args
+        .iter()
+        .filter(|t| !t.is_bool_constant(skip_term))
+        .cloned()
+        .collect();
+    let deduped: Vec<_> = without_skip_terms.iter().dedup().cloned().collect();
+
+    // If a term is the "short-circuit term", or is the negation of another term, the result is
+    // short-circuited
+    let seen: IndexSet<(bool, &Rc<Term>)> = deduped
+        .iter()
+        .map(Rc::remove_all_negations_with_polarity)
+        .collect();
+    let is_short_circuited = deduped.iter().any(|term| {
+        let (polarity, inner) = term.remove_all_negations_with_polarity();
+        seen.contains(&(!polarity, inner)) || term.is_bool_constant(short_circuit_term)
+    });
+
+    AndOrSimplification {
+        without_skip_terms,
+        deduped,
+        is_short_circuited,
+    }
+}
+
+/// Applies the `and_simplify` or `or_simplify` transformations to the term, depending on
+/// `rule_kind`. Returns `None` if the term is not an application of `rule_kind`, or if it can't be
+/// simplified.
+pub fn simplify_and_or(
+    term: &Term,
+    pool: &mut dyn TermPool,
+    rule_kind: Operator,
+) -> Option<Rc<Term>> {
+    let args = match term {
+        Term::Op(op, args) if *op == rule_kind => args,
+        _ => return None,
+    };
+    let skip_term = rule_kind == Operator::And;
+    let AndOrSimplification { deduped, is_short_circuited, .. } =
+        and_or_simplification(args, rule_kind);
+    if is_short_circuited {
+        return Some(pool.bool_constant(!skip_term));
+    }
+    match deduped.as_slice() {
+        [] => Some(pool.bool_constant(skip_term)),
+        [single] => Some(single.clone()),
+        _ if deduped == *args => None,
+        _ => Some(pool.add(Term::Op(rule_kind, deduped))),
+    }
+}
+
+/// Used for both the `and_simplify` and `or_simplify` rules, depending on `rule_kind`. `rule_kind`
+/// has to be either `Operator::And` or `Operator::Or`.
+fn generic_and_or_simplify(
+    pool: &mut dyn TermPool,
+    conclusion: &[Rc<Term>],
+    rule_kind: Operator,
+) -> RuleResult {
+    assert_clause_len(conclusion, 1)?;
+
     let (phis, result_term) = match_term_err!((= phi psi) = &conclusion[0])?;
     let mut phis = match rule_kind {
         Operator::And => match_term_err!((and ...) = phis),
         Operator::Or => match_term_err!((or ...) = phis),
         _ => unreachable!(),
-    }?
-    .to_vec();
+    }?;
     let result_args = match result_term.as_ref() {
         Term::Op(op, args) if *op == rule_kind => args,
         _ => std::slice::from_ref(result_term),
@@ -195,67 +268,44 @@ fn generic_and_or_simplify(
     // If we encounter this, we remove the outer application
     if phis.len() == 1 {
         match phis[0].as_ref() {
-            Term::Op(op, args) if *op == rule_kind => phis = args.clone(),
+            Term::Op(op, args) if *op == rule_kind => phis = args,
             _ => (),
         }
     }
 
-    // First, we remove all "skip term"s from the arguments. If at this point we already found the
-    // result, we return true. While doing this before the main loop requires an additional
-    // allocation for the `phis` vector, it allows us to exit early in some cases, which overall
-    // improves performance significantly. More importantly, it is necessary in some examples,
-    // where not all steps of the simplification are applied
-    phis.retain(|t| !t.is_bool_constant(skip_term));
-    if result_args.iter().eq(&phis) {
-        return Ok(());
-    }
+    let AndOrSimplification {
+        without_skip_terms,
+        deduped,
+        is_short_circuited,
+    } = and_or_simplification(phis, rule_kind);
 
-    // Then, we remove all duplicate terms. We do this in place to avoid another allocation.
-    // Similarly to the step that removes the "skip term", we check if we already found the result
-    // after this step. This is also necessary in some examples
-    let mut seen = IndexSet::with_capacity(phis.len());
-    phis.retain(|t| seen.insert(t.clone()));
-    if result_args.iter().eq(&phis) {
+    // In some examples, not all steps of the simplification are applied, so we also accept the
+    // result after only removing the "skip term"s, or after only removing the duplicates
+    if result_args.iter().eq(&without_skip_terms) || result_args.iter().eq(&deduped) {
         return Ok(());
     }
 
-    // Finally, we check to see if the result was short-circuited
-    let seen: IndexSet<(bool, &Rc<Term>)> = phis
-        .iter()
-        .map(Rc::remove_all_negations_with_polarity)
-        .collect();
-    for term in &phis {
-        // If the term is the "short-circuit term", or is the negation of a term previously
-        // encountered, the result is short-circuited
-        let (polarity, inner) = term.remove_all_negations_with_polarity();
-        if seen.contains(&(!polarity, inner)) || term.is_bool_constant(short_circuit_term) {
-            return if result_args.len() == 1 {
-                assert_is_bool_constant(&result_args[0], short_circuit_term)
-            } else {
-                Err(CheckerError::ExpectedBoolConstant(
-                    short_circuit_term,
-                    result_term.clone(),
-                ))
-            };
-        }
-    }
-
-    if phis.is_empty() {
-        // If the filtered conjunction or disjunction is empty, the expected result is just the
-        // "skip term", which represents an empty conjunction or disjunction
-        if result_args.len() == 1 {
-            assert_is_bool_constant(&result_args[0], skip_term)
-        } else {
-            Err(CheckerError::ExpectedBoolConstant(
-                skip_term,
-                result_term.clone(),
-            ))
-        }
-    } else if result_args.iter().eq(&phis) {
-        Ok(())
+    // If the result was short-circuited, the expected result is the "short-circuit term". If the
+    // filtered conjunction or disjunction is empty, the expected result is the "skip term", which
+    // represents an empty conjunction or disjunction
+    let skip_term = rule_kind == Operator::And;
+    let expected_constant = if is_short_circuited {
+        Some(!skip_term)
+    } else if deduped.is_empty() {
+        Some(skip_term)
     } else {
-        let expected = pool.add(Term::Op(rule_kind, phis));
-        Err(EqualityError::ExpectedToBe { expected, got: result_term.clone() }.into())
+        None
+    };
+    match expected_constant {
+        Some(value) if result_args.len() == 1 => assert_is_bool_constant(&result_args[0], value),
+        Some(value) => Err(CheckerError::ExpectedBoolConstant(
+            value,
+            result_term.clone(),
+        )),
+        None => {
+            let expected = pool.add(Term::Op(rule_kind, deduped));
+            Err(EqualityError::ExpectedToBe { expected, got: result_term.clone() }.into())
+        }
     }
 }
 
@@ -268,127 +318,139 @@ pub fn or_simplify(RuleArgs { conclusion, pool, .. }: RuleArgs) -> RuleResult {
 }
 
 pub fn not_simplify(args: RuleArgs) -> RuleResult {
-    generic_simplify_rule(args.conclusion, args.pool, |term, pool| {
-        simplify!(term {
-            // ¬(¬phi) => phi
-            (not (not phi)): phi => phi.clone(),
+    generic_simplify_rule(args.conclusion, args.pool, simplify_not)
+}
 
-            // ¬false => true
-            (not false): _ => pool.bool_true(),
+/// Applies the first `not_simplify` transformation that matches the term, if any.
+pub fn simplify_not(term: &Term, pool: &mut dyn TermPool) -> Option<Rc<Term>> {
+    simplify!(term {
+        // ¬(¬phi) => phi
+        (not (not phi)): phi => phi.clone(),
 
-            // ¬true => false
-            (not true): _ => pool.bool_false(),
-        })
+        // ¬false => true
+        (not false): _ => pool.bool_true(),
+
+        // ¬true => false
+        (not true): _ => pool.bool_false(),
     })
 }
 
 pub fn implies_simplify(args: RuleArgs) -> RuleResult {
-    generic_simplify_rule(args.conclusion, args.pool, |term, pool| {
-        simplify!(term {
-            // ¬phi_1 -> ¬phi_2 => phi_2 -> phi_1
-            (=> (not phi_1) (not phi_2)): (phi_1, phi_2) => {
-                build_term!(pool, (=> {phi_2.clone()} {phi_1.clone()}))
-            },
-
-            // false -> phi => true
-            (=> false phi): _ => pool.bool_true(),
-
-            // phi -> true => true
-            (=> phi true): _ => pool.bool_true(),
-
-            // true -> phi => phi
-            (=> true phi): (_, phi) => phi.clone(),
-
-            // phi -> false => ¬phi
-            (=> phi false): (phi, _) => build_term!(pool, (not {phi.clone()})),
-
-            // phi -> phi => true
-            (=> phi phi): (phi_1, phi_2) if phi_1 == phi_2 => pool.bool_true(),
-
-            // ¬phi -> phi => phi
-            // phi -> ¬phi => ¬phi
-            (=> phi_1 phi_2): (phi_1, phi_2) if {
-                phi_1.remove_negation() == Some(phi_2) || phi_2.remove_negation() == Some(phi_1)
-            } => phi_2.clone(),
-
-            // (phi_1 -> phi_2) -> phi_2 => phi_1 v phi_2
-            (=> (=> phi_1 phi_2) phi_3): ((phi_1, phi_2), phi_3) if phi_2 == phi_3 => {
-                build_term!(pool, (or {phi_1.clone()} {phi_2.clone()}))
-            },
-        })
+    generic_simplify_rule(args.conclusion, args.pool, simplify_implies)
+}
+
+/// Applies the first `implies_simplify` transformation that matches the term, if any.
+pub fn simplify_implies(term: &Term, pool: &mut dyn TermPool) -> Option<Rc<Term>> {
+    simplify!(term {
+        // ¬phi_1 -> ¬phi_2 => phi_2 -> phi_1
+        (=> (not phi_1) (not phi_2)): (phi_1, phi_2) => {
+            build_term!(pool, (=> {phi_2.clone()} {phi_1.clone()}))
+        },
+
+        // false -> phi => true
+        (=> false phi): _ => pool.bool_true(),
+
+        // phi -> true => true
+        (=> phi true): _ => pool.bool_true(),
+
+        // true -> phi => phi
+        (=> true phi): (_, phi) => phi.clone(),
+
+        // phi -> false => ¬phi
+        (=> phi false): (phi, _) => build_term!(pool, (not {phi.clone()})),
+
+        // phi -> phi => true
+        (=> phi phi): (phi_1, phi_2) if phi_1 == phi_2 => pool.bool_true(),
+
+        // ¬phi -> phi => phi
+        // phi -> ¬phi => ¬phi
+        (=> phi_1 phi_2): (phi_1, phi_2) if {
+            phi_1.remove_negation() == Some(phi_2) || phi_2.remove_negation() == Some(phi_1)
+        } => phi_2.clone(),
+
+        // (phi_1 -> phi_2) -> phi_2 => phi_1 v phi_2
+        (=> (=> phi_1 phi_2) phi_3): ((phi_1, phi_2), phi_3) if phi_2 == phi_3 => {
+            build_term!(pool, (or {phi_1.clone()} {phi_2.clone()}))
+        },
     })
 }
 
 pub fn equiv_simplify(args: RuleArgs) -> RuleResult {
-    generic_simplify_rule(args.conclusion, args.pool, |term, pool| {
-        simplify!(term {
-            // ¬phi_1 = ¬phi_2 => phi_1 = phi_2
-            (= (not phi_1) (not phi_2)): (phi_1, phi_2) => {
-                build_term!(pool, (= {phi_1.clone()} {phi_2.clone()}))
-            },
+    generic_simplify_rule(args.conclusion, args.pool, simplify_equiv)
+}
 
-            // phi = phi => true
-            (= phi_1 phi_2): (phi_1, phi_2) if phi_1 == phi_2 => pool.bool_true(),
+/// Applies the first `equiv_simplify` transformation that matches the term, if any.
+pub fn simplify_equiv(term: &Term, pool: &mut dyn TermPool) -> Option<Rc<Term>> {
+    simplify!(term {
+        // ¬phi_1 = ¬phi_2 => phi_1 = phi_2
+        (= (not phi_1) (not phi_2)): (phi_1, phi_2) => {
+            build_term!(pool, (= {phi_1.clone()} {phi_2.clone()}))
+        },
 
-            // phi = ¬phi => false
-            (= phi_1 (not phi_2)): (phi_1, phi_2) if phi_1 == phi_2 => pool.bool_false(),
+        // phi = phi => true
+        (= phi_1 phi_2): (phi_1, phi_2) if phi_1 == phi_2 => pool.bool_true(),
 
-            // ¬phi = phi => false
-            (= (not phi_1) phi_2): (phi_1, phi_2) if phi_1 == phi_2 => pool.bool_false(),
+        // phi = ¬phi => false
+        (= phi_1 (not phi_2)): (phi_1, phi_2) if phi_1 == phi_2 => pool.bool_false(),
 
-            // true = phi => phi
-            (= true phi_1): (_, phi_1) => phi_1.clone(),
+        // ¬phi = phi => false
+        (= (not phi_1) phi_2): (phi_1, phi_2) if phi_1 == phi_2 => pool.bool_false(),
 
-            // phi = true => phi
-            (= phi_1 true): (phi_1, _) => phi_1.clone(),
+        // true = phi => phi
+        (= true phi_1): (_, phi_1) => phi_1.clone(),
 
-            // false = phi => ¬phi
-            (= false phi_1): (_, phi_1) => build_term!(pool, (not {phi_1.clone()})),
+        // phi = true => phi
+        (= phi_1 true): (phi_1, _) => phi_1.clone(),
 
-            // phi = false => ¬phi
-            (= phi_1 false): (phi_1, _) => build_term!(pool, (not {phi_1.clone()})),
-        })
+        // false = phi => ¬phi
+        (= false phi_1): (_, phi_1) => build_term!(pool, (not {phi_1.clone()})),
+
+        // phi = false => ¬phi
+        (= phi_1 false): (phi_1, _) => build_term!(pool, (not {phi_1.clone()})),
     })
 }
 
 pub fn bool_simplify(args: RuleArgs) -> RuleResult {
-    generic_simplify_rule(args.conclusion, args.pool, |term, pool| {
-        simplify!(term {
-            // ¬(phi_1 -> phi_2) => (phi_1 ^ ¬phi_2)
-            (not (=> phi_1 phi_2)): (phi_1, phi_2) => {
-                build_term!(pool, (and {phi_1.clone()} (not {phi_2.clone()})))
-            },
-
-            // ¬(phi_1 v phi_2) => (¬phi_1 ^ ¬phi_2)
-            (not (or phi_1 phi_2)): (phi_1, phi_2) => {
-                build_term!(pool, (and (not {phi_1.clone()}) (not {phi_2.clone()})))
-            },
-
-            // ¬(phi_1 ^ phi_2) => (¬phi_1 v ¬phi_2)
-            (not (and phi_1 phi_2)): (phi_1, phi_2) => {
-                build_term!(pool, (or (not {phi_1.clone()}) (not {phi_2.clone()})))
-            },
-
-            // (phi_1 -> (phi_2 -> phi_3)) => ((phi_1 ^ phi_2) -> phi_3)
-            (=> phi_1 (=> phi_2 phi_3)): (phi_1, (phi_2, phi_3)) => {
-                build_term!(pool, (=> (and {phi_1.clone()} {phi_2.clone()}) {phi_3.clone()}))
-            },
-
-            // ((phi_1 -> phi_2) -> phi_2) => (phi_1 v phi_2)
-            (=> (=> phi_1 phi_2) phi_3): ((phi_1, phi_2), phi_3) if phi_2 == phi_3 => {
-                build_term!(pool, (or {phi_1.clone()} {phi_2.clone()}))
-            },
-
-            // (phi_1 ^ (phi_1 -> phi_2)) => (phi_1 ^ phi_2)
-            (and phi_1 (=> phi_2 phi_3)): (phi_1, (phi_2, phi_3)) if phi_1 == phi_2 => {
-                build_term!(pool, (and {phi_1.clone()} {phi_3.clone()}))
-            },
-
-            // ((phi_1 -> phi_2) ^ phi_1) => (phi_1 ^ phi_2)
-            (and (=> phi_1 phi_2) phi_3): ((phi_1, phi_2), phi_3) if phi_1 == phi_3 => {
-                build_term!(pool, (and {phi_1.clone()} {phi_2.clone()}))
-            },
-        })
+    generic_simplify_rule(args.conclusion, args.pool, simplify_bool)
+}
+
+/// Applies the first `bool_simplify` transformation that matches the term, if any.
+pub fn simplify_bool(term: &Term, pool: &mut dyn TermPool) -> Option<Rc<Term>> {
+    simplify!(term {
+        // ¬(phi_1 -> phi_2) => (phi_1 ^ ¬phi_2)
+        (not (=> phi_1 phi_2)): (phi_1, phi_2) => {
+            build_term!(pool, (and {phi_1.clone()} (not {phi_2.clone()})))
+        },
+
+        // ¬(phi_1 v phi_2) => (¬phi_1 ^ ¬phi_2)
+        (not (or phi_1 phi_2)): (phi_1, phi_2) => {
+            build_term!(pool, (and (not {phi_1.clone()}) (not {phi_2.clone()})))
+        },
+
+        // ¬(phi_1 ^ phi_2) => (¬phi_1 v ¬phi_2)
+        (not (and phi_1 phi_2)): (phi_1, phi_2) => {
+            build_term!(pool, (or (not {phi_1.clone()}) (not {phi_2.clone()})))
+        },
+
+        // (phi_1 -> (phi_2 -> phi_3)) => ((phi_1 ^ phi_2) -> phi_3)
+        (=> phi_1 (=> phi_2 phi_3)): (phi_1, (phi_2, phi_3)) => {
+            build_term!(pool, (=> (and {phi_1.clone()} {phi_2.clone()}) {phi_3.clone()}))
+        },
+
+        // ((phi_1 -> phi_2) -> phi_2) => (phi_1 v phi_2)
+        (=> (=> phi_1 phi_2) phi_3): ((phi_1, phi_2), phi_3) if phi_2 == phi_3 => {
+            build_term!(pool, (or {phi_1.clone()} {phi_2.clone()}))
+        },
+
+        // (phi_1 ^ (phi_1 -> phi_2)) => (phi_1 ^ phi_2)
+        (and phi_1 (=> phi_2 phi_3)): (phi_1, (phi_2, phi_3)) if phi_1 == phi_2 => {
+            build_term!(pool, (and {phi_1.clone()} {phi_3.clone()}))
+        },
+
+        // ((phi_1 -> phi_2) ^ phi_1) => (phi_1 ^ phi_2)
+        (and (=> phi_1 phi_2) phi_3): ((phi_1, phi_2), phi_3) if phi_1 == phi_3 => {
+            build_term!(pool, (and {phi_1.clone()} {phi_2.clone()}))
+        },
     })
 }
 
@@ -396,15 +458,32 @@ pub fn qnt_simplify(RuleArgs { conclusion, .. }: RuleArgs) -> RuleResult {
     assert_clause_len(conclusion, 1)?;
     let (left, right) = match_term_err!((= l r) = &conclusion[0])?;
     let (_, _, inner) = left.as_quant_err()?;
-    rassert!(
-        inner.is_bool_false() || inner.is_bool_true(),
-        CheckerError::ExpectedAnyBoolConstant(inner.clone())
-    );
-    assert_eq(right, inner)?;
-    Ok(())
+    let simplified =
+        simplify_qnt(left).ok_or_else(|| CheckerError::ExpectedAnyBoolConstant(inner.clone()))?;
+    assert_eq(right, &simplified)
 }
 
-pub fn div_simplify(RuleArgs { conclusion, .. }: RuleArgs) -> RuleResult {
+/// Applies the `qnt_simplify` transformation, which removes quantifiers over boolean constants.
+pub fn simplify_qnt(term: &Term) -> Option<Rc<Term>> {
+    let (_, _, inner) = term.as_quant()?;
+    (inner.is_bool_true() || inner.is_bool_false()).then(|| inner.clone())
+}
+
+/// Returns `true` if the terms are equal, or if they are numerical constants with the same value.
+fn is_same_value(a: &Rc<Term>, b: &Rc<Term>) -> bool {
+    a == b
+        || matches!(
+            (a.as_fraction(), b.as_fraction()),
+            (Some(a), Some(b)) if a == b
+        )
+}
+
+/// Returns `true` if the term has sort `Int`.
+fn is_int(pool: &mut dyn TermPool, term: &Rc<Term>) -> bool {
+    pool.sort(term).as_sort() == Some(&Sort::Int)
+}
+
+pub fn div_simplify(RuleArgs { conclusion, pool, .. }: RuleArgs) -> RuleResult {
     assert_clause_len(conclusion, 1)?;
     let (left, right) = match_term_err!((= l r) = &conclusion[0])?;
 
@@ -418,135 +497,132 @@ pub fn div_simplify(RuleArgs { conclusion, .. }: RuleArgs) -> RuleResult {
         return Err(CheckerError::ExpectedNumber(Rational::new(), right.clone()));
     }
 
-    let ((numer, denom), is_int_div) = match match_term!((div n d) = left) {
-        Some(v) => (v, true),
-        None => (match_term_err!((/ n d) = left)?, false),
+    let (numer, denom) = match match_term!((div n d) = left) {
+        Some(v) => v,
+        None => match_term_err!((/ n d) = left)?,
     };
+    let expected = simplify_div(left, pool).ok_or_else(|| match denom.as_signed_number() {
+        Some(d) if d.is_zero() => CheckerError::DivOrModByZero,
+        Some(_) => CheckerError::ExpectedAnyNumber(numer.clone()),
+        None => CheckerError::ExpectedAnyNumber(denom.clone()),
+    })?;
+    match expected.as_fraction() {
+        Some(expected) => {
+            rassert!(
+                right.as_fraction_err()? == expected,
+                CheckerError::ExpectedNumber(expected, right.clone())
+            );
+            Ok(())
+        }
+        None => assert_eq(right, &expected),
+    }
+}
 
+/// Applies the `div_simplify` transformations to the term, if it is an integer or real division.
+pub fn simplify_div(term: &Term, pool: &mut dyn TermPool) -> Option<Rc<Term>> {
+    let (op, [numer, denom]) = term.as_op()? else {
+        return None;
+    };
+    let is_int_div = match op {
+        Operator::IntDiv => true,
+        Operator::RealDiv => false,
+        _ => return None,
+    };
     if numer == denom {
-        rassert!(
-            right.as_signed_number_err()? == 1,
-            CheckerError::ExpectedNumber(Rational::new(), right.clone())
-        );
-        Ok(())
-    } else if denom.as_number().is_some_and(|n| n == 1) {
-        assert_eq(right, numer)
-    } else {
-        let denom = denom.as_signed_number_err()?;
-        if denom.is_zero() {
-            return Err(CheckerError::DivOrModByZero);
-        }
-        let numer = numer.as_signed_number_err()?;
-        let expected = if is_int_div {
-            assert!(numer.is_integer() && denom.is_integer()); // This is guaranteed by the Alethe typing rules
-            let [numer, denom] = [numer, denom].map(|n| n.into_numer_denom().0);
-            Rational::from(numer.div_rem_euc(denom).0)
-        } else {
-            numer / denom
-        };
-        rassert!(
-            right.as_fraction_err()? == expected,
-            CheckerError::ExpectedNumber(expected, right.clone())
-        );
-        Ok(())
+        return Some(number_term(pool, Rational::from(1), is_int_div));
+    }
+    let denom = denom.as_signed_number()?;
+    if denom == 1 {
+        return Some(numer.clone());
     }
+    let numer = numer.as_signed_number()?;
+    if denom.is_zero() {
+        return None;
+    }
+    let result = if is_int_div {
+        let [numer, denom] = [numer, denom].map(|n| n.into_numer_denom().0);
+        Rational::from(numer.div_rem_euc(denom).0)
+    } else {
+        numer / denom
+    };
+    Some(number_term(pool, result, is_int_div))
 }
 
-/// Used for both the `sum_simplify` and `prod_simplify` rules, depending on `rule_kind`.
-/// `rule_kind` has to be either `Operator::Add` or `Operator::Mult`.
-fn generic_sum_prod_simplify_rule(
+/// Applies the `sum_simplify` or `prod_simplify` transformations to the term, depending on
+/// `rule_kind`. `rule_kind` has to be either `Operator::Add` or `Operator::Mult`. The constant
+/// arguments are combined into a single leading constant, which is omitted if it is the identity
+/// value.
+pub fn simplify_sum_prod(
+    term: &Rc<Term>,
     pool: &mut dyn TermPool,
-    ts: &Rc<Term>,
-    u: &Rc<Term>,
     rule_kind: Operator,
-) -> RuleResult {
+) -> Option<Rc<Term>> {
+    let args = match term.as_ref() {
+        Term::Op(op, args) if *op == rule_kind => args,
+        _ => return None,
+    };
     let identity_value = match rule_kind {
         Operator::Add => Rational::new(),
         Operator::Mult => Rational::from(1),
         _ => unreachable!(),
     };
+    let is_int = is_int(pool, term);
 
-    // Check if the u term is valid and extract from it the leading constant and the remaining
-    // arguments
-    let (u_constant, u_args) = match u.as_ref() {
-        Term::Op(op, args) if *op == rule_kind => {
-            // We check if there are any constants in u (aside from the leading constant). If there
-            // are any, we know this u term is invalid
-            if args[1..].iter().any(|t| t.as_fraction().is_some()) {
-                None
-            } else {
-                match args[0].as_fraction() {
-                    // If the leading constant is the identity value, it should have been omitted
-                    Some(constant) if constant == identity_value => None,
-                    Some(constant) => Some((constant, &args[1..])),
-                    None => Some((identity_value.clone(), args.as_slice())),
-                }
-            }
+    // First, we go through the arguments, adding/multiplying all the constants we find together,
+    // and push the non-constant terms to the `result` vector
+    let mut constant_total = identity_value.clone();
+    let mut result = Vec::with_capacity(args.len());
+    for t in args {
+        match t.as_fraction() {
+            Some(r) if rule_kind == Operator::Add => constant_total += r,
+            Some(r) => constant_total *= r,
+            None => result.push(t.clone()),
         }
+    }
 
-        // If u is not an application of the operator, we consider it a product/sum of a single
-        // term. That term might be a regular term or the leading constant, depending on if u
-        // is a constant or not
-        _ => Some(match u.as_fraction() {
-            Some(u) => (u, &[] as &[_]),
-            None => (identity_value.clone(), std::slice::from_ref(u)),
-        }),
+    // If the rule kind is `prod_simplify` and we find a zero, the result is just the zero constant
+    if rule_kind == Operator::Mult && constant_total == 0 {
+        return Some(number_term(pool, Rational::new(), is_int));
+    }
+    if constant_total != identity_value {
+        result.insert(0, number_term(pool, constant_total, is_int));
+    }
+    match result.as_slice() {
+        [] => Some(number_term(pool, identity_value, is_int)),
+        [single] => Some(single.clone()),
+        _ if result == *args => None,
+        _ => Some(pool.add(Term::Op(rule_kind, result))),
     }
-    .ok_or_else(|| CheckerError::SumProdSimplifyInvalidConclusion(u.clone()))?;
+}
 
-    let ts = match rule_kind {
+/// Used for both the `sum_simplify` and `prod_simplify` rules, depending on `rule_kind`.
+/// `rule_kind` has to be either `Operator::Add` or `Operator::Mult`.
+fn generic_sum_prod_simplify_rule(
+    pool: &mut dyn TermPool,
+    ts: &Rc<Term>,
+    u: &Rc<Term>,
+    rule_kind: Operator,
+) -> RuleResult {
+    match rule_kind {
         Operator::Add => match_term_err!((+ ...) = ts),
         Operator::Mult => match_term_err!((* ...) = ts),
         _ => unreachable!(),
     }?;
 
-    let mut result = Vec::with_capacity(ts.len());
-    let mut constant_total = identity_value.clone();
-
-    // First, we go through the t_i terms, adding/multiplying all the constants we find together,
-    // and push the non-constant terms to the `result` vector
-    for t in ts {
-        match (t.as_fraction(), rule_kind) {
-            (Some(r), Operator::Add) => constant_total += r,
-            (Some(r), Operator::Mult) => constant_total *= r,
-            (Some(_), _) => unreachable!(),
-            (None, _) => {
-                result.push(t);
-                continue; // Since `constant_total` didn't change, we can skip the check
-            }
-        }
-        // If the rule kind is `prod_simplify` and we find a zero, we can leave the loop early. We
-        // also clear the `result` vector because we expect the u term to be just the zero constant
-        if rule_kind == Operator::Mult && constant_total == 0 {
-            result.clear();
-            break;
+    // If no argument can be simplified, the expected result is the original term. Since the
+    // constants may be written differently, we compare them by their values
+    let expected = simplify_sum_prod(ts, pool, rule_kind).unwrap_or_else(|| ts.clone());
+    let is_expected = match (expected.as_ref(), u.as_ref()) {
+        (Term::Op(f, f_args), Term::Op(g, g_args)) if f == g => {
+            f_args.len() == g_args.len()
+                && f_args.iter().zip(g_args).all(|(a, b)| is_same_value(a, b))
         }
-    }
-
-    // This covers a tricky edge case that happens when the only non-constant term in `ts` is also a
-    // valid application of the rule operator. For example:
-    // ```
-    //     (step t1 (cl
-    //         (= (* 1 (* 2 x)) (* 2 x))
-    //     ) :rule prod_simplify)
-    // ```
-    // In this step, the term `(* 2 x)` on the right-hand side should be interpreted as an atom,
-    // but since it is a valid `u` term, it is unwrapped such that `u_constant` is 2 and `u_args`
-    // is `x`. To handle this, we first check if the expected result is just one term, and try to
-    // interpret `u` as that term.
-    if result.len() == 1 && constant_total == identity_value && u == result[0] {
-        return Ok(());
-    }
-
-    // Finally, we verify that the constant and the remaining arguments are what we expect
-    rassert!(u_constant == constant_total && u_args.iter().eq(result), {
-        let expected = {
-            let mut expected_args = vec![pool.add(Term::new_real(constant_total))];
-            expected_args.extend(u_args.iter().cloned());
-            pool.add(Term::Op(rule_kind, expected_args))
-        };
+        _ => is_same_value(&expected, u),
+    };
+    rassert!(
+        is_expected,
         EqualityError::ExpectedToBe { expected, got: u.clone() }
-    });
+    );
     Ok(())
 }
 
@@ -562,66 +638,55 @@ pub fn prod_simplify(RuleArgs { conclusion, pool, .. }: RuleArgs) -> RuleResult
     generic_sum_prod_simplify_rule(pool, first, second, Operator::Mult)
 }
 
-pub fn minus_simplify(RuleArgs { conclusion, .. }: RuleArgs) -> RuleResult {
+pub fn minus_simplify(RuleArgs { conclusion, pool, .. }: RuleArgs) -> RuleResult {
     // Despite being separate rules in the documentation, this rule is used to do the job of both
     // the `minus_simplify` and the `unary_minus_simplify` rules
-    fn try_unary_minus_simplify(t: &Rc<Term>, u: &Rc<Term>) -> bool {
-        // First case of `unary_minus_simplify`
-        if match_term!((-(-t)) = t) == Some(u) {
-            return true;
-        }
-
-        // Second case of `unary_minus_simplify`
-        match (t.as_signed_number(), u.as_signed_number()) {
-            (Some(t), Some(u)) if t == u => return true,
-            _ => (),
-        }
+    assert_clause_len(conclusion, 1)?;
+    let (left, right) = match_term_err!((= l r) = &conclusion[0])?;
 
-        false
+    // Second case of `unary_minus_simplify`, where both terms are the same numerical constant
+    match (left.as_signed_number(), right.as_signed_number()) {
+        (Some(l), Some(r)) if l == r => return Ok(()),
+        _ => (),
     }
 
-    fn check(t_1: &Rc<Term>, t_2: &Rc<Term>, u: &Rc<Term>) -> RuleResult {
-        if t_1 == t_2 {
-            rassert!(
-                u.as_number_err()? == 0,
-                CheckerError::ExpectedNumber(Rational::from(1), u.clone()),
-            );
-            return Ok(());
+    // The equality in the conclusion may be flipped, so we first check assuming that the original
+    // term is in the right. If that fails, we check the other case
+    if simplify_minus(right, pool).is_some_and(|t| is_same_value(&t, left)) {
+        return Ok(());
+    }
+    let result =
+        simplify_minus(left, pool).ok_or_else(|| match match_term!((- t_1 t_2) = left) {
+            Some((_, t_2)) => CheckerError::ExpectedAnyNumber(t_2.clone()),
+            None => CheckerError::TermOfWrongForm("(- t_1 t_2)", left.clone()),
+        })?;
+    rassert!(
+        is_same_value(&result, right),
+        CheckerError::SimplificationFailed {
+            original: left.clone(),
+            result,
+            target: right.clone(),
         }
-        match (t_1.as_signed_number(), t_2.as_signed_number()) {
-            (_, Some(z)) if z == 0 => assert_eq(u, t_1),
-            (Some(z), _) if z == 0 => assert_eq(match_term_err!((-t) = u)?, t_2),
-            (Some(t_1), Some(t_2)) => {
-                let expected = t_1 - t_2;
-                rassert!(
-                    u.as_signed_number_err()? == expected,
-                    CheckerError::ExpectedNumber(expected, u.clone())
-                );
-                Ok(())
-            }
+    );
+    Ok(())
+}
 
-            // We expect at least one of the terms to be a number
-            _ => Err(CheckerError::ExpectedAnyNumber(t_2.clone())),
-        }
+/// Applies the `minus_simplify` and `unary_minus_simplify` transformations to the term.
+pub fn simplify_minus(term: &Rc<Term>, pool: &mut dyn TermPool) -> Option<Rc<Term>> {
+    if let Some(t) = match_term!((-(-t)) = term) {
+        return Some(t.clone());
     }
-
-    assert_clause_len(conclusion, 1)?;
-    let (left, right) = match_term_err!((= l r) = &conclusion[0])?;
-
-    if try_unary_minus_simplify(left, right) || try_unary_minus_simplify(right, left) {
-        return Ok(());
+    let (t_1, t_2) = match_term!((- t_1 t_2) = term)?;
+    let is_int = is_int(pool, term);
+    if t_1 == t_2 {
+        return Some(number_term(pool, Rational::new(), is_int));
     }
-
-    // The equality in the conclusion may be flipped, but one of the terms in it must be of the
-    // form '(- t_1 t_2)'. We first check assuming that 't' is in the right. If that fails, we
-    // check the other case and return any error it finds
-    if let Some((t_1, t_2)) = match_term!((- t_1 t_2) = right) {
-        if check(t_1, t_2, left).is_ok() {
-            return Ok(());
-        }
+    match (t_1.as_signed_number(), t_2.as_signed_number()) {
+        (_, Some(z)) if z == 0 => Some(t_1.clone()),
+        (Some(z), _) if z == 0 => Some(build_term!(pool, (-{ t_2.clone() }))),
+        (Some(t_1), Some(t_2)) => Some(number_term(pool, t_1 - t_2, is_int)),
+        _ => None,
     }
-    let (t_1, t_2) = match_term_err!((- t_1 t_2) = left)?;
-    check(t_1, t_2, right)
 }
 
 pub fn sum_simplify(RuleArgs { conclusion, pool, .. }: RuleArgs) -> RuleResult {
@@ -637,45 +702,74 @@ pub fn sum_simplify(RuleArgs { conclusion, pool, .. }: RuleArgs) -> RuleResult {
 }
 
 pub fn comp_simplify(args: RuleArgs) -> RuleResult {
-    generic_simplify_rule(args.conclusion, args.pool, |term, pool| {
-        simplify!(term {
-            (< t_1 t_2): (t_1, t_2) => {
-                if let (Some(t_1), Some(t_2)) =
-                    (t_1.as_fraction(), t_2.as_fraction())
-                {
-                    // t_1 < t_2 => phi, where t_1 and t_2 are numerical constants
-                    pool.bool_constant(t_1 < t_2)
-                } else if t_1 == t_2 {
-                    // t < t => false
-                    pool.bool_false()
-                } else {
-                    // t_1 < t_2 => ¬(t_2 <= t_1)
-                    build_term!(pool, (not (<= {t_2.clone()} {t_1.clone()})))
-                }
-            },
-            (<= t_1 t_2): (t_1, t_2) => {
-                if let (Some(t_1), Some(t_2)) =
-                    (t_1.as_fraction(), t_2.as_fraction())
-                {
-                    // t_1 <= t_2 => phi, where t_1 and t_2 are numerical constants
-                    pool.bool_constant(t_1 <= t_2)
-                } else if t_1 == t_2 {
-                    // t <= t => true
-                    pool.bool_true()
-                } else {
-                    return None
-                }
-            },
+    generic_simplify_rule(args.conclusion, args.pool, simplify_comp)
+}
+
+/// Applies the first `comp_simplify` transformation that matches the term, if any.
+pub fn simplify_comp(term: &Term, pool: &mut dyn TermPool) -> Option<Rc<Term>> {
+    simplify!(term {
+        (< t_1 t_2): (t_1, t_2) => {
+            if let (Some(t_1), Some(t_2)) =
+                (t_1.as_fraction(), t_2.as_fraction())
+            {
+                // t_1 < t_2 => phi, where t_1 and t_2 are numerical constants
+                pool.bool_constant(t_1 < t_2)
+            } else if t_1 == t_2 {
+                // t < t => false
+                pool.bool_false()
+            } else {
+                // t_1 < t_2 => ¬(t_2 <= t_1)
+                build_term!(pool, (not (<= {t_2.clone()} {t_1.clone()})))
+            }
+        },
+        (<= t_1 t_2): (t_1, t_2) => {
+            if let (Some(t_1), Some(t_2)) =
+                (t_1.as_fraction(), t_2.as_fraction())
+            {
+                // t_1 <= t_2 => phi, where t_1 and t_2 are numerical constants
+                pool.bool_constant(t_1 <= t_2)
+            } else if t_1 == t_2 {
+                // t <= t => true
+                pool.bool_true()
+            } else {
+                return None
+            }
+        },
 
-            // t_1 >= t_2 => t_2 <= t_1
-            (>= t_1 t_2): (t_1, t_2) => build_term!(pool, (<= {t_2.clone()} {t_1.clone()})),
+        // t_1 >= t_2 => t_2 <= t_1
+        (>= t_1 t_2): (t_1, t_2) => build_term!(pool, (<= {t_2.clone()} {t_1.clone()})),
 
-            // t_1 > t_2 => ¬(t_1 <= t_2)
-            (> t_1 t_2): (t_1, t_2) => build_term!(pool, (not (<= {t_1.clone()} {t_2.clone()}))),
-        })
+        // t_1 > t_2 => ¬(t_1 <= t_2)
+        (> t_1 t_2): (t_1, t_2) => build_term!(pool, (not (<= {t_1.clone()} {t_2.clone()}))),
     })
 }
 
+/// Flattens nested applications of the operator `op` in its arguments, and removes duplicate
+/// arguments.
+fn flatten_args(op: Operator, args: impl IntoIterator<Item = Rc<Term>>) -> Vec<Rc<Term>> {
+    args.into_iter()
+        .flat_map(|term| match term.as_ref() {
+            Term::Op(inner_op, inner_args) if *inner_op == op => inner_args.clone(),
+            _ => vec![term.clone()],
+        })
+        .dedup()
+        .collect()
+}
+
+/// Applies the `ac_simp` transformation to the root of the term, flattening nested applications of
+/// `and` and `or` and removing duplicate arguments.
+pub fn flatten_and_or(term: &Term, pool: &mut dyn TermPool) -> Option<Rc<Term>> {
+    let Term::Op(op @ (Operator::And | Operator::Or), args) = term else {
+        return None;
+    };
+    let flattened = flatten_args(*op, args.iter().cloned());
+    match flattened.as_slice() {
+        [single] => Some(single.clone()),
+        _ if flattened == *args => None,
+        _ => Some(pool.add(Term::Op(*op, flattened))),
+    }
+}
+
 fn apply_ac_simp(
     pool: &mut dyn TermPool,
     cache: &mut IndexMap<Rc<Term>, Rc<Term>>,
@@ -686,17 +780,7 @@ fn apply_ac_simp(
     }
     let result = match term.as_ref() {
         Term::Op(op @ (Operator::And | Operator::Or), args) => {
-            let args: Vec<_> = args
-                .iter()
-                .flat_map(|term| {
-                    let term = apply_ac_simp(pool, cache, term);
-                    match term.as_ref() {
-                        Term::Op(inner_op, inner_args) if inner_op == op => inner_args.clone(),
-                        _ => vec![term.clone()],
-                    }
-                })
-                .dedup()
-                .collect();
+            let args = flatten_args(*op, args.iter().map(|t| apply_ac_simp(pool, cache, t)));
             if args.len() == 1 {
                 return args[0].clone();
             } else {
@@ -736,3 +820,12 @@ pub fn ac_simp(RuleArgs { conclusion, pool, .. }: RuleArgs) -> RuleResult {
         &apply_ac_simp(pool, &mut IndexMap::new(), original),
     )
 }
+
+/// `all_simplify` steps can't be checked by a single rule, so this only checks the form of the
+/// conclusion. The step itself is checked by the rewriter, if one is configured, and is otherwise
+/// considered a hole.
+pub fn all_simplify(RuleArgs { conclusion, .. }: RuleArgs) -> RuleResult {
+    assert_clause_len(conclusion, 1)?;
+    match_term_err!((= l r) = &conclusion[0])?;
+    Ok(())
+}
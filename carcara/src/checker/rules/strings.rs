@@ -149,6 +149,17 @@ fn expand_string_constants(pool: &mut dyn TermPool, term: &Rc<Term>) -> Rc<Term>
                 args: new_args,
             })
         }
+        Term::Match(scrutinee, cases) => {
+            let new_scrutinee = expand_string_constants(pool, scrutinee);
+            let new_cases = cases
+                .iter()
+                .map(|c| MatchCase {
+                    body: expand_string_constants(pool, &c.body),
+                    ..c.clone()
+                })
+                .collect();
+            pool.add(Term::Match(new_scrutinee, new_cases))
+        }
         Term::Var(..) | Term::Const(_) | Term::Sort(_) => term.clone(),
     }
 }
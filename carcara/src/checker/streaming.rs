@@ -0,0 +1,291 @@
+//! Checking of proofs while they are being parsed, without building the whole `Proof`.
+//!
+//! The streaming checker receives the commands one at a time from
+//! `Parser::parse_proof_streaming`, and only keeps the commands that may still be used as premises
+//! by later steps. If the number of times each step is used as a premise is known in advance (see
+//! `parser::count_premise_uses`), a command is discarded as soon as its last use is checked.
+//! Otherwise, only the commands of subproofs that were already closed are discarded.
+
+use super::{
+    error::CheckerError, rules::Premise, CheckerFailure, CheckerStatistics, Config, ProofChecker,
+};
+use crate::{
+    ast::*, benchmarking::OnlineBenchmarkResults, parser::StreamedCommand, CarcaraResult, Error,
+};
+use std::collections::HashMap;
+
+/// The minimum number of terms in the pool before the streaming checker tries to collect garbage.
+const MIN_GC_THRESHOLD: usize = 1 << 16;
+
+/// The commands of a subproof (or of the root proof) that are still alive.
+#[derive(Debug, Default)]
+struct Frame {
+    /// The commands that may still be used, indexed by their position in the subproof.
+    commands: HashMap<usize, ProofCommand>,
+
+    /// The number of commands in the subproof so far. A nested subproof counts as a single command.
+    len: usize,
+
+    /// The position and id of each `assume` command in the subproof. These are needed to check
+    /// that all local assumptions are discharged by the step that ends the subproof.
+    assumes: Vec<(usize, String)>,
+
+    /// The id of the step that ends the subproof.
+    end_step_id: String,
+}
+
+/// The state of a `ProofChecker`, without the term pool. Since the parser holds the pool while
+/// parsing the proof, the checker can only borrow it while checking each command.
+#[derive(Default)]
+struct CheckerState {
+    config: Config,
+    context: ContextStack,
+    reached_empty_clause: bool,
+    is_holey: bool,
+    failures: Vec<CheckerFailure>,
+}
+
+impl CheckerState {
+    fn attach<'c>(&mut self, pool: &'c mut PrimitivePool) -> ProofChecker<'c> {
+        ProofChecker {
+            pool,
+            config: std::mem::take(&mut self.config),
+            context: std::mem::take(&mut self.context),
+            reached_empty_clause: self.reached_empty_clause,
+            is_holey: self.is_holey,
+            failures: std::mem::take(&mut self.failures),
+        }
+    }
+
+    fn detach(&mut self, checker: ProofChecker) {
+        self.config = checker.config;
+        self.context = checker.context;
+        self.reached_empty_clause = checker.reached_empty_clause;
+        self.is_holey = checker.is_holey;
+        self.failures = checker.failures;
+    }
+}
+
+pub struct StreamingChecker {
+    state: CheckerState,
+
+    /// The stack of open subproofs. The first frame represents the root proof.
+    frames: Vec<Frame>,
+
+    /// The number of remaining uses as a premise of each step id, if known.
+    premise_uses: Option<HashMap<String, usize>>,
+
+    /// When the pool grows past this number of terms, unreferenced terms are collected.
+    gc_threshold: usize,
+}
+
+impl StreamingChecker {
+    /// Constructs a new streaming checker. If `premise_uses` is given, it must contain the number
+    /// of times each step id is used as a premise in the proof, as computed by
+    /// `parser::count_premise_uses`.
+    pub fn new(config: Config, premise_uses: Option<HashMap<String, usize>>) -> Self {
+        Self {
+            state: CheckerState { config, ..Default::default() },
+            frames: vec![Frame::default()],
+            premise_uses,
+            gc_threshold: MIN_GC_THRESHOLD,
+        }
+    }
+
+    /// Checks a command given by the parser. This can be passed directly as the callback to
+    /// `Parser::parse_proof_streaming`.
+    pub fn process(
+        &mut self,
+        pool: &mut PrimitivePool,
+        problem: &Problem,
+        command: StreamedCommand,
+    ) -> CarcaraResult<()> {
+        let mut checker = self.state.attach(pool);
+        let result = self.process_with(&mut checker, problem, command);
+        self.state.detach(checker);
+
+        if pool.num_terms() >= self.gc_threshold {
+            pool.collect_garbage();
+            self.gc_threshold = MIN_GC_THRESHOLD.max(2 * pool.num_terms());
+        }
+        result
+    }
+
+    /// Finishes checking, after all commands were processed. Returns `true` if the proof has holes.
+    pub fn finish(mut self) -> CarcaraResult<bool> {
+        if !self.state.failures.is_empty() {
+            Err(Error::CheckerFailures(std::mem::take(
+                &mut self.state.failures,
+            )))
+        } else if self.state.reached_empty_clause {
+            Ok(self.state.is_holey)
+        } else {
+            Err(Error::DoesNotReachEmptyClause)
+        }
+    }
+
+    fn process_with(
+        &mut self,
+        checker: &mut ProofChecker,
+        problem: &Problem,
+        command: StreamedCommand,
+    ) -> CarcaraResult<()> {
+        let command = match command {
+            StreamedCommand::Anchor { end_step_id, args, .. } => {
+                checker.context.push(&args);
+                self.frames
+                    .push(Frame { end_step_id, ..Default::default() });
+                return Ok(());
+            }
+            StreamedCommand::Command(c) => c,
+        };
+
+        let depth = self.frames.len() - 1;
+        let index = self.frames[depth].len;
+        let mut is_end_of_subproof = false;
+        match &command {
+            ProofCommand::Assume { id, term } => {
+                let mut stats = None::<&mut CheckerStatistics<OnlineBenchmarkResults>>;
+                if !checker.check_assume(id, term, &problem.premises, depth > 0, &mut stats) {
+                    checker.record_failure(CheckerFailure {
+                        step: id.clone(),
+                        rule: "assume".into(),
+                        inner: CheckerError::Assume(term.clone()),
+                    })?;
+                }
+                if depth > 0 {
+                    self.frames[depth].assumes.push((index, id.clone()));
+                }
+            }
+            ProofCommand::Step(step) => {
+                is_end_of_subproof = depth > 0 && self.frames[depth].end_step_id == step.id;
+                if let Err(e) = self.check_step(checker, step, is_end_of_subproof) {
+                    checker.record_failure(CheckerFailure {
+                        step: step.id.clone(),
+                        rule: step.rule.clone(),
+                        inner: e,
+                    })?;
+                }
+                if is_end_of_subproof {
+                    checker.context.pop();
+                }
+                if step.clause.is_empty() && checker.context.is_empty() {
+                    checker.reached_empty_clause = true;
+                }
+                for &(d, i) in &step.premises {
+                    self.release(d, i);
+                }
+            }
+            ProofCommand::Subproof(_) => unreachable!("the parser never streams whole subproofs"),
+        }
+
+        // The step that ends a subproof takes the place of the whole subproof in the outer frame
+        if is_end_of_subproof {
+            self.frames.pop();
+        }
+        self.push(command);
+        Ok(())
+    }
+
+    fn check_step(
+        &self,
+        checker: &mut ProofChecker,
+        step: &ProofStep,
+        is_end_of_subproof: bool,
+    ) -> Result<(), CheckerError> {
+        let depth = self.frames.len() - 1;
+        let frame = &self.frames[depth];
+        let get = |(d, i): (usize, usize)| {
+            self.frames[d]
+                .commands
+                .get(&i)
+                .ok_or(CheckerError::DiscardedPremise(d, i))
+        };
+
+        // If this step ends a subproof, it might need to implicitly reference the previous command
+        // in the subproof. The last command of a frame is never discarded, so it is always available
+        let previous_command = if is_end_of_subproof {
+            let index = frame.len - 1;
+            frame
+                .commands
+                .get(&index)
+                .map(|command| Premise::new((depth, index), command))
+        } else {
+            None
+        };
+        let premises = step
+            .premises
+            .iter()
+            .map(|&p| get(p).map(|command| Premise::new(p, command)))
+            .collect::<Result<Vec<_>, _>>()?;
+        let discharge = step
+            .discharge
+            .iter()
+            .map(|&p| get(p))
+            .collect::<Result<Vec<_>, _>>()?;
+        let local_assumes: Option<Vec<_>> = is_end_of_subproof.then(|| {
+            let assumes = frame.assumes.iter();
+            assumes.map(|(i, id)| ((depth, *i), id.as_str())).collect()
+        });
+
+        checker.check_step_with_premises(
+            step,
+            previous_command,
+            &premises,
+            &discharge,
+            local_assumes.as_deref(),
+            &mut None::<&mut CheckerStatistics<OnlineBenchmarkResults>>,
+        )
+    }
+
+    /// Returns `true` if a command will never be used again. Local assumptions are kept until the
+    /// end of their subproof, since they are referenced by its last step.
+    fn is_dead(&self, depth: usize, command: &ProofCommand) -> bool {
+        let Some(uses) = &self.premise_uses else {
+            return false;
+        };
+        let is_local_assume = depth > 0 && command.is_assume();
+        !is_local_assume && !uses.contains_key(command.id())
+    }
+
+    /// Records that the command at the given position was used as a premise, discarding it if that
+    /// was its last use.
+    fn release(&mut self, depth: usize, index: usize) {
+        let Some(uses) = &mut self.premise_uses else {
+            return;
+        };
+        let frame = &self.frames[depth];
+        let Some(command) = frame.commands.get(&index) else {
+            return;
+        };
+        if let Some(n) = uses.get_mut(command.id()) {
+            *n -= 1;
+            if *n == 0 {
+                uses.remove(command.id());
+            }
+        }
+        if index + 1 != frame.len && self.is_dead(depth, command) {
+            self.frames[depth].commands.remove(&index);
+        }
+    }
+
+    /// Adds a command to the innermost frame, discarding the previous last command of the frame if
+    /// it will never be used again.
+    fn push(&mut self, command: ProofCommand) {
+        let depth = self.frames.len() - 1;
+        let index = self.frames[depth].len;
+        if let Some(previous) = index.checked_sub(1) {
+            let frame = &self.frames[depth];
+            if frame
+                .commands
+                .get(&previous)
+                .is_some_and(|c| self.is_dead(depth, c))
+            {
+                self.frames[depth].commands.remove(&previous);
+            }
+        }
+        let frame = &mut self.frames[depth];
+        frame.commands.insert(index, command);
+        frame.len += 1;
+    }
+}
@@ -0,0 +1,247 @@
+//! Backend of the diff command, which compares two proofs of the same problem.
+//!
+//! Steps of the two proofs are matched by their clause and depth, modulo reordering of equalities
+//! and of the clause literals. Matched steps that use a different rule, different arguments, or
+//! premises that don't match each other are reported as changed. Steps that are not transitively
+//! used to derive the conclusion of a proof are not considered.
+use crate::{
+    ast::{compare_nodes, Polyeq, Proof, ProofNode, Rc, Term},
+    checker::HOLE_RULES,
+};
+use std::collections::HashMap;
+
+/// A step of one of the proofs being compared.
+#[derive(Debug, Clone)]
+pub struct DiffStep {
+    pub id: String,
+    pub depth: usize,
+    pub rule: String,
+    pub clause: Vec<Rc<Term>>,
+}
+
+impl DiffStep {
+    fn new(node: &ProofNode) -> Self {
+        let rule = match node {
+            ProofNode::Step(s) => s.rule.clone(),
+            _ => "assume".to_owned(),
+        };
+        Self {
+            id: node.id().to_owned(),
+            depth: node.depth(),
+            rule,
+            clause: node.clause().to_vec(),
+        }
+    }
+}
+
+/// A pair of matched steps that conclude the same clause, but differ in how they derive it.
+#[derive(Debug, Clone)]
+pub struct ChangedStep {
+    pub old: DiffStep,
+    pub new: DiffStep,
+    pub rule_changed: bool,
+    pub args_changed: bool,
+    pub premises_changed: bool,
+}
+
+/// The differences between two proofs.
+#[derive(Debug, Clone, Default)]
+pub struct ProofDiff {
+    /// The steps of the new proof that have no matching step in the old proof.
+    pub added: Vec<DiffStep>,
+
+    /// The steps of the old proof that have no matching step in the new proof.
+    pub removed: Vec<DiffStep>,
+
+    pub changed: Vec<ChangedStep>,
+
+    /// The rules whose number of uses differs between the proofs, together with the number of
+    /// steps that use them in the old and in the new proof.
+    pub rule_usage: Vec<(String, usize, usize)>,
+
+    /// The number of holes of each kind in the old and in the new proof. Only rules that appear in
+    /// at least one of the proofs are included.
+    pub holes: Vec<(String, usize, usize)>,
+}
+
+impl ProofDiff {
+    /// Returns `true` if no differences were found between the proofs.
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty()
+            && self.removed.is_empty()
+            && self.changed.is_empty()
+            && self.rule_usage.is_empty()
+    }
+}
+
+/// Collects the assumptions and steps that are used to derive the conclusion of the proof, in
+/// postorder. Subproofs are represented by their last step.
+fn collect_steps(root: &Rc<ProofNode>) -> Vec<Rc<ProofNode>> {
+    let mut steps = Vec::new();
+    root.traverse(|node| {
+        if !node.is_subproof() {
+            steps.push(node.clone());
+        }
+    });
+    steps
+}
+
+fn last_step(node: &Rc<ProofNode>) -> &Rc<ProofNode> {
+    match node.as_ref() {
+        ProofNode::Subproof(s) => &s.last_step,
+        _ => node,
+    }
+}
+
+/// Returns `true` if each literal in `a` can be paired with a distinct literal in `b` that is equal
+/// to it modulo reordering of equalities.
+fn clauses_match(comp: &mut Polyeq, a: &[Rc<Term>], b: &[Rc<Term>]) -> bool {
+    if a.len() != b.len() {
+        return false;
+    }
+    let mut used = vec![false; b.len()];
+    a.iter().all(|t| {
+        let found = (0..b.len()).find(|&i| !used[i] && (b[i] == *t || comp.eq(&b[i], t)));
+        found.map(|i| used[i] = true).is_some()
+    })
+}
+
+fn count_rules(steps: &[Rc<ProofNode>]) -> HashMap<&str, usize> {
+    let mut counts = HashMap::new();
+    for node in steps {
+        if let ProofNode::Step(s) = node.as_ref() {
+            *counts.entry(s.rule.as_str()).or_default() += 1;
+        }
+    }
+    counts
+}
+
+/// Compares two proofs of the same problem. If both proofs were parsed using the same term pool,
+/// most clauses can be matched by the identity of their terms, which is much faster.
+pub fn diff_proofs(old: &Proof, new: &Proof) -> ProofDiff {
+    let old_root = ProofNode::from_commands(old.commands.clone());
+    let new_root = ProofNode::from_commands(new.commands.clone());
+    let old_steps = collect_steps(&old_root);
+    let new_steps = collect_steps(&new_root);
+
+    let (old_counts, new_counts) = (count_rules(&old_steps), count_rules(&new_steps));
+    let mut rule_usage: Vec<_> = old_counts
+        .keys()
+        .chain(new_counts.keys())
+        .map(|rule| {
+            let (a, b) = (old_counts.get(rule), new_counts.get(rule));
+            (
+                (*rule).to_owned(),
+                a.copied().unwrap_or(0),
+                b.copied().unwrap_or(0),
+            )
+        })
+        .collect();
+    rule_usage.sort();
+    rule_usage.dedup();
+    let holes = rule_usage
+        .iter()
+        .filter(|(rule, ..)| HOLE_RULES.contains(&rule.as_str()))
+        .cloned()
+        .collect();
+    rule_usage.retain(|(_, a, b)| a != b);
+
+    if compare_nodes(&old_root, &new_root) {
+        return ProofDiff { holes, ..Default::default() };
+    }
+
+    // First, we try to match each step with a step of the same depth whose clause has exactly the
+    // same terms. Only if that fails we compare the clauses modulo reordering
+    let mut exact: HashMap<(usize, &[Rc<Term>]), Vec<usize>> = HashMap::new();
+    let mut by_size: HashMap<(usize, usize), Vec<usize>> = HashMap::new();
+    for (i, node) in old_steps.iter().enumerate().rev() {
+        exact
+            .entry((node.depth(), node.clause()))
+            .or_default()
+            .push(i);
+        by_size
+            .entry((node.depth(), node.clause().len()))
+            .or_default()
+            .push(i);
+    }
+
+    let mut comp = Polyeq::new().mod_reordering(true).mod_nary(true);
+    let mut old_matched: Vec<Option<usize>> = vec![None; old_steps.len()];
+    let mut new_matched: Vec<Option<usize>> = vec![None; new_steps.len()];
+    let mut unmatched = Vec::new();
+    for (j, node) in new_steps.iter().enumerate() {
+        let candidates = exact.get_mut(&(node.depth(), node.clause()));
+        match candidates.and_then(Vec::pop) {
+            Some(i) => {
+                old_matched[i] = Some(j);
+                new_matched[j] = Some(i);
+            }
+            None => unmatched.push(j),
+        }
+    }
+    for j in unmatched {
+        let node = &new_steps[j];
+        let Some(candidates) = by_size.get(&(node.depth(), node.clause().len())) else {
+            continue;
+        };
+        let found = candidates.iter().rev().copied().find(|&i| {
+            old_matched[i].is_none()
+                && clauses_match(&mut comp, old_steps[i].clause(), node.clause())
+        });
+        if let Some(i) = found {
+            old_matched[i] = Some(j);
+            new_matched[j] = Some(i);
+        }
+    }
+
+    let index_of = |steps: &[Rc<ProofNode>]| -> HashMap<Rc<ProofNode>, usize> {
+        steps
+            .iter()
+            .enumerate()
+            .map(|(i, n)| (n.clone(), i))
+            .collect()
+    };
+    let (old_index, new_index) = (index_of(&old_steps), index_of(&new_steps));
+
+    let mut changed = Vec::new();
+    for (j, new_node) in new_steps.iter().enumerate() {
+        let Some(i) = new_matched[j] else { continue };
+        let old_node = &old_steps[i];
+        let (rule_changed, args_changed, premises_changed) =
+            match (old_node.as_step(), new_node.as_step()) {
+                (Some(a), Some(b)) => {
+                    let premises_match = a.premises.len() == b.premises.len()
+                        && a.premises.iter().zip(&b.premises).all(|(p, q)| {
+                            let q = new_index[last_step(q)];
+                            new_matched[q] == Some(old_index[last_step(p)])
+                        });
+                    (a.rule != b.rule, a.args != b.args, !premises_match)
+                }
+                (None, None) => (false, false, false),
+                _ => (true, false, false),
+            };
+        if rule_changed || args_changed || premises_changed {
+            changed.push(ChangedStep {
+                old: DiffStep::new(old_node),
+                new: DiffStep::new(new_node),
+                rule_changed,
+                args_changed,
+                premises_changed,
+            });
+        }
+    }
+
+    let unmatched_steps = |steps: &[Rc<ProofNode>], matched: &[Option<usize>]| {
+        (steps.iter().zip(matched))
+            .filter(|(_, m)| m.is_none())
+            .map(|(n, _)| DiffStep::new(n))
+            .collect()
+    };
+    ProofDiff {
+        added: unmatched_steps(&new_steps, &new_matched),
+        removed: unmatched_steps(&old_steps, &old_matched),
+        changed,
+        rule_usage,
+        holes,
+    }
+}
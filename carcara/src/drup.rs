@@ -2,7 +2,7 @@ use crate::ast::*;
 use indexmap::IndexSet;
 use std::borrow::{Borrow, BorrowMut};
 use std::collections::hash_map::DefaultHasher;
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::hash::{Hash, Hasher};
 use thiserror::Error;
 
@@ -17,10 +17,12 @@ pub enum Implied<T, X> {
 // A RUP Addition is a vector of the clause plus the unit clause and the hash of the clause
 pub type RupAddition = Vec<(IndexSet<Literal>, Option<Literal>, u64)>;
 
-//This enum is used to bookkeeping the action performed by a reverse unit propagation
+//This enum is used to bookkeeping the action performed by a reverse unit propagation. Each added
+// lemma also carries its original `cl` term, as its hash identifies it in later propagations
 #[allow(dead_code)]
 pub enum DRupProofAction {
-    RupStory(IndexSet<Literal>, RupAddition),
+    RupStory(IndexSet<Literal>, RupAddition, Rc<Term>),
+    RatStory(IndexSet<Literal>, RupAddition, Rc<Term>),
     Delete(Rc<Term>),
 }
 
@@ -40,6 +42,25 @@ pub enum DrupFormatError {
     CheckingRatInEmptyClause,
     #[error("the clause isn't in RAT format")]
     NotInRatFormat,
+    #[error("a lemma that was checked as RAT can't be converted into resolution steps")]
+    RatLemmaInResolution,
+    #[error("propagation conflict in a clause that isn't available")]
+    UnknownConflictClause,
+}
+
+impl DrupFormatError {
+    /// Returns a stable identifier for the kind of this error.
+    pub fn code(&self) -> &'static str {
+        match self {
+            DrupFormatError::NoConclusionInPremise => "drup.no_conclusion_in_premise",
+            DrupFormatError::NoFinalBottomInDrup => "drup.no_final_bottom_in_drup",
+            DrupFormatError::PotentialNoDrupFormat => "drup.potential_no_drup_format",
+            DrupFormatError::CheckingRatInEmptyClause => "drup.checking_rat_in_empty_clause",
+            DrupFormatError::NotInRatFormat => "drup.not_in_rat_format",
+            DrupFormatError::RatLemmaInResolution => "drup.rat_lemma_in_resolution",
+            DrupFormatError::UnknownConflictClause => "drup.unknown_conflict_clause",
+        }
+    }
 }
 
 pub fn hash_term<T: Borrow<Rc<Term>>>(pool: &mut dyn TermPool, term: T) -> u64 {
@@ -248,7 +269,8 @@ pub fn check_drup(
 
         let terms = match_term!((cl ...) = &t).unwrap();
         let mut unit_history = rup(pool, premises.borrow(), terms);
-        if unit_history.is_none() && !terms.is_empty() && check_rat {
+        let is_rat = unit_history.is_none() && !terms.is_empty() && check_rat;
+        if is_rat {
             unit_history = check_drat(pool, premises.borrow(), terms);
         }
 
@@ -268,9 +290,15 @@ pub fn check_drup(
             })
             .collect::<IndexSet<_>>();
 
-        drup_history.push(DRupProofAction::RupStory(
+        let action = if is_rat {
+            DRupProofAction::RatStory
+        } else {
+            DRupProofAction::RupStory
+        };
+        drup_history.push(action(
             terms_indexed_set.clone(),
             unit_history.unwrap(),
+            t.clone(),
         ));
 
         premises.insert(hash_term(pool, t), terms_indexed_set);
@@ -321,3 +349,161 @@ pub fn check_drat(
 
     Some(unit_history)
 }
+
+fn literal_to_term(pool: &mut dyn TermPool, (polarity, atom): &Literal) -> Rc<Term> {
+    if *polarity {
+        atom.clone()
+    } else {
+        build_term!(pool, (not { atom.clone() }))
+    }
+}
+
+// A premise of a step in the derivation of a lemma. It can be either a clause that was already
+// available, identified by its hash, or the previous step in the same derivation
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DerivationPremise {
+    Clause(u64),
+    Previous,
+}
+
+#[derive(Debug)]
+pub struct DerivationStep {
+    pub rule: &'static str,
+    pub clause: Vec<Rc<Term>>,
+    pub premises: Vec<DerivationPremise>,
+    pub args: Vec<Rc<Term>>,
+}
+
+// The steps that derive a lemma of a DRUP proof. The last step concludes exactly the lemma clause,
+// with the literals in the same order. If there are no steps, the lemma is identical to the clause
+// `conflict`, where the unit propagation reached a conflict
+#[derive(Debug)]
+pub struct LemmaDerivation {
+    pub hash: u64,
+    pub conflict: u64,
+    pub steps: Vec<DerivationStep>,
+}
+
+// Converts the propagations recorded while checking a RUP lemma into a `resolution` step, possibly
+// followed by `weakening` and `reordering` steps. The propagation chain is walked backwards from
+// the conflict clause, and each false literal in the current resolvent is resolved against the
+// clause that propagated its negation. Assumption units (coming from the negation of the lemma)
+// are not resolved, since their literals are exactly the ones that remain in the resolvent
+fn derive_rup_lemma(
+    pool: &mut dyn TermPool,
+    lemma: &Rc<Term>,
+    propagations: &RupAddition,
+    available: &HashSet<u64>,
+) -> Result<LemmaDerivation, DrupFormatError> {
+    use DerivationPremise::*;
+
+    let Some(((conflict, None, conflict_hash), chain)) = propagations.split_last() else {
+        return Err(DrupFormatError::NoFinalBottomInDrup);
+    };
+    if !available.contains(conflict_hash) {
+        return Err(DrupFormatError::UnknownConflictClause);
+    }
+
+    let mut resolvent = conflict.clone();
+    let mut premises = vec![Clause(*conflict_hash)];
+    let mut args = Vec::new();
+    for (clause, implied, hash) in chain.iter().rev() {
+        let Some((polarity, atom)) = implied else {
+            continue;
+        };
+        if !available.contains(hash) || !resolvent.swap_remove(&(!polarity, atom.clone())) {
+            continue;
+        }
+        resolvent.extend(
+            clause
+                .iter()
+                .filter(|&l| l != &(*polarity, atom.clone()))
+                .cloned(),
+        );
+        premises.push(Clause(*hash));
+        args.push(atom.clone());
+        args.push(pool.bool_constant(!polarity));
+    }
+
+    let lemma_clause = match_term!((cl ...) = lemma).unwrap();
+    let mut steps = Vec::new();
+    let mut current: Vec<_> = resolvent.iter().map(|l| literal_to_term(pool, l)).collect();
+    if premises.len() > 1 {
+        steps.push(DerivationStep {
+            rule: "resolution",
+            clause: current.clone(),
+            premises,
+            args,
+        });
+    }
+    if current == lemma_clause {
+        return Ok(LemmaDerivation {
+            hash: hash_term(pool, lemma),
+            conflict: *conflict_hash,
+            steps,
+        });
+    }
+    let previous = |steps: &Vec<_>| {
+        if steps.is_empty() {
+            Clause(*conflict_hash)
+        } else {
+            Previous
+        }
+    };
+
+    // The resolvent may be a strict subset of the lemma, in which case we weaken it to reach the
+    // lemma. Note that `weakening` requires the premise to be a prefix of the conclusion. We also
+    // add any repeated literals in the lemma, so the result is a permutation of it
+    let mut missing = lemma_clause.to_vec();
+    for t in &current {
+        if let Some(i) = missing.iter().position(|u| u == t) {
+            missing.remove(i);
+        }
+    }
+    if !missing.is_empty() {
+        current.extend(missing);
+        steps.push(DerivationStep {
+            rule: "weakening",
+            clause: current.clone(),
+            premises: vec![previous(&steps)],
+            args: Vec::new(),
+        });
+    }
+    if current != lemma_clause {
+        steps.push(DerivationStep {
+            rule: "reordering",
+            clause: lemma_clause.to_vec(),
+            premises: vec![previous(&steps)],
+            args: Vec::new(),
+        });
+    }
+    Ok(LemmaDerivation {
+        hash: hash_term(pool, lemma),
+        conflict: *conflict_hash,
+        steps,
+    })
+}
+
+// Converts the story produced by `check_drup` into the derivations of each of its lemmas, in
+// order. `premises` are the hashes of the clauses available before the first lemma. Lemmas
+// checked as RAT can't be represented in resolution, so they result in an error
+pub fn story_to_resolution(
+    pool: &mut dyn TermPool,
+    story: &DRupStory,
+    premises: impl IntoIterator<Item = u64>,
+) -> Result<Vec<LemmaDerivation>, DrupFormatError> {
+    let mut available: HashSet<u64> = premises.into_iter().collect();
+    let mut result = Vec::new();
+    for action in story {
+        match action {
+            DRupProofAction::RupStory(_, propagations, lemma) => {
+                let derivation = derive_rup_lemma(pool, lemma, propagations, &available)?;
+                available.insert(derivation.hash);
+                result.push(derivation);
+            }
+            DRupProofAction::RatStory(..) => return Err(DrupFormatError::RatLemmaInResolution),
+            DRupProofAction::Delete(_) => (),
+        }
+    }
+    Ok(result)
+}
@@ -1,4 +1,4 @@
-use super::{IdHelper, PolyeqElaborator};
+use super::{assert_clause_len, assert_num_premises, IdHelper, PolyeqElaborator};
 use crate::{
     ast::*,
     checker::{apply_bfun_elim, error::CheckerError},
@@ -6,12 +6,13 @@ use crate::{
 use indexmap::IndexMap;
 
 pub fn bfun_elim(
-    pool: &mut PrimitivePool,
+    pool: &mut dyn TermPool,
     _: &mut ContextStack,
     step: &StepNode,
 ) -> Result<Rc<ProofNode>, CheckerError> {
-    assert_eq!(step.premises.len(), 1);
-    assert_eq!(step.clause.len(), 1);
+    assert_num_premises(&step.premises, 1)?;
+    assert_clause_len(&step.clause, 1)?;
+    assert_clause_len(step.premises[0].clause(), 1)?;
     let psi = &step.premises[0].clause()[0];
     let expected = apply_bfun_elim(pool, psi, &mut IndexMap::new())?;
     let got = &step.clause[0];
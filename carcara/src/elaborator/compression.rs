@@ -0,0 +1,259 @@
+//! Elaboration steps that make a proof smaller, without changing its conclusion.
+
+use super::*;
+
+/// The number of commands in the proof, counting each subproof as one command in addition to the
+/// commands inside it.
+pub fn proof_size(root: &Rc<ProofNode>) -> usize {
+    let mut size = 0;
+    root.traverse(|_| size += 1);
+    size
+}
+
+/// Removes steps that don't contribute to the conclusion of the proof. Steps that are not
+/// transitively used as premises of the root are already dropped when the proof is converted into a
+/// `ProofNode`, so this only has to bypass steps that conclude the same clause as one of their
+/// premises, like a `contraction` of a clause with no repeated literals.
+pub fn prune(root: &Rc<ProofNode>) -> Rc<ProofNode> {
+    mutate(root, |_, node| {
+        let ProofNode::Step(s) = node.as_ref() else {
+            return node.clone();
+        };
+
+        // The last step of a subproof can't be bypassed, since it is what the subproof concludes
+        if s.previous_step.is_some() || !s.discharge.is_empty() {
+            return node.clone();
+        }
+        s.premises
+            .iter()
+            .find(|p| p.depth() == s.depth && p.clause() == s.clause)
+            .cloned()
+            .unwrap_or_else(|| node.clone())
+    })
+}
+
+/// Moves steps out of subproofs whose anchors have no arguments, if they don't depend on the local
+/// assumptions of those subproofs. Such subproofs don't change the context, so the steps can be
+/// checked outside of them, and can then be merged with other steps that conclude the same clause.
+/// Each step is moved to the depth of its deepest premise, but never out of a subproof whose anchor
+/// has arguments.
+pub fn lift(root: &Rc<ProofNode>) -> Rc<ProofNode> {
+    mutate(root, |context, node| {
+        let ProofNode::Step(s) = node.as_ref() else {
+            return node.clone();
+        };
+
+        // The last step of a subproof must stay in it
+        if s.previous_step.is_some() || !s.discharge.is_empty() {
+            return node.clone();
+        }
+        let depth = (s.premises.iter())
+            .map(|p| p.depth())
+            .fold(context.len_with_args(), usize::max);
+        if depth >= s.depth {
+            return node.clone();
+        }
+        Rc::new(ProofNode::Step(StepNode { depth, ..s.clone() }))
+    })
+}
+
+/// Merges steps that conclude the same clause in the same context. Since terms are hash consed,
+/// clauses are compared by the identity of their terms. Each step is replaced by the first step
+/// found with the same clause, and assumptions are only merged outside of subproofs, since local
+/// assumptions are discharged by name.
+pub fn merge(root: &Rc<ProofNode>) -> Rc<ProofNode> {
+    let mut seen: HashMap<(Vec<Rc<Term>>, Vec<usize>), Rc<ProofNode>> = HashMap::new();
+    mutate(root, |context, node| {
+        let can_merge = match node.as_ref() {
+            ProofNode::Assume { depth, .. } => *depth == 0,
+            ProofNode::Step(s) => s.previous_step.is_none(),
+            ProofNode::Subproof(_) => false,
+        };
+        if !can_merge {
+            return node.clone();
+        }
+        // Steps that were lifted out of subproofs are in the context of an outer subproof
+        let key = (
+            node.clause().to_vec(),
+            context.ids()[..node.depth()].to_vec(),
+        );
+        seen.entry(key).or_insert_with(|| node.clone()).clone()
+    })
+}
+
+/// Whether the clause of a step can be recomputed from its premises, if they change. This is the
+/// case for `contraction` steps, and for `resolution` and `th_resolution` steps that have their
+/// pivots as arguments.
+fn is_recomputable(step: &StepNode) -> bool {
+    if step.depth > 0 {
+        return false;
+    }
+    match step.rule.as_str() {
+        "resolution" | "th_resolution" => {
+            step.premises.len() >= 2 && step.args.len() == 2 * (step.premises.len() - 1)
+        }
+        "contraction" => step.premises.len() == 1,
+        _ => false,
+    }
+}
+
+/// The literal that is removed from the clause of a premise of a binary resolution, given the pivot
+/// argument and its polarity. If `in_current` is true, this is the literal in the clause derived so
+/// far, otherwise it is the literal in the next premise.
+fn pivot_literal<'a>(
+    pivot: &'a Rc<Term>,
+    polarity: &Rc<Term>,
+    in_current: bool,
+) -> (u32, &'a Rc<Term>) {
+    let (n, term) = pivot.remove_all_negations();
+    if polarity.is_bool_true() == in_current {
+        (n, term)
+    } else {
+        (n + 1, term)
+    }
+}
+
+/// Recomputes the conclusion of a resolution step whose premises may have changed. Premises that no
+/// longer contain their pivot are dropped: if the clause derived so far doesn't contain the pivot,
+/// the premise is unnecessary, and if the premise doesn't contain it, resolution restarts from that
+/// premise, since it is stronger than the clause derived so far.
+fn recompute_resolution(step: &StepNode, premises: &[Rc<ProofNode>]) -> Rc<ProofNode> {
+    let has_literal =
+        |clause: &[Rc<Term>], literal| clause.iter().any(|t| t.remove_all_negations() == literal);
+
+    let mut clause = premises[0].clause().to_vec();
+    let mut new_premises = vec![premises[0].clone()];
+    let mut args = Vec::new();
+    for (premise, pivot) in premises[1..].iter().zip(step.args.chunks(2)) {
+        let in_current = pivot_literal(&pivot[0], &pivot[1], true);
+        let in_next = pivot_literal(&pivot[0], &pivot[1], false);
+        if !has_literal(&clause, in_current) {
+            continue;
+        }
+        if !has_literal(premise.clause(), in_next) {
+            clause = premise.clause().to_vec();
+            new_premises = vec![premise.clone()];
+            args.clear();
+            continue;
+        }
+
+        clause.retain(|t| t.remove_all_negations() != in_current);
+        let mut found_pivot = false;
+        for t in premise.clause() {
+            if !found_pivot && t.remove_all_negations() == in_next {
+                found_pivot = true;
+            } else if !clause.contains(t) {
+                clause.push(t.clone());
+            }
+        }
+        new_premises.push(premise.clone());
+        args.extend(pivot.iter().cloned());
+    }
+
+    if new_premises.len() == 1 {
+        return new_premises.pop().unwrap();
+    }
+    Rc::new(ProofNode::Step(StepNode {
+        clause,
+        premises: new_premises,
+        args,
+        ..step.clone()
+    }))
+}
+
+/// Compresses resolution chains in the style of the `RecycleUnits` and `LowerUnits` algorithms. If a
+/// premise of a resolution step is used only to remove a literal `l`, and the proof derives the
+/// unit clause `(cl l)` before that step, the premise is replaced by the unit clause. The
+/// resolution steps and `contraction` steps that depend on it are then recomputed, dropping
+/// premises that are no longer necessary. Steps whose conclusion must be preserved, because they
+/// are used by other rules, or inside subproofs, are not changed.
+pub fn recycle_units(root: &Rc<ProofNode>) -> Rc<ProofNode> {
+    let mut order = Vec::new();
+    root.traverse(|node| order.push(node.clone()));
+    let index: HashMap<&Rc<ProofNode>, usize> =
+        order.iter().enumerate().map(|(i, n)| (n, i)).collect();
+
+    let mut units: HashMap<&Rc<Term>, &Rc<ProofNode>> = HashMap::new();
+    for node in &order {
+        if let (0, [literal]) = (node.depth(), node.clause()) {
+            units.entry(literal).or_insert(node);
+        }
+    }
+
+    // A node is frozen if its conclusion can't change, either because it is the root of the proof,
+    // or because it is used by a step whose conclusion can't be recomputed
+    let mut frozen: HashSet<&Rc<ProofNode>> = HashSet::new();
+    if !root.clause().is_empty() {
+        frozen.insert(root);
+    }
+    for node in order.iter().rev() {
+        if let ProofNode::Step(s) = node.as_ref() {
+            if frozen.contains(node) || !is_recomputable(s) {
+                frozen.extend(
+                    s.premises
+                        .iter()
+                        .chain(&s.discharge)
+                        .chain(&s.previous_step),
+                );
+            }
+        }
+    }
+
+    let mut mutated: HashMap<Rc<ProofNode>, Rc<ProofNode>> = HashMap::new();
+    mutate_with_original(root, |_, original, node| {
+        let result = match (original.as_ref(), node.as_ref()) {
+            (ProofNode::Step(original_step), ProofNode::Step(s))
+                if !frozen.contains(original) && is_recomputable(s) =>
+            {
+                let mut premises = s.premises.clone();
+                if s.rule != "contraction" {
+                    for (i, premise) in premises.iter_mut().enumerate() {
+                        if premise.clause().len() == 1 {
+                            continue;
+                        }
+                        let pivot = &s.args[2 * i.saturating_sub(1)..];
+                        let literal = pivot_literal(&pivot[0], &pivot[1], i == 0);
+                        let Some(literal) = premise
+                            .clause()
+                            .iter()
+                            .find(|t| t.remove_all_negations() == literal)
+                        else {
+                            continue;
+                        };
+
+                        // The unit must come before this step, so the proof stays acyclic
+                        let unit = units
+                            .get(literal)
+                            .filter(|u| index[**u] < index[original])
+                            .and_then(|u| mutated.get(*u))
+                            .filter(|u| u.clause() == std::slice::from_ref(literal));
+                        if let Some(unit) = unit {
+                            *premise = unit.clone();
+                        }
+                    }
+                }
+
+                let unchanged = premises
+                    .iter()
+                    .zip(&original_step.premises)
+                    .all(|(new, old)| new.clause() == old.clause());
+                if unchanged {
+                    node.clone()
+                } else if s.rule == "contraction" {
+                    let mut clause = Vec::new();
+                    for t in premises[0].clause() {
+                        if !clause.contains(t) {
+                            clause.push(t.clone());
+                        }
+                    }
+                    Rc::new(ProofNode::Step(StepNode { clause, ..s.clone() }))
+                } else {
+                    recompute_resolution(s, &premises)
+                }
+            }
+            _ => node.clone(),
+        };
+        mutated.insert(original.clone(), result.clone());
+        result
+    })
+}
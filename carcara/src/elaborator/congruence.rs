@@ -1,5 +1,8 @@
-use super::IdHelper;
-use crate::{ast::*, checker::error::CheckerError};
+use super::{assert_clause_len, assert_num_premises, IdHelper};
+use crate::{
+    ast::*,
+    checker::error::{CheckerError, CongruenceError},
+};
 
 fn check_cong(
     premises: &[(&Rc<Term>, &Rc<Term>)],
@@ -32,7 +35,7 @@ fn check_cong(
 }
 
 fn build_eq_symm_step(
-    pool: &mut PrimitivePool,
+    pool: &mut dyn TermPool,
     a: &Rc<Term>,
     b: &Rc<Term>,
     id: String,
@@ -50,12 +53,12 @@ fn build_eq_symm_step(
 }
 
 pub fn cong(
-    pool: &mut PrimitivePool,
+    pool: &mut dyn TermPool,
     _: &mut ContextStack,
     step: &StepNode,
 ) -> Result<Rc<ProofNode>, CheckerError> {
-    assert_eq!(step.clause.len(), 1);
-    assert!(!step.premises.is_empty());
+    assert_clause_len(&step.clause, 1)?;
+    assert_num_premises(&step.premises, 1..)?;
 
     let unchanged = || Ok(Rc::new(ProofNode::Step(step.clone())));
 
@@ -80,8 +83,10 @@ pub fn cong(
     let ([f1, f2], [g1, g2]) = (f_args.as_slice(), g_args.as_slice()) else {
         return unchanged();
     };
-    // At this point, we know there are either one or two premises
-    assert!(step.premises.len() <= 2);
+    // Since `f` and `g` have two arguments each, there can be at most two premises
+    if step.premises.len() > 2 {
+        return Err(CongruenceError::TooManyPremises.into());
+    }
 
     // Similar to the `refl` case, sometimes `cong` is used to derive `(= (= a b) (= b a))`. In this
     // case, we turn the step into a `eq_symmetric` step, without any premise
@@ -96,8 +101,11 @@ pub fn cong(
     let premises: Vec<_> = step
         .premises
         .iter()
-        .map(|p| match_term!((= a b) = p.clause()[0]).unwrap())
-        .collect();
+        .map(|p| {
+            assert_clause_len(p.clause(), 1)?;
+            match_term_err!((= a b) = &p.clause()[0])
+        })
+        .collect::<Result<_, _>>()?;
 
     let mut ids = IdHelper::new(&step.id);
     if check_cong(&premises, [f1, f2], [g1, g2]) {
@@ -149,14 +157,18 @@ pub fn cong(
         Ok(trans_step)
     } else if check_cong(&premises, [f2, f1], [g2, g1]) {
         // Both are flipped. This can only happen if there are two premises
-        assert_eq!(step.premises.len(), 2);
+        assert_num_premises(&step.premises, 2)?;
 
         // In this case, we can just reverse the order of the premises
         let mut new_step = step.clone();
         new_step.premises.reverse();
         Ok(Rc::new(ProofNode::Step(new_step)))
     } else {
-        // the step is invalid
-        unreachable!()
+        // The premises don't justify the conclusion in any order, so the step is invalid
+        Err(CongruenceError::PremiseDoesntJustifyArgs {
+            args: (f1.clone(), g1.clone()),
+            premise: (premises[0].0.clone(), premises[0].1.clone()),
+        }
+        .into())
     }
 }
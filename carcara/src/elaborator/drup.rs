@@ -0,0 +1,172 @@
+use super::IdHelper;
+use crate::{ast::*, checker::error::CheckerError, drup::*};
+use std::collections::HashMap;
+
+/// Elaborates a `drup` or `drat` step into `resolution` steps with explicit pivots, one chain for
+/// each lemma in the step arguments. This fails if the step has any lemma that can only be checked
+/// as RAT.
+pub fn drup(pool: &mut PrimitivePool, step: &StepNode) -> Result<Rc<ProofNode>, CheckerError> {
+    let premises: Vec<_> = step
+        .premises
+        .iter()
+        .map(|p| build_term!(pool, (cl[p.clause().to_vec()])))
+        .collect();
+    let conclusion = build_term!(pool, (cl[step.clause.clone()]));
+    let check_rat = step.rule == "drat";
+
+    let story = check_drup(pool, conclusion.clone(), &premises, &step.args, check_rat)
+        .map_err(CheckerError::DrupFormatError)?;
+
+    let mut nodes: HashMap<u64, Rc<ProofNode>> = premises
+        .iter()
+        .zip(&step.premises)
+        .map(|(term, node)| (hash_term(pool, term), node.clone()))
+        .collect();
+    let derivations = story_to_resolution(pool, &story, nodes.keys().copied())
+        .map_err(CheckerError::DrupFormatError)?;
+
+    let mut ids = IdHelper::new(&step.id);
+    for derivation in derivations {
+        let mut previous = nodes[&derivation.conflict].clone();
+        for s in derivation.steps {
+            let premises = s
+                .premises
+                .iter()
+                .map(|p| match p {
+                    DerivationPremise::Clause(hash) => nodes[hash].clone(),
+                    DerivationPremise::Previous => previous.clone(),
+                })
+                .collect();
+            previous = Rc::new(ProofNode::Step(StepNode {
+                id: ids.next_id(),
+                depth: step.depth,
+                clause: s.clause,
+                rule: s.rule.to_owned(),
+                premises,
+                args: s.args,
+                ..Default::default()
+            }));
+        }
+        nodes.insert(derivation.hash, previous);
+    }
+
+    // The node that concludes the step's clause is either one of the premises, or the last step
+    // of the derivation of some lemma. If it is a step that concludes exactly the same clause, we
+    // can just rename it. Otherwise, we need extra steps to conclude the clause
+    let result = &nodes[&hash_term(pool, &conclusion)];
+    let new_step = match result.as_ref() {
+        ProofNode::Step(s) if s.clause == step.clause && !step.premises.contains(result) => {
+            StepNode { id: step.id.clone(), ..s.clone() }
+        }
+        _ => weaken_and_reorder(&mut ids, step, result.clone()),
+    };
+    Ok(Rc::new(ProofNode::Step(new_step)))
+}
+
+/// Concludes the clause of `step` from `premise`, whose literals must all appear in that clause.
+/// If the premise is not a prefix of the clause, it is first weakened with the missing literals,
+/// and then reordered.
+fn weaken_and_reorder(ids: &mut IdHelper, step: &StepNode, premise: Rc<ProofNode>) -> StepNode {
+    let conclude = |rule: &str, premise| StepNode {
+        id: step.id.clone(),
+        depth: step.depth,
+        clause: step.clause.clone(),
+        rule: rule.to_owned(),
+        premises: vec![premise],
+        ..Default::default()
+    };
+    if step.clause.starts_with(premise.clause()) {
+        return conclude("weakening", premise);
+    }
+
+    // We count the occurrences of each literal in the premise, so that repeated literals in the
+    // conclusion are also added by the weakening step
+    let mut remaining: HashMap<&Rc<Term>, usize> = HashMap::new();
+    for literal in premise.clause() {
+        *remaining.entry(literal).or_default() += 1;
+    }
+    let missing: Vec<_> = (step.clause.iter())
+        .filter(|&l| match remaining.get_mut(l) {
+            Some(n) if *n > 0 => {
+                *n -= 1;
+                false
+            }
+            _ => true,
+        })
+        .cloned()
+        .collect();
+
+    let premise = if missing.is_empty() {
+        premise
+    } else {
+        let clause = premise.clause().iter().chain(&missing).cloned().collect();
+        Rc::new(ProofNode::Step(StepNode {
+            id: ids.next_id(),
+            depth: step.depth,
+            clause,
+            rule: "weakening".to_owned(),
+            premises: vec![premise],
+            ..Default::default()
+        }))
+    };
+    conclude("reordering", premise)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::{self, parse_instance};
+
+    #[test]
+    fn test_weaken_and_reorder() {
+        fn run(proof: &str) -> Vec<(String, Vec<String>)> {
+            let problem = b"
+                (declare-const a Bool)
+                (declare-const b Bool)
+                (declare-const c Bool)
+            ";
+            let (_, proof, _) =
+                parse_instance(&problem[..], proof.as_bytes(), parser::Config::new()).unwrap();
+            let proof = ProofNode::from_commands(proof.commands);
+            let ProofNode::Step(step) = proof.as_ref() else {
+                unreachable!();
+            };
+            let premise = step.premises[0].clone();
+            let got = weaken_and_reorder(&mut IdHelper::new(&step.id), step, premise);
+
+            // Returns the rule and clause of each new step, starting from the conclusion
+            let mut result = Vec::new();
+            let mut current = Rc::new(ProofNode::Step(got));
+            while let ProofNode::Step(s) = current.as_ref() {
+                if s.rule == "hole" {
+                    break;
+                }
+                let clause = s.clause.iter().map(ToString::to_string).collect();
+                result.push((s.rule.clone(), clause));
+                current = s.premises[0].clone();
+            }
+            result
+        }
+        let step = |rule: &str, clause: &[&str]| {
+            let clause = clause.iter().map(|&l| l.to_owned()).collect();
+            (rule.to_owned(), clause)
+        };
+
+        let got =
+            run("(step t1 (cl a b) :rule hole) (step t2 (cl a b c) :rule drup :premises (t1))");
+        assert_eq!(got, [step("weakening", &["a", "b", "c"])]);
+
+        let got = run("(step t1 (cl a b) :rule hole) (step t2 (cl b a) :rule drup :premises (t1))");
+        assert_eq!(got, [step("reordering", &["b", "a"])]);
+
+        let got = run("(step t1 (cl a (not b)) :rule hole)
+            (step t2 (cl (not b) c a c) :rule drup :premises (t1))");
+        assert_eq!(
+            got,
+            [
+                step("reordering", &["(not b)", "c", "a", "c"]),
+                step("weakening", &["a", "(not b)", "c", "c"]),
+            ]
+        );
+    }
+}
@@ -58,7 +58,8 @@ fn get_problem_string(
     problem
 }
 
-pub fn hole(elaborator: &mut Elaborator, step: &StepNode) -> Option<Rc<ProofNode>> {
+/// Builds the problem that is given to the solver to elaborate a hole step.
+pub fn get_problem(elaborator: &mut Elaborator, step: &StepNode) -> String {
     let prelude = elaborator.problem.prelude.clone();
     let prelude = if prelude.logic.as_deref() == Some("QF_LIA") {
         ProblemPrelude {
@@ -68,21 +69,23 @@ pub fn hole(elaborator: &mut Elaborator, step: &StepNode) -> Option<Rc<ProofNode
     } else {
         prelude
     };
-    let problem = get_problem_string(elaborator.pool, &prelude, &step.clause);
-    let options = elaborator.config.hole_options.as_ref().unwrap();
-    let commands = match get_solver_proof(elaborator.pool, problem.clone(), options) {
-        Ok((c, false)) => c,
-        Ok((_, true)) => {
-            log::warn!("failed to elaborate `all_simplify` step: solver proof contains holes");
-            return None;
-        }
-        Err(e) => {
-            log::warn!("failed to elaborate `all_simplify` step: {}", e);
-            return None;
-        }
-    };
+    get_problem_string(elaborator.pool, &prelude, &step.clause)
+}
 
-    Some(insert_solver_proof(
+/// Builds the node that replaces a hole step, given the output of the solver on the problem built
+/// by `get_problem`. Returns an error if the solver failed, or if its proof is invalid or has holes.
+pub fn insert_proof(
+    elaborator: &mut Elaborator,
+    step: &StepNode,
+    problem: &str,
+    output: Result<Vec<u8>, HoleError>,
+) -> Result<Rc<ProofNode>, ElaborationErrorKind> {
+    let (commands, has_holes) =
+        output.and_then(|o| get_solver_proof(elaborator.pool, problem, &o))?;
+    if has_holes {
+        return Err(ElaborationErrorKind::SolverProofHasHoles);
+    }
+    Ok(insert_solver_proof(
         elaborator.pool,
         commands,
         &step.clause,
@@ -91,11 +94,7 @@ pub fn hole(elaborator: &mut Elaborator, step: &StepNode) -> Option<Rc<ProofNode
     ))
 }
 
-fn get_solver_proof(
-    pool: &mut PrimitivePool,
-    problem: String,
-    options: &HoleOptions,
-) -> Result<(Vec<ProofCommand>, bool), HoleError> {
+pub fn run_solver(options: &HoleOptions, problem: &str) -> Result<Vec<u8>, HoleError> {
     let mut process = Command::new(options.solver.as_ref())
         .args(options.arguments.iter().map(AsRef::as_ref))
         .stdin(Stdio::piped())
@@ -104,12 +103,14 @@ fn get_solver_proof(
         .spawn()
         .map_err(HoleError::FailedSpawnSolver)?;
 
-    process
-        .stdin
-        .take()
-        .expect("failed to open solver stdin")
+    let mut stdin = process.stdin.take().ok_or_else(|| {
+        let e = io::Error::new(io::ErrorKind::BrokenPipe, "solver stdin is not available");
+        HoleError::FailedWriteToSolverStdin(e)
+    })?;
+    stdin
         .write_all(problem.as_bytes())
         .map_err(HoleError::FailedWriteToSolverStdin)?;
+    drop(stdin);
 
     let output = process
         .wait_with_output()
@@ -124,7 +125,15 @@ fn get_solver_proof(
         return Err(HoleError::NonZeroExitCode(output.status.code()));
     }
 
-    let mut proof = output.stdout.as_slice();
+    Ok(output.stdout)
+}
+
+fn get_solver_proof(
+    pool: &mut PrimitivePool,
+    problem: &str,
+    output: &[u8],
+) -> Result<(Vec<ProofCommand>, bool), HoleError> {
+    let mut proof = output;
     let mut first_line = String::new();
 
     proof
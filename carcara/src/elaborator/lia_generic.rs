@@ -58,18 +58,21 @@ fn get_problem_string(
     problem
 }
 
-pub fn lia_generic(elaborator: &mut Elaborator, step: &StepNode) -> Option<Rc<ProofNode>> {
-    let problem = get_problem_string(elaborator.pool, &elaborator.problem.prelude, &step.clause);
-    let options = elaborator.config.lia_options.as_ref().unwrap();
-    let commands = match get_solver_proof(elaborator.pool, problem, options) {
-        Ok(c) => c,
-        Err(e) => {
-            log::warn!("failed to elaborate `lia_generic` step: {}", e);
-            return None;
-        }
-    };
+/// Builds the problem that is given to the solver to elaborate a `lia_generic` step.
+pub fn get_problem(elaborator: &mut Elaborator, step: &StepNode) -> String {
+    get_problem_string(elaborator.pool, &elaborator.problem.prelude, &step.clause)
+}
 
-    Some(insert_solver_proof(
+/// Builds the node that replaces a `lia_generic` step, given the output of the solver on the problem
+/// built by `get_problem`. Returns an error if the solver failed, or if its proof is invalid.
+pub fn insert_proof(
+    elaborator: &mut Elaborator,
+    step: &StepNode,
+    problem: &str,
+    output: Result<Vec<u8>, LiaGenericError>,
+) -> Result<Rc<ProofNode>, ElaborationErrorKind> {
+    let commands = output.and_then(|o| get_solver_proof(elaborator.pool, problem, &o))?;
+    Ok(insert_solver_proof(
         elaborator.pool,
         commands,
         &step.clause,
@@ -78,11 +81,7 @@ pub fn lia_generic(elaborator: &mut Elaborator, step: &StepNode) -> Option<Rc<Pr
     ))
 }
 
-fn get_solver_proof(
-    pool: &mut PrimitivePool,
-    problem: String,
-    options: &LiaGenericOptions,
-) -> Result<Vec<ProofCommand>, LiaGenericError> {
+pub fn run_solver(options: &LiaGenericOptions, problem: &str) -> Result<Vec<u8>, LiaGenericError> {
     let mut process = Command::new(options.solver.as_ref())
         .args(options.arguments.iter().map(AsRef::as_ref))
         .stdin(Stdio::piped())
@@ -91,12 +90,14 @@ fn get_solver_proof(
         .spawn()
         .map_err(LiaGenericError::FailedSpawnSolver)?;
 
-    process
-        .stdin
-        .take()
-        .expect("failed to open solver stdin")
+    let mut stdin = process.stdin.take().ok_or_else(|| {
+        let e = io::Error::new(io::ErrorKind::BrokenPipe, "solver stdin is not available");
+        LiaGenericError::FailedWriteToSolverStdin(e)
+    })?;
+    stdin
         .write_all(problem.as_bytes())
         .map_err(LiaGenericError::FailedWriteToSolverStdin)?;
+    drop(stdin);
 
     let output = process
         .wait_with_output()
@@ -111,7 +112,15 @@ fn get_solver_proof(
         return Err(LiaGenericError::NonZeroExitCode(output.status.code()));
     }
 
-    let mut proof = output.stdout.as_slice();
+    Ok(output.stdout)
+}
+
+fn get_solver_proof(
+    pool: &mut PrimitivePool,
+    problem: &str,
+    output: &[u8],
+) -> Result<Vec<ProofCommand>, LiaGenericError> {
+    let mut proof = output;
     let mut first_line = String::new();
 
     proof
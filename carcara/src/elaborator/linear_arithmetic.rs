@@ -0,0 +1,36 @@
+use crate::{ast::*, checker::error::CheckerError, checker::find_la_generic_args};
+use rug::Integer;
+
+fn coefficients_to_terms(pool: &mut dyn TermPool, coefficients: Vec<Integer>) -> Vec<Rc<Term>> {
+    coefficients
+        .into_iter()
+        .map(|c| {
+            if c < 0 {
+                let c = pool.add(Term::new_int(-c));
+                build_term!(pool, (-{ c }))
+            } else {
+                pool.add(Term::new_int(c))
+            }
+        })
+        .collect()
+}
+
+/// Adds the coefficients to a `la_generic` step that omits them. If the coefficients can't be
+/// found, the step is kept as is.
+pub fn la_generic(
+    pool: &mut dyn TermPool,
+    _: &mut ContextStack,
+    step: &StepNode,
+) -> Result<Rc<ProofNode>, CheckerError> {
+    let mut step = step.clone();
+    if step.args.is_empty() {
+        match find_la_generic_args(&step.clause)? {
+            Some(coefficients) => step.args = coefficients_to_terms(pool, coefficients),
+            None => log::warn!(
+                "couldn't find coefficients for `la_generic` step '{}'",
+                step.id
+            ),
+        }
+    }
+    Ok(Rc::new(ProofNode::Step(step)))
+}
@@ -1,7 +1,12 @@
 mod clausification;
+mod compression;
 mod congruence;
+mod drup;
 mod hole;
 mod lia_generic;
+mod linear_arithmetic;
+mod parallel;
+mod pipeline;
 mod polyeq;
 mod quantifiers;
 mod reflexivity;
@@ -12,13 +17,24 @@ mod tautology;
 mod transitivity;
 mod uncrowding;
 
-use crate::{ast::*, CheckerError};
+use crate::{
+    ast::*,
+    lia_prover::{self, LiaProverResult},
+    utils::Range,
+    CheckerError,
+};
 use indexmap::IndexSet;
 use polyeq::PolyeqElaborator;
 use std::{
     collections::{HashMap, HashSet},
+    sync::Arc,
     time::{Duration, Instant},
 };
+use thiserror::Error;
+
+pub use hole::HoleError;
+pub use lia_generic::LiaGenericError;
+pub use pipeline::{parse_pipeline, PipelineError, PipelineStep};
 
 #[derive(Debug, Clone)]
 pub struct Config {
@@ -33,16 +49,85 @@ pub struct Config {
     pub uncrowd_rotation: bool,
 
     pub hole_options: Option<HoleOptions>,
+
+    /// The number of threads used to elaborate steps of local rules (in the `Local` elaboration
+    /// step). If this is 0 or 1, these steps are elaborated sequentially.
+    pub num_threads: usize,
+
+    /// The maximum number of external solver processes that may run at the same time, when
+    /// elaborating `lia_generic` or hole steps. If this is 0 or 1, the solver is called for one
+    /// step at a time.
+    pub num_solver_processes: usize,
 }
 
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum ElaborationStep {
     Polyeq,
     LiaGeneric,
+    Drup,
     Local,
     Uncrowd,
     Reordering,
     Hole,
+    Prune,
+    Lift,
+    Merge,
+    RecycleUnits,
+}
+
+/// A step that could not be elaborated. Such steps are kept unchanged in the elaborated proof, and
+/// the remaining steps are still elaborated.
+#[derive(Debug, Error)]
+#[error("couldn't elaborate step '{step}' with rule '{rule}': {inner}")]
+pub struct ElaborationError {
+    pub step: String,
+    pub rule: String,
+    pub inner: ElaborationErrorKind,
+}
+
+#[derive(Debug, Error)]
+pub enum ElaborationErrorKind {
+    #[error(transparent)]
+    Checker(#[from] CheckerError),
+
+    #[error("term '{0}' is not an assertion of the problem")]
+    AssumeNotInProblem(Rc<Term>),
+
+    #[error("`lia_generic` solver failed: {0}")]
+    LiaGenericSolver(#[from] LiaGenericError),
+
+    #[error("hole solver failed: {0}")]
+    HoleSolver(#[from] HoleError),
+
+    #[error("solver proof contains holes")]
+    SolverProofHasHoles,
+}
+
+impl ElaborationError {
+    fn new(node: &ProofNode, inner: impl Into<ElaborationErrorKind>) -> Self {
+        let rule = match node {
+            ProofNode::Assume { .. } => "assume",
+            ProofNode::Step(s) => &s.rule,
+            ProofNode::Subproof(_) => unreachable!("subproofs are never elaborated as a whole"),
+        };
+        Self {
+            step: node.id().to_owned(),
+            rule: rule.to_owned(),
+            inner: inner.into(),
+        }
+    }
+
+    /// Returns a stable identifier for the kind of this error. For checker errors, this is the code
+    /// of the underlying error (see `CheckerError::code`).
+    pub fn code(&self) -> &'static str {
+        match &self.inner {
+            ElaborationErrorKind::Checker(e) => e.code(),
+            ElaborationErrorKind::AssumeNotInProblem(_) => "elaboration.assume_not_in_problem",
+            ElaborationErrorKind::LiaGenericSolver(_) => "elaboration.lia_generic_solver",
+            ElaborationErrorKind::HoleSolver(_) => "elaboration.hole_solver",
+            ElaborationErrorKind::SolverProofHasHoles => "elaboration.solver_proof_has_holes",
+        }
+    }
 }
 
 /// The options that control how `lia_generic` steps are elaborated using an external solver.
@@ -67,55 +152,189 @@ pub struct HoleOptions {
     pub arguments: Vec<Box<str>>,
 }
 
+/// A function that elaborates steps of a specific rule, used in the `Local` elaboration step. The
+/// function receives the step to be elaborated, and returns the node that replaces it. Functions for
+/// additional rules can be added using [`Elaborator::register_rule`].
+pub trait ElaborationFunc: Send + Sync {
+    fn elaborate(
+        &self,
+        pool: &mut dyn TermPool,
+        context: &mut ContextStack,
+        step: &StepNode,
+    ) -> Result<Rc<ProofNode>, CheckerError>;
+}
+
+impl<F> ElaborationFunc for F
+where
+    F: Fn(&mut dyn TermPool, &mut ContextStack, &StepNode) -> Result<Rc<ProofNode>, CheckerError>
+        + Send
+        + Sync,
+{
+    fn elaborate(
+        &self,
+        pool: &mut dyn TermPool,
+        context: &mut ContextStack,
+        step: &StepNode,
+    ) -> Result<Rc<ProofNode>, CheckerError> {
+        self(pool, context, step)
+    }
+}
+
+type LocalRules = HashMap<String, Arc<dyn ElaborationFunc>>;
+
+/// The functions used to elaborate each rule in the `Local` elaboration step, unless they are
+/// replaced using [`Elaborator::register_rule`].
+fn default_local_rules() -> LocalRules {
+    let rules: [(&str, Arc<dyn ElaborationFunc>); 11] = [
+        ("eq_transitive", Arc::new(transitivity::eq_transitive)),
+        ("trans", Arc::new(transitivity::trans)),
+        ("resolution", Arc::new(resolution::resolution)),
+        ("th_resolution", Arc::new(resolution::resolution)),
+        ("cong", Arc::new(congruence::cong)),
+        ("forall_inst", Arc::new(quantifiers::forall_inst)),
+        ("qnt_cnf", Arc::new(quantifiers::qnt_cnf)),
+        ("subproof", Arc::new(subproof::subproof)),
+        ("ite_intro", Arc::new(tautology::ite_intro)),
+        ("bfun_elim", Arc::new(clausification::bfun_elim)),
+        ("la_generic", Arc::new(linear_arithmetic::la_generic)),
+    ];
+    rules
+        .into_iter()
+        .map(|(rule, func)| (rule.to_owned(), func))
+        .collect()
+}
+
 pub struct Elaborator<'e> {
     pool: &'e mut PrimitivePool,
     problem: &'e Problem,
     config: Config,
+    errors: Vec<ElaborationError>,
+    compressions: Vec<(ElaborationStep, usize, usize)>,
+    local_rules: LocalRules,
 }
 
 impl<'e> Elaborator<'e> {
     pub fn new(pool: &'e mut PrimitivePool, problem: &'e Problem, config: Config) -> Self {
-        Self { pool, problem, config }
+        Self {
+            pool,
+            problem,
+            config,
+            errors: Vec::new(),
+            compressions: Vec::new(),
+            local_rules: default_local_rules(),
+        }
+    }
+
+    /// Registers a function to elaborate steps of the given rule in the `Local` elaboration step.
+    /// If there already is a function for that rule, it is replaced.
+    pub fn register_rule(&mut self, rule: &str, func: impl ElaborationFunc + 'static) {
+        self.local_rules.insert(rule.to_owned(), Arc::new(func));
+    }
+
+    /// The steps that could not be elaborated so far, in the order they were found.
+    pub fn errors(&self) -> &[ElaborationError] {
+        &self.errors
+    }
+
+    /// The compression steps applied so far, together with the number of commands in the proof
+    /// before and after each of them.
+    pub fn compressions(&self) -> &[(ElaborationStep, usize, usize)] {
+        &self.compressions
+    }
+
+    /// Consumes the elaborator, returning the steps that could not be elaborated.
+    pub fn into_errors(self) -> Vec<ElaborationError> {
+        self.errors
+    }
+
+    /// Records the error if elaborating `node` failed, in which case `node` is kept as it is.
+    fn keep_on_error<E>(
+        &mut self,
+        node: &Rc<ProofNode>,
+        result: Result<Rc<ProofNode>, E>,
+    ) -> Rc<ProofNode>
+    where
+        E: Into<ElaborationErrorKind>,
+    {
+        result.unwrap_or_else(|e| {
+            self.errors.push(ElaborationError::new(node, e));
+            node.clone()
+        })
     }
 
     pub fn elaborate_with_default_pipeline(&mut self, root: &Rc<ProofNode>) -> Rc<ProofNode> {
         use ElaborationStep::*;
-        let pipeline = vec![Polyeq, LiaGeneric, Local, Uncrowd, Reordering];
+        let pipeline = vec![Polyeq, LiaGeneric, Drup, Local, Uncrowd, Reordering];
         self.elaborate(root, pipeline)
     }
 
-    pub fn elaborate(
+    /// Elaborates the proof using the given pipeline. Each step of the pipeline can be either an
+    /// [`ElaborationStep`], or a [`PipelineStep`] that restricts which rules it applies to.
+    pub fn elaborate<S: Into<PipelineStep>>(
         &mut self,
         root: &Rc<ProofNode>,
-        pipeline: Vec<ElaborationStep>,
+        pipeline: Vec<S>,
     ) -> Rc<ProofNode> {
         self.elaborate_with_stats(root, pipeline).0
     }
 
-    pub fn elaborate_with_stats(
+    pub fn elaborate_with_stats<S: Into<PipelineStep>>(
         &mut self,
         root: &Rc<ProofNode>,
-        pipeline: Vec<ElaborationStep>,
+        pipeline: Vec<S>,
     ) -> (Rc<ProofNode>, Vec<Duration>) {
         let mut durations = Vec::new();
         let mut current = root.clone();
         for step in pipeline {
+            let step: PipelineStep = step.into();
             let time = Instant::now();
-            current = match step {
-                ElaborationStep::Polyeq => self.elaborate_polyeq(&current),
-                ElaborationStep::LiaGeneric if self.config.lia_options.is_some() => {
-                    mutate(&current, |_, node| match node.as_ref() {
+            current = match step.step {
+                ElaborationStep::Polyeq => self.elaborate_polyeq(&current, &step),
+                ElaborationStep::LiaGeneric if !step.applies_to("lia_generic") => current,
+                ElaborationStep::LiaGeneric => {
+                    // We first try to prove each step with the built-in prover, and only call
+                    // the solver for the steps it couldn't prove
+                    let current = mutate(&current, |_, node| match node.as_ref() {
                         ProofNode::Step(s) if s.rule == "lia_generic" => {
-                            lia_generic::lia_generic(self, s).unwrap_or_else(|| node.clone())
+                            match lia_prover::prove(self.pool, &s.clause, &s.id, s.depth) {
+                                LiaProverResult::Proved(proof) => proof,
+                                _ => node.clone(),
+                            }
                         }
                         _ => node.clone(),
-                    })
+                    });
+                    let default = (self.config.lia_options.as_ref())
+                        .map(|o| (o.solver.as_ref(), o.arguments.as_slice()));
+                    match step.solver_options(default) {
+                        Some((solver, arguments)) => {
+                            let options = LiaGenericOptions { solver, arguments };
+                            parallel::elaborate_with_solver(
+                                self,
+                                &current,
+                                |s| s.rule == "lia_generic",
+                                lia_generic::get_problem,
+                                |problem| lia_generic::run_solver(&options, problem),
+                                lia_generic::insert_proof,
+                            )
+                        }
+                        None => current,
+                    }
                 }
-                ElaborationStep::LiaGeneric => current.clone(),
-                ElaborationStep::Local => self.elaborate_local(&current),
+                ElaborationStep::Drup => mutate(&current, |_, node| match node.as_ref() {
+                    ProofNode::Step(s)
+                        if (s.rule == "drup" || s.rule == "drat") && step.applies_to(&s.rule) =>
+                    {
+                        // Steps with RAT lemmas can't be elaborated, so we keep them as they are
+                        let result = drup::drup(self.pool, s);
+                        self.keep_on_error(node, result)
+                    }
+                    _ => node.clone(),
+                }),
+                ElaborationStep::Local => self.elaborate_local(&current, &step),
                 ElaborationStep::Uncrowd => mutate(&current, |_, node| match node.as_ref() {
                     ProofNode::Step(s)
                         if (s.rule == "resolution" || s.rule == "th_resolution")
+                            && step.applies_to(&s.rule)
                             && !s.args.is_empty() =>
                     {
                         uncrowding::uncrowd_resolution(self.pool, s, self.config.uncrowd_rotation)
@@ -124,61 +343,104 @@ impl<'e> Elaborator<'e> {
                 }),
                 ElaborationStep::Reordering => reordering::remove_reorderings(&current),
                 ElaborationStep::Hole => {
-                    if self.config.hole_options.is_none() {
-                        current.clone()
-                    } else {
-                        mutate(&current, |_, node| match node.as_ref() {
-                            ProofNode::Step(s)
-                                if (s.rule == "all_simplify" || s.rule == "rare_rewrite") =>
-                            {
-                                hole::hole(self, s).unwrap_or_else(|| node.clone())
-                            }
-                            _ => node.clone(),
-                        })
+                    let default = (self.config.hole_options.as_ref())
+                        .map(|o| (o.solver.as_ref(), o.arguments.as_slice()));
+                    match step.solver_options(default) {
+                        Some((solver, arguments)) => {
+                            let options = HoleOptions { solver, arguments };
+                            let is_hole = |s: &StepNode| match &step.rules {
+                                Some(rules) => rules.contains(&s.rule),
+                                None => s.rule == "all_simplify" || s.rule == "rare_rewrite",
+                            };
+                            parallel::elaborate_with_solver(
+                                self,
+                                &current,
+                                is_hole,
+                                hole::get_problem,
+                                |problem| hole::run_solver(&options, problem),
+                                hole::insert_proof,
+                            )
+                        }
+                        None => current,
                     }
                 }
+                ElaborationStep::Prune => self.compress(&current, step.step, compression::prune),
+                ElaborationStep::Lift => self.compress(&current, step.step, compression::lift),
+                ElaborationStep::Merge => self.compress(&current, step.step, compression::merge),
+                ElaborationStep::RecycleUnits => {
+                    self.compress(&current, step.step, compression::recycle_units)
+                }
             };
             durations.push(time.elapsed());
         }
         (current, durations)
     }
 
-    fn elaborate_polyeq(&mut self, root: &Rc<ProofNode>) -> Rc<ProofNode> {
-        mutate(root, |context, node| {
-            match node.as_ref() {
-                ProofNode::Assume { id, depth, term }
-                    if context.is_empty() && !self.problem.premises.contains(term) =>
-                {
-                    self.elaborate_assume(id, *depth, term)
-                }
-                ProofNode::Step(s) if s.rule == "refl" => {
-                    reflexivity::refl(self.pool, context, s).unwrap() // TODO: add proper error handling
-                }
-                _ => node.clone(),
+    /// Applies a compression step to the proof, and records the size of the proof before and after
+    /// it.
+    fn compress(
+        &mut self,
+        root: &Rc<ProofNode>,
+        step: ElaborationStep,
+        pass: fn(&Rc<ProofNode>) -> Rc<ProofNode>,
+    ) -> Rc<ProofNode> {
+        let compressed = pass(root);
+        let sizes = (
+            compression::proof_size(root),
+            compression::proof_size(&compressed),
+        );
+        log::info!(
+            "{:?} step reduced the proof from {} to {} commands",
+            step,
+            sizes.0,
+            sizes.1,
+        );
+        self.compressions.push((step, sizes.0, sizes.1));
+        compressed
+    }
+
+    fn elaborate_polyeq(&mut self, root: &Rc<ProofNode>, step: &PipelineStep) -> Rc<ProofNode> {
+        mutate(root, |context, node| match node.as_ref() {
+            ProofNode::Assume { id, depth, term }
+                if context.is_empty()
+                    && step.applies_to("assume")
+                    && !self.problem.premises.contains(term) =>
+            {
+                let result = self.elaborate_assume(id, *depth, term);
+                self.keep_on_error(node, result)
             }
+            ProofNode::Step(s) if s.rule == "refl" && step.applies_to("refl") => {
+                let result = reflexivity::refl(self.pool, context, s);
+                self.keep_on_error(node, result)
+            }
+            _ => node.clone(),
         })
     }
 
-    fn elaborate_local(&mut self, root: &Rc<ProofNode>) -> Rc<ProofNode> {
-        fn get_elaboration_function(rule: &str) -> Option<ElaborationFunc> {
-            Some(match rule {
-                "eq_transitive" => transitivity::eq_transitive,
-                "trans" => transitivity::trans,
-                "resolution" | "th_resolution" => resolution::resolution,
-                "cong" => congruence::cong,
-                "forall_inst" => quantifiers::forall_inst,
-                "subproof" => subproof::subproof,
-                "ite_intro" => tautology::ite_intro,
-                "bfun_elim" => clausification::bfun_elim,
-                _ => return None,
-            })
+    fn elaborate_local(&mut self, root: &Rc<ProofNode>, step: &PipelineStep) -> Rc<ProofNode> {
+        let local_rules = self.local_rules.clone();
+        let get_elaboration_function = |rule: &str| {
+            let func = local_rules.get(rule).filter(|_| step.applies_to(rule));
+            func.cloned()
+        };
+
+        if self.config.num_threads > 1 {
+            let num_threads = self.config.num_threads;
+            return parallel::mutate_parallel(
+                self.pool,
+                &mut self.errors,
+                root,
+                num_threads,
+                get_elaboration_function,
+            );
         }
 
         mutate(root, |context, node| {
             match node.as_ref() {
                 ProofNode::Step(s) => {
                     if let Some(func) = get_elaboration_function(&s.rule) {
-                        return func(self.pool, context, s).unwrap(); // TODO: add proper error handling
+                        let result = func.elaborate(self.pool, context, s);
+                        return self.keep_on_error(node, result);
                     }
                 }
                 ProofNode::Subproof(_) => unreachable!(),
@@ -188,7 +450,12 @@ impl<'e> Elaborator<'e> {
         })
     }
 
-    fn elaborate_assume(&mut self, id: &str, depth: usize, term: &Rc<Term>) -> Rc<ProofNode> {
+    fn elaborate_assume(
+        &mut self,
+        id: &str,
+        depth: usize,
+        term: &Rc<Term>,
+    ) -> Result<Rc<ProofNode>, ElaborationErrorKind> {
         let mut found = None;
         for p in &self.problem.premises {
             if Polyeq::new()
@@ -200,7 +467,8 @@ impl<'e> Elaborator<'e> {
                 break;
             }
         }
-        let premise = found.expect("trying to elaborate assume, but it is invalid!");
+        let premise =
+            found.ok_or_else(|| ElaborationErrorKind::AssumeNotInProblem(term.clone()))?;
 
         let new_assume = Rc::new(ProofNode::Assume {
             id: id.to_owned(),
@@ -227,7 +495,7 @@ impl<'e> Elaborator<'e> {
             ..Default::default()
         }));
 
-        Rc::new(ProofNode::Step(StepNode {
+        Ok(Rc::new(ProofNode::Step(StepNode {
             id: ids.next_id(),
             depth,
             clause: vec![term.clone()],
@@ -235,7 +503,7 @@ impl<'e> Elaborator<'e> {
             premises: vec![new_assume, equiv1_step],
             args: vec![premise, self.pool.bool_true()],
             ..Default::default()
-        }))
+        })))
     }
 }
 
@@ -258,12 +526,18 @@ pub fn add_refl_step(
     }))
 }
 
-type ElaborationFunc =
-    fn(&mut PrimitivePool, &mut ContextStack, &StepNode) -> Result<Rc<ProofNode>, CheckerError>;
-
 fn mutate<F>(root: &Rc<ProofNode>, mut mutate_func: F) -> Rc<ProofNode>
 where
     F: FnMut(&mut ContextStack, &Rc<ProofNode>) -> Rc<ProofNode>,
+{
+    mutate_with_original(root, |context, _, node| mutate_func(context, node))
+}
+
+/// Like `mutate`, but the mutation function also receives the original node, from before its
+/// premises were mutated.
+fn mutate_with_original<F>(root: &Rc<ProofNode>, mut mutate_func: F) -> Rc<ProofNode>
+where
+    F: FnMut(&mut ContextStack, &Rc<ProofNode>, &Rc<ProofNode>) -> Rc<ProofNode>,
 {
     let mut cache: HashMap<&Rc<ProofNode>, Rc<ProofNode>> = HashMap::new();
     let mut did_outbound: HashSet<&Rc<ProofNode>> = HashSet::new();
@@ -278,7 +552,7 @@ where
         }
 
         let mutated = match node.as_ref() {
-            ProofNode::Assume { .. } => mutate_func(&mut context, node),
+            ProofNode::Assume { .. } => mutate_func(&mut context, node, node),
             ProofNode::Step(s) if !is_done => {
                 todo.push((node, true));
 
@@ -305,7 +579,7 @@ where
                     previous_step,
                     ..s.clone()
                 }));
-                mutate_func(&mut context, &new_node)
+                mutate_func(&mut context, node, &new_node)
             }
             ProofNode::Subproof(s) if !is_done => {
                 assert!(
@@ -347,6 +621,27 @@ where
     cache[root].clone()
 }
 
+/// Returns an error if the length of the clause is not in the given range.
+fn assert_clause_len<T: Into<Range>>(clause: &[Rc<Term>], range: T) -> Result<(), CheckerError> {
+    let range = range.into();
+    if !range.contains(clause.len()) {
+        return Err(CheckerError::WrongLengthOfClause(range, clause.len()));
+    }
+    Ok(())
+}
+
+/// Returns an error if the number of premises is not in the given range.
+fn assert_num_premises<T: Into<Range>>(
+    premises: &[Rc<ProofNode>],
+    range: T,
+) -> Result<(), CheckerError> {
+    let range = range.into();
+    if !range.contains(premises.len()) {
+        return Err(CheckerError::WrongNumberOfPremises(range, premises.len()));
+    }
+    Ok(())
+}
+
 struct IdHelper {
     root: String,
     stack: Vec<usize>,
@@ -0,0 +1,309 @@
+//! Elaboration of independent proof nodes using multiple threads.
+//!
+//! The steps to be elaborated are first collected from the original proof, and elaborated
+//! concurrently, each one using the original nodes as its premises. Then, the elaborated steps are
+//! spliced into the proof sequentially, in the same order used by `mutate`, replacing the original
+//! premises by their elaborated versions. Since the elaboration of a step only depends on the
+//! conclusions of its premises, which are unchanged by elaboration, the resulting proof is the same
+//! as the one produced by the sequential elaborator.
+
+use super::*;
+use crate::ast::pool::advanced::{ContextPool, LocalPool};
+use std::{
+    panic, ptr,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    },
+    thread,
+};
+
+/// The number of consecutive steps that a thread takes at a time when elaborating local rules.
+/// Consecutive steps are usually in the same subproof, so the thread can reuse its context.
+const LOCAL_CHUNK_SIZE: usize = 64;
+
+/// A step to be elaborated, together with the arguments of the anchors of the subproofs that
+/// enclose it, from the outermost to the innermost.
+type Job<'a> = (&'a Rc<ProofNode>, Vec<&'a [AnchorArg]>);
+
+/// Applies `func` to every item, using up to `num_threads` threads. Each thread takes
+/// `chunk_size` consecutive items at a time, and keeps its own state, created by `init`. The
+/// results are returned in the same order as the items.
+pub(super) fn map_parallel<T, S, R, I, F>(
+    items: &[T],
+    num_threads: usize,
+    chunk_size: usize,
+    init: I,
+    func: F,
+) -> Vec<R>
+where
+    T: Sync,
+    R: Send,
+    I: Fn() -> S + Sync,
+    F: Fn(&mut S, &T) -> R + Sync,
+{
+    if num_threads <= 1 || items.len() <= 1 {
+        let mut state = init();
+        return items.iter().map(|item| func(&mut state, item)).collect();
+    }
+
+    let num_chunks = items.len().div_ceil(chunk_size);
+    let next_chunk = AtomicUsize::new(0);
+    let worker = || {
+        let mut state = init();
+        let mut results = Vec::new();
+        loop {
+            let chunk = next_chunk.fetch_add(1, Ordering::Relaxed);
+            if chunk >= num_chunks {
+                return results;
+            }
+            let start = chunk * chunk_size;
+            let end = items.len().min(start + chunk_size);
+            for (i, item) in items[start..end].iter().enumerate() {
+                results.push((start + i, func(&mut state, item)));
+            }
+        }
+    };
+    let mut results: Vec<(usize, R)> = thread::scope(|s| {
+        let handles: Vec<_> = (0..num_threads.min(num_chunks))
+            .map(|_| s.spawn(worker))
+            .collect();
+        handles
+            .into_iter()
+            .flat_map(|h| h.join().unwrap_or_else(|e| panic::resume_unwind(e)))
+            .collect()
+    });
+    results.sort_unstable_by_key(|(i, _)| *i);
+    results.into_iter().map(|(_, r)| r).collect()
+}
+
+/// Finds all steps in the proof for which `filter` returns `true`.
+fn collect_steps(root: &Rc<ProofNode>, filter: impl Fn(&StepNode) -> bool) -> Vec<Job> {
+    let mut seen = HashSet::new();
+    let mut result = Vec::new();
+    let mut todo: Vec<Job> = vec![(root, Vec::new())];
+    while let Some((node, mut anchors)) = todo.pop() {
+        if !seen.insert(node) {
+            continue;
+        }
+        // A node can only be used as a premise inside subproofs nested in its own subproof, so its
+        // anchors are a prefix of the current ones
+        anchors.truncate(node.depth());
+        match node.as_ref() {
+            ProofNode::Assume { .. } => (),
+            ProofNode::Step(s) => {
+                if filter(s) {
+                    result.push((node, anchors.clone()));
+                }
+                let premises = s
+                    .premises
+                    .iter()
+                    .chain(&s.discharge)
+                    .chain(&s.previous_step);
+                todo.extend(premises.map(|p| (p, anchors.clone())));
+            }
+            ProofNode::Subproof(s) => {
+                todo.extend(s.outbound_premises.iter().map(|p| (p, anchors.clone())));
+                anchors.push(s.args.as_slice());
+                todo.push((&s.last_step, anchors));
+            }
+        }
+    }
+    result
+}
+
+/// The state of a thread that elaborates local rules.
+struct LocalWorker<'a> {
+    pool: LocalPool,
+    context: ContextStack,
+    anchors: Vec<&'a [AnchorArg]>,
+}
+
+impl<'a> LocalWorker<'a> {
+    /// Changes the context to the one given by `anchors`, keeping the contexts that are shared with
+    /// the current one.
+    fn enter(&mut self, anchors: &[&'a [AnchorArg]]) {
+        let common = (self.anchors.iter().zip(anchors))
+            .take_while(|(a, b)| ptr::eq(**a, **b))
+            .count();
+        for _ in common..self.anchors.len() {
+            self.context.pop();
+        }
+        self.anchors.truncate(common);
+        for args in &anchors[common..] {
+            self.context.push(args);
+            self.anchors.push(args);
+        }
+    }
+}
+
+/// Elaborates every step for which `get_func` returns an elaboration function, using up to
+/// `num_threads` threads. The result is the same as calling the elaboration functions sequentially
+/// using `mutate`.
+pub(super) fn mutate_parallel<G>(
+    pool: &mut PrimitivePool,
+    errors: &mut Vec<ElaborationError>,
+    root: &Rc<ProofNode>,
+    num_threads: usize,
+    get_func: G,
+) -> Rc<ProofNode>
+where
+    G: Fn(&str) -> Option<Arc<dyn ElaborationFunc>> + Sync,
+{
+    let jobs = collect_steps(root, |s| get_func(&s.rule).is_some());
+
+    // While the threads are running, the pool is shared between them, and each thread adds any new
+    // terms to its own local pool
+    let global_pool = Arc::new(std::mem::take(pool));
+    let ctx_pool = ContextPool::from_global(&global_pool);
+    let init = || LocalWorker {
+        pool: LocalPool::from_previous(&ctx_pool),
+        context: ContextStack::new(),
+        anchors: Vec::new(),
+    };
+    let results = map_parallel(
+        &jobs,
+        num_threads,
+        LOCAL_CHUNK_SIZE,
+        init,
+        |worker, (node, anchors)| {
+            let step = node.as_step().unwrap();
+            worker.enter(anchors);
+            let func = get_func(&step.rule).unwrap();
+            func.elaborate(&mut worker.pool, &mut worker.context, step)
+        },
+    );
+    drop(ctx_pool);
+    *pool = Arc::try_unwrap(global_pool).expect("all threads should have released the pool");
+
+    let mut results: HashMap<_, _> = jobs.iter().map(|(node, _)| *node).zip(results).collect();
+    let mut terms = HashMap::new();
+    mutate_with_original(root, |_, original, node| match results.remove(original) {
+        Some(Ok(result)) => splice(pool, &mut terms, original, node, &result),
+        Some(Err(e)) => {
+            errors.push(ElaborationError::new(node, e));
+            node.clone()
+        }
+        None => node.clone(),
+    })
+}
+
+/// Replaces the original premises used when elaborating a step by their elaborated versions, and
+/// imports any terms created by other threads into the pool.
+fn splice(
+    pool: &mut PrimitivePool,
+    terms: &mut HashMap<Rc<Term>, Rc<Term>>,
+    original: &Rc<ProofNode>,
+    node: &Rc<ProofNode>,
+    result: &Rc<ProofNode>,
+) -> Rc<ProofNode> {
+    let (ProofNode::Step(original), ProofNode::Step(node)) = (original.as_ref(), node.as_ref())
+    else {
+        unreachable!("only steps are elaborated in parallel")
+    };
+    let mut nodes: HashMap<_, _> = (original.premises.iter().zip(&node.premises))
+        .chain(original.discharge.iter().zip(&node.discharge))
+        .chain(original.previous_step.iter().zip(&node.previous_step))
+        .map(|(old, new)| (old, new.clone()))
+        .collect();
+    import_node(pool, terms, &mut nodes, result)
+}
+
+fn import_node<'a>(
+    pool: &mut PrimitivePool,
+    terms: &mut HashMap<Rc<Term>, Rc<Term>>,
+    nodes: &mut HashMap<&'a Rc<ProofNode>, Rc<ProofNode>>,
+    node: &'a Rc<ProofNode>,
+) -> Rc<ProofNode> {
+    if let Some(new) = nodes.get(node) {
+        return new.clone();
+    }
+    let new = match node.as_ref() {
+        ProofNode::Assume { id, depth, term } => ProofNode::Assume {
+            id: id.clone(),
+            depth: *depth,
+            term: pool.import(term, terms),
+        },
+        ProofNode::Step(s) => ProofNode::Step(StepNode {
+            id: s.id.clone(),
+            depth: s.depth,
+            clause: s.clause.iter().map(|t| pool.import(t, terms)).collect(),
+            rule: s.rule.clone(),
+            premises: (s.premises.iter())
+                .map(|p| import_node(pool, terms, nodes, p))
+                .collect(),
+            args: s.args.iter().map(|t| pool.import(t, terms)).collect(),
+            discharge: (s.discharge.iter())
+                .map(|p| import_node(pool, terms, nodes, p))
+                .collect(),
+            previous_step: (s.previous_step.as_ref()).map(|p| import_node(pool, terms, nodes, p)),
+        }),
+        ProofNode::Subproof(s) => ProofNode::Subproof(SubproofNode {
+            last_step: import_node(pool, terms, nodes, &s.last_step),
+            args: (s.args.iter())
+                .map(|arg| match arg {
+                    AnchorArg::Variable((name, sort)) => {
+                        AnchorArg::Variable((name.clone(), pool.import(sort, terms)))
+                    }
+                    AnchorArg::Assign((name, sort), value) => AnchorArg::Assign(
+                        (name.clone(), pool.import(sort, terms)),
+                        pool.import(value, terms),
+                    ),
+                })
+                .collect(),
+            outbound_premises: (s.outbound_premises.iter())
+                .map(|p| import_node(pool, terms, nodes, p))
+                .collect(),
+        }),
+    };
+    let new = Rc::new(new);
+    nodes.insert(node, new.clone());
+    new
+}
+
+/// Elaborates every step for which `filter` returns `true` using an external solver, running up to
+/// `num_processes` solver processes at the same time. For each step, `get_problem` builds the
+/// problem given to the solver, `run_solver` runs the solver on it, and `insert_proof` builds the
+/// node that replaces the step from the solver output, if possible. Only `run_solver` is called
+/// concurrently, so the result is the same as elaborating each step sequentially.
+pub(super) fn elaborate_with_solver<R, P, S, I>(
+    elaborator: &mut Elaborator,
+    root: &Rc<ProofNode>,
+    filter: impl Fn(&StepNode) -> bool,
+    mut get_problem: P,
+    run_solver: S,
+    mut insert_proof: I,
+) -> Rc<ProofNode>
+where
+    R: Send,
+    P: FnMut(&mut Elaborator, &StepNode) -> String,
+    S: Fn(&str) -> R + Sync,
+    I: FnMut(&mut Elaborator, &StepNode, &str, R) -> Result<Rc<ProofNode>, ElaborationErrorKind>,
+{
+    let steps: Vec<_> = collect_steps(root, filter)
+        .into_iter()
+        .map(|(node, _)| node)
+        .collect();
+    let problems: Vec<_> = steps
+        .iter()
+        .map(|node| get_problem(elaborator, node.as_step().unwrap()))
+        .collect();
+    let num_processes = elaborator.config.num_solver_processes;
+    let outputs = map_parallel(&problems, num_processes, 1, || (), |_, p| run_solver(p));
+
+    let mut results = HashMap::new();
+    for ((node, problem), output) in steps.into_iter().zip(&problems).zip(outputs) {
+        match insert_proof(elaborator, node.as_step().unwrap(), problem, output) {
+            Ok(new) => {
+                results.insert(node, new);
+            }
+            Err(e) => elaborator.errors.push(ElaborationError::new(node, e)),
+        }
+    }
+    mutate_with_original(root, |_, original, node| {
+        results
+            .get(original)
+            .cloned()
+            .unwrap_or_else(|| node.clone())
+    })
+}
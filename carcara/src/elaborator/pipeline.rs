@@ -0,0 +1,268 @@
+//! Elaboration pipelines, and a parser for pipeline files.
+//!
+//! Each line of a pipeline file describes one step of the pipeline. It starts with the name of the
+//! step (like `local` or `hole`), followed by any number of options of the form `key=value`. Values
+//! that contain spaces can be written between double quotes. Empty lines, and lines starting with
+//! `#`, are ignored. For example:
+//! ```text
+//! polyeq
+//! local rules=trans,cong,resolution
+//! hole rules=all_simplify,rare_rewrite solver=cvc5 solver-args="--lang=smt2 --proof-format-mode=alethe"
+//! hole rules=trust solver=veriT
+//! reordering
+//! ```
+//! The available options are:
+//! - `rules`: a comma-separated list of the rules the step applies to;
+//! - `solver`: the external solver used by the `lia-generic` and `hole` steps;
+//! - `solver-args`: the arguments passed to that solver.
+
+use super::ElaborationStep;
+use std::collections::HashSet;
+use thiserror::Error;
+
+#[derive(Debug, Error)]
+pub enum PipelineError {
+    #[error("unknown elaboration step on line {0}: '{1}'")]
+    UnknownStep(usize, String),
+
+    #[error("unknown option on line {0}: '{1}'")]
+    UnknownOption(usize, String),
+
+    #[error("option '{1}' on line {0} can't be used with the '{2}' step")]
+    InvalidOption(usize, String, String),
+
+    #[error("option '{1}' on line {0} has no value")]
+    MissingValue(usize, String),
+
+    #[error("unterminated string on line {0}")]
+    UnterminatedString(usize),
+}
+
+impl PipelineError {
+    /// Returns a stable identifier for the kind of this error.
+    pub fn code(&self) -> &'static str {
+        match self {
+            PipelineError::UnknownStep(..) => "pipeline.unknown_step",
+            PipelineError::UnknownOption(..) => "pipeline.unknown_option",
+            PipelineError::InvalidOption(..) => "pipeline.invalid_option",
+            PipelineError::MissingValue(..) => "pipeline.missing_value",
+            PipelineError::UnterminatedString(_) => "pipeline.unterminated_string",
+        }
+    }
+}
+
+/// A step of an elaboration pipeline, together with the options that control which proof steps it
+/// elaborates.
+#[derive(Debug, Clone)]
+pub struct PipelineStep {
+    pub step: ElaborationStep,
+
+    /// The rules of the proof steps that this step applies to. If this is `None`, the step applies
+    /// to all of the rules it can elaborate. For the `Hole` step, these are the rules of the steps
+    /// that are given to the external solver, which are `all_simplify` and `rare_rewrite` by
+    /// default.
+    pub rules: Option<HashSet<String>>,
+
+    /// The external solver used by the `LiaGeneric` and `Hole` steps. If this is `None`, the solver
+    /// given in the elaborator [`Config`](super::Config) is used.
+    pub solver: Option<Box<str>>,
+
+    /// The arguments passed to the external solver. If this is `None`, the arguments given in the
+    /// elaborator [`Config`](super::Config) are used.
+    pub solver_args: Option<Vec<Box<str>>>,
+}
+
+impl From<ElaborationStep> for PipelineStep {
+    fn from(step: ElaborationStep) -> Self {
+        Self {
+            step,
+            rules: None,
+            solver: None,
+            solver_args: None,
+        }
+    }
+}
+
+impl PipelineStep {
+    /// Returns `true` if this step applies to proof steps of the given rule. This doesn't consider
+    /// whether the step is able to elaborate that rule.
+    pub fn applies_to(&self, rule: &str) -> bool {
+        self.rules.as_ref().is_none_or(|rules| rules.contains(rule))
+    }
+
+    /// Combines the solver options of this step with the default ones, given as the solver path
+    /// and its arguments. Returns `None` if neither of them specifies a solver.
+    pub(super) fn solver_options(
+        &self,
+        default: Option<(&str, &[Box<str>])>,
+    ) -> Option<(Box<str>, Vec<Box<str>>)> {
+        let solver = self.solver.as_deref().or(default.map(|(s, _)| s))?;
+        let arguments = match &self.solver_args {
+            Some(args) => args.clone(),
+            None => default.map(|(_, args)| args.to_vec()).unwrap_or_default(),
+        };
+        Some((solver.into(), arguments))
+    }
+}
+
+fn parse_step_name(name: &str) -> Option<ElaborationStep> {
+    use ElaborationStep::*;
+    Some(match name {
+        "polyeq" => Polyeq,
+        "lia-generic" => LiaGeneric,
+        "drup" => Drup,
+        "local" => Local,
+        "uncrowd" => Uncrowd,
+        "reordering" => Reordering,
+        "hole" => Hole,
+        "prune" => Prune,
+        "lift" => Lift,
+        "merge" => Merge,
+        "recycle-units" => RecycleUnits,
+        _ => return None,
+    })
+}
+
+/// Splits a line into whitespace-separated words. Parts of a word that are between double quotes
+/// may contain whitespace.
+fn split_words(line_number: usize, line: &str) -> Result<Vec<String>, PipelineError> {
+    let mut words = Vec::new();
+    let mut current: Option<String> = None;
+    let mut in_quotes = false;
+    for c in line.chars() {
+        match c {
+            '"' => {
+                in_quotes = !in_quotes;
+                current.get_or_insert_with(String::new);
+            }
+            _ if c.is_whitespace() && !in_quotes => words.extend(current.take()),
+            _ => current.get_or_insert_with(String::new).push(c),
+        }
+    }
+    if in_quotes {
+        return Err(PipelineError::UnterminatedString(line_number));
+    }
+    words.extend(current);
+    Ok(words)
+}
+
+/// Parses a pipeline file. See the module documentation for a description of the format.
+pub fn parse_pipeline(text: &str) -> Result<Vec<PipelineStep>, PipelineError> {
+    let mut pipeline = Vec::new();
+    for (i, line) in text.lines().enumerate() {
+        let line_number = i + 1;
+        if line.trim_start().starts_with('#') {
+            continue;
+        }
+        let words = split_words(line_number, line)?;
+        let Some((name, options)) = words.split_first() else {
+            continue;
+        };
+        let step = parse_step_name(name)
+            .ok_or_else(|| PipelineError::UnknownStep(line_number, name.clone()))?;
+        let mut result = PipelineStep::from(step);
+
+        let uses_solver = matches!(step, ElaborationStep::LiaGeneric | ElaborationStep::Hole);
+        let is_global = matches!(
+            step,
+            ElaborationStep::Reordering
+                | ElaborationStep::Prune
+                | ElaborationStep::Lift
+                | ElaborationStep::Merge
+                | ElaborationStep::RecycleUnits
+        );
+        for option in options {
+            let Some((key, value)) = option.split_once('=') else {
+                return Err(PipelineError::MissingValue(line_number, option.clone()));
+            };
+            let is_valid = match key {
+                "rules" => !is_global,
+                "solver" | "solver-args" => uses_solver,
+                _ => return Err(PipelineError::UnknownOption(line_number, key.to_owned())),
+            };
+            if !is_valid {
+                let (key, name) = (key.to_owned(), name.clone());
+                return Err(PipelineError::InvalidOption(line_number, key, name));
+            }
+            match key {
+                "rules" => {
+                    let rules = value.split(',').map(str::trim).filter(|r| !r.is_empty());
+                    result.rules = Some(rules.map(str::to_owned).collect());
+                }
+                "solver" => result.solver = Some(value.into()),
+                _ => result.solver_args = Some(value.split_whitespace().map(Into::into).collect()),
+            }
+        }
+        pipeline.push(result);
+    }
+    Ok(pipeline)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_pipeline() {
+        let text = "
+            # A comment
+            polyeq
+            local rules=trans,cong
+            hole rules=trust solver=cvc5 solver-args=\"--lang=smt2 --tlimit=100\"
+            recycle-units
+        ";
+        let pipeline = parse_pipeline(text).unwrap();
+        let steps: Vec<_> = pipeline.iter().map(|s| s.step).collect();
+        assert!(matches!(
+            steps[..],
+            [
+                ElaborationStep::Polyeq,
+                ElaborationStep::Local,
+                ElaborationStep::Hole,
+                ElaborationStep::RecycleUnits,
+            ]
+        ));
+        assert!(pipeline[0].rules.is_none());
+        assert!(pipeline[1].applies_to("cong") && !pipeline[1].applies_to("resolution"));
+        assert_eq!(pipeline[2].solver.as_deref(), Some("cvc5"));
+        let args = pipeline[2].solver_args.as_ref().unwrap();
+        assert_eq!(args, &["--lang=smt2".into(), "--tlimit=100".into()]);
+
+        let cases = [
+            ("local\nfoo", "pipeline.unknown_step"),
+            ("local solver=cvc5", "pipeline.invalid_option"),
+            ("reordering rules=trans", "pipeline.invalid_option"),
+            ("local ruls=trans", "pipeline.unknown_option"),
+            ("local rules", "pipeline.missing_value"),
+            (
+                "hole solver-args=\"--lang=smt2",
+                "pipeline.unterminated_string",
+            ),
+        ];
+        for (text, expected) in cases {
+            assert_eq!(parse_pipeline(text).unwrap_err().code(), expected);
+        }
+    }
+
+    #[test]
+    fn test_solver_options() {
+        let default_args: Vec<Box<str>> = vec!["--default".into()];
+        let default = Some(("default-solver", default_args.as_slice()));
+
+        let mut step = PipelineStep::from(ElaborationStep::Hole);
+        assert_eq!(step.solver_options(None), None);
+        let (solver, args) = step.solver_options(default).unwrap();
+        assert_eq!(
+            (solver.as_ref(), args),
+            ("default-solver", default_args.clone())
+        );
+
+        step.solver = Some("cvc5".into());
+        let (solver, args) = step.solver_options(default).unwrap();
+        assert_eq!((solver.as_ref(), args), ("cvc5", default_args));
+
+        step.solver_args = Some(Vec::new());
+        let (solver, args) = step.solver_options(None).unwrap();
+        assert_eq!((solver.as_ref(), args), ("cvc5", Vec::new()));
+    }
+}
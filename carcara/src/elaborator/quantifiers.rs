@@ -1,30 +1,51 @@
 use super::*;
-use crate::{ast::*, checker::error::CheckerError};
-use indexmap::IndexMap;
+use crate::{
+    ast::*,
+    checker::{
+        error::{CheckerError, EqualityError},
+        qnt_cnf_clauses,
+    },
+};
+use indexmap::{IndexMap, IndexSet};
 
 pub fn forall_inst(
-    pool: &mut PrimitivePool,
+    pool: &mut dyn TermPool,
     _: &mut ContextStack,
     step: &StepNode,
 ) -> Result<Rc<ProofNode>, CheckerError> {
-    assert_eq!(step.clause.len(), 1);
+    assert_clause_len(&step.clause, 1)?;
 
     let (quantified, substituted) =
         match_term_err!((or (not quantified) result) = &step.clause[0])?;
     let (bindings, original) = match_term_err!((forall ... original) = quantified)?;
 
-    assert_eq!(step.args.len(), bindings.len());
+    // Older proofs may omit the instantiation terms, or give them in a different order than the
+    // bindings. In that case, we try to recover them by matching the conclusion
+    let args = if is_valid_instance(pool, bindings, &step.args, original, substituted) {
+        step.args.clone()
+    } else if let Some(args) = infer_forall_inst_args(pool, bindings, original, substituted) {
+        args
+    } else {
+        log::warn!(
+            "couldn't infer the instantiation for `forall_inst` step '{}'",
+            step.id
+        );
+        return Ok(Rc::new(ProofNode::Step(step.clone())));
+    };
 
     // iterate over the bindings and arguments simultaneously, building the substitution
     let substitution: IndexMap<_, _> = bindings
         .iter()
-        .zip(&step.args)
+        .zip(&args)
         .map(|((var_name, sort), value)| {
-            assert_eq!(sort, &pool.sort(value));
+            let value_sort = pool.sort(value);
+            if *sort != value_sort {
+                return Err(EqualityError::ExpectedEqual(sort.clone(), value_sort).into());
+            }
             let var = pool.add(Term::new_var(var_name, sort.clone()));
-            (var.clone(), value.clone())
+            Ok((var.clone(), value.clone()))
         })
-        .collect();
+        .collect::<Result<_, CheckerError>>()?;
     let mut substitution = Substitution::new(pool, substitution)?;
 
     // Equalities may be reordered, and the application of the substitution might rename bound
@@ -32,8 +53,8 @@ pub fn forall_inst(
     let expected = substitution.apply(pool, original);
 
     if *substituted == expected {
-        // There was no polyeq needed, so we return the step unchanged
-        return Ok(Rc::new(ProofNode::Step(step.clone())));
+        // There was no polyeq needed, so we only need to make sure the step has the right arguments
+        return Ok(Rc::new(ProofNode::Step(StepNode { args, ..step.clone() })));
     }
 
     // Given that the original forall_inst step has conclusion (cl (or (not quant)) p'), where
@@ -95,7 +116,7 @@ pub fn forall_inst(
         depth: step.depth,
         clause: vec![or_term.clone()],
         rule: "forall_inst".to_owned(),
-        args: step.args.clone(),
+        args,
         ..StepNode::default()
     }));
 
@@ -112,3 +133,339 @@ pub fn forall_inst(
 
     Ok(resolution_step)
 }
+
+/// Returns `true` if instantiating the quantifier with `args` results in a term that is
+/// alpha-equivalent to `substituted`, modulo the reordering of equalities.
+fn is_valid_instance(
+    pool: &mut dyn TermPool,
+    bindings: &BindingList,
+    args: &[Rc<Term>],
+    original: &Rc<Term>,
+    substituted: &Rc<Term>,
+) -> bool {
+    if args.len() != bindings.len() {
+        return false;
+    }
+    let map: IndexMap<_, _> = bindings
+        .iter()
+        .zip(args)
+        .map(|(var, value)| (pool.add(var.clone().into()), value.clone()))
+        .collect();
+    let Ok(mut substitution) = Substitution::new(pool, map) else {
+        return false;
+    };
+    let expected = substitution.apply(pool, original);
+    Polyeq::new()
+        .mod_reordering(true)
+        .alpha_equiv(true)
+        .eq(substituted, &expected)
+}
+
+/// Tries to find the instantiation terms of a `forall_inst` step by matching the quantified term
+/// against the instantiated one. Bindings that don't appear in the quantified term can be
+/// instantiated with anything, so they are instantiated with the variable itself.
+fn infer_forall_inst_args(
+    pool: &mut dyn TermPool,
+    bindings: &BindingList,
+    original: &Rc<Term>,
+    substituted: &Rc<Term>,
+) -> Option<Vec<Rc<Term>>> {
+    let vars: IndexSet<_> = bindings
+        .iter()
+        .map(|v| pool.add(v.clone().into()))
+        .collect();
+    let mut matching = IndexMap::new();
+    if !match_instance(pool, &vars, original, substituted, &mut matching) {
+        return None;
+    }
+    let args: Vec<_> = vars
+        .iter()
+        .map(|v| matching.get(v).unwrap_or(v).clone())
+        .collect();
+    is_valid_instance(pool, bindings, &args, original, substituted).then_some(args)
+}
+
+/// First-order matching of `pattern` against `target`, where the terms in `vars` are the pattern
+/// variables. Equalities may be flipped. If the matching succeeds, `matching` is extended with the
+/// term matched by each pattern variable.
+fn match_instance(
+    pool: &mut dyn TermPool,
+    vars: &IndexSet<Rc<Term>>,
+    pattern: &Rc<Term>,
+    target: &Rc<Term>,
+    matching: &mut IndexMap<Rc<Term>, Rc<Term>>,
+) -> bool {
+    if vars.contains(pattern) {
+        if let Some(matched) = matching.get(pattern) {
+            return matched == target;
+        }
+        if pool.sort(pattern) != pool.sort(target) {
+            return false;
+        }
+        matching.insert(pattern.clone(), target.clone());
+        return true;
+    }
+    if pattern == target {
+        return true;
+    }
+    match (pattern.as_ref(), target.as_ref()) {
+        (Term::Op(Operator::Equals, p_args), Term::Op(Operator::Equals, t_args))
+            if p_args.len() == 2 && t_args.len() == 2 =>
+        {
+            // We first try matching the equality as it is, and then flipped, restoring the
+            // matching in between
+            let saved = matching.clone();
+            if match_all(pool, vars, p_args, t_args, matching) {
+                return true;
+            }
+            *matching = saved;
+            let flipped = [t_args[1].clone(), t_args[0].clone()];
+            match_all(pool, vars, p_args, &flipped, matching)
+        }
+        (Term::Op(p_op, p_args), Term::Op(t_op, t_args)) if p_op == t_op => {
+            match_all(pool, vars, p_args, t_args, matching)
+        }
+        (
+            Term::ParamOp {
+                op: p_op,
+                op_args: p_op_args,
+                args: p_args,
+            },
+            Term::ParamOp {
+                op: t_op,
+                op_args: t_op_args,
+                args: t_args,
+            },
+        ) if p_op == t_op && p_op_args == t_op_args => {
+            match_all(pool, vars, p_args, t_args, matching)
+        }
+        (Term::App(p_func, p_args), Term::App(t_func, t_args)) if p_func == t_func => {
+            match_all(pool, vars, p_args, t_args, matching)
+        }
+        (
+            Term::Binder(p_binder, p_bindings, p_inner),
+            Term::Binder(t_binder, t_bindings, t_inner),
+        ) if p_binder == t_binder
+            && p_bindings.len() == t_bindings.len()
+            && p_bindings
+                .iter()
+                .zip(t_bindings.iter())
+                .all(|(p, t)| p.1 == t.1) =>
+        {
+            // The instantiation may have renamed the bound variables, so we rename them in the
+            // pattern as well. The variables bound here shadow any pattern variable with the same
+            // name
+            let renaming: IndexMap<_, _> = p_bindings
+                .iter()
+                .zip(t_bindings.iter())
+                .map(|(p, t)| (pool.add(p.clone().into()), pool.add(t.clone().into())))
+                .collect();
+            let Ok(mut renaming) = Substitution::new(pool, renaming) else {
+                return false;
+            };
+            let p_inner = renaming.apply(pool, p_inner);
+            let vars: IndexSet<_> = vars
+                .iter()
+                .filter(|v| !p_bindings.iter().any(|(name, _)| v.as_var() == Some(name)))
+                .cloned()
+                .collect();
+            match_instance(pool, &vars, &p_inner, t_inner, matching)
+        }
+        _ => false,
+    }
+}
+
+fn match_all(
+    pool: &mut dyn TermPool,
+    vars: &IndexSet<Rc<Term>>,
+    patterns: &[Rc<Term>],
+    targets: &[Rc<Term>],
+    matching: &mut IndexMap<Rc<Term>, Rc<Term>>,
+) -> bool {
+    patterns.len() == targets.len()
+        && (patterns.iter().zip(targets)).all(|(p, t)| match_instance(pool, vars, p, t, matching))
+}
+
+/// Elaborates a `qnt_cnf` step by splitting it into finer-grained steps. Directly nested universal
+/// quantifiers in the left-hand side are first joined with `qnt_join` steps, and bindings that
+/// don't appear in the selected clause are removed with a `qnt_rm_unused` step. If the body of the
+/// joined quantifier is already the selected clause, the `qnt_cnf` step is removed entirely, and
+/// the conclusion is derived from the tautology `(or (not q) q)`.
+pub fn qnt_cnf(
+    pool: &mut dyn TermPool,
+    _: &mut ContextStack,
+    step: &StepNode,
+) -> Result<Rc<ProofNode>, CheckerError> {
+    assert_clause_len(&step.clause, 1)?;
+
+    let (quantified, result) = match_term_err!((or (not quantified) result) = &step.clause[0])?;
+    let (l_bindings, phi) = match_term_err!((forall ... phi) = quantified)?;
+    let (r_bindings, clause) = match_term_err!((forall ... clause) = result)?;
+
+    let mut ids = IdHelper::new(&step.id);
+    let new_step = |ids: &mut IdHelper, clause, rule: &str, premises, args| {
+        Rc::new(ProofNode::Step(StepNode {
+            id: ids.next_id(),
+            depth: step.depth,
+            clause,
+            rule: rule.to_owned(),
+            premises,
+            args,
+            ..StepNode::default()
+        }))
+    };
+
+    // First, we join the nested quantifiers, as long as that doesn't introduce repeated bindings.
+    // Otherwise, the bindings of the joined term may differ from the ones computed by `qnt_cnf`
+    let mut joined = quantified.clone();
+    let mut join_steps = Vec::new();
+    let (mut bindings, mut body) = (l_bindings.0.clone(), phi.clone());
+    while let Some((inner_bindings, inner)) = match_term!((forall ... inner) = &body) {
+        let combined: IndexSet<_> = bindings.iter().chain(inner_bindings).cloned().collect();
+        if combined.len() != bindings.len() + inner_bindings.len() {
+            break;
+        }
+        let (outer, inner) = (joined.clone(), inner.clone());
+        bindings = combined.into_iter().collect();
+        joined = pool.add(Term::Binder(
+            Binder::Forall,
+            BindingList(bindings.clone()),
+            inner.clone(),
+        ));
+        body = inner;
+        let clause = vec![build_term!(pool, (= {outer} {joined.clone()}))];
+        join_steps.push(new_step(
+            &mut ids,
+            clause,
+            "qnt_join",
+            Vec::new(),
+            Vec::new(),
+        ));
+    }
+
+    let (all_bindings, clauses) = qnt_cnf_clauses(pool, &BindingList(bindings.clone()), &body);
+    let full = pool.add(Term::Binder(
+        Binder::Forall,
+        BindingList(all_bindings.iter().cloned().collect()),
+        clause.clone(),
+    ));
+    let free_vars = pool.free_vars(clause);
+    let used: Vec<_> = all_bindings
+        .iter()
+        .filter(|&var| free_vars.contains(&pool.add(var.clone().into())))
+        .cloned()
+        .collect();
+
+    // If the bindings are not in the order `qnt_rm_unused` expects, or if the step is not valid,
+    // we can't split it
+    if !clauses.contains(clause) || (*result != full && (used.is_empty() || r_bindings.0 != used)) {
+        return Ok(Rc::new(ProofNode::Step(step.clone())));
+    }
+    if join_steps.is_empty() && *result == full && joined != full {
+        return Ok(Rc::new(ProofNode::Step(step.clone())));
+    }
+
+    // The clause `(or (not joined) full)` is derived either from a `qnt_cnf` step, or, if the body
+    // is already the selected clause, from the two `or_neg` steps:
+    //
+    // (step t1.t1 (cl (or (not joined) joined) (not (not joined))) :rule or_neg :args (0))
+    // (step t1.t2 (cl (or (not joined) joined) (not joined)) :rule or_neg :args (1))
+    // (step t1.t3 (cl (or (not joined) joined)) :rule resolution
+    //     :premises (t1.t2 t1.t1) :args ((not joined) true))
+    let or_term = build_term!(pool, (or (not {joined.clone()}) {full.clone()}));
+    let cnf_step = if joined == full {
+        let not_joined = build_term!(pool, (not {joined.clone()}));
+        let neg_not_joined = build_term!(pool, (not {not_joined.clone()}));
+        let first = vec![or_term.clone(), neg_not_joined];
+        let first = new_step(
+            &mut ids,
+            first,
+            "or_neg",
+            Vec::new(),
+            vec![pool.add(Term::new_int(0))],
+        );
+        let second = vec![or_term.clone(), not_joined.clone()];
+        let second = new_step(
+            &mut ids,
+            second,
+            "or_neg",
+            Vec::new(),
+            vec![pool.add(Term::new_int(1))],
+        );
+        let args = vec![not_joined, pool.bool_true()];
+        new_step(
+            &mut ids,
+            vec![or_term.clone()],
+            "resolution",
+            vec![second, first],
+            args,
+        )
+    } else {
+        new_step(
+            &mut ids,
+            vec![or_term.clone()],
+            "qnt_cnf",
+            Vec::new(),
+            Vec::new(),
+        )
+    };
+    if or_term == step.clause[0] {
+        let ProofNode::Step(s) = cnf_step.as_ref() else {
+            unreachable!()
+        };
+        return Ok(Rc::new(ProofNode::Step(StepNode {
+            id: step.id.clone(),
+            ..s.clone()
+        })));
+    }
+
+    // Then, we show that `(or (not joined) full)` is equal to the original conclusion, using the
+    // equalities introduced by the `qnt_join` and `qnt_rm_unused` steps:
+    //
+    // (step t1.t4 (cl (= q q')) :rule trans :premises (<qnt_join steps>))
+    // (step t1.t5 (cl (= (not joined) (not q))) :rule cong :premises (t1.t4))
+    // (step t1.t6 (cl (= full r)) :rule qnt_rm_unused)
+    // (step t1.t7 (cl (= (or (not joined) full) (or (not q) r))) :rule cong :premises (t1.t5 t1.t6))
+    // (step t1.t8 (cl (not (or (not joined) full)) (or (not q) r)) :rule equiv1 :premises (t1.t7))
+    // (step t1 (cl (or (not q) r)) :rule resolution
+    //     :premises (t1.t8 t1.t3) :args ((or (not joined) full) false))
+    let mut cong_premises = Vec::new();
+    if !join_steps.is_empty() {
+        let join_step = if join_steps.len() == 1 {
+            join_steps.pop().unwrap()
+        } else {
+            let clause = vec![build_term!(pool, (= {quantified.clone()} {joined.clone()}))];
+            new_step(&mut ids, clause, "trans", join_steps, Vec::new())
+        };
+        let clause = vec![build_term!(pool, (= (not {joined.clone()}) (not {quantified.clone()})))];
+        cong_premises.push(new_step(
+            &mut ids,
+            clause,
+            "cong",
+            vec![join_step],
+            Vec::new(),
+        ));
+    }
+    if *result != full {
+        let clause = vec![build_term!(pool, (= {full} {result.clone()}))];
+        let rm_unused_step = new_step(&mut ids, clause, "qnt_rm_unused", Vec::new(), Vec::new());
+        cong_premises.push(rm_unused_step);
+    }
+    let clause = vec![build_term!(pool, (= {or_term.clone()} {step.clause[0].clone()}))];
+    let cong_step = new_step(&mut ids, clause, "cong", cong_premises, Vec::new());
+    let clause = vec![
+        build_term!(pool, (not {or_term.clone()})),
+        step.clause[0].clone(),
+    ];
+    let equiv1_step = new_step(&mut ids, clause, "equiv1", vec![cong_step], Vec::new());
+
+    Ok(Rc::new(ProofNode::Step(StepNode {
+        id: step.id.clone(),
+        depth: step.depth,
+        clause: step.clause.clone(),
+        rule: "resolution".to_owned(),
+        premises: vec![equiv1_step, cnf_step],
+        args: vec![or_term, pool.bool_false()],
+        ..StepNode::default()
+    })))
+}
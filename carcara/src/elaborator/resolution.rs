@@ -2,7 +2,7 @@ use super::*;
 use crate::{ast::*, checker::error::CheckerError, resolution::*, utils::DedupIterator};
 
 pub fn resolution(
-    pool: &mut PrimitivePool,
+    pool: &mut dyn TermPool,
     _: &mut ContextStack,
     step: &StepNode,
 ) -> Result<Rc<ProofNode>, CheckerError> {
@@ -1,11 +1,15 @@
-use super::IdHelper;
+use super::{assert_clause_len, IdHelper};
 use crate::{ast::*, checker::error::CheckerError};
 
-fn add_symm_step(pool: &mut PrimitivePool, node: &Rc<ProofNode>, id: String) -> Rc<ProofNode> {
-    assert_eq!(node.clause().len(), 1);
-    let (a, b) = match_term!((= a b) = node.clause()[0]).unwrap();
+fn add_symm_step(
+    pool: &mut dyn TermPool,
+    node: &Rc<ProofNode>,
+    id: String,
+) -> Result<Rc<ProofNode>, CheckerError> {
+    assert_clause_len(node.clause(), 1)?;
+    let (a, b) = match_term_err!((= a b) = &node.clause()[0])?;
     let clause = vec![build_term!(pool, (= {b.clone()} {a.clone()}))];
-    Rc::new(ProofNode::Step(StepNode {
+    Ok(Rc::new(ProofNode::Step(StepNode {
         id,
         depth: node.depth(),
         clause,
@@ -14,7 +18,7 @@ fn add_symm_step(pool: &mut PrimitivePool, node: &Rc<ProofNode>, id: String) ->
         args: Vec::new(),
         discharge: Vec::new(),
         previous_step: None,
-    }))
+    })))
 }
 
 /// Similar to `find_chain`, but reorders a premises vector to match the found chain. In `trans`,
@@ -64,18 +68,18 @@ fn find_and_trace_chain<'a, T>(
 }
 
 pub fn trans(
-    pool: &mut PrimitivePool,
+    pool: &mut dyn TermPool,
     _: &mut ContextStack,
     step: &StepNode,
 ) -> Result<Rc<ProofNode>, CheckerError> {
-    assert_eq!(step.clause.len(), 1);
+    assert_clause_len(&step.clause, 1)?;
 
     let conclusion_equality = match_term_err!((= t u) = &step.clause[0])?;
     let mut premise_equalities: Vec<_> = step
         .premises
         .iter()
         .map(|premise| {
-            assert_eq!(premise.clause().len(), 1);
+            assert_clause_len(premise.clause(), 1)?;
             match_term_err!((= t u) = &premise.clause()[0])
         })
         .collect::<Result<_, _>>()?;
@@ -95,7 +99,7 @@ pub fn trans(
     // needed equalities
     let mut ids = IdHelper::new(&step.id);
     for i in should_flip {
-        new_premises[i] = add_symm_step(pool, &new_premises[i], ids.next_id());
+        new_premises[i] = add_symm_step(pool, &new_premises[i], ids.next_id())?;
     }
 
     Ok(Rc::new(ProofNode::Step(StepNode {
@@ -105,16 +109,16 @@ pub fn trans(
 }
 
 pub fn eq_transitive(
-    pool: &mut PrimitivePool,
+    pool: &mut dyn TermPool,
     _: &mut ContextStack,
     step: &StepNode,
 ) -> Result<Rc<ProofNode>, CheckerError> {
+    assert_clause_len(&step.clause, 3..)?;
     let n = step.clause.len();
-    assert!(n > 2);
 
     // The last term in the conclusion clause should be an equality, and it will be the conclusion
     // of the transitive chain
-    let conclusion_equality = match_term_err!((= t u) = step.clause.last().unwrap())?;
+    let conclusion_equality = match_term_err!((= t u) = &step.clause[n - 1])?;
 
     // The first `conclusion.len()` - 1 terms in the conclusion clause must be a sequence of
     // inequalities, and they will be the premises of the transitive chain
@@ -135,11 +139,8 @@ pub fn eq_transitive(
     }
 
     for &i in &should_flip {
-        new_clause[i] = if let Some((a, b)) = match_term!((not (= a b)) = &new_clause[i]) {
-            build_term!(pool, (not (= {b.clone()} {a.clone()})))
-        } else {
-            panic!()
-        };
+        let (a, b) = match_term_err!((not (= a b)) = &new_clause[i])?;
+        new_clause[i] = build_term!(pool, (not (= {b.clone()} {a.clone()})));
     }
 
     let not_needed = if num_needed == n - 1 {
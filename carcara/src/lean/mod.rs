@@ -0,0 +1,627 @@
+//! Export of Alethe proofs as Lean 4 proof scripts.
+//!
+//! The declarations and assertions of the problem become `axiom`s, and each proof step becomes a
+//! `theorem` whose statement is the step clause, written as a disjunction. Steps inside subproofs
+//! take the variables and local assumptions of the enclosing subproofs as explicit parameters, so
+//! the conclusion of a subproof can refer to its last inner step as a function of them. The theorem
+//! of the last step is then a proof of `False` from the axioms of the problem.
+//!
+//! The core rules of elaborated proofs (resolution and the other clause manipulation rules, `refl`,
+//! `trans`, `cong`, and the `and`/`or` clausification rules) are reconstructed directly by the
+//! proof of their theorem. The proof of every other step is a call to the `alethe_step` tactic,
+//! which receives the rule name and the premises of the step. The exported script contains a
+//! default implementation of that tactic that only tries general-purpose tactics; it can be
+//! replaced by a reconstruction of the remaining Alethe rules.
+//!
+//! Proofs should be elaborated before being exported, since elaborated steps are much easier to
+//! reconstruct.
+//!
+//! The generated scripts are not compiled with Lean by Carcara or by its tests, which only compare
+//! the generated text. In particular, `ALETHE_STEP_TACTIC` and the reconstructions of the core
+//! rules are not machine-checked.
+
+#[cfg(test)]
+mod tests;
+
+use crate::ast::*;
+use std::io;
+use thiserror::Error;
+
+#[derive(Debug, Error)]
+pub enum LeanExportError {
+    #[error("IO error: {0}")]
+    Io(#[from] io::Error),
+
+    #[error("term can't be exported to Lean: '{0}'")]
+    UnsupportedTerm(Rc<Term>),
+
+    #[error("sort can't be exported to Lean: '{0}'")]
+    UnsupportedSort(Rc<Term>),
+
+    #[error("datatype '{0}' can't be exported to Lean")]
+    UnsupportedDatatype(String),
+}
+
+impl LeanExportError {
+    /// Returns a stable identifier for the kind of this error.
+    pub fn code(&self) -> &'static str {
+        match self {
+            LeanExportError::Io(_) => "io",
+            LeanExportError::UnsupportedTerm(_) => "lean_export.unsupported_term",
+            LeanExportError::UnsupportedSort(_) => "lean_export.unsupported_sort",
+            LeanExportError::UnsupportedDatatype(_) => "lean_export.unsupported_datatype",
+        }
+    }
+}
+
+type LeanResult<T> = Result<T, LeanExportError>;
+
+/// The default implementation of the `alethe_step` tactic, included in every exported script.
+/// Like the rest of the generated script, it is not checked with Lean.
+const ALETHE_STEP_TACTIC: &str = "\
+/-- Proves the conclusion of an Alethe step, given the rule name and the premises of the step. This
+default implementation only tries general-purpose tactics. -/
+macro \"alethe_step \" _rule:ident \" [\" ps:term,* \"]\" : tactic =>
+  `(tactic| first | simp_all [$ps,*] | omega)
+";
+
+/// Writes `proof` as a Lean 4 proof script to `dest`. See the module documentation for a
+/// description of the exported script.
+pub fn write_lean_proof(
+    pool: &mut PrimitivePool,
+    problem: &Problem,
+    proof: &Proof,
+    dest: &mut dyn io::Write,
+) -> LeanResult<()> {
+    let mut exporter = LeanExporter {
+        pool,
+        frames: vec![Frame::default()],
+        uses_reals: false,
+        body: String::new(),
+    };
+    exporter.write_prelude(&problem.prelude)?;
+    exporter.write_commands(&proof.commands)?;
+
+    writeln!(dest, "-- Exported by Carcara")?;
+    if exporter.uses_reals {
+        writeln!(dest, "import Mathlib.Data.Real.Basic")?;
+    }
+    writeln!(dest)?;
+    writeln!(dest, "open Classical")?;
+    writeln!(dest)?;
+    writeln!(dest, "{}", ALETHE_STEP_TACTIC)?;
+    write!(dest, "{}", exporter.body)?;
+    Ok(())
+}
+
+/// A proof command that can be used as a premise: the name of its theorem or hypothesis, and the
+/// parameters the theorem takes.
+type Reference = (String, Vec<String>);
+
+/// The state of a subproof (or of the root proof) while it is being exported.
+#[derive(Default)]
+struct Frame {
+    /// The names of the parameters introduced by this subproof.
+    params: Vec<String>,
+
+    /// The binders corresponding to each parameter, like `(x : Int)`.
+    binders: Vec<String>,
+
+    /// The reference to each command of the subproof that was already exported.
+    refs: Vec<Reference>,
+
+    /// The clause of each command of the subproof that was already exported.
+    clauses: Vec<Vec<Rc<Term>>>,
+}
+
+struct LeanExporter<'a> {
+    pool: &'a mut PrimitivePool,
+    frames: Vec<Frame>,
+    uses_reals: bool,
+    body: String,
+}
+
+impl LeanExporter<'_> {
+    fn write_prelude(&mut self, prelude: &ProblemPrelude) -> LeanResult<()> {
+        if let Some(dt) = prelude.datatype_declarations.first() {
+            return Err(LeanExportError::UnsupportedDatatype(dt.name.clone()));
+        }
+        for (name, arity) in &prelude.sort_declarations {
+            let sort = vec!["Type"; arity + 1].join(" → ");
+            self.body += &format!("axiom {} : {}\n", ident(name), sort);
+        }
+        for (name, sort) in &prelude.function_declarations {
+            let sort = self.sort(sort)?;
+            self.body += &format!("axiom {} : {}\n", ident(name), sort);
+        }
+        Ok(())
+    }
+
+    fn write_commands(&mut self, commands: &[ProofCommand]) -> LeanResult<()> {
+        for (i, command) in commands.iter().enumerate() {
+            let depth = self.frames.len() - 1;
+            let reference = match command {
+                ProofCommand::Assume { id, term } if depth == 0 => {
+                    let name = format!("assume.{}", qualified_ident(id));
+                    let statement = self.term(term)?;
+                    self.body += &format!("\naxiom {} : {}\n", name, statement);
+                    (name, Vec::new())
+                }
+                ProofCommand::Assume { id, term } => {
+                    let name = ident(&format!("h_{}", id));
+                    let binder = format!("({} : {})", name, self.term(term)?);
+                    let frame = self.frames.last_mut().unwrap();
+                    frame.params.push(name.clone());
+                    frame.binders.push(binder);
+                    (name, Vec::new())
+                }
+                ProofCommand::Step(step) => {
+                    let is_end_step = depth > 0 && i == commands.len() - 1;
+                    // The last step of a subproof may implicitly use the step before it
+                    let previous = (is_end_step && i > 0).then(|| (depth, i - 1));
+                    self.write_step(step, is_end_step, previous)?
+                }
+                ProofCommand::Subproof(subproof) => {
+                    let mut frame = Frame::default();
+                    for arg in &subproof.args {
+                        let (var, sort) = match arg {
+                            AnchorArg::Variable(var) | AnchorArg::Assign(var, _) => var,
+                        };
+                        let name = ident(var);
+                        frame
+                            .binders
+                            .push(format!("({} : {})", name, self.sort(sort)?));
+                        frame.params.push(name.clone());
+                        if let AnchorArg::Assign(_, value) = arg {
+                            let hypothesis = ident(&format!("h_{}", var));
+                            let eq = if sort.as_sort() == Some(&Sort::Bool) {
+                                "↔"
+                            } else {
+                                "="
+                            };
+                            let value = self.term(value)?;
+                            frame
+                                .binders
+                                .push(format!("({} : {} {} {})", hypothesis, name, eq, value));
+                            frame.params.push(hypothesis);
+                        }
+                    }
+                    self.frames.push(frame);
+                    self.write_commands(&subproof.commands)?;
+                    let mut frame = self.frames.pop().unwrap();
+                    frame.refs.pop().unwrap()
+                }
+            };
+            let frame = self.frames.last_mut().unwrap();
+            frame.refs.push(reference);
+            frame.clauses.push(command.clause().to_vec());
+        }
+        Ok(())
+    }
+
+    fn write_step(
+        &mut self,
+        step: &ProofStep,
+        is_end_step: bool,
+        previous: Option<(usize, usize)>,
+    ) -> LeanResult<Reference> {
+        // The conclusion of a subproof holds in the outer context, so it doesn't take the
+        // parameters of the subproof
+        let scope = if is_end_step {
+            &self.frames[..self.frames.len() - 1]
+        } else {
+            &self.frames[..]
+        };
+        let params: Vec<String> = scope.iter().flat_map(|f| f.params.clone()).collect();
+        let binders: Vec<String> = scope.iter().flat_map(|f| f.binders.clone()).collect();
+
+        let premises: Vec<(String, Vec<Rc<Term>>)> = step
+            .premises
+            .iter()
+            .copied()
+            .chain(previous)
+            .map(|(depth, i)| {
+                let (name, args) = &self.frames[depth].refs[i];
+                // Only the parameters that are in scope are applied, so premises from inside a
+                // subproof become functions of the subproof parameters
+                let applied = args.iter().zip(&params).take_while(|(a, p)| a == p);
+                let name = applied.fold(name.clone(), |acc, (a, _)| acc + " " + a);
+                (name, self.frames[depth].clauses[i].clone())
+            })
+            .collect();
+
+        let name = format!("step.{}", qualified_ident(&step.id));
+        let mut header = format!("\ntheorem {}", name);
+        for b in binders {
+            header += " ";
+            header += &b;
+        }
+        let statement = self.clause(&step.clause)?;
+        self.body += &format!(
+            "{} : {} := by\n{}",
+            header,
+            statement,
+            step_proof(step, &premises),
+        );
+        Ok((name, params))
+    }
+
+    fn clause(&mut self, clause: &[Rc<Term>]) -> LeanResult<String> {
+        if clause.is_empty() {
+            return Ok("False".to_owned());
+        }
+        let terms = clause
+            .iter()
+            .map(|t| self.term(t))
+            .collect::<LeanResult<Vec<_>>>()?;
+        Ok(terms.join(" ∨ "))
+    }
+
+    fn sort(&mut self, sort: &Rc<Term>) -> LeanResult<String> {
+        let unsupported = || LeanExportError::UnsupportedSort(sort.clone());
+        Ok(match sort.as_sort().ok_or_else(unsupported)? {
+            Sort::Bool => "Prop".to_owned(),
+            Sort::Int => "Int".to_owned(),
+            Sort::Real => {
+                self.uses_reals = true;
+                "Real".to_owned()
+            }
+            Sort::String => "String".to_owned(),
+            Sort::BitVec(w) => format!("(BitVec {})", w),
+            Sort::Atom(name, args) if args.is_empty() => ident(name),
+            Sort::Atom(name, args) => {
+                let args = args
+                    .iter()
+                    .map(|a| self.sort(a))
+                    .collect::<LeanResult<Vec<_>>>()?;
+                format!("({} {})", ident(name), args.join(" "))
+            }
+            Sort::Function(sorts) => {
+                let sorts = sorts
+                    .iter()
+                    .map(|s| self.sort(s))
+                    .collect::<LeanResult<Vec<_>>>()?;
+                format!("({})", sorts.join(" → "))
+            }
+            _ => return Err(unsupported()),
+        })
+    }
+
+    fn binders(&mut self, bindings: &BindingList) -> LeanResult<String> {
+        let binders = bindings
+            .iter()
+            .map(|(name, sort)| Ok(format!("({} : {})", ident(name), self.sort(sort)?)))
+            .collect::<LeanResult<Vec<_>>>()?;
+        Ok(binders.join(" "))
+    }
+
+    fn term(&mut self, term: &Rc<Term>) -> LeanResult<String> {
+        let unsupported = || LeanExportError::UnsupportedTerm(term.clone());
+        Ok(match term.as_ref() {
+            Term::Const(Constant::Integer(i)) => format!("({} : Int)", i),
+            Term::Const(Constant::Real(r)) => {
+                self.uses_reals = true;
+                if r.is_integer() {
+                    format!("({} : Real)", r.numer())
+                } else {
+                    format!("({} / {} : Real)", r.numer(), r.denom())
+                }
+            }
+            Term::Const(Constant::String(s)) => format!("{:?}", s),
+            Term::Const(Constant::BitVec(v, w)) => format!("({}#{})", v, w),
+            Term::Var(name, _) => ident(name),
+            Term::App(f, args) => {
+                let mut result = format!("({}", self.term(f)?);
+                for a in args {
+                    result += " ";
+                    result += &self.term(a)?;
+                }
+                result + ")"
+            }
+            Term::Op(op, args) => self.operation(term, *op, args)?,
+            Term::ParamOp { op, op_args, args } => {
+                let [arg] = args.as_slice() else {
+                    return Err(unsupported());
+                };
+                let indices = op_args
+                    .iter()
+                    .map(|a| a.as_integer().ok_or_else(unsupported))
+                    .collect::<LeanResult<Vec<_>>>()?;
+                let width = match self.pool.sort(arg).as_sort() {
+                    Some(Sort::BitVec(w)) => w.clone(),
+                    _ => return Err(unsupported()),
+                };
+                let arg = self.term(arg)?;
+                match (op, indices.as_slice()) {
+                    (ParamOperator::BvExtract, [i, j]) => {
+                        format!("(BitVec.extractLsb {} {} {})", i, j, arg)
+                    }
+                    (ParamOperator::ZeroExtend, [i]) => {
+                        format!("(BitVec.zeroExtend {} {})", width + i, arg)
+                    }
+                    (ParamOperator::SignExtend, [i]) => {
+                        format!("(BitVec.signExtend {} {})", width + i, arg)
+                    }
+                    _ => return Err(unsupported()),
+                }
+            }
+            Term::Binder(binder, bindings, body) => {
+                let binders = self.binders(bindings)?;
+                let body = self.term(body)?;
+                match binder {
+                    Binder::Forall => format!("(∀ {}, {})", binders, body),
+                    Binder::Exists => format!("(∃ {}, {})", binders, body),
+                    Binder::Lambda => format!("(fun {} => {})", binders, body),
+                    Binder::Choice if bindings.len() == 1 => {
+                        format!("(Classical.epsilon fun {} => {})", binders, body)
+                    }
+                    Binder::Choice => return Err(unsupported()),
+                }
+            }
+            Term::Let(bindings, body) => {
+                let mut result = "(".to_owned();
+                for (name, value) in bindings {
+                    result += &format!("let {} := {}; ", ident(name), self.term(value)?);
+                }
+                result + &self.term(body)? + ")"
+            }
+            Term::Sort(_) | Term::Match(..) => return Err(unsupported()),
+        })
+    }
+
+    fn operation(
+        &mut self,
+        term: &Rc<Term>,
+        op: Operator,
+        args: &[Rc<Term>],
+    ) -> LeanResult<String> {
+        let unsupported = || LeanExportError::UnsupportedTerm(term.clone());
+        let is_bool =
+            |pool: &mut PrimitivePool, t: &Rc<Term>| pool.sort(t).as_sort() == Some(&Sort::Bool);
+        let args = args
+            .iter()
+            .map(|a| Ok((self.term(a)?, a)))
+            .collect::<LeanResult<Vec<_>>>()?;
+
+        // Writes the arguments joined by an infix operator
+        let infix = |args: &[(String, &Rc<Term>)], op: &str| {
+            let args: Vec<_> = args.iter().map(|(a, _)| a.as_str()).collect();
+            format!("({})", args.join(&format!(" {} ", op)))
+        };
+        // Writes a chain of binary relations as a conjunction, like SMT-LIB chainable operators
+        let chain = |args: &[(String, &Rc<Term>)], op: &str| {
+            let pairs: Vec<_> = args
+                .windows(2)
+                .map(|w| format!("{} {} {}", w[0].0, op, w[1].0))
+                .collect();
+            format!("({})", pairs.join(" ∧ "))
+        };
+
+        Ok(match (op, args.as_slice()) {
+            (Operator::True, []) => "True".to_owned(),
+            (Operator::False, []) => "False".to_owned(),
+            (Operator::Not, [(a, _)]) => format!("(¬{})", a),
+            (Operator::And, [_, _, ..]) => infix(&args, "∧"),
+            (Operator::Or, [_, _, ..]) => infix(&args, "∨"),
+            (Operator::Implies, [_, _, ..]) => infix(&args, "→"),
+            (Operator::And | Operator::Or, [(a, _)]) => a.clone(),
+            (Operator::Xor, [(a, _), (b, _)]) => format!("(¬({} ↔ {}))", a, b),
+            (Operator::Equals, [(_, first), _, ..]) => {
+                let op = if is_bool(self.pool, first) {
+                    "↔"
+                } else {
+                    "="
+                };
+                chain(&args, op)
+            }
+            (Operator::Distinct, [_, _, ..]) => {
+                let mut pairs = Vec::new();
+                for (i, (a, _)) in args.iter().enumerate() {
+                    for (b, _) in &args[i + 1..] {
+                        pairs.push(format!("{} ≠ {}", a, b));
+                    }
+                }
+                format!("({})", pairs.join(" ∧ "))
+            }
+            (Operator::Ite, [(c, _), (a, _), (b, _)]) => {
+                format!("(if {} then {} else {})", c, a, b)
+            }
+
+            (Operator::Add, [_, _, ..]) => infix(&args, "+"),
+            (Operator::Sub, [(a, _)]) => format!("(-{})", a),
+            (Operator::Sub, [_, _, ..]) => infix(&args, "-"),
+            (Operator::Mult, [_, _, ..]) => infix(&args, "*"),
+            (Operator::RealDiv, [_, _, ..]) => infix(&args, "/"),
+            (Operator::IntDiv, [_, _, ..]) => infix(&args, "/"),
+            (Operator::Mod, [_, _]) => infix(&args, "%"),
+            (Operator::Abs, [(a, _)]) => format!("(if {a} < 0 then -{a} else {a})", a = a),
+            (Operator::LessThan, [_, _, ..]) => chain(&args, "<"),
+            (Operator::GreaterThan, [_, _, ..]) => chain(&args, ">"),
+            (Operator::LessEq, [_, _, ..]) => chain(&args, "≤"),
+            (Operator::GreaterEq, [_, _, ..]) => chain(&args, "≥"),
+            (Operator::ToReal, [(a, _)]) => {
+                self.uses_reals = true;
+                format!("(({} : Int) : Real)", a)
+            }
+
+            (Operator::BvNot, [(a, _)]) => format!("(~~~{})", a),
+            (Operator::BvNeg, [(a, _)]) => format!("(-{})", a),
+            (Operator::BvAnd, [_, _, ..]) => infix(&args, "&&&"),
+            (Operator::BvOr, [_, _, ..]) => infix(&args, "|||"),
+            (Operator::BvXor, [_, _, ..]) => infix(&args, "^^^"),
+            (Operator::BvAdd, [_, _, ..]) => infix(&args, "+"),
+            (Operator::BvSub, [_, _, ..]) => infix(&args, "-"),
+            (Operator::BvMul, [_, _, ..]) => infix(&args, "*"),
+            (Operator::BvConcat, [_, _, ..]) => infix(&args, "++"),
+            (Operator::BvUDiv, [(a, _), (b, _)]) => format!("(BitVec.smtUDiv {} {})", a, b),
+            (Operator::BvURem, [(a, _), (b, _)]) => format!("(BitVec.umod {} {})", a, b),
+            (Operator::BvShl, [_, _]) => infix(&args, "<<<"),
+            (Operator::BvLShr, [_, _]) => infix(&args, ">>>"),
+            (Operator::BvULt, [_, _]) => infix(&args, "<"),
+            (Operator::BvULe, [_, _]) => infix(&args, "≤"),
+            (Operator::BvUGt, [_, _]) => infix(&args, ">"),
+            (Operator::BvUGe, [_, _]) => infix(&args, "≥"),
+            (Operator::BvSLt, [(a, _), (b, _)]) => format!("(BitVec.slt {} {} = true)", a, b),
+            (Operator::BvSLe, [(a, _), (b, _)]) => format!("(BitVec.sle {} {} = true)", a, b),
+            (Operator::BvSGt, [(a, _), (b, _)]) => format!("(BitVec.slt {} {} = true)", b, a),
+            (Operator::BvSGe, [(a, _), (b, _)]) => format!("(BitVec.sle {} {} = true)", b, a),
+            _ => return Err(unsupported()),
+        })
+    }
+}
+
+/// Returns the tactic proof of a step, given the names and clauses of its premises. The core rules
+/// of elaborated proofs are reconstructed directly, and the other rules are left to the
+/// `alethe_step` tactic.
+fn step_proof(step: &ProofStep, premises: &[(String, Vec<Rc<Term>>)]) -> String {
+    let names: Vec<_> = premises.iter().map(|(name, _)| name.as_str()).collect();
+    let tactics = match step.rule.as_str() {
+        // These rules are propositional tautologies once the premises are known, so they are
+        // proved by negating the conclusion and propagating the premises
+        "resolution" | "th_resolution" | "strict_resolution" | "contraction" | "reordering"
+        | "weakening" | "not_and" | "not_or" | "and_pos" | "and_neg" | "or_pos" | "or_neg" => {
+            let mut tactics: Vec<_> = names.iter().map(|p| format!("have := {}", p)).collect();
+            tactics.push("apply Classical.byContradiction".to_owned());
+            tactics.push("intro".to_owned());
+            tactics.push("simp_all".to_owned());
+            Some(tactics)
+        }
+        "and" => and_projection(step, premises).map(|p| vec![format!("exact {}", p)]),
+        "or" if premises.len() == 1 => Some(vec![format!("exact {}", names[0])]),
+        // Inside subproofs, the two sides may only be equal because of the hypotheses introduced
+        // by the anchor
+        "refl" => Some(vec!["first | rfl | simp_all".to_owned()]),
+        "trans" => trans_chain(step, premises).map(|p| vec![format!("exact {}", p)]),
+        // Rewriting with the premises makes both sides equal, regardless of their orientation
+        "cong" if !premises.is_empty() => Some(vec![format!("simp only [{}]", names.join(", "))]),
+        _ => None,
+    };
+    let tactics = tactics.unwrap_or_else(|| {
+        let rule = ident(&step.rule);
+        vec![format!("alethe_step {} [{}]", rule, names.join(", "))]
+    });
+    tactics.iter().map(|t| format!("  {}\n", t)).collect()
+}
+
+/// Builds the projection that proves the conclusion of an `and` step from its premise. Since `∧` is
+/// right associative, the `i`-th conjunct is reached by taking the right side `i` times.
+fn and_projection(step: &ProofStep, premises: &[(String, Vec<Rc<Term>>)]) -> Option<String> {
+    let ([(name, premise)], [conclusion]) = (premises, step.clause.as_slice()) else {
+        return None;
+    };
+    let [conjunction] = premise.as_slice() else {
+        return None;
+    };
+    let conjuncts = match conjunction.as_op() {
+        Some((Operator::And, args)) => args,
+        _ => return None,
+    };
+    let i = conjuncts.iter().position(|c| c == conclusion)?;
+    let mut result = format!("({}){}", name, ".2".repeat(i));
+    if i + 1 < conjuncts.len() {
+        result += ".1";
+    }
+    Some(result)
+}
+
+/// Builds the chain of `trans` applications that proves the conclusion of a `trans` step from its
+/// premises, which may be in any order and orientation.
+fn trans_chain(step: &ProofStep, premises: &[(String, Vec<Rc<Term>>)]) -> Option<String> {
+    let [conclusion] = step.clause.as_slice() else {
+        return None;
+    };
+    let (first, last) = match_term!((= t u) = conclusion)?;
+    let premises = premises
+        .iter()
+        .map(|(name, clause)| match clause.as_slice() {
+            [eq] => match_term!((= t u) = eq).map(|(t, u)| (name, t, u)),
+            _ => None,
+        })
+        .collect::<Option<Vec<_>>>()?;
+
+    let mut used = vec![false; premises.len()];
+    let mut current = first;
+    let mut result: Option<String> = None;
+    for _ in 0..premises.len() {
+        let (i, link, next) = premises.iter().enumerate().find_map(|(i, (name, t, u))| {
+            if used[i] {
+                None
+            } else if *t == current {
+                Some((i, format!("({})", name), *u))
+            } else if *u == current {
+                Some((i, format!("({}).symm", name), *t))
+            } else {
+                None
+            }
+        })?;
+        used[i] = true;
+        current = next;
+        result = Some(match result {
+            Some(acc) => format!("({}.trans {})", acc, link),
+            None => link,
+        });
+    }
+    if current != last {
+        return None;
+    }
+    result
+}
+
+/// Returns `true` if `name` can be written as a Lean identifier without quoting it.
+fn is_plain_ident(name: &str) -> bool {
+    const KEYWORDS: &[&str] = &[
+        "at",
+        "axiom",
+        "by",
+        "def",
+        "do",
+        "else",
+        "end",
+        "example",
+        "fun",
+        "have",
+        "if",
+        "import",
+        "in",
+        "instance",
+        "let",
+        "match",
+        "namespace",
+        "open",
+        "section",
+        "show",
+        "then",
+        "theorem",
+        "universe",
+        "variable",
+        "where",
+        "with",
+        "Prop",
+        "Sort",
+        "Type",
+    ];
+    name.starts_with(|c: char| c.is_ascii_alphabetic() || c == '_')
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '\'')
+        && !KEYWORDS.contains(&name)
+}
+
+/// Converts an SMT-LIB symbol into a Lean identifier, quoting it with `«»` if necessary.
+fn ident(name: &str) -> String {
+    if is_plain_ident(name) {
+        name.to_owned()
+    } else {
+        format!("«{}»", name.replace('»', "_"))
+    }
+}
+
+/// Converts a command id into a hierarchical Lean name, so the steps of a subproof are in the
+/// namespace of the step that concludes it. For example, `t5.t1` becomes `t5.t1`, and `t5.a!0`
+/// becomes `t5.«a!0»`.
+fn qualified_ident(id: &str) -> String {
+    if id.split('.').any(str::is_empty) {
+        return ident(id);
+    }
+    id.split('.').map(ident).collect::<Vec<_>>().join(".")
+}
@@ -0,0 +1,212 @@
+use super::*;
+use crate::parser;
+
+fn export(definitions: &str, proof: &str) -> LeanResult<String> {
+    let (problem, proof, mut pool) = parser::parse_instance(
+        definitions.as_bytes(),
+        proof.as_bytes(),
+        parser::Config::new(),
+    )
+    .unwrap();
+    let mut buf = Vec::new();
+    write_lean_proof(&mut pool, &problem, &proof, &mut buf)?;
+    Ok(String::from_utf8(buf).unwrap())
+}
+
+#[test]
+fn test_steps() {
+    let definitions = "
+        (declare-fun p () Bool)
+        (declare-fun q () Bool)
+        (declare-sort U 0)
+        (declare-fun f (U Int) Int)
+    ";
+    let proof = "
+        (assume h1 (or p q))
+        (assume h2 (not p))
+        (assume h3 (not q))
+        (step t1 (cl p q) :rule or :premises (h1))
+        (step t2 (cl) :rule resolution :premises (t1 h2 h3) :args (p true q true))
+    ";
+    let expected = "\
+        axiom U : Type\n\
+        axiom p : Prop\n\
+        axiom q : Prop\n\
+        axiom f : (U → Int → Int)\n\
+        \n\
+        axiom assume.h1 : (p ∨ q)\n\
+        \n\
+        axiom assume.h2 : (¬p)\n\
+        \n\
+        axiom assume.h3 : (¬q)\n\
+        \n\
+        theorem step.t1 : p ∨ q := by\n  exact assume.h1\n\
+        \n\
+        theorem step.t2 : False := by\n  \
+            have := step.t1\n  \
+            have := assume.h2\n  \
+            have := assume.h3\n  \
+            apply Classical.byContradiction\n  \
+            intro\n  \
+            simp_all\n\
+    ";
+    let got = export(definitions, proof).unwrap();
+    assert!(got.starts_with("-- Exported by Carcara\n\nopen Classical\n"));
+    assert!(got.ends_with(expected), "{}", got);
+}
+
+#[test]
+fn test_core_rules() {
+    let definitions = "
+        (declare-fun p () Bool)
+        (declare-fun q () Bool)
+        (declare-fun r () Bool)
+        (declare-fun a () Int)
+        (declare-fun b () Int)
+        (declare-fun c () Int)
+    ";
+    let proof = "
+        (assume h1 (and p q r))
+        (assume h2 (= a b))
+        (assume h3 (= c b))
+        (step t1 (cl q) :rule and :premises (h1))
+        (step t2 (cl r) :rule and :premises (h1))
+        (step t3 (cl (= a c)) :rule trans :premises (h2 h3))
+        (step t4 (cl (not (and p q r)) p) :rule and_pos :args (0))
+        (step t5 (cl (= a c)) :rule hole :premises (t3))
+    ";
+    let got = export(definitions, proof).unwrap();
+    let proof_of = |id: &str| {
+        let header = format!("theorem step.{} ", id);
+        let start = got.find(&header).unwrap();
+        let proof = &got[start..].split_once(":= by\n").unwrap().1;
+        proof.split("\n\n").next().unwrap().trim_end().to_owned()
+    };
+    assert_eq!(proof_of("t1"), "  exact (assume.h1).2.1");
+    assert_eq!(proof_of("t2"), "  exact (assume.h1).2.2");
+    assert_eq!(
+        proof_of("t3"),
+        "  exact ((assume.h2).trans (assume.h3).symm)"
+    );
+    assert_eq!(
+        proof_of("t4"),
+        "  apply Classical.byContradiction\n  intro\n  simp_all"
+    );
+    assert_eq!(proof_of("t5"), "  alethe_step hole [step.t3]");
+}
+
+#[test]
+fn test_terms() {
+    let definitions = "
+        (declare-fun x () Int)
+        (declare-fun r () Real)
+        (declare-fun a () (_ BitVec 4))
+        (declare-fun |weird name| () Bool)
+    ";
+    let cases = [
+        (
+            "(= x (+ x 1 (- 2)))",
+            "(x = (x + (1 : Int) + (-(2 : Int))))",
+        ),
+        ("(< 0 x 5)", "((0 : Int) < x ∧ x < (5 : Int))"),
+        (
+            "(distinct x 1 2)",
+            "(x ≠ (1 : Int) ∧ x ≠ (2 : Int) ∧ (1 : Int) ≠ (2 : Int))",
+        ),
+        (
+            "(= |weird name| (> x 0))",
+            "(«weird name» ↔ (x > (0 : Int)))",
+        ),
+        (
+            "(=> |weird name| (not |weird name|))",
+            "(«weird name» → (¬«weird name»))",
+        ),
+        (
+            "(forall ((y Int) (fun Int)) (>= (ite (< y 0) (- y) y) fun))",
+            "(∀ (y : Int) («fun» : Int), ((if (y < (0 : Int)) then (-y) else y) ≥ «fun»))",
+        ),
+        (
+            "(= (to_real x) (/ r 2.0))",
+            "(((x : Int) : Real) = (r / (2 : Real)))",
+        ),
+        (
+            "(= ((_ extract 1 0) a) #b01)",
+            "((BitVec.extractLsb 1 0 a) = (1#2))",
+        ),
+        (
+            "(bvult (bvadd a a) ((_ zero_extend 2) #b11))",
+            "((a + a) < (BitVec.zeroExtend 4 (3#2)))",
+        ),
+    ];
+    for (term, expected) in cases {
+        let proof = format!("(step t1 (cl {}) :rule hole)", term);
+        let got = export(definitions, &proof).unwrap();
+        let statement = got
+            .lines()
+            .find_map(|l| l.strip_prefix("theorem step.t1 : "));
+        assert_eq!(statement, Some(format!("{} := by", expected).as_str()));
+    }
+
+    let reals = export(definitions, "(step t1 (cl (= r 1.5)) :rule hole)").unwrap();
+    assert!(reals.contains("import Mathlib.Data.Real.Basic\n"));
+    assert!(reals.contains("(r = (3 / 2 : Real))"));
+}
+
+#[test]
+fn test_subproofs() {
+    let definitions = "(declare-fun p () Bool)";
+    let proof = "
+        (anchor :step t1 :args ((y Int) (:= (x Int) y)))
+        (step t1.t1 (cl (= x y)) :rule refl)
+        (step t1.t2 (cl (= (> x 0) (> y 0))) :rule cong :premises (t1.t1))
+        (step t1 (cl (= (forall ((x Int)) (> x 0)) (forall ((y Int)) (> y 0)))) :rule bind)
+        (anchor :step t2)
+        (assume t2.a0 p)
+        (step t2.t1 (cl p) :rule hole :premises (t2.a0))
+        (step t2 (cl (not p) p) :rule subproof :discharge (t2.a0))
+    ";
+    let expected = "\
+        theorem step.t1.t1 (y : Int) (x : Int) (h_x : x = y) : (x = y) := by\n  \
+            first | rfl | simp_all\n\
+        \n\
+        theorem step.t1.t2 (y : Int) (x : Int) (h_x : x = y) : \
+            ((x > (0 : Int)) ↔ (y > (0 : Int))) := by\n  \
+            simp only [step.t1.t1 y x h_x]\n\
+        \n\
+        theorem step.t1 : ((∀ (x : Int), (x > (0 : Int))) ↔ (∀ (y : Int), (y > (0 : Int)))) := by\n  \
+            alethe_step bind [step.t1.t2]\n\
+        \n\
+        theorem step.t2.t1 («h_t2.a0» : p) : p := by\n  \
+            alethe_step hole [«h_t2.a0»]\n\
+        \n\
+        theorem step.t2 : (¬p) ∨ p := by\n  \
+            alethe_step subproof [step.t2.t1]\n\
+    ";
+    let got = export(definitions, proof).unwrap();
+    assert!(got.ends_with(expected), "{}", got);
+}
+
+#[test]
+fn test_errors() {
+    let cases = [
+        (
+            "(declare-fun a () (Array Int Int))",
+            "(step t1 (cl (= a a)) :rule hole)",
+            "lean_export.unsupported_sort",
+        ),
+        (
+            "(declare-fun s () String)",
+            "(step t1 (cl (= (str.len s) 0)) :rule hole)",
+            "lean_export.unsupported_term",
+        ),
+        (
+            "(declare-datatype Unit ((unit)))",
+            "(step t1 (cl) :rule hole)",
+            "lean_export.unsupported_datatype",
+        ),
+    ];
+    for (definitions, proof, expected) in cases {
+        let got = export(definitions, proof).unwrap_err();
+        assert_eq!(got.code(), expected);
+    }
+}
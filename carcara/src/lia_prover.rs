@@ -0,0 +1,312 @@
+//! A prover for clauses of linear integer arithmetic, used to check and elaborate `lia_generic`
+//! steps without calling an external solver.
+//!
+//! The prover uses branch and bound over the simplex method, strengthened with cutting planes. The
+//! resulting proof only uses the `la_generic`, `la_disequality`, `or` and `resolution` rules:
+//!
+//! - If the negation of the clause is unsatisfiable over the reals, the clause is proved by a
+//!   single `la_generic` step.
+//! - If the clause contains an equality `(= a b)`, its negation is a disequality, which can't be
+//!   handled by `la_generic`. Instead, we prove the clause twice, replacing the equality by `(<= a
+//!   b)` and `(<= b a)`, and combine both proofs with the `la_disequality` lemma.
+//! - Otherwise, we take a linear combination `e` of integer variables and a constant `m` such that
+//!   the current solution has `m < e < m + g`, where `g` is the greatest common divisor of the
+//!   coefficients in `e`. Since `e` is always a multiple of `g`, the clause `(<= e m) ∨ (>= e (+ m
+//!   g))` is valid, and can be proved by `la_generic` thanks to its strengthening rules. We then
+//!   prove the original clause extended with `(not (<= e m))`, and extended with `(not (>= e (+ m
+//!   g)))`, and combine all three using resolution. The combination `e` is either a single
+//!   variable (which gives the usual branch and bound), or the left side of a constraint whose
+//!   constant is not a multiple of `g` (which gives a cutting plane).
+
+use crate::{
+    ast::*,
+    checker::la_generic_constraints,
+    simplex::{self, Constraint, Relation, Solution},
+};
+use indexmap::IndexSet;
+use rug::{Integer, Rational};
+
+/// The maximum number of times the prover may call the simplex method when proving a single
+/// clause. If the proof needs more calls than this, the prover gives up.
+const MAX_SIMPLEX_CALLS: usize = 1000;
+
+/// The result of the prover.
+pub enum LiaProverResult {
+    /// The clause is valid, and this is a proof of it.
+    Proved(Rc<ProofNode>),
+
+    /// The negation of the clause has an integer solution, so the clause is not valid.
+    CounterModel,
+
+    /// The prover gave up.
+    Unknown,
+}
+
+/// Tries to prove a clause of linear integer arithmetic. The root step of the resulting proof has
+/// the given id and depth, and all other steps have ids of the form `<id>.t<n>`.
+pub fn prove(
+    pool: &mut dyn TermPool,
+    clause: &[Rc<Term>],
+    id: &str,
+    depth: usize,
+) -> LiaProverResult {
+    let mut prover = Prover {
+        pool,
+        id,
+        depth,
+        next_id: 0,
+        num_simplex_calls: 0,
+    };
+    match prover.prove_clause(clause.to_vec()) {
+        Ok(mut node) => {
+            // The root step must have the id of the step being proved
+            let ProofNode::Step(mut root) = node.as_ref().clone() else {
+                unreachable!()
+            };
+            root.id = id.to_owned();
+            node = Rc::new(ProofNode::Step(root));
+            LiaProverResult::Proved(node)
+        }
+        Err(Failure::Sat) => LiaProverResult::CounterModel,
+        Err(Failure::Unknown) => LiaProverResult::Unknown,
+    }
+}
+
+enum Failure {
+    Sat,
+    Unknown,
+}
+
+struct Prover<'a> {
+    pool: &'a mut dyn TermPool,
+    id: &'a str,
+    depth: usize,
+    next_id: usize,
+    num_simplex_calls: usize,
+}
+
+impl Prover<'_> {
+    fn step(
+        &mut self,
+        clause: Vec<Rc<Term>>,
+        rule: &str,
+        premises: Vec<Rc<ProofNode>>,
+        args: Vec<Rc<Term>>,
+    ) -> Rc<ProofNode> {
+        self.next_id += 1;
+        Rc::new(ProofNode::Step(StepNode {
+            id: format!("{}.t{}", self.id, self.next_id),
+            depth: self.depth,
+            clause,
+            rule: rule.to_owned(),
+            premises,
+            args,
+            ..Default::default()
+        }))
+    }
+
+    fn is_arithmetic(&self, term: &Rc<Term>) -> bool {
+        let sort = self.pool.sort(term);
+        matches!(sort.as_sort(), Some(Sort::Int | Sort::Real))
+    }
+
+    fn is_int(&self, term: &Rc<Term>) -> bool {
+        self.pool.sort(term).as_sort() == Some(&Sort::Int)
+    }
+
+    fn integer_term(&mut self, value: Integer) -> Rc<Term> {
+        if value < 0 {
+            let value = self.pool.add(Term::new_int(-value));
+            build_term!(self.pool, (-{ value }))
+        } else {
+            self.pool.add(Term::new_int(value))
+        }
+    }
+
+    /// Proves a clause, first removing any equalities from it.
+    fn prove_clause(&mut self, clause: Vec<Rc<Term>>) -> Result<Rc<ProofNode>, Failure> {
+        let equality = clause.iter().enumerate().find_map(|(i, l)| {
+            let (a, b) = match_term!((= a b) = l)?;
+            self.is_arithmetic(a).then(|| (i, a.clone(), b.clone()))
+        });
+        let Some((i, a, b)) = equality else {
+            let tightened = vec![false; clause.len()];
+            return self.branch_and_bound(clause, tightened);
+        };
+
+        let (a_le_b, b_le_a) = (
+            build_term!(self.pool, (<= {a.clone()} {b.clone()})),
+            build_term!(self.pool, (<= {b.clone()} {a.clone()})),
+        );
+        let mut first = clause.clone();
+        first[i] = a_le_b.clone();
+        let first = self.prove_clause(first)?;
+        let mut second = clause.clone();
+        second[i] = b_le_a.clone();
+        let second = self.prove_clause(second)?;
+
+        let lemma_clause = vec![
+            clause[i].clone(),
+            build_term!(self.pool, (not {a_le_b.clone()})),
+            build_term!(self.pool, (not {b_le_a.clone()})),
+        ];
+        let lemma_term = self.pool.add(Term::Op(Operator::Or, lemma_clause.clone()));
+        let lemma = self.step(vec![lemma_term], "la_disequality", Vec::new(), Vec::new());
+        let lemma = self.step(lemma_clause, "or", vec![lemma], Vec::new());
+
+        let false_term = self.pool.bool_false();
+        Ok(self.step(
+            clause,
+            "resolution",
+            vec![lemma, first, second],
+            vec![a_le_b, false_term.clone(), b_le_a, false_term],
+        ))
+    }
+
+    /// Proves a clause of disequalities. The vector `tightened` indicates which literals were
+    /// already used to add a cutting plane.
+    fn branch_and_bound(
+        &mut self,
+        clause: Vec<Rc<Term>>,
+        mut tightened: Vec<bool>,
+    ) -> Result<Rc<ProofNode>, Failure> {
+        let mut vars = IndexSet::new();
+        let constraints =
+            la_generic_constraints(&clause, &mut vars).map_err(|_| Failure::Unknown)?;
+
+        // The `la_generic` rule rejects terms multiplied by zero, since they are kept in the final
+        // disequality, so we can't prove clauses that contain them
+        let has_zero_coeff =
+            (constraints.iter()).any(|c| c.coefficients.iter().any(|(_, coeff)| *coeff == 0));
+        if has_zero_coeff {
+            return Err(Failure::Unknown);
+        }
+
+        self.num_simplex_calls += 1;
+        if self.num_simplex_calls > MAX_SIMPLEX_CALLS {
+            return Err(Failure::Unknown);
+        }
+        let model = match simplex::solve(vars.len(), &constraints) {
+            Solution::Unsat(coefficients) => {
+                let args = (coefficients.into_iter())
+                    .map(|c| self.integer_term(c))
+                    .collect();
+                return Ok(self.step(clause, "la_generic", Vec::new(), args));
+            }
+            Solution::Sat(model) => model,
+        };
+        let is_int: Vec<_> = vars.iter().map(|v| self.is_int(v)).collect();
+
+        // If possible, we add a cutting plane. Otherwise, we branch on a variable whose value is
+        // not an integer. If there is no such variable, the model is also an integer solution
+        let cut = constraints.iter().enumerate().find_map(|(i, c)| {
+            let is_integer_constraint =
+                !c.coefficients.is_empty() && c.coefficients.iter().all(|(var, _)| is_int[*var]);
+            if tightened[i] || !is_integer_constraint {
+                return None;
+            }
+            let (coefficients, constant) = scale_to_integers(c);
+            let gcd = (coefficients.iter()).fold(Integer::new(), |acc, (_, c)| acc.gcd(c));
+            let is_multiple = constant.is_integer() && constant.numer().is_divisible(&gcd);
+            if c.relation != Relation::Greater && is_multiple {
+                return None;
+            }
+            let bound = Integer::from((constant / &gcd).floor_ref()) * &gcd;
+
+            // The cut is only useful if the current solution violates it
+            let value = (coefficients.iter()).fold(Rational::new(), |acc, (var, c)| {
+                acc + Rational::from(&model[*var] * c)
+            });
+            if value >= Integer::from(&bound + &gcd) {
+                return None;
+            }
+            Some((i, coefficients, bound, gcd))
+        });
+        let (coefficients, bound, step) = match cut {
+            Some((i, coefficients, bound, gcd)) => {
+                tightened[i] = true;
+                (coefficients, bound, gcd)
+            }
+            None => {
+                let var = (0..vars.len()).find(|&v| is_int[v] && !model[v].is_integer());
+                // If some atom is not a variable (for example, a non-linear multiplication), the
+                // solution may not correspond to an actual model of the clause
+                let failure = if vars.iter().all(|v| v.is_var()) {
+                    Failure::Sat
+                } else {
+                    Failure::Unknown
+                };
+                let var = var.ok_or(failure)?;
+                let bound = Integer::from(model[var].floor_ref());
+                (vec![(var, Integer::from(1))], bound, Integer::from(1))
+            }
+        };
+
+        let expression = self.linear_combination(&vars, coefficients);
+        let upper = bound.clone() + &step;
+        let (bound, upper) = (self.integer_term(bound), self.integer_term(upper));
+        let first = build_term!(self.pool, (<= {expression.clone()} {bound}));
+        let second = build_term!(self.pool, (>= {expression} {upper}));
+
+        let one = self.pool.add(Term::new_int(1));
+        let lemma = self.step(
+            vec![first.clone(), second.clone()],
+            "la_generic",
+            Vec::new(),
+            vec![one.clone(), one],
+        );
+
+        let mut prove_branch = |branch: &Rc<Term>| {
+            let mut clause = clause.clone();
+            clause.push(build_term!(self.pool, (not {branch.clone()})));
+            let mut tightened = tightened.clone();
+            tightened.push(true);
+            self.branch_and_bound(clause, tightened)
+        };
+        let first_proof = prove_branch(&first)?;
+        let second_proof = prove_branch(&second)?;
+
+        let true_term = self.pool.bool_true();
+        Ok(self.step(
+            clause,
+            "resolution",
+            vec![lemma, first_proof, second_proof],
+            vec![first, true_term.clone(), second, true_term],
+        ))
+    }
+
+    fn linear_combination(
+        &mut self,
+        vars: &IndexSet<Rc<Term>>,
+        coefficients: Vec<(usize, Integer)>,
+    ) -> Rc<Term> {
+        let mut terms: Vec<_> = coefficients
+            .into_iter()
+            .map(|(var, coeff)| {
+                let var = vars[var].clone();
+                if coeff == 1 {
+                    var
+                } else {
+                    let coeff = self.integer_term(coeff);
+                    build_term!(self.pool, (* {coeff} {var}))
+                }
+            })
+            .collect();
+        if terms.len() == 1 {
+            terms.pop().unwrap()
+        } else {
+            self.pool.add(Term::Op(Operator::Add, terms))
+        }
+    }
+}
+
+/// Multiplies a constraint by the least common multiple of the denominators of its coefficients,
+/// so they all become integers. Returns the resulting coefficients and constant.
+fn scale_to_integers(constraint: &Constraint) -> (Vec<(usize, Integer)>, Rational) {
+    let lcm =
+        (constraint.coefficients.iter()).fold(Integer::from(1), |acc, (_, c)| acc.lcm(c.denom()));
+    let coefficients = (constraint.coefficients.iter())
+        .map(|(var, c)| (*var, c.numer() * Integer::from(&lcm / c.denom())))
+        .collect();
+    (coefficients, Rational::from(&constraint.constant * &lcm))
+}
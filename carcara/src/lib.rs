@@ -37,16 +37,25 @@
 pub mod ast;
 pub mod benchmarking;
 pub mod checker;
+pub mod diff;
 mod drup;
 pub mod elaborator;
+pub mod lean;
+mod lia_prover;
 pub mod parser;
+pub mod pb_proof;
+pub mod proof_stats;
+pub mod rare;
 mod resolution;
+pub mod sat_proof;
+mod simplex;
 pub mod slice;
 mod utils;
 
 use crate::benchmarking::{CollectResults, OnlineBenchmarkResults, RunMeasurement};
-use checker::{error::CheckerError, CheckerStatistics};
+use checker::{error::CheckerError, CheckerFailure, CheckerStatistics};
 use parser::{ParserError, Position};
+use std::collections::HashMap;
 use std::io;
 use std::time::{Duration, Instant};
 use thiserror::Error;
@@ -62,6 +71,16 @@ fn wrap_parser_error_message(e: &ParserError, pos: &Position) -> String {
     }
 }
 
+fn format_checker_failures(failures: &[CheckerFailure]) -> String {
+    use std::fmt::Write;
+
+    let mut result = format!("checking failed on {} steps:", failures.len());
+    for f in failures {
+        write!(&mut result, "\n  {}", f).unwrap();
+    }
+    result
+}
+
 #[derive(Debug, Error)]
 pub enum Error {
     #[error("IO error: {0}")]
@@ -77,10 +96,45 @@ pub enum Error {
         step: String,
     },
 
+    // When the checker is run with `checker::Config::keep_going`, it reports all the steps that
+    // failed at once, instead of stopping at the first one
+    #[error("{}", format_checker_failures(.0))]
+    CheckerFailures(Vec<CheckerFailure>),
+
     // While this is a kind of checking error, it does not happen in a specific step like all other
     // checker errors, so we model it as a different variant
     #[error("checker error: proof does not conclude empty clause")]
     DoesNotReachEmptyClause,
+
+    #[error("{0}")]
+    SatProof(#[from] sat_proof::SatProofError),
+
+    #[error("{0}")]
+    PbProof(#[from] pb_proof::PbProofError),
+
+    #[error("{0}")]
+    LeanExport(#[from] lean::LeanExportError),
+
+    #[error("{0}")]
+    Pipeline(#[from] elaborator::PipelineError),
+}
+
+impl Error {
+    /// Returns a stable identifier for the kind of this error. For parser and checker errors, this
+    /// is the code of the underlying error (see `ParserError::code` and `CheckerError::code`).
+    pub fn code(&self) -> &'static str {
+        match self {
+            Error::Io(_) => "io",
+            Error::Parser(e, _) => e.code(),
+            Error::Checker { inner, .. } => inner.code(),
+            Error::CheckerFailures(_) => "multiple_failures",
+            Error::DoesNotReachEmptyClause => "does_not_reach_empty_clause",
+            Error::SatProof(e) => e.code(),
+            Error::PbProof(e) => e.code(),
+            Error::LeanExport(e) => e.code(),
+            Error::Pipeline(e) => e.code(),
+        }
+    }
 }
 
 pub fn check<T: io::BufRead>(
@@ -89,6 +143,26 @@ pub fn check<T: io::BufRead>(
     parser_config: parser::Config,
     checker_config: checker::Config,
     collect_stats: bool,
+) -> Result<bool, Error> {
+    if collect_stats {
+        let mut stats = OnlineBenchmarkResults::new();
+        let res = check_with_stats(problem, proof, parser_config, checker_config, &mut stats);
+        print_stats(&stats);
+        res
+    } else {
+        let (problem, proof, mut pool) = parser::parse_instance(problem, proof, parser_config)?;
+        checker::ProofChecker::new(&mut pool, checker_config).check(&problem, &proof)
+    }
+}
+
+/// Similar to `check`, but collects performance statistics and holes into `stats` instead of
+/// printing them.
+pub fn check_with_stats<T: io::BufRead>(
+    problem: T,
+    proof: T,
+    parser_config: parser::Config,
+    checker_config: checker::Config,
+    stats: &mut OnlineBenchmarkResults,
 ) -> Result<bool, Error> {
     let mut run_measures: RunMeasurement = RunMeasurement::default();
 
@@ -100,42 +174,58 @@ pub fn check<T: io::BufRead>(
     // Checking
     let checking = Instant::now();
     let mut checker = checker::ProofChecker::new(&mut pool, checker_config);
-    if collect_stats {
-        let mut checker_stats = CheckerStatistics {
-            file_name: "this",
-            polyeq_time: Duration::ZERO,
-            assume_time: Duration::ZERO,
-            assume_core_time: Duration::ZERO,
-            results: OnlineBenchmarkResults::new(),
-        };
-        let res = checker.check_with_stats(&problem, &proof, &mut checker_stats);
-
-        run_measures.checking = checking.elapsed();
-        run_measures.total = total.elapsed();
-
-        checker_stats.results.add_run_measurement(
-            &("this".to_owned(), 0),
-            RunMeasurement {
-                parsing: run_measures.parsing,
-                checking: run_measures.checking,
-                elaboration: run_measures.elaboration,
-                scheduling: run_measures.scheduling,
-                total: run_measures.total,
-                polyeq: checker_stats.polyeq_time,
-                assume: checker_stats.assume_time,
-                assume_core: checker_stats.assume_core_time,
-                elaboration_pipeline: Vec::new(),
-            },
-        );
-        // Print the statistics
-        checker_stats.results.print(false);
+    let mut checker_stats = CheckerStatistics {
+        file_name: "this",
+        polyeq_time: Duration::ZERO,
+        assume_time: Duration::ZERO,
+        assume_core_time: Duration::ZERO,
+        results: std::mem::take(stats),
+    };
+    let res = checker.check_with_stats(&problem, &proof, &mut checker_stats);
+
+    run_measures.checking = checking.elapsed();
+    run_measures.total = total.elapsed();
+
+    checker_stats.results.add_run_measurement(
+        &("this".to_owned(), 0),
+        RunMeasurement {
+            parsing: run_measures.parsing,
+            checking: run_measures.checking,
+            elaboration: run_measures.elaboration,
+            scheduling: run_measures.scheduling,
+            total: run_measures.total,
+            polyeq: checker_stats.polyeq_time,
+            assume: checker_stats.assume_time,
+            assume_core: checker_stats.assume_core_time,
+            elaboration_pipeline: Vec::new(),
+        },
+    );
+    *stats = checker_stats.results;
+    res
+}
 
-        res
-    } else {
-        checker.check(&problem, &proof)
-    }
+/// Checks a proof while it is being parsed, without building the whole proof in memory. Each
+/// command is discarded as soon as it can no longer be used as a premise, which is determined using
+/// `premise_uses` (see `parser::count_premise_uses`). If `premise_uses` is `None`, only the commands
+/// of subproofs that were already closed are discarded.
+pub fn check_streaming<T: io::BufRead>(
+    problem: T,
+    proof: T,
+    premise_uses: Option<HashMap<String, usize>>,
+    parser_config: parser::Config,
+    checker_config: checker::Config,
+) -> Result<bool, Error> {
+    let mut pool = ast::PrimitivePool::new();
+    let mut parser = parser::Parser::new(&mut pool, parser_config, problem)?;
+    let problem = parser.parse_problem()?;
+    parser.reset(proof)?;
+
+    let mut checker = checker::StreamingChecker::new(checker_config, premise_uses);
+    parser.parse_proof_streaming(|pool, command| checker.process(pool, &problem, command))?;
+    checker.finish()
 }
 
+#[allow(clippy::too_many_arguments)]
 pub fn check_parallel<T: io::BufRead>(
     problem: T,
     proof: T,
@@ -144,6 +234,52 @@ pub fn check_parallel<T: io::BufRead>(
     collect_stats: bool,
     num_threads: usize,
     stack_size: usize,
+    step_weights: &checker::StepWeights,
+) -> Result<bool, Error> {
+    use crate::checker::Scheduler;
+    use std::sync::Arc;
+
+    if collect_stats {
+        let mut stats = OnlineBenchmarkResults::new();
+        let res = check_parallel_with_stats(
+            problem,
+            proof,
+            parser_config,
+            checker_config,
+            num_threads,
+            stack_size,
+            step_weights,
+            &mut stats,
+        );
+        print_stats(&stats);
+        return res;
+    }
+
+    let (problem, proof, pool) = parser::parse_instance(problem, proof, parser_config)?;
+    let (scheduler, schedule_context_usage) =
+        Scheduler::with_weights(num_threads, &proof, step_weights);
+    checker::ParallelProofChecker::new(
+        Arc::new(pool),
+        checker_config,
+        &problem.prelude,
+        &schedule_context_usage,
+        stack_size,
+    )
+    .check(&problem, &proof, &scheduler)
+}
+
+/// Similar to `check_parallel`, but collects performance statistics and holes into `stats` instead
+/// of printing them.
+#[allow(clippy::too_many_arguments)]
+pub fn check_parallel_with_stats<T: io::BufRead>(
+    problem: T,
+    proof: T,
+    parser_config: parser::Config,
+    checker_config: checker::Config,
+    num_threads: usize,
+    stack_size: usize,
+    step_weights: &checker::StepWeights,
+    stats: &mut OnlineBenchmarkResults,
 ) -> Result<bool, Error> {
     use crate::checker::Scheduler;
     use std::sync::Arc;
@@ -156,7 +292,8 @@ pub fn check_parallel<T: io::BufRead>(
 
     // Checking
     let checking = Instant::now();
-    let (scheduler, schedule_context_usage) = Scheduler::new(num_threads, &proof);
+    let (scheduler, schedule_context_usage) =
+        Scheduler::with_weights(num_threads, &proof, step_weights);
     run_measures.scheduling = checking.elapsed();
     let mut checker = checker::ParallelProofChecker::new(
         Arc::new(pool),
@@ -166,51 +303,97 @@ pub fn check_parallel<T: io::BufRead>(
         stack_size,
     );
 
+    let mut checker_stats = CheckerStatistics {
+        file_name: "this",
+        polyeq_time: Duration::ZERO,
+        assume_time: Duration::ZERO,
+        assume_core_time: Duration::ZERO,
+        results: std::mem::take(stats),
+    };
+    let res = checker.check_with_stats(&problem, &proof, &scheduler, &mut checker_stats);
+
+    run_measures.checking = checking.elapsed();
+    run_measures.total = total.elapsed();
+
+    checker_stats.results.add_run_measurement(
+        &("this".to_owned(), 0),
+        RunMeasurement {
+            parsing: run_measures.parsing,
+            checking: run_measures.checking,
+            elaboration: run_measures.elaboration,
+            scheduling: run_measures.scheduling,
+            total: run_measures.total,
+            polyeq: checker_stats.polyeq_time,
+            assume: checker_stats.assume_time,
+            assume_core: checker_stats.assume_core_time,
+            elaboration_pipeline: Vec::new(),
+        },
+    );
+    *stats = checker_stats.results;
+    res
+}
+
+/// The result of checking and elaborating a proof: whether the original proof has holes, the
+/// problem, the elaborated proof, the term pool, and the steps that could not be elaborated. These
+/// steps are kept unchanged in the elaborated proof.
+pub type ElaborationResult = (
+    bool,
+    ast::Problem,
+    ast::Proof,
+    ast::PrimitivePool,
+    Vec<elaborator::ElaborationError>,
+);
+
+pub fn check_and_elaborate<T: io::BufRead, S: Into<elaborator::PipelineStep>>(
+    problem: T,
+    proof: T,
+    parser_config: parser::Config,
+    checker_config: checker::Config,
+    elaborator_config: elaborator::Config,
+    pipeline: Vec<S>,
+    collect_stats: bool,
+) -> Result<ElaborationResult, Error> {
     if collect_stats {
-        let mut checker_stats = CheckerStatistics {
-            file_name: "this",
-            polyeq_time: Duration::ZERO,
-            assume_time: Duration::ZERO,
-            assume_core_time: Duration::ZERO,
-            results: OnlineBenchmarkResults::new(),
-        };
-        let res = checker.check_with_stats(&problem, &proof, &scheduler, &mut checker_stats);
-
-        run_measures.checking = checking.elapsed();
-        run_measures.total = total.elapsed();
-
-        checker_stats.results.add_run_measurement(
-            &("this".to_owned(), 0),
-            RunMeasurement {
-                parsing: run_measures.parsing,
-                checking: run_measures.checking,
-                elaboration: run_measures.elaboration,
-                scheduling: run_measures.scheduling,
-                total: run_measures.total,
-                polyeq: checker_stats.polyeq_time,
-                assume: checker_stats.assume_time,
-                assume_core: checker_stats.assume_core_time,
-                elaboration_pipeline: Vec::new(),
-            },
+        let mut stats = OnlineBenchmarkResults::new();
+        let res = check_and_elaborate_with_stats(
+            problem,
+            proof,
+            parser_config,
+            checker_config,
+            elaborator_config,
+            pipeline,
+            &mut stats,
         );
-        // Print the statistics
-        checker_stats.results.print(false);
-
-        res
-    } else {
-        checker.check(&problem, &proof, &scheduler)
+        print_stats(&stats);
+        return res;
     }
+
+    let (problem, proof, mut pool) = parser::parse_instance(problem, proof, parser_config)?;
+    let checking_result =
+        checker::ProofChecker::new(&mut pool, checker_config).check(&problem, &proof)?;
+
+    let node = ast::ProofNode::from_commands(proof.commands);
+    let mut elaborator = elaborator::Elaborator::new(&mut pool, &problem, elaborator_config);
+    let elaborated = elaborator.elaborate(&node, pipeline);
+    let errors = elaborator.into_errors();
+    let elaborated = ast::Proof {
+        commands: elaborated.into_commands(),
+        ..proof
+    };
+    Ok((checking_result, problem, elaborated, pool, errors))
 }
 
-pub fn check_and_elaborate<T: io::BufRead>(
+/// Similar to `check_and_elaborate`, but collects performance statistics and holes into `stats`
+/// instead of printing them.
+pub fn check_and_elaborate_with_stats<T: io::BufRead, S: Into<elaborator::PipelineStep>>(
     problem: T,
     proof: T,
     parser_config: parser::Config,
     checker_config: checker::Config,
     elaborator_config: elaborator::Config,
-    pipeline: Vec<elaborator::ElaborationStep>,
-    collect_stats: bool,
-) -> Result<(bool, ast::Problem, ast::Proof, ast::PrimitivePool), Error> {
+    pipeline: Vec<S>,
+    stats: &mut OnlineBenchmarkResults,
+) -> Result<ElaborationResult, Error> {
     let mut run: RunMeasurement = RunMeasurement::default();
 
     // Parsing
@@ -218,55 +401,62 @@ pub fn check_and_elaborate<T: io::BufRead>(
     let (problem, proof, mut pool) = parser::parse_instance(problem, proof, parser_config)?;
     run.parsing = total.elapsed();
 
-    let mut stats = OnlineBenchmarkResults::new();
-
     // Checking
     let checking = Instant::now();
     let mut checker = checker::ProofChecker::new(&mut pool, checker_config);
-    let checking_result = if collect_stats {
-        let mut checker_stats = CheckerStatistics {
-            file_name: "this",
-            polyeq_time: Duration::ZERO,
-            assume_time: Duration::ZERO,
-            assume_core_time: Duration::ZERO,
-            results: std::mem::take(&mut stats),
-        };
-
-        let res = checker.check_with_stats(&problem, &proof, &mut checker_stats);
-        run.checking = checking.elapsed();
-        run.polyeq = checker_stats.polyeq_time;
-        run.assume = checker_stats.assume_time;
-        run.assume_core = checker_stats.assume_core_time;
-
-        stats = checker_stats.results;
-        res
-    } else {
-        checker.check(&problem, &proof)
-    }?;
+    let mut checker_stats = CheckerStatistics {
+        file_name: "this",
+        polyeq_time: Duration::ZERO,
+        assume_time: Duration::ZERO,
+        assume_core_time: Duration::ZERO,
+        results: std::mem::take(stats),
+    };
+
+    let checking_result = checker.check_with_stats(&problem, &proof, &mut checker_stats);
+    run.checking = checking.elapsed();
+    run.polyeq = checker_stats.polyeq_time;
+    run.assume = checker_stats.assume_time;
+    run.assume_core = checker_stats.assume_core_time;
+    *stats = checker_stats.results;
+
+    // If checking failed, we still record the time spent, so the statistics are not lost
+    let checking_result = match checking_result {
+        Ok(r) => r,
+        Err(e) => {
+            run.total = total.elapsed();
+            stats.add_run_measurement(&("this".to_owned(), 0), run);
+            return Err(e);
+        }
+    };
 
     // Elaborating
     let elaboration = Instant::now();
 
     let node = ast::ProofNode::from_commands(proof.commands);
-    let (elaborated, pipeline_durations) =
-        elaborator::Elaborator::new(&mut pool, &problem, elaborator_config)
-            .elaborate_with_stats(&node, pipeline);
+    let mut elaborator = elaborator::Elaborator::new(&mut pool, &problem, elaborator_config);
+    let (elaborated, pipeline_durations) = elaborator.elaborate_with_stats(&node, pipeline);
+    for (step, before, after) in elaborator.compressions() {
+        stats.add_compression(&format!("{:?}", step), *before, *after);
+    }
+    let errors = elaborator.into_errors();
     let elaborated = ast::Proof {
         commands: elaborated.into_commands(),
         ..proof
     };
 
-    if collect_stats {
-        run.elaboration = elaboration.elapsed();
-        run.total = total.elapsed();
-        run.elaboration_pipeline = pipeline_durations;
+    run.elaboration = elaboration.elapsed();
+    run.total = total.elapsed();
+    run.elaboration_pipeline = pipeline_durations;
+    stats.add_run_measurement(&("this".to_owned(), 0), run);
 
-        stats.add_run_measurement(&("this".to_owned(), 0), run);
+    Ok((checking_result, problem, elaborated, pool, errors))
+}
 
+fn print_stats(stats: &OnlineBenchmarkResults) {
+    // If parsing failed, no statistics were collected
+    if !stats.is_empty() {
         stats.print(false);
     }
-
-    Ok((checking_result, problem, elaborated, pool))
 }
 
 pub fn generate_lia_smt_instances<T: io::BufRead>(
@@ -69,6 +69,15 @@ pub enum ParserError {
     #[error("expected integer constant, got '{0}'")]
     ExpectedIntegerConstant(Rc<Term>),
 
+    /// Expected a datatype sort, e.g. in the matched term of a `match` term.
+    #[error("expected datatype sort, got '{0}'")]
+    ExpectedDatatypeSort(Sort),
+
+    /// A symbol that is not a constructor of the expected datatype was used as one, e.g. in a
+    /// `match` pattern or in a tester.
+    #[error("'{0}' is not a constructor of datatype '{1}'")]
+    NotAConstructor(String, String),
+
     /// A term that is not a function was used as a function.
     #[error("'{0}' is not a function sort")]
     NotAFunction(Sort), // TODO: This should also carry the actual function term
@@ -134,6 +143,80 @@ pub enum ParserError {
     InvalidQualifiedOp(String),
 }
 
+impl ParserError {
+    /// Returns a stable identifier for the kind of this error. Unlike the error message, this does
+    /// not depend on the input, so it can be used to group errors by their cause.
+    pub fn code(&self) -> &'static str {
+        match self {
+            ParserError::UnexpectedChar(_) => "parser.unexpected_char",
+            ParserError::LeadingZero(_) => "parser.leading_zero",
+            ParserError::DivisionByZeroInLiteral(_) => "parser.division_by_zero_in_literal",
+            ParserError::BackslashInQuotedSymbol => "parser.backslash_in_quoted_symbol",
+            ParserError::EofInQuotedSymbol => "parser.eof_in_quoted_symbol",
+            ParserError::EofInString => "parser.eof_in_string",
+            ParserError::InvalidUnicode(_) => "parser.invalid_unicode",
+            ParserError::EmptyBitvector => "parser.empty_bitvector",
+            ParserError::TooLargeBitvector => "parser.too_large_bitvector",
+            ParserError::UnexpectedToken(_) => "parser.unexpected_token",
+            ParserError::EmptySequence => "parser.empty_sequence",
+            ParserError::SortError(_) => "parser.sort_error",
+            ParserError::ExpectedBvSort(_) => "parser.expected_bv_sort",
+            ParserError::ExpectedIntegerConstant(_) => "parser.expected_integer_constant",
+            ParserError::ExpectedDatatypeSort(_) => "parser.expected_datatype_sort",
+            ParserError::NotAConstructor(..) => "parser.not_a_constructor",
+            ParserError::NotAFunction(_) => "parser.not_a_function",
+            ParserError::IncompatibleSorts(..) => "parser.incompatible_sorts",
+            ParserError::UndefinedIden(_) => "parser.undefined_iden",
+            ParserError::UndefinedSort(_) => "parser.undefined_sort",
+            ParserError::UndefinedStepId(_) => "parser.undefined_step_id",
+            ParserError::WrongNumberOfArgs(..) => "parser.wrong_number_of_args",
+            ParserError::WrongValueOfArgs(..) => "parser.wrong_value_of_args",
+            ParserError::InvalidExtractArgs(..) => "parser.invalid_extract_args",
+            ParserError::RepeatedStepId(_) => "parser.repeated_step_id",
+            ParserError::InvalidSortArity(_) => "parser.invalid_sort_arity",
+            ParserError::EmptySubproof(_) => "parser.empty_subproof",
+            ParserError::LastSubproofStepIsNotStep(_) => "parser.last_subproof_step_is_not_step",
+            ParserError::UnclosedSubproof(_) => "parser.unclosed_subproof",
+            ParserError::AssumeAfterStepInSubproof(_) => "parser.assume_after_step_in_subproof",
+            ParserError::InvalidIndexedOp(_) => "parser.invalid_indexed_op",
+            ParserError::InvalidQualifiedOp(_) => "parser.invalid_qualified_op",
+        }
+    }
+
+    /// Returns the sorts carried by this error. For sort errors, the expected sorts come first,
+    /// followed by the sort that was found.
+    pub fn sorts(&self) -> Vec<&Sort> {
+        match self {
+            ParserError::SortError(SortError { expected, got }) => {
+                expected.iter().chain(std::iter::once(got)).collect()
+            }
+            ParserError::ExpectedBvSort(s)
+            | ParserError::ExpectedDatatypeSort(s)
+            | ParserError::NotAFunction(s) => vec![s],
+            ParserError::IncompatibleSorts(a, b) => vec![a, b],
+            _ => Vec::new(),
+        }
+    }
+
+    /// Returns the identifier (symbol, step id or operator name) carried by this error, if any.
+    pub fn identifier(&self) -> Option<&str> {
+        match self {
+            ParserError::UndefinedIden(s)
+            | ParserError::UndefinedSort(s)
+            | ParserError::NotAConstructor(s, _)
+            | ParserError::UndefinedStepId(s)
+            | ParserError::RepeatedStepId(s)
+            | ParserError::EmptySubproof(s)
+            | ParserError::LastSubproofStepIsNotStep(s)
+            | ParserError::UnclosedSubproof(s)
+            | ParserError::AssumeAfterStepInSubproof(s)
+            | ParserError::InvalidIndexedOp(s)
+            | ParserError::InvalidQualifiedOp(s) => Some(s),
+            _ => None,
+        }
+    }
+}
+
 /// Returns an error if the length of `sequence` is not in the `expected` range.
 pub fn assert_num_args<T, R>(sequence: &[T], range: R) -> Result<(), ParserError>
 where
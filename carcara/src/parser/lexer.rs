@@ -96,6 +96,12 @@ pub enum Reserved {
     /// The `declare-sort` reserved word.
     DeclareSort,
 
+    /// The `declare-datatype` reserved word.
+    DeclareDatatype,
+
+    /// The `declare-datatypes` reserved word.
+    DeclareDatatypes,
+
     /// The `define-fun` reserved word.
     DefineFun,
 
@@ -135,6 +141,8 @@ impl_str_conversion_traits!(Reserved {
     DeclareFun: "declare-fun",
     DeclareConst: "declare-const",
     DeclareSort: "declare-sort",
+    DeclareDatatype: "declare-datatype",
+    DeclareDatatypes: "declare-datatypes",
     DefineFun: "define-fun",
     DefineFunRec: "define-fun-rec",
     DefineFunsRec: "define-funs-rec",
@@ -17,10 +17,24 @@ use crate::{
 use error::assert_num_args;
 use indexmap::{IndexMap, IndexSet};
 use rug::{Integer, Rational};
-use std::{io::BufRead, str::FromStr};
+use std::{collections::HashMap, io::BufRead, str::FromStr};
 
 use self::error::assert_indexed_op_args_value;
 
+/// A command given by `Parser::parse_proof_streaming`.
+#[derive(Debug)]
+pub enum StreamedCommand {
+    /// An `anchor` command, which opens a new subproof.
+    Anchor {
+        end_step_id: String,
+        args: Vec<AnchorArg>,
+        context_id: usize,
+    },
+
+    /// An `assume` or `step` command, in the innermost open subproof.
+    Command(ProofCommand),
+}
+
 #[derive(Debug, Clone, Copy, Default)]
 pub struct Config {
     /// If `true`, the parser will automatically expand function definitions introduced by
@@ -85,6 +99,28 @@ pub fn parse_instance_with_pool<T: BufRead>(
     Ok((problem, proof))
 }
 
+/// Counts how many times each step id is used as a premise in a proof, by scanning the `:premises`
+/// attributes of its steps. This only runs the lexer, so it is much cheaper than parsing the proof,
+/// and can be used to know when a command can no longer be referenced while streaming the proof.
+/// Note that uses in `:discharge` attributes are not counted.
+pub fn count_premise_uses<T: BufRead>(proof: T) -> CarcaraResult<HashMap<String, usize>> {
+    let mut lexer = Lexer::new(proof)?;
+    let mut result = HashMap::new();
+    let mut in_premises = false;
+    loop {
+        let (token, _) = lexer.next_token()?;
+        match token {
+            Token::Eof => return Ok(result),
+            Token::Keyword(k) if k == "premises" => {
+                lexer.next_token()?; // The opening parenthesis
+                in_premises = true;
+            }
+            Token::Symbol(id) if in_premises => *result.entry(id).or_default() += 1,
+            _ => in_premises = false,
+        }
+    }
+}
+
 /// A function definition, from a `define-fun` command.
 struct FunctionDef {
     params: Vec<SortedVar>,
@@ -136,6 +172,8 @@ struct ParserState {
     function_defs: IndexMap<String, FunctionDef>,
     sort_declarations: HashMapStack<String, usize>,
     sort_defs: IndexMap<String, SortDef>,
+    datatype_declarations: IndexMap<String, usize>,
+    datatype_params: Vec<String>,
     step_ids: HashMapStack<HashCache<String>, usize>,
 }
 
@@ -701,6 +739,8 @@ impl<'a, R: BufRead> Parser<'a, R> {
     /// - `declare-const`
     /// - `declare-fun`
     /// - `declare-sort`
+    /// - `declare-datatype`
+    /// - `declare-datatypes`
     /// - `define-fun`
     /// - `set-logic`
     ///
@@ -733,6 +773,49 @@ impl<'a, R: BufRead> Parser<'a, R> {
                     // argument which is a string terminal representing the sort name.
                     self.state.sort_declarations.insert(name, arity);
                 }
+                Token::ReservedWord(Reserved::DeclareDatatype) => {
+                    let name = self.expect_symbol()?;
+                    self.state.datatype_declarations.insert(name.clone(), 0);
+                    let def = self.parse_datatype_dec(name, 0)?;
+                    self.expect_token(Token::CloseParen)?;
+                    self.add_datatype(def);
+                }
+                Token::ReservedWord(Reserved::DeclareDatatypes) => {
+                    // Since the datatypes in a `declare-datatypes` command may be mutually
+                    // recursive, we first declare all their names, and only then parse the
+                    // constructors
+                    self.expect_token(Token::OpenParen)?;
+                    let names = self.parse_sequence(
+                        |p| {
+                            p.expect_token(Token::OpenParen)?;
+                            let name = p.expect_symbol()?;
+                            let (arity, pos) = p.next_token()?;
+                            let Token::Numeral(arity) = arity else {
+                                return Err(Error::Parser(
+                                    ParserError::UnexpectedToken(arity),
+                                    pos,
+                                ));
+                            };
+                            let arity = arity
+                                .to_usize()
+                                .ok_or(Error::Parser(ParserError::InvalidSortArity(arity), pos))?;
+                            p.expect_token(Token::CloseParen)?;
+                            p.state.datatype_declarations.insert(name.clone(), arity);
+                            Ok((name, arity))
+                        },
+                        true,
+                    )?;
+                    self.expect_token(Token::OpenParen)?;
+                    let mut defs = Vec::with_capacity(names.len());
+                    for (name, arity) in names {
+                        defs.push(self.parse_datatype_dec(name, arity)?);
+                    }
+                    self.expect_token(Token::CloseParen)?;
+                    self.expect_token(Token::CloseParen)?;
+                    for def in defs {
+                        self.add_datatype(def);
+                    }
+                }
                 Token::ReservedWord(Reserved::DefineFun) => {
                     let (name, func_def) = self.parse_define_fun()?;
 
@@ -804,15 +887,61 @@ impl<'a, R: BufRead> Parser<'a, R> {
     /// should already be in the parser state. Note that the `premises` field in the proof will not
     /// be set.
     pub fn parse_proof(&mut self) -> CarcaraResult<Proof> {
+        // Each frame of the stack stores the subproof that is being constructed and the id of the
+        // step that will end it. The first frame of the stack represents the root proof, so every
+        // field except for the subproof commands is irrelevant.
+        let mut stack: Vec<(Subproof, String)> = vec![(Subproof::default(), String::new())];
+
+        let constant_definitions = self.parse_proof_streaming(|_, command| {
+            match command {
+                StreamedCommand::Anchor { end_step_id, args, context_id } => {
+                    let subproof = Subproof {
+                        commands: Vec::new(),
+                        args,
+                        context_id,
+                    };
+                    stack.push((subproof, end_step_id));
+                }
+                StreamedCommand::Command(command) => {
+                    let (top_subproof, top_end_step) = stack.last_mut().unwrap();
+                    let is_end_step = top_end_step == command.id();
+                    top_subproof.commands.push(command);
+                    if is_end_step {
+                        let (subproof, _) = stack.pop().unwrap();
+                        let (outer, _) = stack.last_mut().unwrap();
+                        outer.commands.push(ProofCommand::Subproof(subproof));
+                    }
+                }
+            }
+            Ok(())
+        })?;
+
+        let commands = stack.pop().unwrap().0.commands;
+        Ok(Proof { constant_definitions, commands })
+    }
+
+    /// Parses a proof in the Alethe format, but instead of building the whole proof, calls
+    /// `on_command` for each command as soon as it is parsed. The commands are given in the order
+    /// they appear in the proof, and the commands inside a subproof are preceded by an
+    /// `StreamedCommand::Anchor`. The subproof is closed by the command whose id is the anchor's
+    /// end step id. This returns the constants defined in the proof using `define-fun`.
+    ///
+    /// Premise indices in the commands are computed as in `Parser::parse_proof`, with each subproof
+    /// counting as a single command in its outer subproof.
+    pub fn parse_proof_streaming<F>(
+        &mut self,
+        mut on_command: F,
+    ) -> CarcaraResult<Vec<(String, Rc<Term>)>>
+    where
+        F: FnMut(&mut PrimitivePool, StreamedCommand) -> CarcaraResult<()>,
+    {
         // To avoid stack overflows in proofs with many nested subproofs, we parse the subproofs
         // iteratively, instead of recursively. Therefore, we need to manually keep a stack.
         //
-        // Each frame of the stack stores the subproof that is being constructed, the id of the
-        // step that will end it, and a bool representing whether a `step` has been issued yet.
-        // The first frame of the stack represents the root proof, so every
-        // field except for the subproof commands is irrelevant.
-        let mut stack: Vec<(Subproof, String, bool)> =
-            vec![(Subproof::default(), String::new(), false)];
+        // Each frame of the stack stores the number of commands in the subproof that is being
+        // parsed, the id of the step that will end it, and a bool representing whether a `step`
+        // has been issued yet. The first frame of the stack represents the root proof.
+        let mut stack: Vec<(usize, String, bool)> = vec![(0, String::new(), false)];
 
         let mut next_subproof_context_id = 0;
 
@@ -880,18 +1009,17 @@ impl<'a, R: BufRead> Parser<'a, R> {
                     let (end_step_id, args) = self.parse_anchor_command()?;
 
                     // When we encounter an `anchor` command, we push a new scope into the step ids
-                    // symbol table, a fresh commands vector into the commands stack for the
-                    // subproof to fill, and the `anchor` data (end step and arguments) into their
-                    // respective stacks. All of this will be popped off at the end of the subproof.
-                    // We don't need to push a new scope into the symbol table because
-                    // `Parser::parse_anchor_command` already does that for us
+                    // symbol table, and a new frame into the stack. All of this will be popped off
+                    // at the end of the subproof. We don't need to push a new scope into the symbol
+                    // table because `Parser::parse_anchor_command` already does that for us
                     self.state.step_ids.push_scope();
-                    let subproof = Subproof {
-                        commands: Vec::new(),
+                    stack.push((0, end_step_id.clone(), false));
+                    let anchor = StreamedCommand::Anchor {
+                        end_step_id,
                         args,
                         context_id: next_subproof_context_id,
                     };
-                    stack.push((subproof, end_step_id, false));
+                    on_command(self.pool, anchor)?;
                     next_subproof_context_id += 1;
                     continue;
                 }
@@ -907,18 +1035,12 @@ impl<'a, R: BufRead> Parser<'a, R> {
                 ));
             }
 
-            let (top_subproof, top_end_step, _) = stack.last_mut().unwrap();
-            top_subproof.commands.push(command);
+            let (top_len, top_end_step, _) = stack.last_mut().unwrap();
+            *top_len += 1;
             if top_end_step == id.as_ref() {
-                // If this is the last step in a subproof, we need to pop all the subproof data off
-                // of the stacks and build the subproof command with it
-                self.state.symbol_table.pop_scope();
-                self.state.step_ids.pop_scope();
-                let (subproof, _, _) = stack.pop().unwrap();
-
                 // The subproof must contain at least two commands: the end step and the previous
                 // command it implicitly references
-                if subproof.commands.len() < 2 {
+                if *top_len < 2 {
                     return Err(Error::Parser(
                         ParserError::EmptySubproof(id.unwrap()),
                         position,
@@ -926,17 +1048,22 @@ impl<'a, R: BufRead> Parser<'a, R> {
                 }
 
                 // We also need to make sure that the last command is in fact a `step`
-                if !subproof.commands.last().unwrap().is_step() {
+                if !command.is_step() {
                     return Err(Error::Parser(
                         ParserError::LastSubproofStepIsNotStep(id.unwrap()),
                         position,
                     ));
                 }
 
-                let (outer, _, _) = stack.last_mut().unwrap();
-                outer.commands.push(ProofCommand::Subproof(subproof));
+                // If this is the last step in a subproof, we need to pop all the subproof data off
+                // of the stacks. In the outer subproof, the whole subproof counts as one command
+                self.state.symbol_table.pop_scope();
+                self.state.step_ids.pop_scope();
+                stack.pop();
+                stack.last_mut().unwrap().0 += 1;
             }
-            let index = stack.last().unwrap().0.commands.len() - 1;
+            on_command(self.pool, StreamedCommand::Command(command))?;
+            let index = stack.last().unwrap().0 - 1;
             self.state.step_ids.insert(id, index);
         }
 
@@ -945,20 +1072,15 @@ impl<'a, R: BufRead> Parser<'a, R> {
         }
         self.expect_token(Token::Eof)?;
 
-        let commands = match stack.len() {
-            0 => unreachable!(),
-            1 => stack.pop().unwrap().0.commands,
-
-            // If there is more than one layer in the stack, we are inside a subproof that should be
-            // closed before the outer proof is finished
-            _ => {
-                return Err(Error::Parser(
-                    ParserError::UnclosedSubproof(stack.pop().unwrap().1),
-                    self.current_position,
-                ))
-            }
-        };
-        Ok(Proof { constant_definitions, commands })
+        // If there is more than one layer in the stack, we are inside a subproof that should be
+        // closed before the outer proof is finished
+        if stack.len() > 1 {
+            return Err(Error::Parser(
+                ParserError::UnclosedSubproof(stack.pop().unwrap().1),
+                self.current_position,
+            ));
+        }
+        Ok(constant_definitions)
     }
 
     /// Parses an `assume` proof command. This method assumes that the `(` and `assume` tokens were
@@ -1157,6 +1279,98 @@ impl<'a, R: BufRead> Parser<'a, R> {
         Ok((name, arity))
     }
 
+    /// Parses a datatype declaration, of the form `(<constructor dec>+)` or
+    /// `(par (<symbol>+) (<constructor dec>+))`, where each constructor declaration is of the form
+    /// `(<symbol> (<symbol> <sort>)*)`. The name and arity of the datatype must have already been
+    /// declared.
+    fn parse_datatype_dec(&mut self, name: String, arity: usize) -> CarcaraResult<DatatypeDef> {
+        let pos = self.current_position;
+        self.expect_token(Token::OpenParen)?;
+        let is_parametric = self.current_token == Token::Symbol("par".to_owned());
+        let params = if is_parametric {
+            self.next_token()?;
+            self.expect_token(Token::OpenParen)?;
+            let params = self.parse_sequence(Self::expect_symbol, true)?;
+            self.expect_token(Token::OpenParen)?;
+            params
+        } else {
+            Vec::new()
+        };
+        if params.len() != arity {
+            return Err(Error::Parser(
+                ParserError::WrongNumberOfArgs(arity.into(), params.len()),
+                pos,
+            ));
+        }
+
+        // While parsing the selector sorts, the sort parameters are interpreted as sort variables
+        self.state.datatype_params = params;
+        let constructors = self.parse_sequence(
+            |p| {
+                p.expect_token(Token::OpenParen)?;
+                let name = p.expect_symbol()?;
+                let selectors = p.parse_sequence(Self::parse_sorted_var, false)?;
+                Ok(ConstructorDef { name, selectors })
+            },
+            true,
+        );
+        let params = std::mem::take(&mut self.state.datatype_params);
+        let constructors = constructors?;
+
+        if is_parametric {
+            self.expect_token(Token::CloseParen)?;
+        }
+        Ok(DatatypeDef { name, params, constructors })
+    }
+
+    /// Registers a datatype definition in the term pool and in the problem prelude, and adds its
+    /// constructors and selectors to the symbol table.
+    ///
+    /// Constructors and selectors are represented as variables with function sorts. If the datatype
+    /// is parametric, these sorts are wrapped in a `Sort::ParamSort`, so they are instantiated when
+    /// applied.
+    fn add_datatype(&mut self, def: DatatypeDef) {
+        let params: Vec<_> = def
+            .params
+            .iter()
+            .map(|p| self.pool.add(Term::Sort(Sort::Var(p.clone()))))
+            .collect();
+        let dt_sort = self
+            .pool
+            .add(Term::Sort(Sort::Datatype(def.name.clone(), params.clone())));
+        let mut make_sort = |sort: Sort| {
+            let sort = self.pool.add(Term::Sort(sort));
+            if params.is_empty() {
+                sort
+            } else {
+                self.pool
+                    .add(Term::Sort(Sort::ParamSort(params.clone(), sort)))
+            }
+        };
+
+        let mut symbols = Vec::new();
+        for c in &def.constructors {
+            let constructor_sort = if c.selectors.is_empty() {
+                make_sort(dt_sort.as_sort().unwrap().clone())
+            } else {
+                let mut sorts: Vec<_> = c.selectors.iter().map(|(_, s)| s.clone()).collect();
+                sorts.push(dt_sort.clone());
+                make_sort(Sort::Function(sorts))
+            };
+            symbols.push((c.name.clone(), constructor_sort));
+            for (selector, field_sort) in &c.selectors {
+                let sort = make_sort(Sort::Function(vec![dt_sort.clone(), field_sort.clone()]));
+                symbols.push((selector.clone(), sort));
+            }
+        }
+        for var in symbols {
+            self.insert_sorted_var(var);
+        }
+
+        self.pool.add_datatype(def.clone());
+        self.prelude().datatype_declarations.push(def);
+    }
+
     /// Parses a function declaration, of the form `(<symbol> (<sorted var>*) <sort>)`. If the
     /// parameter `consume_parens` is `false`, the opening and closing parentheses are not consumed
     fn parse_function_dec(
@@ -1433,6 +1647,101 @@ impl<'a, R: BufRead> Parser<'a, R> {
         }
     }
 
+    /// Parses a `match` term. This method assumes that the `(` and `match` tokens were already
+    /// consumed.
+    fn parse_match_term(&mut self) -> CarcaraResult<Rc<Term>> {
+        let pos = self.current_position;
+        let scrutinee = self.parse_term()?;
+        let scrutinee_sort = self.pool.sort(&scrutinee);
+        let Sort::Datatype(dt_name, sort_args) = scrutinee_sort.as_sort().unwrap() else {
+            let sort = scrutinee_sort.as_sort().unwrap().clone();
+            return Err(Error::Parser(ParserError::ExpectedDatatypeSort(sort), pos));
+        };
+        let def = self.pool.datatype(dt_name).unwrap().clone();
+
+        // If the datatype is parametric, the sorts of the selectors must be instantiated with the
+        // sort arguments of the matched term
+        let mut instantiation = {
+            let map = def
+                .params
+                .iter()
+                .zip(sort_args)
+                .map(|(p, arg)| (self.pool.add(Term::Sort(Sort::Var(p.clone()))), arg.clone()))
+                .collect();
+            Substitution::new(self.pool, map).unwrap()
+        };
+
+        self.expect_token(Token::OpenParen)?;
+        let cases = self.parse_sequence(
+            |p| {
+                p.expect_token(Token::OpenParen)?;
+                let pattern_pos = p.current_position;
+                let (constructor, bindings) = match p.next_token()?.0 {
+                    Token::Symbol(s) => match def.constructors.iter().find(|c| c.name == s) {
+                        Some(c) if c.selectors.is_empty() => {
+                            let constructor =
+                                p.make_var(s).map_err(|e| Error::Parser(e, pattern_pos))?;
+                            (Some(constructor), Vec::new())
+                        }
+                        Some(c) => {
+                            let err = ParserError::WrongNumberOfArgs(c.selectors.len().into(), 0);
+                            return Err(Error::Parser(err, pattern_pos));
+                        }
+                        // A symbol that is not a nullary constructor is a variable pattern, that
+                        // matches any value
+                        None => (None, vec![(s, scrutinee_sort.clone())]),
+                    },
+                    Token::OpenParen => {
+                        let name = p.expect_symbol()?;
+                        let Some(c) = def.constructors.iter().find(|c| c.name == name) else {
+                            let err = ParserError::NotAConstructor(name, def.name.clone());
+                            return Err(Error::Parser(err, pattern_pos));
+                        };
+                        let vars = p.parse_sequence(Self::expect_symbol, true)?;
+                        assert_num_args(&vars, c.selectors.len())
+                            .map_err(|e| Error::Parser(e, pattern_pos))?;
+                        let bindings = vars
+                            .into_iter()
+                            .zip(&c.selectors)
+                            .map(|(var, (_, sort))| (var, instantiation.apply(p.pool, sort)))
+                            .collect();
+                        let constructor = p
+                            .make_var(name)
+                            .map_err(|e| Error::Parser(e, pattern_pos))?;
+                        (Some(constructor), bindings)
+                    }
+                    other => {
+                        let err = ParserError::UnexpectedToken(other);
+                        return Err(Error::Parser(err, pattern_pos));
+                    }
+                };
+
+                p.state.symbol_table.push_scope();
+                for var in &bindings {
+                    p.insert_sorted_var(var.clone());
+                }
+                let body = p.parse_term();
+                p.state.symbol_table.pop_scope();
+                let body = body?;
+                p.expect_token(Token::CloseParen)?;
+
+                Ok(MatchCase {
+                    constructor,
+                    bindings: BindingList(bindings),
+                    body,
+                })
+            },
+            true,
+        )?;
+        self.expect_token(Token::CloseParen)?;
+
+        let sorts: Vec<_> = cases.iter().map(|c| self.pool.sort(&c.body)).collect();
+        let sorts: Vec<_> = sorts.iter().map(|s| s.as_sort().unwrap()).collect();
+        SortError::assert_all_eq(&sorts).map_err(|e| Error::Parser(e.into(), pos))?;
+
+        Ok(self.pool.add(Term::Match(scrutinee, cases)))
+    }
+
     /// Parses an annotated term, of the form `(! <term> <attribute>+)`. This method assumes that
     /// the `(` and `!` tokens were already consumed.
     ///
@@ -1510,6 +1819,18 @@ impl<'a, R: BufRead> Parser<'a, R> {
                 self.current_position,
             )
         })?;
+
+        // The argument of a tester is a constructor symbol, not an integer
+        if op == ParamOperator::DtTester {
+            let pos = self.current_position;
+            let constructor = self.expect_symbol()?;
+            let constructor = self
+                .make_var(constructor)
+                .map_err(|e| Error::Parser(e, pos))?;
+            self.expect_token(Token::CloseParen)?;
+            return Ok((op, vec![constructor]));
+        }
+
         let args = self.parse_sequence(Self::parse_term, true)?;
         let mut constant_args = Vec::new();
         for arg in args {
@@ -1538,6 +1859,68 @@ impl<'a, R: BufRead> Parser<'a, R> {
         Ok((op, sort))
     }
 
+    /// Parses a qualified identifier that is not an operator, of the form `(as <symbol> <sort>)`.
+    /// This is used to give a sort to parametric datatype constructors, like `(as nil (List Int))`.
+    /// This method assumes that the `(` and `as` tokens were already consumed.
+    fn parse_qualified_identifier(&mut self) -> CarcaraResult<Rc<Term>> {
+        let pos = self.current_position;
+        let name = self.expect_symbol()?;
+        let sort = self.parse_sort()?;
+        self.expect_token(Token::CloseParen)?;
+        self.make_qualified_identifier(name, sort)
+            .map_err(|e| Error::Parser(e, pos))
+    }
+
+    /// Constructs and sort checks a qualified identifier. If the identifier has a parametric sort,
+    /// it is instantiated so that its result sort is `sort`.
+    fn make_qualified_identifier(
+        &mut self,
+        name: String,
+        sort: Rc<Term>,
+    ) -> Result<Rc<Term>, ParserError> {
+        let var = self.make_var(name.clone()).map_err(|e| match e {
+            ParserError::UndefinedIden(name) => ParserError::InvalidQualifiedOp(name),
+            e => e,
+        })?;
+        let var_sort = self.pool.sort(&var);
+        let Sort::ParamSort(_, inner) = var_sort.as_sort().unwrap() else {
+            SortError::assert_eq(sort.as_sort().unwrap(), var_sort.as_sort().unwrap())?;
+            return Ok(var);
+        };
+        let result_sort = match inner.as_sort().unwrap() {
+            Sort::Function(sorts) => sorts.last().unwrap(),
+            _ => inner,
+        };
+        let mut map = IndexMap::new();
+        if !result_sort
+            .as_sort()
+            .unwrap()
+            .match_with(sort.as_sort().unwrap(), &mut map)
+        {
+            return Err(ParserError::IncompatibleSorts(
+                result_sort.as_sort().unwrap().clone(),
+                sort.as_sort().unwrap().clone(),
+            ));
+        }
+        let substitution = map
+            .into_iter()
+            .map(|(var_name, sort)| {
+                let var = self.pool.add(Term::Sort(Sort::Var(var_name)));
+                (var, self.pool.add(Term::Sort(sort)))
+            })
+            .collect();
+        let instantiated = Substitution::new(self.pool, substitution)
+            .unwrap()
+            .apply(self.pool, inner);
+        Ok(self.pool.add(Term::Var(name, instantiated)))
+    }
+
+    /// Returns `true` if the current token is the symbol of an operator that can be qualified with
+    /// `as`.
+    fn current_token_is_qualified_op(&self) -> bool {
+        matches!(&self.current_token, Token::Symbol(s) if ParamOperator::from_str(s).is_ok())
+    }
+
     /// Constructs, check operation arguments and sort checks an indexed operation term.
     fn make_indexed_op(
         &mut self,
@@ -1645,6 +2028,21 @@ impl<'a, R: BufRead> Parser<'a, R> {
                 SortError::assert_eq(&Sort::RegLan, sorts[0])?;
                 assert_indexed_op_args_value(&op_args, 0..)?;
             }
+            ParamOperator::DtTester => {
+                assert_num_args(&op_args, 1)?;
+                assert_num_args(&args, 1)?;
+                let Sort::Datatype(dt_name, _) = sorts[0] else {
+                    return Err(ParserError::ExpectedDatatypeSort(sorts[0].clone()));
+                };
+                let constructor = op_args[0].as_var().unwrap();
+                let def = self.pool.datatype(dt_name).unwrap();
+                if !def.constructors.iter().any(|c| c.name == constructor) {
+                    return Err(ParserError::NotAConstructor(
+                        constructor.to_owned(),
+                        dt_name.clone(),
+                    ));
+                }
+            }
             ParamOperator::ArrayConst => return Err(ParserError::InvalidIndexedOp(op.to_string())),
         }
         Ok(self.pool.add(Term::ParamOp { op, op_args, args }))
@@ -1688,6 +2086,9 @@ impl<'a, R: BufRead> Parser<'a, R> {
                         self.make_indexed_op(op, op_args, Vec::new())
                             .map_err(|err| Error::Parser(err, head_pos))
                     }
+                    Reserved::As if !self.current_token_is_qualified_op() => {
+                        self.parse_qualified_identifier()
+                    }
                     Reserved::As => {
                         let (op, sort) = self.parse_qualified_operator()?;
                         self.make_qualified_op(op, sort, Vec::new())
@@ -1699,6 +2100,7 @@ impl<'a, R: BufRead> Parser<'a, R> {
                     Reserved::Lambda => self.parse_binder(Binder::Lambda),
                     Reserved::Bang => self.parse_annotated_term(),
                     Reserved::Let => self.parse_let_term(),
+                    Reserved::Match => self.parse_match_term(),
                     Reserved::Cl => {
                         let args = self.parse_sequence(Self::parse_term, false)?;
                         self.make_op(Operator::Cl, args)
@@ -1744,6 +2146,13 @@ impl<'a, R: BufRead> Parser<'a, R> {
                     }
                     Token::ReservedWord(Reserved::As) => {
                         self.next_token()?;
+                        if !self.current_token_is_qualified_op() {
+                            let func = self.parse_qualified_identifier()?;
+                            let args = self.parse_sequence(Self::parse_term, true)?;
+                            return self
+                                .make_app(func, args)
+                                .map_err(|err| Error::Parser(err, head_pos));
+                        }
                         let (op, op_sort) = self.parse_qualified_operator()?;
                         let args = self.parse_sequence(Self::parse_term, true)?;
                         self.make_qualified_op(op, op_sort, args)
@@ -1768,6 +2177,10 @@ impl<'a, R: BufRead> Parser<'a, R> {
 
     fn make_sort(&mut self, name: String, args: Vec<Rc<Term>>) -> Result<Rc<Term>, ParserError> {
         let sort = match name.as_str() {
+            // Inside a parametric datatype declaration, the sort parameters are sort variables
+            other if args.is_empty() && self.state.datatype_params.iter().any(|p| p == other) => {
+                Ok(Sort::Var(name))
+            }
             "Bool" | "Int" | "Real" | "String" | "RegLan" if !args.is_empty() => {
                 Err(ParserError::WrongNumberOfArgs(0.into(), args.len()))
             }
@@ -1804,6 +2217,14 @@ impl<'a, R: BufRead> Parser<'a, R> {
                     Ok(result)
                 };
             }
+            other if self.state.datatype_declarations.contains_key(other) => {
+                let arity = self.state.datatype_declarations[other];
+                if arity == args.len() {
+                    Ok(Sort::Datatype(name, args))
+                } else {
+                    Err(ParserError::WrongNumberOfArgs(arity.into(), args.len()))
+                }
+            }
             _ => match self.state.sort_declarations.get(&name) {
                 Some(arity) if *arity == args.len() => Ok(Sort::Atom(name, args)),
                 Some(arity) => Err(ParserError::WrongNumberOfArgs((*arity).into(), args.len())),
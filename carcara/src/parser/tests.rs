@@ -112,6 +112,45 @@ fn test_hash_consing() {
     }
 }
 
+#[test]
+fn test_collect_garbage() {
+    let mut pool = PrimitivePool::new();
+    let kept = parse_term(&mut pool, "(+ 1 2)");
+    let discarded = parse_term(&mut pool, "(- (* 3 4) 5)");
+    let discarded_sort = parse_term(&mut pool, "(/ 1.0 2.0)");
+    drop((discarded, discarded_sort));
+
+    // `(- (* 3 4) 5)`, `(* 3 4)`, `3`, `4`, `5`, `(/ 1.0 2.0)`, `1.0`, `2.0` and `Real`
+    assert_eq!(pool.collect_garbage(), 9);
+    assert_eq!(pool.collect_garbage(), 0);
+
+    // Terms that are still referenced are kept, so hash consing still works for them
+    assert_eq!(parse_term(&mut pool, "(+ 1 2)"), kept);
+    let expected = ["Int", "1", "2", "(+ 1 2)"];
+    let pool_terms = pool.storage.into_vec();
+    assert_eq!(pool_terms.len(), expected.len());
+    for got in pool_terms {
+        assert!(expected.contains(&format!("{:#}", got).as_str()), "{}", got);
+    }
+}
+
+#[test]
+fn test_count_premise_uses() {
+    let proof = "(assume h1 (! p :named @p))
+        (step t1 (cl p) :rule hole :premises (h1))
+        (anchor :step t2)
+        (assume t2.h1 q)
+        (step t2.t1 (cl p) :rule hole :premises (t1 t2.h1) :args (1 2))
+        (step t2 (cl (not q) p) :rule subproof :discharge (t2.h1))
+        (step t3 (cl) :rule resolution :premises (t2 t1 t1))";
+    let uses = count_premise_uses(proof.as_bytes()).unwrap();
+    let expected = [("h1", 1), ("t1", 3), ("t2.h1", 1), ("t2", 1)];
+    assert_eq!(uses.len(), expected.len());
+    for (id, n) in expected {
+        assert_eq!(uses[id], n);
+    }
+}
+
 #[test]
 fn test_constant_terms() {
     let mut p = PrimitivePool::new();
@@ -812,6 +851,152 @@ fn test_qualified_operators() {
     ));
 }
 
+#[test]
+fn test_declare_datatypes() {
+    const DEFINITIONS: &str = "
+        (declare-datatype Color ((red) (green) (blue)))
+        (declare-datatypes ((Tree 0) (Forest 0)) (
+            ((leaf (val Int)) (node (children Forest)))
+            ((nil) (cons (head Tree) (tail Forest)))
+        ))
+        (declare-datatypes ((List 1)) ((par (T) ((empty) (ins (hd T) (tl (List T)))))))
+        (declare-const c Color)
+        (declare-const t Tree)
+        (declare-const l (List Int))
+    ";
+    let mut p = PrimitivePool::new();
+    let [color, cons, node_val, tester, is_empty, qualified, param_ins, param_hd] = parse_terms(
+        &mut p,
+        DEFINITIONS,
+        [
+            "red",
+            "(cons t nil)",
+            "(val (leaf 1))",
+            "((_ is node) t)",
+            "((_ is empty) l)",
+            "(as empty (List Int))",
+            "(ins 1 l)",
+            "(hd l)",
+        ],
+    );
+
+    let color_sort = p.add(Term::Sort(Sort::Datatype("Color".into(), Vec::new())));
+    let forest_sort = p.add(Term::Sort(Sort::Datatype("Forest".into(), Vec::new())));
+    let int_list_sort = {
+        let int = p.add(Term::Sort(Sort::Int));
+        p.add(Term::Sort(Sort::Datatype("List".into(), vec![int])))
+    };
+    assert_eq!(p.add(Term::new_var("red", color_sort.clone())), color);
+    assert_eq!(color_sort, p.sort(&color));
+    assert_eq!(forest_sort, p.sort(&cons));
+    assert_eq!(Sort::Int, *p.sort(&node_val).as_sort().unwrap());
+    assert_eq!(Sort::Bool, *p.sort(&tester).as_sort().unwrap());
+    assert_eq!(Sort::Bool, *p.sort(&is_empty).as_sort().unwrap());
+    assert_eq!(int_list_sort, p.sort(&qualified));
+    assert_eq!(int_list_sort, p.sort(&param_ins));
+    assert_eq!(Sort::Int, *p.sort(&param_hd).as_sort().unwrap());
+
+    let def = p.datatype("Tree").unwrap();
+    assert_eq!(
+        vec!["leaf", "node"],
+        def.constructors.iter().map(|c| &c.name).collect::<Vec<_>>()
+    );
+    assert_eq!(vec!["T".to_owned()], p.datatype("List").unwrap().params);
+
+    let mut parser = Parser::new(&mut p, TEST_CONFIG, DEFINITIONS.as_bytes()).unwrap();
+    let problem = parser.parse_problem().unwrap();
+    assert!(problem.prelude.to_string().contains(
+        "(declare-datatypes ((Color 0) (Tree 0) (Forest 0) (List 1)) (\
+        ((red) (green) (blue)) \
+        ((leaf (val Int)) (node (children Forest))) \
+        ((nil) (cons (head Tree) (tail Forest))) \
+        (par (T) ((empty) (ins (hd T) (tl (List T)))))))\n"
+    ));
+    for (input, error_code) in [
+        ("(cons 1 nil)", "parser.sort_error"),
+        ("((_ is red) t)", "parser.not_a_constructor"),
+        ("((_ is node) 1)", "parser.expected_datatype_sort"),
+        ("(as empty (List Bool Int))", "parser.wrong_number_of_args"),
+    ] {
+        parser.reset(input.as_bytes()).unwrap();
+        match parser.parse_term() {
+            Err(Error::Parser(e, _)) => assert_eq!(error_code, e.code(), "{}", input),
+            other => panic!("expected parser error for '{}', got {:?}", input, other),
+        }
+    }
+}
+
+#[test]
+fn test_match_terms() {
+    const DEFINITIONS: &str = "
+        (declare-datatypes ((List 1)) ((par (T) ((empty) (ins (hd T) (tl (List T)))))))
+        (declare-const l (List Int))
+    ";
+    let mut p = PrimitivePool::new();
+    let [got, with_default] = parse_terms(
+        &mut p,
+        DEFINITIONS,
+        [
+            "(match l ((empty 0) ((ins x xs) (+ x 1))))",
+            "(match l (((ins x xs) x) (other 0)))",
+        ],
+    );
+    let Term::Match(scrutinee, cases) = got.as_ref() else {
+        panic!("expected match term, got '{}'", got);
+    };
+    let [int, int_list] = ["Int", "(List Int)"].map(|s| {
+        let mut parser = Parser::new(&mut p, TEST_CONFIG, DEFINITIONS.as_bytes()).unwrap();
+        parser.parse_problem().unwrap();
+        parser.reset(s.as_bytes()).unwrap();
+        parser.parse_sort().unwrap()
+    });
+    assert_eq!(p.add(Term::new_var("l", int_list.clone())), *scrutinee);
+    assert_eq!(2, cases.len());
+    assert!(cases[0].bindings.is_empty());
+    assert_eq!(
+        BindingList(vec![
+            ("x".into(), int.clone()),
+            ("xs".into(), int_list.clone())
+        ]),
+        cases[1].bindings,
+    );
+    assert_eq!(int, p.sort(&got));
+    assert!(p.free_vars(&got).iter().all(|v| v.as_var() == Some("l")));
+
+    let Term::Match(_, cases) = with_default.as_ref() else {
+        panic!("expected match term, got '{}'", with_default);
+    };
+    assert_eq!(None, cases[1].constructor);
+    assert_eq!(
+        BindingList(vec![("other".into(), int_list)]),
+        cases[1].bindings
+    );
+
+    // Printing a match term and parsing it back should give the same term
+    for term in [got, with_default] {
+        let [reparsed] = parse_terms(&mut p, DEFINITIONS, [&term.to_string()]);
+        assert_eq!(term, reparsed);
+    }
+
+    let mut parser = Parser::new(&mut p, TEST_CONFIG, DEFINITIONS.as_bytes()).unwrap();
+    parser.parse_problem().unwrap();
+    for (input, error_code) in [
+        ("(match 1 ((x x)))", "parser.expected_datatype_sort"),
+        (
+            "(match l ((empty 0) ((ins x xs) false)))",
+            "parser.sort_error",
+        ),
+        ("(match l (((cons x xs) 0)))", "parser.not_a_constructor"),
+        ("(match l (((ins x) 0)))", "parser.wrong_number_of_args"),
+    ] {
+        parser.reset(input.as_bytes()).unwrap();
+        match parser.parse_term() {
+            Err(Error::Parser(e, _)) => assert_eq!(error_code, e.code(), "{}", input),
+            other => panic!("expected parser error for '{}', got {:?}", input, other),
+        }
+    }
+}
+
 #[test]
 fn test_proofs_with_extra_parens() {
     let mut p = PrimitivePool::new();
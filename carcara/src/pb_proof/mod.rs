@@ -0,0 +1,569 @@
+//! Checking of `VeriPB` proofs of unsatisfiability of pseudo-Boolean instances in the OPB format.
+//!
+//! Constraints are kept in a normalized form, in which every coefficient is positive and each
+//! variable appears at most once. The operations of `pol` rules are computed in this form, and each
+//! of them is then checked again as an Alethe step of the corresponding cutting planes rule (like
+//! `cp_addition` or `cp_division`). In these steps, each variable is an integer variable, and a
+//! negated literal `~x` is written as `(- 1 x)`. Constraints derived by `rup` and `red` rules are
+//! checked using reverse unit propagation.
+
+mod parser;
+#[cfg(test)]
+mod tests;
+
+use crate::{
+    ast::*,
+    checker::{check_isolated_step, error::CheckerError},
+};
+use parser::{LinearConstraint, Literal, PolToken, ProofLine, Relation, WitnessValue};
+use rug::Integer;
+use std::{
+    collections::{BTreeMap, HashMap},
+    io, iter,
+};
+use thiserror::Error;
+
+#[derive(Debug, Error)]
+pub enum PbProofError {
+    #[error("IO error: {0}")]
+    Io(#[from] io::Error),
+
+    #[error("invalid token on line {0}: '{1}'")]
+    InvalidToken(usize, String),
+
+    #[error("constraint starting on line {0} is not terminated by ';'")]
+    UnterminatedConstraint(usize),
+
+    #[error("non-linear term on line {0}: only linear constraints are supported")]
+    NonLinearTerm(usize),
+
+    #[error("missing argument on line {0}")]
+    MissingArgument(usize),
+
+    #[error("unsupported rule on line {0}: '{1}'")]
+    UnsupportedRule(usize, String),
+
+    #[error("proof expects {0} constraints in the formula, but {1} were found")]
+    WrongNumberOfConstraints(usize, usize),
+
+    #[error("unknown constraint id on line {0}: {1}")]
+    UnknownConstraintId(usize, Integer),
+
+    #[error("invalid reverse Polish notation on line {0}")]
+    InvalidPolishNotation(usize),
+
+    #[error("invalid scalar on line {0}: {1} is not positive")]
+    InvalidScalar(usize, Integer),
+
+    #[error("cannot saturate constraint on line {0}: its degree is not positive")]
+    CannotSaturate(usize),
+
+    #[error("constraint on line {0} does not follow by reverse unit propagation")]
+    RupFailed(usize),
+
+    #[error("constraint on line {0} is not redundant with the given witness")]
+    RedundanceFailed(usize),
+
+    #[error("constraint {1} is not equal to the one given on line {0}")]
+    NotEqual(usize, Integer),
+
+    #[error("constraint {1} on line {0} is not a contradiction")]
+    NotContradiction(usize, Integer),
+
+    #[error("no contradiction can be derived on line {0}")]
+    NoContradiction(usize),
+
+    #[error("step on line {line} failed to check with rule '{rule}': {inner}")]
+    Rule {
+        line: usize,
+        rule: &'static str,
+        inner: CheckerError,
+    },
+}
+
+impl PbProofError {
+    /// Returns a stable identifier for the kind of this error. For steps that fail to check with
+    /// one of the cutting planes rules, this is the code of the underlying checker error.
+    pub fn code(&self) -> &'static str {
+        match self {
+            PbProofError::Io(_) => "io",
+            PbProofError::InvalidToken(_, _) => "pb_proof.invalid_token",
+            PbProofError::UnterminatedConstraint(_) => "pb_proof.unterminated_constraint",
+            PbProofError::NonLinearTerm(_) => "pb_proof.non_linear_term",
+            PbProofError::MissingArgument(_) => "pb_proof.missing_argument",
+            PbProofError::UnsupportedRule(_, _) => "pb_proof.unsupported_rule",
+            PbProofError::WrongNumberOfConstraints(_, _) => "pb_proof.wrong_number_of_constraints",
+            PbProofError::UnknownConstraintId(_, _) => "pb_proof.unknown_constraint_id",
+            PbProofError::InvalidPolishNotation(_) => "pb_proof.invalid_polish_notation",
+            PbProofError::InvalidScalar(_, _) => "pb_proof.invalid_scalar",
+            PbProofError::CannotSaturate(_) => "pb_proof.cannot_saturate",
+            PbProofError::RupFailed(_) => "pb_proof.rup_failed",
+            PbProofError::RedundanceFailed(_) => "pb_proof.redundance_failed",
+            PbProofError::NotEqual(_, _) => "pb_proof.not_equal",
+            PbProofError::NotContradiction(_, _) => "pb_proof.not_contradiction",
+            PbProofError::NoContradiction(_) => "pb_proof.no_contradiction",
+            PbProofError::Rule { inner, .. } => inner.code(),
+        }
+    }
+}
+
+/// A pseudo-Boolean constraint in normalized form: a sum of literals with positive coefficients,
+/// which must be greater than or equal to the degree.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Constraint {
+    /// Maps each variable to its coefficient, and whether it appears negated.
+    terms: BTreeMap<String, (Integer, bool)>,
+    degree: Integer,
+}
+
+impl Constraint {
+    /// Normalizes the constraint `sum >= degree`. Using the fact that `~x = 1 - x`, opposite
+    /// literals are cancelled out, and negative coefficients are made positive by negating the
+    /// literal.
+    fn normalize(sum: impl IntoIterator<Item = (Integer, Literal)>, mut degree: Integer) -> Self {
+        let mut coefficients: BTreeMap<String, Integer> = BTreeMap::new();
+        for (c, lit) in sum {
+            let entry = coefficients.entry(lit.var).or_default();
+            if lit.negated {
+                degree -= &c;
+                *entry -= c;
+            } else {
+                *entry += c;
+            }
+        }
+        let terms = coefficients
+            .into_iter()
+            .filter(|(_, c)| *c != 0)
+            .map(|(var, c)| {
+                if c < 0 {
+                    degree -= &c;
+                    (var, (-c, true))
+                } else {
+                    (var, (c, false))
+                }
+            })
+            .collect();
+        Self { terms, degree }
+    }
+
+    /// Converts a constraint read from a file into normalized `>=` constraints. An equality is
+    /// converted into two constraints.
+    fn from_linear(constraint: &LinearConstraint) -> Vec<Self> {
+        let at_least = || Self::normalize(constraint.terms.clone(), constraint.degree.clone());
+        let at_most = || {
+            let negated = constraint
+                .terms
+                .iter()
+                .map(|(c, l)| (-c.clone(), l.clone()));
+            Self::normalize(negated, -constraint.degree.clone())
+        };
+        match constraint.relation {
+            Relation::GreaterEq => vec![at_least()],
+            Relation::LessEq => vec![at_most()],
+            Relation::Equal => vec![at_least(), at_most()],
+        }
+    }
+
+    fn literals(&self) -> impl Iterator<Item = (Integer, Literal)> + '_ {
+        self.terms
+            .iter()
+            .map(|(var, (c, negated))| (c.clone(), Literal { var: var.clone(), negated: *negated }))
+    }
+
+    fn map_coefficients(&self, f: impl Fn(&Integer) -> Integer, degree: Integer) -> Self {
+        let terms = self
+            .terms
+            .iter()
+            .map(|(var, (c, negated))| (var.clone(), (f(c), *negated)))
+            .collect();
+        Self { terms, degree }
+    }
+
+    /// The negation of `sum >= k`, that is, `-sum >= 1 - k`.
+    fn negation(&self) -> Self {
+        let negated = self.literals().map(|(c, l)| (-c, l));
+        Self::normalize(negated, 1 - self.degree.clone())
+    }
+
+    fn add(&self, other: &Self) -> Self {
+        let degree = self.degree.clone() + &other.degree;
+        Self::normalize(self.literals().chain(other.literals()), degree)
+    }
+
+    fn multiply(&self, scalar: &Integer) -> Self {
+        self.map_coefficients(|c| c.clone() * scalar, self.degree.clone() * scalar)
+    }
+
+    /// Divides the constraint by `divisor`, rounding up. This uses the same formula as the
+    /// `cp_division` rule.
+    fn divide(&self, divisor: &Integer) -> Self {
+        let div_ceil = |n: &Integer| (n.clone() + divisor - 1u32) / divisor;
+        self.map_coefficients(div_ceil, div_ceil(&self.degree))
+    }
+
+    fn saturate(&self) -> Self {
+        let degree = &self.degree;
+        self.map_coefficients(|c| Ord::min(c, degree).clone(), degree.clone())
+    }
+
+    /// Returns the slack of the constraint under a partial assignment. This is the sum of the
+    /// coefficients of the literals that are not falsified, minus the degree.
+    fn slack(&self, assignment: &HashMap<&str, bool>) -> Integer {
+        let not_falsified = self
+            .terms
+            .iter()
+            .filter(|(var, (_, negated))| assignment.get(var.as_str()) != Some(negated))
+            .map(|(_, (c, _))| c);
+        Integer::from(Integer::sum(not_falsified)) - &self.degree
+    }
+
+    fn is_contradiction(&self) -> bool {
+        self.slack(&HashMap::new()) < 0
+    }
+
+    /// Applies the substitution given by the witness of a `red` rule.
+    fn substitute(&self, witness: &HashMap<&str, &WitnessValue>) -> Self {
+        let mut degree = self.degree.clone();
+        let mut sum = Vec::new();
+        for (c, lit) in self.literals() {
+            match witness.get(lit.var.as_str()) {
+                None => sum.push((c, lit)),
+                Some(WitnessValue::Constant(value)) => {
+                    if *value != lit.negated {
+                        degree -= c;
+                    }
+                }
+                Some(WitnessValue::Literal(other)) => {
+                    let negated = other.negated != lit.negated;
+                    sum.push((c, Literal { var: other.var.clone(), negated }));
+                }
+            }
+        }
+        Self::normalize(sum, degree)
+    }
+
+    fn to_term(&self, pool: &mut dyn TermPool) -> Rc<Term> {
+        let mut sum: Vec<_> = self
+            .literals()
+            .map(|(c, lit)| {
+                let c = pool.add(Term::new_int(c));
+                let lit = literal_to_term(pool, &lit);
+                build_term!(pool, (* {c} {lit}))
+            })
+            .collect();
+        let sum = match sum.len() {
+            0 => pool.add(Term::new_int(0)),
+            1 => sum.pop().unwrap(),
+            _ => build_term!(pool, (+[sum])),
+        };
+        let degree = if self.degree < 0 {
+            let abs = pool.add(Term::new_int(self.degree.clone().abs()));
+            build_term!(pool, (-{ abs }))
+        } else {
+            pool.add(Term::new_int(self.degree.clone()))
+        };
+        build_term!(pool, (>= {sum} {degree}))
+    }
+}
+
+fn literal_to_term(pool: &mut dyn TermPool, lit: &Literal) -> Rc<Term> {
+    let int_sort = pool.add(Term::Sort(Sort::Int));
+    let var = pool.add(Term::new_var(lit.var.clone(), int_sort));
+    if lit.negated {
+        build_term!(pool, (- 1 {var}))
+    } else {
+        var
+    }
+}
+
+/// Returns `true` if unit propagation on the given constraints leads to a conflict. A literal is
+/// propagated when its coefficient is larger than the slack of the constraint.
+fn propagates_to_conflict<'a>(constraints: impl Iterator<Item = &'a Constraint> + Clone) -> bool {
+    let mut assignment: HashMap<&str, bool> = HashMap::new();
+    loop {
+        let mut changed = false;
+        for constraint in constraints.clone() {
+            let slack = constraint.slack(&assignment);
+            if slack < 0 {
+                return true;
+            }
+            for (var, (c, negated)) in &constraint.terms {
+                if *c > slack && !assignment.contains_key(var.as_str()) {
+                    assignment.insert(var, !negated);
+                    changed = true;
+                }
+            }
+        }
+        if !changed {
+            return false;
+        }
+    }
+}
+
+/// A proof that was successfully checked.
+#[derive(Debug)]
+pub struct CheckedPbProof {
+    /// Whether the proof derives a contradiction, showing that the instance is unsatisfiable. If
+    /// this is `false`, every rule in the proof is correct, but they don't prove anything about the
+    /// instance.
+    pub derived_contradiction: bool,
+}
+
+/// Checks a `VeriPB` proof of the pseudo-Boolean instance given in `opb`. The constraints of the
+/// instance are numbered starting from 1, and each constraint derived by the proof receives the
+/// next id.
+pub fn check_pb_proof<R: io::BufRead, S: io::BufRead>(
+    mut opb: R,
+    mut proof: S,
+) -> Result<CheckedPbProof, PbProofError> {
+    let mut text = String::new();
+    opb.read_to_string(&mut text)?;
+    let opb = parser::parse_opb(&text)?;
+
+    text.clear();
+    proof.read_to_string(&mut text)?;
+    let lines = parser::parse_veripb(&text)?;
+
+    let mut checker = PbChecker {
+        pool: PrimitivePool::new(),
+        constraints: BTreeMap::new(),
+        next_id: 1,
+        derived_contradiction: false,
+    };
+    for constraint in &opb.constraints {
+        for c in Constraint::from_linear(constraint) {
+            checker.add_constraint(c);
+        }
+    }
+    let num_formula_constraints = checker.constraints.len();
+    for (line, proof_line) in &lines {
+        if let ProofLine::Formula(Some(n)) = proof_line {
+            if *n != num_formula_constraints {
+                return Err(PbProofError::WrongNumberOfConstraints(
+                    *n,
+                    num_formula_constraints,
+                ));
+            }
+        }
+        checker.check_line(*line, proof_line)?;
+    }
+    Ok(CheckedPbProof {
+        derived_contradiction: checker.derived_contradiction,
+    })
+}
+
+/// An element of the stack used to evaluate the reverse Polish notation of `pol` rules.
+enum StackItem {
+    Number(Integer),
+    Constraint(Constraint),
+}
+
+struct PbChecker {
+    pool: PrimitivePool,
+    constraints: BTreeMap<usize, Constraint>,
+    next_id: usize,
+    derived_contradiction: bool,
+}
+
+impl PbChecker {
+    fn add_constraint(&mut self, constraint: Constraint) {
+        if constraint.is_contradiction() {
+            self.derived_contradiction = true;
+        }
+        self.constraints.insert(self.next_id, constraint);
+        self.next_id += 1;
+    }
+
+    /// Resolves a constraint id. Negative ids are relative to the next id, so `-1` refers to the
+    /// last derived constraint.
+    fn resolve_id(&self, line: usize, id: &Integer) -> Result<usize, PbProofError> {
+        let absolute = if *id < 0 {
+            Integer::from(self.next_id) + id
+        } else {
+            id.clone()
+        };
+        absolute
+            .to_usize()
+            .filter(|i| self.constraints.contains_key(i))
+            .ok_or_else(|| PbProofError::UnknownConstraintId(line, id.clone()))
+    }
+
+    fn get(&self, line: usize, id: &Integer) -> Result<&Constraint, PbProofError> {
+        let id = self.resolve_id(line, id)?;
+        Ok(&self.constraints[&id])
+    }
+
+    fn implies_by_rup(&self, constraint: &Constraint, extra: Option<&Constraint>) -> bool {
+        let negation = constraint.negation();
+        let all = self
+            .constraints
+            .values()
+            .chain(extra)
+            .chain(iter::once(&negation));
+        propagates_to_conflict(all)
+    }
+
+    /// Checks a `pol` operation by building the corresponding Alethe step and checking it with the
+    /// given cutting planes rule.
+    fn check_rule(
+        &mut self,
+        line: usize,
+        rule: &'static str,
+        premises: &[&Constraint],
+        args: &[Rc<Term>],
+        conclusion: &Constraint,
+    ) -> Result<(), PbProofError> {
+        let pool = &mut self.pool;
+        let premises: Vec<_> = premises.iter().map(|p| [p.to_term(pool)]).collect();
+        let premises: Vec<_> = premises.iter().map(|p| ("", p.as_slice())).collect();
+        let conclusion = [conclusion.to_term(pool)];
+        check_isolated_step(pool, rule, &premises, args, &conclusion)
+            .map_err(|inner| PbProofError::Rule { line, rule, inner })
+    }
+
+    fn pop_constraint(
+        &self,
+        line: usize,
+        stack: &mut Vec<StackItem>,
+    ) -> Result<Constraint, PbProofError> {
+        match stack.pop() {
+            Some(StackItem::Constraint(c)) => Ok(c),
+            Some(StackItem::Number(id)) => self.get(line, &id).cloned(),
+            None => Err(PbProofError::InvalidPolishNotation(line)),
+        }
+    }
+
+    fn pop_scalar(line: usize, stack: &mut Vec<StackItem>) -> Result<Integer, PbProofError> {
+        match stack.pop() {
+            Some(StackItem::Number(n)) if n > 0 => Ok(n),
+            Some(StackItem::Number(n)) => Err(PbProofError::InvalidScalar(line, n)),
+            _ => Err(PbProofError::InvalidPolishNotation(line)),
+        }
+    }
+
+    fn check_pol(&mut self, line: usize, tokens: &[PolToken]) -> Result<Constraint, PbProofError> {
+        let mut stack = Vec::new();
+        for token in tokens {
+            let result = match token {
+                PolToken::Number(n) => StackItem::Number(n.clone()),
+                PolToken::Literal(lit) => {
+                    let result = Constraint::normalize([(Integer::from(1), lit.clone())], 0.into());
+                    let arg = literal_to_term(&mut self.pool, lit);
+                    self.check_rule(line, "cp_literal", &[], &[arg], &result)?;
+                    StackItem::Constraint(result)
+                }
+                PolToken::Add => {
+                    let b = self.pop_constraint(line, &mut stack)?;
+                    let a = self.pop_constraint(line, &mut stack)?;
+                    let result = a.add(&b);
+                    self.check_rule(line, "cp_addition", &[&a, &b], &[], &result)?;
+                    StackItem::Constraint(result)
+                }
+                PolToken::Multiply | PolToken::Divide => {
+                    let scalar = Self::pop_scalar(line, &mut stack)?;
+                    let a = self.pop_constraint(line, &mut stack)?;
+                    let (rule, result) = if *token == PolToken::Multiply {
+                        ("cp_multiplication", a.multiply(&scalar))
+                    } else {
+                        ("cp_division", a.divide(&scalar))
+                    };
+                    let arg = self.pool.add(Term::new_int(scalar));
+                    self.check_rule(line, rule, &[&a], &[arg], &result)?;
+                    StackItem::Constraint(result)
+                }
+                PolToken::Saturate => {
+                    let a = self.pop_constraint(line, &mut stack)?;
+                    if a.degree <= 0 {
+                        return Err(PbProofError::CannotSaturate(line));
+                    }
+                    let result = a.saturate();
+                    self.check_rule(line, "cp_saturation", &[&a], &[], &result)?;
+                    StackItem::Constraint(result)
+                }
+            };
+            stack.push(result);
+        }
+        let result = self.pop_constraint(line, &mut stack)?;
+        if !stack.is_empty() {
+            return Err(PbProofError::InvalidPolishNotation(line));
+        }
+        Ok(result)
+    }
+
+    /// Checks that `constraint` is redundant with respect to the database, using the given witness.
+    /// For that, `constraint` itself, and every constraint in the database that is affected by the
+    /// substitution, must follow by reverse unit propagation from the database and the negation of
+    /// `constraint`, after applying the substitution.
+    fn check_red(&self, constraint: &Constraint, witness: &[(String, WitnessValue)]) -> bool {
+        let witness: HashMap<&str, &WitnessValue> = witness
+            .iter()
+            .map(|(var, value)| (var.as_str(), value))
+            .collect();
+        let negation = constraint.negation();
+
+        // The constraint itself must always be checked, even if the witness doesn't affect it,
+        // otherwise an empty witness would allow deriving any constraint
+        let affected = self
+            .constraints
+            .values()
+            .filter(|d| d.terms.keys().any(|var| witness.contains_key(var.as_str())));
+        iter::once(constraint)
+            .chain(affected)
+            .all(|d| self.implies_by_rup(&d.substitute(&witness), Some(&negation)))
+    }
+
+    fn check_line(&mut self, line: usize, proof_line: &ProofLine) -> Result<(), PbProofError> {
+        match proof_line {
+            ProofLine::Formula(_) => (),
+            ProofLine::Pol(tokens) => {
+                let result = self.check_pol(line, tokens)?;
+                self.add_constraint(result);
+            }
+            ProofLine::Rup(constraint) => {
+                for c in Constraint::from_linear(constraint) {
+                    if !self.implies_by_rup(&c, None) {
+                        return Err(PbProofError::RupFailed(line));
+                    }
+                    self.add_constraint(c);
+                }
+            }
+            ProofLine::Red(constraint, witness) => {
+                for c in Constraint::from_linear(constraint) {
+                    if !self.check_red(&c, witness) {
+                        return Err(PbProofError::RedundanceFailed(line));
+                    }
+                    self.add_constraint(c);
+                }
+            }
+            ProofLine::Delete(ids) => {
+                for id in ids {
+                    let id = self.resolve_id(line, id)?;
+                    self.constraints.remove(&id);
+                }
+            }
+            ProofLine::Equal(id, constraint) => {
+                // An equality is stored as two consecutive constraints, so each of them must match
+                let first = self.resolve_id(line, id)?;
+                let expected = Constraint::from_linear(constraint);
+                for (i, c) in expected.iter().enumerate() {
+                    if self.constraints.get(&(first + i)) != Some(c) {
+                        return Err(PbProofError::NotEqual(line, id.clone()));
+                    }
+                }
+            }
+            ProofLine::Contradiction(Some(id)) => {
+                if !self.get(line, id)?.is_contradiction() {
+                    return Err(PbProofError::NotContradiction(line, id.clone()));
+                }
+                self.derived_contradiction = true;
+            }
+            ProofLine::Contradiction(None) => {
+                if !propagates_to_conflict(self.constraints.values()) {
+                    return Err(PbProofError::NoContradiction(line));
+                }
+                self.derived_contradiction = true;
+            }
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,319 @@
+//! Parsers for OPB pseudo-Boolean instances and for `VeriPB` proofs.
+
+use super::PbProofError;
+use rug::Integer;
+use std::iter::Peekable;
+
+/// A literal of a pseudo-Boolean constraint, that is, a variable or its negation.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Literal {
+    pub var: String,
+    pub negated: bool,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Relation {
+    GreaterEq,
+    LessEq,
+    Equal,
+}
+
+/// A linear pseudo-Boolean constraint, as it is written in the file. Coefficients may be negative,
+/// and the same variable may appear more than once.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LinearConstraint {
+    pub terms: Vec<(Integer, Literal)>,
+    pub relation: Relation,
+    pub degree: Integer,
+}
+
+/// A pseudo-Boolean instance read from an OPB file. The objective function, if any, is ignored.
+#[derive(Debug, Default)]
+pub struct Opb {
+    pub constraints: Vec<LinearConstraint>,
+}
+
+/// The value a variable is mapped to by the witness of a `red` rule.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum WitnessValue {
+    Constant(bool),
+    Literal(Literal),
+}
+
+/// An operation in the reverse Polish notation of a `pol` rule.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PolToken {
+    /// Either a constraint id, or the scalar argument of a multiplication or division.
+    Number(Integer),
+
+    /// The literal axiom `l >= 0`.
+    Literal(Literal),
+    Add,
+    Multiply,
+    Divide,
+    Saturate,
+}
+
+/// A rule of a `VeriPB` proof. Constraint ids may be negative, in which case they are relative to the
+/// last derived constraint.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ProofLine {
+    /// `f n`: the formula has `n` constraints.
+    Formula(Option<usize>),
+
+    /// `pol ...`: derives a constraint by applying cutting planes operations.
+    Pol(Vec<PolToken>),
+
+    /// `rup C`: derives `C` by reverse unit propagation.
+    Rup(LinearConstraint),
+
+    /// `red C ; w`: derives `C` by redundance-based strengthening, using the witness `w`.
+    Red(LinearConstraint, Vec<(String, WitnessValue)>),
+
+    /// `del id ...`: deletes constraints from the database.
+    Delete(Vec<Integer>),
+
+    /// `e id C`: checks that the constraint with the given id is `C`.
+    Equal(Integer, LinearConstraint),
+
+    /// `c id`, or `conclusion UNSAT : id`: claims that a constraint is a contradiction. If no id is
+    /// given, some constraint in the database must be a contradiction.
+    Contradiction(Option<Integer>),
+}
+
+/// Iterates over the whitespace-separated tokens of a line. The terminating `;` of constraints is
+/// always its own token, even if it is written right after the degree.
+fn line_tokens(line: &str) -> impl Iterator<Item = &str> {
+    line.split_whitespace()
+        .flat_map(|t| match t.strip_suffix(';') {
+            Some("") => vec![";"],
+            Some(rest) => vec![rest, ";"],
+            None => vec![t],
+        })
+}
+
+/// Iterates over the tokens of a text file, together with their line numbers. Comment lines, which
+/// start with `*`, are skipped.
+fn tokens(text: &str) -> impl Iterator<Item = (usize, &str)> {
+    text.lines()
+        .enumerate()
+        .filter(|(_, line)| !line.trim_start().starts_with('*'))
+        .flat_map(|(i, line)| line_tokens(line).map(move |t| (i + 1, t)))
+}
+
+fn parse_integer(line: usize, token: &str) -> Result<Integer, PbProofError> {
+    // `Integer` doesn't accept an explicit `+` sign
+    let digits = token.strip_prefix('+').unwrap_or(token);
+    Integer::from_str_radix(digits, 10)
+        .map_err(|_| PbProofError::InvalidToken(line, token.to_owned()))
+}
+
+fn is_variable_name(name: &str) -> bool {
+    name.starts_with(|c: char| c.is_ascii_alphabetic() || c == '_')
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || "_-[]{}^".contains(c))
+}
+
+fn parse_literal(line: usize, token: &str) -> Result<Literal, PbProofError> {
+    let (var, negated) = match token.strip_prefix('~') {
+        Some(var) => (var, true),
+        None => (token, false),
+    };
+    if !is_variable_name(var) {
+        return Err(PbProofError::InvalidToken(line, token.to_owned()));
+    }
+    Ok(Literal { var: var.to_owned(), negated })
+}
+
+/// Reads a constraint from `tokens`, up to and including the terminating `;`. In proofs, the `;` may
+/// be omitted at the end of the line.
+fn parse_constraint<'a>(
+    line: usize,
+    tokens: &mut Peekable<impl Iterator<Item = (usize, &'a str)>>,
+    require_terminator: bool,
+) -> Result<LinearConstraint, PbProofError> {
+    let mut terms = Vec::new();
+    let relation = loop {
+        let (line, token) = tokens
+            .next()
+            .ok_or(PbProofError::UnterminatedConstraint(line))?;
+        match token {
+            ">=" => break Relation::GreaterEq,
+            "<=" => break Relation::LessEq,
+            "=" => break Relation::Equal,
+            _ => {
+                let coefficient = parse_integer(line, token)?;
+                let (line, token) = tokens
+                    .next()
+                    .ok_or(PbProofError::UnterminatedConstraint(line))?;
+                terms.push((coefficient, parse_literal(line, token)?));
+                if let Some(&(line, next)) = tokens.peek() {
+                    if next.starts_with('~') || is_variable_name(next) {
+                        return Err(PbProofError::NonLinearTerm(line));
+                    }
+                }
+            }
+        }
+    };
+    let (line, token) = tokens
+        .next()
+        .ok_or(PbProofError::UnterminatedConstraint(line))?;
+    let degree = parse_integer(line, token)?;
+    match tokens.peek() {
+        Some((_, ";")) => {
+            tokens.next();
+        }
+        _ if require_terminator => return Err(PbProofError::UnterminatedConstraint(line)),
+        _ => (),
+    }
+    Ok(LinearConstraint { terms, relation, degree })
+}
+
+pub fn parse_opb(text: &str) -> Result<Opb, PbProofError> {
+    let mut tokens = tokens(text).peekable();
+    let mut constraints = Vec::new();
+    while let Some(&(line, token)) = tokens.peek() {
+        if token == "min:" || token == "max:" {
+            // The objective function is irrelevant to proofs of unsatisfiability
+            for (_, token) in tokens.by_ref() {
+                if token == ";" {
+                    break;
+                }
+            }
+            continue;
+        }
+        constraints.push(parse_constraint(line, &mut tokens, true)?);
+    }
+    Ok(Opb { constraints })
+}
+
+fn parse_id(line: usize, token: Option<&str>) -> Result<Integer, PbProofError> {
+    let token = token.ok_or(PbProofError::MissingArgument(line))?;
+    parse_integer(line, token)
+}
+
+fn parse_pol<'a>(
+    line: usize,
+    tokens: impl Iterator<Item = &'a str>,
+) -> Result<Vec<PolToken>, PbProofError> {
+    let mut result = Vec::new();
+    for token in tokens {
+        result.push(match token {
+            ";" => break,
+            "+" => PolToken::Add,
+            "*" => PolToken::Multiply,
+            "d" => PolToken::Divide,
+            "s" => PolToken::Saturate,
+            _ if token.starts_with(|c: char| c.is_ascii_digit() || c == '-' || c == '+') => {
+                PolToken::Number(parse_integer(line, token)?)
+            }
+            "w" => return Err(PbProofError::UnsupportedRule(line, "pol ... w".to_owned())),
+            _ => PolToken::Literal(parse_literal(line, token)?),
+        });
+    }
+    Ok(result)
+}
+
+/// Reads the witness of a `red` rule, which is a list of mappings of the form `x -> v`, where `v` is
+/// `0`, `1`, or a literal. The arrow may be omitted.
+fn parse_witness<'a>(
+    line: usize,
+    tokens: impl Iterator<Item = &'a str>,
+) -> Result<Vec<(String, WitnessValue)>, PbProofError> {
+    let mut tokens = tokens.filter(|&t| t != "->").peekable();
+    let mut result = Vec::new();
+    while let Some(var) = tokens.next() {
+        match var {
+            ";" => {
+                return match tokens.next() {
+                    // Redundance rules with subproofs are not supported
+                    Some("begin") => {
+                        Err(PbProofError::UnsupportedRule(line, "red ... begin".into()))
+                    }
+                    Some(t) => Err(PbProofError::InvalidToken(line, t.to_owned())),
+                    None => Ok(result),
+                };
+            }
+            _ if !is_variable_name(var) => {
+                return Err(PbProofError::InvalidToken(line, var.to_owned()))
+            }
+            _ => (),
+        }
+        let value = match tokens.next().ok_or(PbProofError::MissingArgument(line))? {
+            "0" => WitnessValue::Constant(false),
+            "1" => WitnessValue::Constant(true),
+            other => WitnessValue::Literal(parse_literal(line, other)?),
+        };
+        result.push((var.to_owned(), value));
+    }
+    Ok(result)
+}
+
+/// Parses a `VeriPB` proof. Each rule must be written in a single line. Besides the rules in
+/// `ProofLine`, the proof header, `output` and `end` lines, and `conclusion NONE` are accepted and
+/// ignored.
+pub fn parse_veripb(text: &str) -> Result<Vec<(usize, ProofLine)>, PbProofError> {
+    let mut result = Vec::new();
+    for (i, line_text) in text.lines().enumerate() {
+        let line = i + 1;
+        let mut tokens = line_tokens(line_text).peekable();
+        let Some(rule) = tokens.next() else {
+            continue;
+        };
+        let parsed = match rule {
+            _ if rule.starts_with('*') => continue,
+            "pseudo-Boolean" | "output" | "end" => continue,
+            "f" => {
+                let count = tokens.find(|&t| t != ";").map(|t| {
+                    t.parse()
+                        .map_err(|_| PbProofError::InvalidToken(line, t.to_owned()))
+                });
+                ProofLine::Formula(count.transpose()?)
+            }
+            "pol" | "p" => ProofLine::Pol(parse_pol(line, tokens)?),
+            "rup" | "u" => {
+                // Any hints after the constraint are ignored
+                let mut tokens = tokens.map(|t| (line, t)).peekable();
+                ProofLine::Rup(parse_constraint(line, &mut tokens, false)?)
+            }
+            "red" => {
+                let mut tokens = tokens.map(|t| (line, t)).peekable();
+                let constraint = parse_constraint(line, &mut tokens, true)?;
+                let witness = parse_witness(line, tokens.map(|(_, t)| t))?;
+                ProofLine::Red(constraint, witness)
+            }
+            "del" | "d" => {
+                if rule == "del" && tokens.next_if_eq(&"id").is_none() {
+                    let kind = tokens.next().unwrap_or_default();
+                    return Err(PbProofError::UnsupportedRule(line, format!("del {}", kind)));
+                }
+                let ids = tokens.filter(|&t| t != ";").map(|t| parse_integer(line, t));
+                ProofLine::Delete(ids.collect::<Result<_, _>>()?)
+            }
+            "e" => {
+                let id = parse_id(line, tokens.next())?;
+                let mut tokens = tokens.map(|t| (line, t)).peekable();
+                ProofLine::Equal(id, parse_constraint(line, &mut tokens, false)?)
+            }
+            "c" => ProofLine::Contradiction(Some(parse_id(line, tokens.next())?)),
+            "conclusion" => match tokens.next() {
+                Some("NONE") => continue,
+                Some("UNSAT") => match tokens.find(|&t| t != ":") {
+                    Some(id) => ProofLine::Contradiction(Some(parse_integer(line, id)?)),
+                    None => ProofLine::Contradiction(None),
+                },
+                other => {
+                    let kind = other.unwrap_or_default();
+                    return Err(PbProofError::UnsupportedRule(
+                        line,
+                        format!("conclusion {}", kind),
+                    ));
+                }
+            },
+            other => return Err(PbProofError::UnsupportedRule(line, other.to_owned())),
+        };
+        result.push((line, parsed));
+    }
+    Ok(result)
+}
@@ -0,0 +1,182 @@
+use super::*;
+
+// All four clauses over two variables, which is unsatisfiable
+const OPB: &str = "* #variable= 2 #constraint= 4
+min: +1 x1 ;
++1 x1 +1 x2 >= 1 ;
++1 ~x1 +1 x2 >= 1;
++1 x1 +1 ~x2 >= 1 ;
++1 ~x1 +1 ~x2 >= 1 ;
+";
+
+fn check(opb: &str, proof: &str) -> Result<CheckedPbProof, PbProofError> {
+    check_pb_proof(opb.as_bytes(), proof.as_bytes())
+}
+
+fn assert_error(opb: &str, proof: &str, expected: &str) {
+    match check(opb, proof) {
+        Ok(_) => panic!("expected error '{}', but proof checked", expected),
+        Err(e) => assert_eq!(e.code(), expected, "unexpected error: {}", e),
+    }
+}
+
+#[test]
+fn test_parse_opb() {
+    let opb = parser::parse_opb(OPB).unwrap();
+    assert_eq!(opb.constraints.len(), 4);
+    let x = |negated| Literal { var: "x2".to_owned(), negated };
+    assert_eq!(opb.constraints[2].terms[1], (Integer::from(1), x(true)));
+
+    let opb = parser::parse_opb("-2 x1 +3 y_2 <= -1;\n+1 x1\n= 1 ;").unwrap();
+    assert_eq!(opb.constraints[0].relation, Relation::LessEq);
+    assert_eq!(opb.constraints[0].degree, -1);
+    assert_eq!(opb.constraints[1].relation, Relation::Equal);
+
+    let cases = [
+        ("+1 x1 +1 x2 >= 1", "pb_proof.unterminated_constraint"),
+        ("+1 x1 x2 >= 1 ;", "pb_proof.non_linear_term"),
+        ("+1 1x >= 1 ;", "pb_proof.invalid_token"),
+        ("+a x1 >= 1 ;", "pb_proof.invalid_token"),
+    ];
+    for (text, expected) in cases {
+        let got = parser::parse_opb(text).unwrap_err();
+        assert_eq!(got.code(), expected);
+    }
+}
+
+#[test]
+fn test_normalize() {
+    let opb = "+2 x1 -3 x2 +1 ~x1 >= -1 ;";
+    let constraint = &parser::parse_opb(opb).unwrap().constraints[0];
+    let [c] = Constraint::from_linear(constraint).try_into().unwrap();
+    // 2 x1 - 3 x2 + (1 - x1) >= -1 is x1 + 3 ~x2 >= 1
+    let expected = Constraint::normalize(
+        [
+            (
+                1.into(),
+                Literal {
+                    var: "x1".to_owned(),
+                    negated: false,
+                },
+            ),
+            (3.into(), Literal { var: "x2".to_owned(), negated: true }),
+        ],
+        1.into(),
+    );
+    assert_eq!(c, expected);
+    assert_eq!(c.negation().negation(), c);
+
+    let mut pool = PrimitivePool::new();
+    assert_eq!(
+        c.to_term(&mut pool).to_string(),
+        "(>= (+ (* 1 x1) (* 3 (- 1 x2))) 1)"
+    );
+    let trivial = Constraint::normalize([], (-2).into());
+    assert_eq!(trivial.to_term(&mut pool).to_string(), "(>= 0 (- 2))");
+}
+
+#[test]
+fn test_pol() {
+    let proof = "pseudo-Boolean proof version 1.2
+f 4
+* x2 >= 1, by adding the first two constraints and dividing by 2
+pol 1 2 + 2 d
+pol 3 4 + 2 d ;
+pol -2 -1 +
+c -1
+";
+    assert!(check(OPB, proof).unwrap().derived_contradiction);
+
+    let proof = "pol 1 3 * 2 + s
+e -1 +2 x1 +3 x2 >= 3 ;
+pol 1 ~x2 +
+e -1 +1 x1 >= 0 ;
+pol x1 ~x1 +
+e -1 >= -1 ;
+pol 2 2 2 * + 3 d
+e -1 +1 ~x1 +1 x2 >= 1 ;
+";
+    assert!(!check(OPB, proof).unwrap().derived_contradiction);
+
+    let cases = [
+        ("pol 1 2 + 2 d\nc -1\n", "pb_proof.not_contradiction"),
+        ("pol 1 +\n", "pb_proof.invalid_polish_notation"),
+        ("pol 1 2\n", "pb_proof.invalid_polish_notation"),
+        ("pol 1 0 *\n", "pb_proof.invalid_scalar"),
+        ("pol 1 5 +\n", "pb_proof.unknown_constraint_id"),
+        ("pol x1 ~x1 + s\n", "pb_proof.cannot_saturate"),
+        ("pol 1 w\n", "pb_proof.unsupported_rule"),
+        ("f 5\n", "pb_proof.wrong_number_of_constraints"),
+        ("pol 1 2 +\ne -1 +2 x2 >= 2 ;\n", "pb_proof.not_equal"),
+        ("del id 1\npol 1 2 +\n", "pb_proof.unknown_constraint_id"),
+    ];
+    for (proof, expected) in cases {
+        assert_error(OPB, proof, expected);
+    }
+}
+
+#[test]
+fn test_equal() {
+    let opb = "+1 x1 +1 x2 = 1 ;
++1 x1 +1 x2 >= 1 ;
++1 ~x1 +1 ~x2 >= 0 ;
+";
+    assert!(check(opb, "e 1 +1 x1 +1 x2 = 1 ;\n").is_ok());
+    assert!(check(opb, "e 2 +1 ~x1 +1 ~x2 >= 1 ;\n").is_ok());
+
+    // The `>=` half of the equality matches constraint 3, but the `<=` half doesn't match
+    // constraint 4
+    assert_error(opb, "e 3 +1 x1 +1 x2 = 1 ;\n", "pb_proof.not_equal");
+    assert_error(opb, "e 1 +1 x1 +1 x2 = 2 ;\n", "pb_proof.not_equal");
+}
+
+#[test]
+fn test_rup() {
+    let proof = "rup +1 x2 >= 1 ;
+u +1 ~x2 >= 1 ;
+conclusion UNSAT
+";
+    assert!(check(OPB, proof).unwrap().derived_contradiction);
+
+    let proof = "rup +1 x2 >= 1 ;
+rup >= 1 ;
+conclusion UNSAT : -1
+";
+    assert!(check(OPB, proof).unwrap().derived_contradiction);
+
+    // With one of the constraints deleted, `x2` no longer follows
+    let proof = "del id 2 ;\nrup +1 x2 >= 1 ;\n";
+    assert_error(OPB, proof, "pb_proof.rup_failed");
+
+    // Equalities are split into two constraints
+    let opb = "+1 x1 +1 x2 = 1 ;\n+1 x1 >= 1 ;\n";
+    let proof = "f 3\nrup +1 ~x2 >= 1 ;\nconclusion NONE\n";
+    assert!(!check(opb, proof).unwrap().derived_contradiction);
+    assert_error(opb, "conclusion UNSAT\n", "pb_proof.no_contradiction");
+}
+
+#[test]
+fn test_red() {
+    let opb = "+1 x1 +1 x2 >= 1 ;\n";
+
+    // Introduces a fresh variable `y` that implies `x1`
+    let proof = "red +1 ~y +1 x1 >= 1 ; y -> 0\ne 2 +1 ~y +1 x1 >= 1 ;\n";
+    assert!(check(opb, proof).is_ok());
+
+    let proof = "red +1 x1 >= 1 ; x1 -> 1 ;\nred +2 ~z +1 x2 >= 2 ; z 0\n";
+    assert!(check(opb, proof).is_ok());
+
+    let proof = "red +1 x1 >= 1 ; x2 -> 0\n";
+    assert_error(opb, proof, "pb_proof.redundance_failed");
+
+    // The derived constraint must be checked even if the witness doesn't affect it
+    let proof = "red +1 x1 >= 1 ;\n";
+    assert_error(opb, proof, "pb_proof.redundance_failed");
+    let proof = "red +1 x1 >= 1 ; z -> 0\n";
+    assert_error(opb, proof, "pb_proof.redundance_failed");
+    let proof = "red +1 x1 >= 1 ;\nrup >= 1 ;\nconclusion UNSAT\n";
+    assert_error("+1 ~x1 >= 1 ;\n", proof, "pb_proof.redundance_failed");
+
+    let proof = "red +1 x1 >= 1 ; x1 -> 1 ; begin\n";
+    assert_error(opb, proof, "pb_proof.unsupported_rule");
+}
@@ -0,0 +1,227 @@
+//! Backend of the stats command, which describes the shape of a proof without checking it.
+use crate::{
+    ast::{PrimitivePool, Proof, ProofCommand, ProofNode, Rc, Term},
+    checker::HOLE_RULES,
+};
+use std::{
+    collections::{BTreeMap, HashMap, HashSet},
+    io,
+};
+
+/// Statistics about the shape of a proof.
+#[derive(Debug, Clone, Default)]
+pub struct ProofStats {
+    /// The number of steps that use each rule, sorted by rule name. This includes steps inside
+    /// subproofs.
+    pub steps_by_rule: Vec<(String, usize)>,
+
+    pub num_subproofs: usize,
+
+    /// The maximum nesting depth of subproofs. This is zero if the proof has no subproofs.
+    pub max_depth: usize,
+
+    /// The number of `assume` commands outside of subproofs.
+    pub num_assumes: usize,
+
+    /// The ids of the `assume` commands outside of subproofs that are not transitively used to
+    /// derive the conclusion of the proof.
+    pub unused_assumes: Vec<String>,
+
+    /// The number of commands with each clause width. Each `assume` command counts as a clause
+    /// of width one.
+    pub clause_widths: BTreeMap<usize, usize>,
+
+    /// The number of clause literals of each size, where the size of a term is its number of nodes
+    /// when printed without sharing. Sizes are grouped in powers of two, so each key is the
+    /// smallest size in its group.
+    pub term_sizes: BTreeMap<u64, usize>,
+
+    /// The sum of the sizes of all clause literals in the proof.
+    pub total_term_size: u64,
+
+    /// The number of distinct terms among the clause literals and their subterms. Since terms are
+    /// hash consed, this is the size of the clauses when represented as a DAG.
+    pub distinct_terms: usize,
+
+    /// The number of terms in the term pool, including the ones from the problem.
+    pub pool_terms: usize,
+
+    /// The steps that may be holes, given as their ids and rules.
+    pub holes: Vec<(String, String)>,
+}
+
+fn children(term: &Term) -> Vec<&Rc<Term>> {
+    match term {
+        Term::Const(_) | Term::Var(..) | Term::Sort(_) => Vec::new(),
+        Term::App(f, args) => std::iter::once(f).chain(args).collect(),
+        Term::Op(_, args) => args.iter().collect(),
+        Term::Binder(_, _, body) => vec![body],
+        Term::Let(bindings, body) => (bindings.0.iter().map(|(_, value)| value))
+            .chain(std::iter::once(body))
+            .collect(),
+        Term::ParamOp { op_args, args, .. } => op_args.iter().chain(args).collect(),
+        Term::Match(scrutinee, cases) => std::iter::once(scrutinee)
+            .chain(cases.iter().map(|c| &c.body))
+            .collect(),
+    }
+}
+
+/// Computes the size of a term when printed without sharing. Sizes of terms that were already seen
+/// are cached, so the size of large shared terms can be computed efficiently.
+fn term_size<'a>(term: &'a Rc<Term>, cache: &mut HashMap<&'a Rc<Term>, u64>) -> u64 {
+    if let Some(size) = cache.get(term) {
+        return *size;
+    }
+    let size = children(term)
+        .into_iter()
+        .fold(1u64, |acc, t| acc.saturating_add(term_size(t, cache)));
+    cache.insert(term, size);
+    size
+}
+
+impl ProofStats {
+    /// Computes the statistics of a proof. The pool must be the one used to parse the proof.
+    pub fn new(proof: &Proof, pool: &PrimitivePool) -> Self {
+        let mut result = Self {
+            pool_terms: pool.num_terms(),
+            ..Self::default()
+        };
+        let mut steps_by_rule: HashMap<&str, usize> = HashMap::new();
+        let mut size_cache = HashMap::new();
+
+        let mut iter = proof.iter();
+        while let Some(command) = iter.next() {
+            result.max_depth = result.max_depth.max(iter.depth());
+            match command {
+                ProofCommand::Assume { .. } if iter.depth() == 0 => result.num_assumes += 1,
+                ProofCommand::Step(s) => {
+                    *steps_by_rule.entry(s.rule.as_str()).or_default() += 1;
+                    if HOLE_RULES.contains(&s.rule.as_str()) {
+                        result.holes.push((s.id.clone(), s.rule.clone()));
+                    }
+                }
+                ProofCommand::Subproof(_) => {
+                    result.num_subproofs += 1;
+                    continue;
+                }
+                ProofCommand::Assume { .. } => (),
+            }
+
+            let clause = command.clause();
+            *result.clause_widths.entry(clause.len()).or_default() += 1;
+            for literal in clause {
+                let size = term_size(literal, &mut size_cache);
+                let bucket = 1 << (63 - size.leading_zeros());
+                *result.term_sizes.entry(bucket).or_default() += 1;
+                result.total_term_size = result.total_term_size.saturating_add(size);
+            }
+        }
+        result.distinct_terms = size_cache.len();
+
+        let mut steps_by_rule: Vec<_> = (steps_by_rule.into_iter())
+            .map(|(rule, count)| (rule.to_owned(), count))
+            .collect();
+        steps_by_rule.sort();
+        result.steps_by_rule = steps_by_rule;
+
+        if !proof.commands.is_empty() {
+            let root = ProofNode::from_commands(proof.commands.clone());
+            let used: HashSet<String> = (root.get_assumptions().iter())
+                .map(|a| a.id().to_owned())
+                .collect();
+            result.unused_assumes = (proof.commands.iter())
+                .filter(|c| c.is_assume() && !used.contains(c.id()))
+                .map(|c| c.id().to_owned())
+                .collect();
+        }
+        result
+    }
+
+    /// The number of steps in the proof, including steps inside subproofs.
+    pub fn num_steps(&self) -> usize {
+        self.steps_by_rule.iter().map(|(_, count)| count).sum()
+    }
+
+    /// The average number of times each distinct term appears in the clauses of the proof, if they
+    /// were printed without sharing.
+    pub fn sharing_ratio(&self) -> f64 {
+        if self.distinct_terms == 0 {
+            return 0.0;
+        }
+        self.total_term_size as f64 / self.distinct_terms as f64
+    }
+
+    pub fn write_text(&self, dest: &mut dyn io::Write) -> io::Result<()> {
+        writeln!(dest, "steps: {}", self.num_steps())?;
+        for (rule, count) in &self.steps_by_rule {
+            writeln!(dest, "  {}: {}", rule, count)?;
+        }
+        writeln!(
+            dest,
+            "subproofs: {} (max depth: {})",
+            self.num_subproofs, self.max_depth
+        )?;
+        writeln!(
+            dest,
+            "assumes: {} ({} unused)",
+            self.num_assumes,
+            self.unused_assumes.len()
+        )?;
+        for id in &self.unused_assumes {
+            writeln!(dest, "  unused: {}", id)?;
+        }
+        writeln!(dest, "clause widths:")?;
+        for (width, count) in &self.clause_widths {
+            writeln!(dest, "  {}: {}", width, count)?;
+        }
+        writeln!(dest, "term sizes:")?;
+        for (bucket, count) in &self.term_sizes {
+            if *bucket == 1 {
+                writeln!(dest, "  1: {}", count)?;
+            } else {
+                writeln!(dest, "  {}-{}: {}", bucket, bucket * 2 - 1, count)?;
+            }
+        }
+        writeln!(
+            dest,
+            "sharing: {} total term size, {} distinct terms, ratio {:.2} ({} terms in pool)",
+            self.total_term_size,
+            self.distinct_terms,
+            self.sharing_ratio(),
+            self.pool_terms
+        )?;
+        writeln!(dest, "holes: {}", self.holes.len())?;
+        for (id, rule) in &self.holes {
+            writeln!(dest, "  {} ({})", id, rule)?;
+        }
+        Ok(())
+    }
+
+    /// Writes the statistics as a CSV file with the columns `category`, `key` and `value`, so the
+    /// results for many proofs can be easily combined.
+    pub fn write_csv(&self, dest: &mut dyn io::Write) -> io::Result<()> {
+        writeln!(dest, "category,key,value")?;
+        writeln!(dest, "steps,total,{}", self.num_steps())?;
+        for (rule, count) in &self.steps_by_rule {
+            writeln!(dest, "rule,{},{}", rule, count)?;
+        }
+        writeln!(dest, "subproofs,total,{}", self.num_subproofs)?;
+        writeln!(dest, "subproofs,max_depth,{}", self.max_depth)?;
+        writeln!(dest, "assumes,total,{}", self.num_assumes)?;
+        writeln!(dest, "assumes,unused,{}", self.unused_assumes.len())?;
+        for (width, count) in &self.clause_widths {
+            writeln!(dest, "clause_width,{},{}", width, count)?;
+        }
+        for (bucket, count) in &self.term_sizes {
+            writeln!(dest, "term_size,{},{}", bucket, count)?;
+        }
+        writeln!(dest, "sharing,total_term_size,{}", self.total_term_size)?;
+        writeln!(dest, "sharing,distinct_terms,{}", self.distinct_terms)?;
+        writeln!(dest, "sharing,ratio,{}", self.sharing_ratio())?;
+        writeln!(dest, "sharing,pool_terms,{}", self.pool_terms)?;
+        for (id, rule) in &self.holes {
+            writeln!(dest, "hole,{},{}", id, rule)?;
+        }
+        Ok(())
+    }
+}
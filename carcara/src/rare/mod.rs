@@ -0,0 +1,368 @@
+//! Loading of RARE rewrite rules, used to check `rare_rewrite` steps.
+//!
+//! RARE is the language in which cvc5 describes its rewrite rules. A RARE file is a sequence of
+//! rule definitions, each of one of these forms:
+//!
+//! ```text
+//! (define-rule <name> (<params>) [(def <definitions>)] <lhs> <rhs>)
+//! (define-rule* <name> (<params>) [(def <definitions>)] <lhs> <rhs> [<context>])
+//! (define-cond-rule <name> (<params>) [(def <definitions>)] <condition> <lhs> <rhs>)
+//! ```
+//!
+//! Since the sorts of rule parameters may be polymorphic (e.g. `?` or `?BitVec`), rules are not
+//! parsed into terms, but into untyped [`Pattern`]s, which are only turned into terms once the
+//! parameters are instantiated. A parameter with the `:list` attribute stands for a (possibly
+//! empty) list of terms, which is spliced into the arguments of the operator it appears in. If
+//! that leaves the operator with a single argument, the application is replaced by that argument,
+//! and if it leaves it with no arguments, the application is replaced by the neutral element of the
+//! operator.
+
+mod parser;
+#[cfg(test)]
+mod tests;
+
+use crate::{
+    ast::*,
+    checker::error::{CheckerError, EqualityError, RareError},
+    CarcaraResult,
+};
+use indexmap::IndexMap;
+use rug::{Integer, Rational};
+use std::io::BufRead;
+
+/// A term in a RARE rule, in which rule parameters may appear.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Pattern {
+    /// The rule parameter with the given index.
+    Param(usize),
+
+    /// A constant term.
+    Const(Constant),
+
+    /// An application of a built-in operator.
+    Op(Operator, Vec<Pattern>),
+
+    /// An application of an indexed operator.
+    ParamOp {
+        op: ParamOperator,
+        op_args: Vec<Pattern>,
+        args: Vec<Pattern>,
+    },
+}
+
+/// A parameter of a RARE rule.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RareParam {
+    pub name: String,
+
+    /// The sort of the parameter, if it is not polymorphic.
+    pub sort: Option<Sort>,
+
+    /// Whether the parameter was declared with the `:list` attribute.
+    pub is_list: bool,
+}
+
+/// A RARE rewrite rule.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RareRule {
+    pub name: String,
+    pub params: Vec<RareParam>,
+
+    /// The conditions of the rule. If the condition of a `define-cond-rule` is a conjunction, each
+    /// conjunct is a separate condition. For rules without conditions, this is empty.
+    pub conditions: Vec<Pattern>,
+
+    pub lhs: Pattern,
+    pub rhs: Pattern,
+}
+
+/// An instance of a RARE rule, obtained by replacing its parameters by terms.
+#[derive(Debug)]
+pub struct RareInstance {
+    pub conditions: Vec<Rc<Term>>,
+    pub lhs: Rc<Term>,
+    pub rhs: Rc<Term>,
+}
+
+/// A set of RARE rules, indexed by name.
+#[derive(Debug, Clone, Default)]
+pub struct RareRules(IndexMap<String, RareRule>);
+
+impl RareRules {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Parses a RARE file and adds its rules to this set. Rules that use operators not supported
+    /// by Carcara are skipped, with a warning.
+    pub fn load<T: BufRead>(&mut self, input: T) -> CarcaraResult<()> {
+        for rule in parser::parse_rules(input)? {
+            self.0.insert(rule.name.clone(), rule);
+        }
+        Ok(())
+    }
+
+    pub fn get(&self, name: &str) -> Option<&RareRule> {
+        self.0.get(name)
+    }
+
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+}
+
+impl RareRule {
+    /// Instantiates the rule, replacing each parameter by the corresponding argument. Arguments for
+    /// list parameters can be given either as a `rare-list` term or as a single term.
+    pub fn instantiate(
+        &self,
+        pool: &mut dyn TermPool,
+        args: &[Rc<Term>],
+    ) -> Result<RareInstance, CheckerError> {
+        if args.len() != self.params.len() {
+            return Err(CheckerError::WrongNumberOfArgs(
+                (self.params.len() + 1).into(),
+                args.len() + 1,
+            ));
+        }
+        let args: Vec<_> = (self.params.iter().zip(args))
+            .map(|(param, arg)| match arg.as_ref() {
+                Term::Op(Operator::RareList, elements) if param.is_list => Ok(elements.clone()),
+                Term::Op(Operator::RareList, _) => {
+                    Err(RareError::UnexpectedList(param.name.clone()))
+                }
+                _ => Ok(vec![arg.clone()]),
+            })
+            .collect::<Result<_, _>>()?;
+        for (param, arg) in self.params.iter().zip(&args) {
+            if let Some(expected) = &param.sort {
+                for a in arg {
+                    let sort = pool.sort(a);
+                    if sort.as_sort() != Some(expected) {
+                        let expected = pool.add(Term::Sort(expected.clone()));
+                        return Err(EqualityError::ExpectedToBe { expected, got: sort }.into());
+                    }
+                }
+            }
+        }
+
+        let instantiator = Instantiator { rule: self, args: &args };
+        Ok(RareInstance {
+            conditions: (self.conditions.iter())
+                .map(|c| instantiator.build(pool, c))
+                .collect::<Result<_, _>>()?,
+            lhs: instantiator.build(pool, &self.lhs)?,
+            rhs: instantiator.build(pool, &self.rhs)?,
+        })
+    }
+}
+
+struct Instantiator<'a> {
+    rule: &'a RareRule,
+    args: &'a [Vec<Rc<Term>>],
+}
+
+impl Instantiator<'_> {
+    fn build(&self, pool: &mut dyn TermPool, pattern: &Pattern) -> Result<Rc<Term>, CheckerError> {
+        Ok(match pattern {
+            Pattern::Param(i) => match self.args[*i].as_slice() {
+                [arg] => arg.clone(),
+                _ => {
+                    let name = self.rule.params[*i].name.clone();
+                    return Err(RareError::ListOutsideOperator(name).into());
+                }
+            },
+            Pattern::Const(c) => pool.add(Term::Const(c.clone())),
+            Pattern::Op(op, arg_patterns) => {
+                let mut args = Vec::with_capacity(arg_patterns.len());
+                let mut list_sort = None;
+                for p in arg_patterns {
+                    match p {
+                        Pattern::Param(i) if self.rule.params[*i].is_list => {
+                            list_sort = Some(&self.rule.params[*i].sort);
+                            args.extend(self.args[*i].iter().cloned());
+                        }
+                        _ => args.push(self.build(pool, p)?),
+                    }
+                }
+                match (list_sort, args.len()) {
+                    // These operators only make sense if they can be evaluated, so we evaluate them
+                    // right away
+                    _ if matches!(op, Operator::BvSize | Operator::BvConst) => {
+                        evaluate_op(pool, *op, &args).ok_or(RareError::CannotEvaluate(*op))?
+                    }
+                    (Some(_), 1) => args.pop().unwrap(),
+                    (Some(sort), 0) => neutral_element(pool, *op, sort.as_ref())
+                        .ok_or(RareError::NoNeutralElement(*op))?,
+                    _ => pool.add(Term::Op(*op, args)),
+                }
+            }
+            Pattern::ParamOp { op, op_args, args } => {
+                // Indices must be integer constants, but in a rule they may be expressions over the
+                // parameters, so we evaluate them
+                let op_args: Vec<_> = (op_args.iter())
+                    .map(|p| {
+                        let arg = self.build(pool, p)?;
+                        let arg = evaluate(pool, &arg);
+                        arg.as_signed_integer()
+                            .map(|i| pool.add(Term::new_int(i)))
+                            .ok_or(CheckerError::ExpectedAnyInteger(arg))
+                    })
+                    .collect::<Result<_, _>>()?;
+                let args: Vec<_> = (args.iter())
+                    .map(|p| self.build(pool, p))
+                    .collect::<Result<_, _>>()?;
+                match (op, op_args.as_slice()) {
+                    // Indexed bitvector constants, like `(_ bv5 8)`, are represented as constants
+                    (ParamOperator::BvConst, [value, width]) if args.is_empty() => {
+                        let (value, width) = (value.as_integer(), width.as_integer());
+                        pool.add(Term::new_bv(value.unwrap(), width.unwrap()))
+                    }
+                    _ => pool.add(Term::ParamOp { op: *op, op_args, args }),
+                }
+            }
+        })
+    }
+}
+
+/// Returns the term an application of `op` with no arguments should be replaced by, if there is
+/// one. The sort is the sort of the list parameter that was spliced into the application.
+fn neutral_element(pool: &mut dyn TermPool, op: Operator, sort: Option<&Sort>) -> Option<Rc<Term>> {
+    let is_real = sort == Some(&Sort::Real);
+    Some(match op {
+        Operator::And => pool.bool_true(),
+        Operator::Or | Operator::Xor => pool.bool_false(),
+        Operator::Add if is_real => pool.add(Term::new_real(0)),
+        Operator::Add => pool.add(Term::new_int(0)),
+        Operator::Mult if is_real => pool.add(Term::new_real(1)),
+        Operator::Mult => pool.add(Term::new_int(1)),
+        Operator::StrConcat => pool.add(Term::new_string("")),
+        Operator::ReConcat => {
+            let empty = pool.add(Term::new_string(""));
+            pool.add(Term::Op(Operator::StrToRe, vec![empty]))
+        }
+        Operator::ReUnion => pool.add(Term::Op(Operator::ReNone, Vec::new())),
+        Operator::ReIntersection => pool.add(Term::Op(Operator::ReAll, Vec::new())),
+        _ => return None,
+    })
+}
+
+/// Evaluates the subterms of a term that only depend on constants, like arithmetic operations over
+/// numerals, or `@bvsize` applications. RARE rules use these to compute new constants from the
+/// parameters, which solvers usually print already evaluated.
+pub fn evaluate(pool: &mut dyn TermPool, term: &Rc<Term>) -> Rc<Term> {
+    match term.as_ref() {
+        Term::Op(op, args) => {
+            let args: Vec<_> = args.iter().map(|a| evaluate(pool, a)).collect();
+            evaluate_op(pool, *op, &args).unwrap_or_else(|| pool.add(Term::Op(*op, args)))
+        }
+        Term::ParamOp { op, op_args, args } => {
+            let args = args.iter().map(|a| evaluate(pool, a)).collect();
+            let op_args = op_args.iter().map(|a| evaluate(pool, a)).collect();
+            pool.add(Term::ParamOp { op: *op, op_args, args })
+        }
+        _ => term.clone(),
+    }
+}
+
+/// Evaluates an operation whose arguments were already evaluated, returning `None` if it can't be
+/// evaluated.
+fn evaluate_op(pool: &mut dyn TermPool, op: Operator, args: &[Rc<Term>]) -> Option<Rc<Term>> {
+    match op {
+        Operator::BvSize => {
+            let [arg] = args else { return None };
+            match pool.sort(arg).as_sort()? {
+                Sort::BitVec(width) => Some(pool.add(Term::new_int(width.clone()))),
+                _ => None,
+            }
+        }
+        Operator::BvConst => {
+            let [value, width] = args else { return None };
+            let (value, width) = (value.as_signed_integer()?, width.as_integer()?);
+            let modulus = Integer::from(1) << width.to_u32()?;
+            Some(pool.add(Term::new_bv(value.div_rem_euc(modulus).1, width)))
+        }
+        Operator::Not => Some(pool.bool_constant(!args[0].as_bool()?)),
+        Operator::And | Operator::Or => {
+            let values: Vec<_> = args.iter().map(|a| a.as_bool()).collect::<Option<_>>()?;
+            let result = if op == Operator::And {
+                values.iter().all(|&b| b)
+            } else {
+                values.iter().any(|&b| b)
+            };
+            Some(pool.bool_constant(result))
+        }
+        Operator::Ite => match args[0].as_bool()? {
+            true => Some(args[1].clone()),
+            false => Some(args[2].clone()),
+        },
+        Operator::Equals if args.iter().all(|a| a.is_signed_number()) => {
+            let first = args[0].as_signed_number()?;
+            let result = args[1..]
+                .iter()
+                .all(|a| a.as_signed_number() == Some(first.clone()));
+            Some(pool.bool_constant(result))
+        }
+        Operator::Equals if args.iter().all(|a| a.is_const() || a.as_bool().is_some()) => {
+            Some(pool.bool_constant(args.iter().all(|a| a == &args[0])))
+        }
+        Operator::LessThan | Operator::LessEq | Operator::GreaterThan | Operator::GreaterEq => {
+            let [a, b] = args else { return None };
+            let (a, b) = (a.as_signed_number()?, b.as_signed_number()?);
+            let result = match op {
+                Operator::LessThan => a < b,
+                Operator::LessEq => a <= b,
+                Operator::GreaterThan => a > b,
+                _ => a >= b,
+            };
+            Some(pool.bool_constant(result))
+        }
+        Operator::Add | Operator::Sub | Operator::Mult => {
+            // Negative constants are represented by a unary `-` application, so they are already
+            // evaluated
+            if op == Operator::Sub && args.len() == 1 && args[0].is_number() {
+                return None;
+            }
+            let values: Vec<_> = (args.iter())
+                .map(|a| a.as_signed_number())
+                .collect::<Option<_>>()?;
+            let result = match op {
+                Operator::Add => values.into_iter().sum(),
+                Operator::Mult => values.into_iter().product(),
+                _ if values.len() == 1 => -values[0].clone(),
+                _ => {
+                    let mut values = values.into_iter();
+                    let first = values.next()?;
+                    values.fold(first, |acc, v| acc - v)
+                }
+            };
+            let is_int = args.iter().all(|a| a.as_signed_integer().is_some());
+            Some(number_term(pool, result, is_int))
+        }
+        Operator::IntDiv | Operator::Mod => {
+            let [a, b] = args else { return None };
+            let (a, b) = (a.as_signed_integer()?, b.as_signed_integer()?);
+            if b == 0 {
+                return None;
+            }
+            // SMT-LIB integer division is euclidean division
+            let (quotient, remainder) = a.div_rem_euc(b);
+            let result = if op == Operator::IntDiv {
+                quotient
+            } else {
+                remainder
+            };
+            Some(number_term(pool, Rational::from(result), true))
+        }
+        Operator::Abs => {
+            let [a] = args else { return None };
+            let is_int = a.as_signed_integer().is_some();
+            Some(number_term(pool, a.as_signed_number()?.abs(), is_int))
+        }
+        _ => None,
+    }
+}
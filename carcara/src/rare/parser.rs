@@ -0,0 +1,258 @@
+//! A parser for RARE rule files.
+
+use super::{Pattern, RareParam, RareRule};
+use crate::{
+    ast::*,
+    parser::{Lexer, ParserError, Position, Reserved, Token},
+    CarcaraResult, Error,
+};
+use rug::Integer;
+use std::{collections::HashMap, io::BufRead, str::FromStr};
+
+/// An S-expression. Rule files are first read as a sequence of S-expressions, so that a rule that
+/// can't be converted can be skipped without losing track of where the next rule starts.
+enum SExpr {
+    Atom(Token, Position),
+    List(Vec<SExpr>, Position),
+}
+
+impl SExpr {
+    fn position(&self) -> Position {
+        match self {
+            SExpr::Atom(_, pos) | SExpr::List(_, pos) => *pos,
+        }
+    }
+
+    fn as_symbol(&self) -> Option<&str> {
+        match self {
+            SExpr::Atom(Token::Symbol(s), _) => Some(s),
+            _ => None,
+        }
+    }
+
+    fn expect_symbol(&self) -> CarcaraResult<&str> {
+        self.as_symbol().ok_or_else(|| self.unexpected())
+    }
+
+    fn expect_list(&self) -> CarcaraResult<&[SExpr]> {
+        match self {
+            SExpr::List(list, _) => Ok(list),
+            SExpr::Atom(..) => Err(self.unexpected()),
+        }
+    }
+
+    /// Returns an "unexpected token" error for the first token of this S-expression.
+    fn unexpected(&self) -> Error {
+        let token = match self {
+            SExpr::Atom(Token::Symbol(s), _) => Token::Symbol(s.clone()),
+            SExpr::Atom(Token::Keyword(s), _) => Token::Keyword(s.clone()),
+            SExpr::Atom(Token::Numeral(n), _) => Token::Numeral(n.clone()),
+            SExpr::Atom(..) | SExpr::List(..) => Token::OpenParen,
+        };
+        Error::Parser(ParserError::UnexpectedToken(token), self.position())
+    }
+}
+
+fn read_sexpr<R: BufRead>(lexer: &mut Lexer<R>, first: (Token, Position)) -> CarcaraResult<SExpr> {
+    match first {
+        (Token::OpenParen, pos) => {
+            let mut list = Vec::new();
+            loop {
+                match lexer.next_token()? {
+                    (Token::CloseParen, _) => return Ok(SExpr::List(list, pos)),
+                    (Token::Eof, pos) => {
+                        return Err(Error::Parser(ParserError::UnexpectedToken(Token::Eof), pos))
+                    }
+                    token => list.push(read_sexpr(lexer, token)?),
+                }
+            }
+        }
+        (token @ (Token::CloseParen | Token::Eof), pos) => {
+            Err(Error::Parser(ParserError::UnexpectedToken(token), pos))
+        }
+        (token, pos) => Ok(SExpr::Atom(token, pos)),
+    }
+}
+
+/// Parses all rules in a RARE file. Rules that use unsupported operators are skipped.
+pub fn parse_rules<R: BufRead>(input: R) -> CarcaraResult<Vec<RareRule>> {
+    let mut lexer = Lexer::new(input)?;
+    let mut rules = Vec::new();
+    loop {
+        let sexpr = match lexer.next_token()? {
+            (Token::Eof, _) => return Ok(rules),
+            token => read_sexpr(&mut lexer, token)?,
+        };
+        match parse_rule(&sexpr) {
+            Ok(rule) => rules.push(rule),
+            Err(Error::Parser(
+                e @ (ParserError::UndefinedIden(_) | ParserError::InvalidIndexedOp(_)),
+                _,
+            )) => {
+                let name = sexpr.expect_list()?.get(1).and_then(SExpr::as_symbol);
+                log::warn!("skipping RARE rule '{}': {}", name.unwrap_or_default(), e);
+            }
+            Err(e) => return Err(e),
+        }
+    }
+}
+
+fn parse_rule(sexpr: &SExpr) -> CarcaraResult<RareRule> {
+    let list = sexpr.expect_list()?;
+    let Some((command, rest)) = list.split_first() else {
+        return Err(sexpr.unexpected());
+    };
+    // The number of terms that follow the parameters and definitions: the condition (if any), the
+    // left and right sides, and the context (if any)
+    let (num_terms, has_condition) = match command.expect_symbol()? {
+        "define-rule" => (2..=2, false),
+        "define-rule*" => (2..=3, false),
+        "define-cond-rule" => (3..=3, true),
+        _ => return Err(command.unexpected()),
+    };
+    let [name, params, rest @ ..] = rest else {
+        return Err(sexpr.unexpected());
+    };
+    let name = name.expect_symbol()?.to_owned();
+
+    let params = params
+        .expect_list()?
+        .iter()
+        .map(parse_param)
+        .collect::<CarcaraResult<Vec<_>>>()?;
+    let mut scope: HashMap<String, Pattern> = (params.iter().enumerate())
+        .map(|(i, p)| (p.name.clone(), Pattern::Param(i)))
+        .collect();
+
+    let rest = match rest {
+        [SExpr::List(defs, _), rest @ ..]
+            if defs.first().and_then(SExpr::as_symbol) == Some("def") =>
+        {
+            for def in &defs[1..] {
+                let [var, value] = def.expect_list()? else {
+                    return Err(def.unexpected());
+                };
+                let value = parse_pattern(&scope, value)?;
+                scope.insert(var.expect_symbol()?.to_owned(), value);
+            }
+            rest
+        }
+        _ => rest,
+    };
+    if !num_terms.contains(&rest.len()) {
+        return Err(sexpr.unexpected());
+    }
+
+    let (conditions, rest) = if has_condition {
+        let conditions = match parse_pattern(&scope, &rest[0])? {
+            Pattern::Op(Operator::And, conjuncts) => conjuncts,
+            condition => vec![condition],
+        };
+        (conditions, &rest[1..])
+    } else {
+        (Vec::new(), rest)
+    };
+    // The context of a `define-rule*` rule only describes how the rule is applied repeatedly, so we
+    // ignore it
+    Ok(RareRule {
+        name,
+        params,
+        conditions,
+        lhs: parse_pattern(&scope, &rest[0])?,
+        rhs: parse_pattern(&scope, &rest[1])?,
+    })
+}
+
+fn parse_param(sexpr: &SExpr) -> CarcaraResult<RareParam> {
+    let [name, sort, attributes @ ..] = sexpr.expect_list()? else {
+        return Err(sexpr.unexpected());
+    };
+    let sort = match sort.as_symbol() {
+        Some("Bool") => Some(Sort::Bool),
+        Some("Int") => Some(Sort::Int),
+        Some("Real") => Some(Sort::Real),
+        Some("String") => Some(Sort::String),
+        Some("RegLan") => Some(Sort::RegLan),
+        _ => None,
+    };
+    let mut is_list = false;
+    for a in attributes {
+        match a {
+            SExpr::Atom(Token::Keyword(k), _) if k == "list" => is_list = true,
+            // Other attributes, like `:const`, only restrict which terms the parameter can be
+            // instantiated with when searching for rewrites, so we can ignore them
+            SExpr::Atom(Token::Keyword(_), _) => (),
+            _ => return Err(a.unexpected()),
+        }
+    }
+    Ok(RareParam {
+        name: name.expect_symbol()?.to_owned(),
+        sort,
+        is_list,
+    })
+}
+
+fn parse_pattern(scope: &HashMap<String, Pattern>, sexpr: &SExpr) -> CarcaraResult<Pattern> {
+    let parse_all = |sexprs: &[SExpr]| {
+        (sexprs.iter())
+            .map(|s| parse_pattern(scope, s))
+            .collect::<CarcaraResult<Vec<_>>>()
+    };
+    let pos = sexpr.position();
+    match sexpr {
+        SExpr::Atom(Token::Numeral(n), _) => Ok(Pattern::Const(Constant::Integer(n.clone()))),
+        SExpr::Atom(Token::Decimal(r), _) => Ok(Pattern::Const(Constant::Real(r.clone()))),
+        SExpr::Atom(Token::String(s), _) => Ok(Pattern::Const(Constant::String(s.clone()))),
+        SExpr::Atom(Token::Bitvector { value, width }, _) => Ok(Pattern::Const(Constant::BitVec(
+            value.clone(),
+            Integer::from(*width),
+        ))),
+        SExpr::Atom(Token::Symbol(s), _) => {
+            if let Some(p) = scope.get(s) {
+                Ok(p.clone())
+            } else if let Ok(op) = Operator::from_str(s) {
+                Ok(Pattern::Op(op, Vec::new()))
+            } else {
+                Err(Error::Parser(ParserError::UndefinedIden(s.clone()), pos))
+            }
+        }
+        SExpr::Atom(..) => Err(sexpr.unexpected()),
+        SExpr::List(list, _) => match list.as_slice() {
+            [SExpr::Atom(Token::ReservedWord(Reserved::Underscore), _), op, op_args @ ..] => {
+                let op = parse_indexed_operator(op)?;
+                Ok(Pattern::ParamOp {
+                    op,
+                    op_args: parse_all(op_args)?,
+                    args: Vec::new(),
+                })
+            }
+            [SExpr::List(head, _), args @ ..] => match head.as_slice() {
+                [SExpr::Atom(Token::ReservedWord(Reserved::Underscore), _), op, op_args @ ..] => {
+                    Ok(Pattern::ParamOp {
+                        op: parse_indexed_operator(op)?,
+                        op_args: parse_all(op_args)?,
+                        args: parse_all(args)?,
+                    })
+                }
+                _ => Err(sexpr.unexpected()),
+            },
+            [head, args @ ..] => {
+                let name = head.expect_symbol()?;
+                let op = Operator::from_str(name)
+                    .map_err(|_| Error::Parser(ParserError::UndefinedIden(name.to_owned()), pos))?;
+                Ok(Pattern::Op(op, parse_all(args)?))
+            }
+            [] => Err(sexpr.unexpected()),
+        },
+    }
+}
+
+fn parse_indexed_operator(sexpr: &SExpr) -> CarcaraResult<ParamOperator> {
+    let name = sexpr.expect_symbol()?;
+    ParamOperator::from_str(name).map_err(|_| {
+        Error::Parser(
+            ParserError::InvalidIndexedOp(name.to_owned()),
+            sexpr.position(),
+        )
+    })
+}
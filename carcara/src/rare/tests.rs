@@ -0,0 +1,192 @@
+use super::*;
+use crate::{checker, parser};
+
+const RULES: &str = "
+; Boolean rules
+(define-rule bool-double-not-elim ((t Bool)) (not (not t)) t)
+(define-rule* bool-and-flatten ((xs Bool :list) (b Bool :list) (ys Bool :list) (zs Bool :list))
+  (and xs (and b ys) zs)
+  (and xs b ys zs))
+(define-rule bool-and-true ((xs Bool :list) (ys Bool :list)) (and xs true ys) (and xs ys))
+(define-rule bool-and-single ((xs Bool :list)) (and xs) xs)
+
+; Arithmetic rules
+(define-rule arith-plus-zero ((t Real :list) (s Real :list)) (+ t 0.0 s) (+ t s))
+(define-cond-rule arith-div-by-const-elim ((t Real) (c Real))
+  (not (= c 0.0))
+  (/ t c)
+  (* t (/ 1.0 c)))
+
+; Bitvector rules
+(define-cond-rule bv-extract-whole ((x ?BitVec) (n Int :const))
+  (>= n (- (@bvsize x) 1))
+  ((_ extract n 0) x)
+  x)
+(define-rule bv-concat-extract ((x ?BitVec))
+  (def (w (@bvsize x)))
+  (concat ((_ extract (- w 1) 1) x) ((_ extract 0 0) x))
+  x)
+(define-rule bv-add-zero ((x ?BitVec)) (bvadd x (@bv 0 (@bvsize x))) x)
+
+; Rules with unsupported operators are skipped
+(define-rule int-log2-one ((x Int)) (int.log2 1) 0)
+";
+
+const DEFINITIONS: &str = "
+(declare-const p Bool)
+(declare-const q Bool)
+(declare-const r Bool)
+(declare-const s Bool)
+(declare-const x Real)
+(declare-const y Real)
+(declare-const c Real)
+(declare-const b (_ BitVec 4))
+(assert (not (= c 0.0)))
+";
+
+fn rules() -> RareRules {
+    let mut rules = RareRules::new();
+    rules.load(RULES.as_bytes()).unwrap();
+    rules
+}
+
+fn check(rules: Option<RareRules>, steps: &str) -> Result<bool, crate::Error> {
+    let proof = format!("{steps}\n(step end (cl) :rule hole)");
+    let (problem, proof, mut pool) = parser::parse_instance(
+        DEFINITIONS.as_bytes(),
+        proof.as_bytes(),
+        parser::Config::new(),
+    )?;
+    let mut config = checker::Config::new();
+    config.rare_rules = rules.map(Into::into);
+    checker::ProofChecker::new(&mut pool, config).check(&problem, &proof)
+}
+
+fn assert_error(steps: &str, code: &str) {
+    match check(Some(rules()), steps) {
+        Err(e) => assert_eq!(e.code(), code, "{}", e),
+        Ok(_) => panic!("expected error '{}', but proof checked", code),
+    }
+}
+
+#[test]
+fn test_parse_rules() {
+    let rules = rules();
+    assert_eq!(rules.len(), 9);
+    assert!(rules.get("int-log2-one").is_none());
+
+    let flatten = rules.get("bool-and-flatten").unwrap();
+    assert_eq!(flatten.params.len(), 4);
+    assert!(flatten
+        .params
+        .iter()
+        .all(|p| p.is_list && p.sort == Some(Sort::Bool)));
+    assert!(flatten.conditions.is_empty());
+
+    let extract = rules.get("bv-extract-whole").unwrap();
+    assert_eq!(extract.params[0].sort, None);
+    assert!(!extract.params[1].is_list);
+    assert_eq!(extract.conditions.len(), 1);
+
+    // Definitions are replaced by their values
+    let concat = rules.get("bv-concat-extract").unwrap();
+    let Pattern::Op(Operator::BvConcat, args) = &concat.lhs else {
+        panic!("unexpected pattern: {:?}", concat.lhs);
+    };
+    let Pattern::ParamOp { op_args, .. } = &args[0] else {
+        panic!("unexpected pattern: {:?}", args[0]);
+    };
+    let size = Pattern::Op(Operator::BvSize, vec![Pattern::Param(0)]);
+    let one = Pattern::Const(Constant::Integer(1.into()));
+    assert_eq!(op_args[0], Pattern::Op(Operator::Sub, vec![size, one]));
+
+    let cases = [
+        "(define-rule r ((t Bool)) t)",
+        "(define-cond-rule r ((t Bool)) t t)",
+        "(define-rule r ((t Bool :list 1)) t t)",
+        "(declare-rule r ((t Bool)) t t)",
+        "(define-rule r ((t Bool)) t t",
+    ];
+    for input in cases {
+        let result = RareRules::new().load(input.as_bytes());
+        assert!(result.is_err(), "expected parser error in '{}'", input);
+    }
+}
+
+#[test]
+fn test_rare_rewrite() {
+    let valid = [
+        r#"(step t1 (cl (= (not (not p)) p)) :rule rare_rewrite :args ("bool-double-not-elim" p))"#,
+        r#"(step t1 (cl (= (and p (and q r) s) (and p q r s)))
+            :rule rare_rewrite :args ("bool-and-flatten" (rare-list p) (rare-list q) r s))"#,
+        // Single-element lists collapse the application, and empty lists are removed
+        r#"(step t1 (cl (= (and (and q r) s) (and q r s)))
+            :rule rare_rewrite :args ("bool-and-flatten" rare-list q r s))"#,
+        r#"(step t1 (cl (= (and p q s) (and p q s)))
+            :rule rare_rewrite :args ("bool-and-flatten" p q rare-list s))"#,
+        // An application with no arguments becomes the neutral element
+        r#"(step t1 (cl (= (and true p) p))
+            :rule rare_rewrite :args ("bool-and-true" rare-list p))"#,
+        r#"(step t1 (cl (= true true))
+            :rule rare_rewrite :args ("bool-and-true" rare-list rare-list))"#,
+        r#"(step t1 (cl (= 0.0 0.0))
+            :rule rare_rewrite :args ("arith-plus-zero" rare-list rare-list))"#,
+        r#"(step t1 (cl (= (+ x 0.0 y) (+ x y)))
+            :rule rare_rewrite :args ("arith-plus-zero" x y))"#,
+        // Conditions can be proved by premises, or evaluated
+        r#"(assume h1 (not (= c 0.0)))
+           (step t1 (cl (= (/ x c) (* x (/ 1.0 c))))
+            :rule rare_rewrite :premises (h1) :args ("arith-div-by-const-elim" x c))"#,
+        r#"(step t1 (cl (= (/ x 2.0) (* x (/ 1.0 2.0))))
+            :rule rare_rewrite :args ("arith-div-by-const-elim" x 2.0))"#,
+        r#"(step t1 (cl (= ((_ extract 3 0) b) b))
+            :rule rare_rewrite :args ("bv-extract-whole" b 3))"#,
+        // Computed indices and constants are evaluated
+        r#"(step t1 (cl (= (concat ((_ extract 3 1) b) ((_ extract 0 0) b)) b))
+            :rule rare_rewrite :args ("bv-concat-extract" b))"#,
+        r#"(step t1 (cl (= (bvadd b #b0000) b)) :rule rare_rewrite :args ("bv-add-zero" b))"#,
+        r#"(step t1 (cl (= p p)) :rule rare_rewrite :args ("bool-and-single" p))"#,
+    ];
+    for steps in valid {
+        if let Err(e) = check(Some(rules()), steps) {
+            panic!("proof failed to check: {}\n{}", e, steps);
+        }
+    }
+
+    assert_error(
+        r#"(step t1 (cl (= (not (not p)) q)) :rule rare_rewrite :args ("bool-double-not-elim" p))"#,
+        "term_equality.expected_to_be",
+    );
+    assert_error(
+        r#"(step t1 (cl (= (not (not p)) p)) :rule rare_rewrite :args ("bool-triple-not-elim" p))"#,
+        "rare.unknown_rule",
+    );
+    assert_error(
+        r#"(step t1 (cl (= (not (not p)) p)) :rule rare_rewrite :args ("bool-double-not-elim"))"#,
+        "wrong_number_of_args",
+    );
+    assert_error(
+        r#"(step t1 (cl (= (not (not p)) p)) :rule rare_rewrite :args ("bool-double-not-elim" (rare-list p q)))"#,
+        "rare.unexpected_list",
+    );
+    assert_error(
+        r#"(step t1 (cl (= (and p q) (and p q))) :rule rare_rewrite :args ("bool-and-single" (rare-list p q)))"#,
+        "rare.list_outside_operator",
+    );
+    assert_error(
+        r#"(step t1 (cl (= x x)) :rule rare_rewrite :args ("bool-double-not-elim" x))"#,
+        "term_equality.expected_to_be",
+    );
+    assert_error(
+        r#"(step t1 (cl (= (/ x y) (* x (/ 1.0 y))))
+            :rule rare_rewrite :args ("arith-div-by-const-elim" x y))"#,
+        "rare.unproven_condition",
+    );
+
+    // Without RARE rules, `rare_rewrite` is an unknown rule
+    let result = check(
+        None,
+        r#"(step t1 (cl (= (not (not p)) p)) :rule rare_rewrite :args ("bool-double-not-elim" p))"#,
+    );
+    assert_eq!(result.unwrap_err().code(), "unknown_rule");
+}
@@ -20,6 +20,30 @@ pub enum ResolutionError {
     PivotNotFound(Rc<Term>),
 }
 
+impl ResolutionError {
+    /// Returns a stable identifier for the kind of this error.
+    pub fn code(&self) -> &'static str {
+        match self {
+            ResolutionError::TautologyFailed => "resolution.tautology_failed",
+            ResolutionError::RemainingPivot(_) => "resolution.remaining_pivot",
+            ResolutionError::ExtraTermInConclusion(_) => "resolution.extra_term_in_conclusion",
+            ResolutionError::MissingTermInConclusion(_) => "resolution.missing_term_in_conclusion",
+            ResolutionError::PivotNotFound(_) => "resolution.pivot_not_found",
+        }
+    }
+
+    /// Returns the terms carried by this error.
+    pub fn terms(&self) -> Vec<&Rc<Term>> {
+        match self {
+            ResolutionError::TautologyFailed => Vec::new(),
+            ResolutionError::RemainingPivot(t)
+            | ResolutionError::ExtraTermInConclusion(t)
+            | ResolutionError::MissingTermInConclusion(t)
+            | ResolutionError::PivotNotFound(t) => vec![t],
+        }
+    }
+}
+
 pub type Literal<'a> = (u32, &'a Rc<Term>);
 
 /// A collection that can be used as a clause during resolution.
@@ -0,0 +1,333 @@
+//! Checking of DRAT and LRAT proofs of unsatisfiability of CNF formulas in the DIMACS format.
+//!
+//! Proofs are checked by the same engine used for the `drup` rule, which re-checks every lemma
+//! using reverse unit propagation, falling back to the RAT property if necessary. After a proof is
+//! checked, it can be converted into an Alethe proof that uses only `resolution` and `weakening`
+//! steps, as long as it doesn't contain RAT lemmas.
+
+mod parser;
+#[cfg(test)]
+mod tests;
+
+use crate::{
+    ast::*,
+    drup::{
+        check_drup, hash_term, story_to_resolution, DRupStory, DerivationPremise, DrupFormatError,
+    },
+};
+use parser::{Cnf, DimacsClause, ProofLine};
+use std::{collections::HashMap, io};
+use thiserror::Error;
+
+#[derive(Debug, Error)]
+pub enum SatProofError {
+    #[error("IO error: {0}")]
+    Io(#[from] io::Error),
+
+    #[error("missing `p cnf` header in DIMACS file")]
+    MissingHeader,
+
+    #[error("invalid DIMACS header on line {0}")]
+    InvalidHeader(usize),
+
+    #[error("header declares {0} clauses, but {1} were found")]
+    WrongNumberOfClauses(usize, usize),
+
+    #[error("literal {1} on line {0} is out of range: only {2} variables were declared")]
+    VariableOutOfRange(usize, i64, usize),
+
+    #[error("invalid token on line {0}: '{1}'")]
+    InvalidToken(usize, String),
+
+    #[error("clause starting on line {0} is not terminated by 0")]
+    UnterminatedClause(usize),
+
+    #[error("invalid byte in binary proof: expected 'a' or 'd', got {0:#04x}")]
+    InvalidBinaryMarker(u8),
+
+    #[error("binary proof ends in the middle of a clause")]
+    TruncatedBinaryProof,
+
+    #[error("unknown clause id: {0}")]
+    UnknownClauseId(u64),
+
+    #[error("invalid proof: {0}")]
+    Check(#[from] DrupFormatError),
+}
+
+impl SatProofError {
+    /// Returns a stable identifier for the kind of this error. For errors found while checking the
+    /// proof, this is the code of the underlying error.
+    pub fn code(&self) -> &'static str {
+        match self {
+            SatProofError::Io(_) => "io",
+            SatProofError::MissingHeader => "sat_proof.missing_header",
+            SatProofError::InvalidHeader(_) => "sat_proof.invalid_header",
+            SatProofError::WrongNumberOfClauses(_, _) => "sat_proof.wrong_number_of_clauses",
+            SatProofError::VariableOutOfRange(_, _, _) => "sat_proof.variable_out_of_range",
+            SatProofError::InvalidToken(_, _) => "sat_proof.invalid_token",
+            SatProofError::UnterminatedClause(_) => "sat_proof.unterminated_clause",
+            SatProofError::InvalidBinaryMarker(_) => "sat_proof.invalid_binary_marker",
+            SatProofError::TruncatedBinaryProof => "sat_proof.truncated_binary_proof",
+            SatProofError::UnknownClauseId(_) => "sat_proof.unknown_clause_id",
+            SatProofError::Check(e) => e.code(),
+        }
+    }
+}
+
+/// The format of a clausal proof.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SatProofFormat {
+    Drat,
+    BinaryDrat,
+    Lrat,
+}
+
+impl SatProofFormat {
+    /// Guesses whether a DRAT proof is in the text or in the binary format, by looking at its first
+    /// bytes. Similarly to `drat-trim`, a proof is considered binary if it starts with an addition
+    /// marker, or if any of its first bytes is not printable.
+    pub fn detect_drat(proof: &[u8]) -> Self {
+        let is_text_byte = |b: &u8| b.is_ascii_graphic() || b.is_ascii_whitespace();
+        if proof.first() == Some(&b'a') || !proof.iter().take(64).all(is_text_byte) {
+            SatProofFormat::BinaryDrat
+        } else {
+            SatProofFormat::Drat
+        }
+    }
+}
+
+/// A proof that was successfully checked. This holds everything necessary to convert it into an
+/// Alethe proof.
+pub struct CheckedSatProof {
+    pool: PrimitivePool,
+
+    /// The Boolean variables that represent the DIMACS variables. Variable `i` is stored at index
+    /// `i - 1`.
+    variables: Vec<Rc<Term>>,
+
+    /// The clauses of the original formula, as `cl` terms.
+    clauses: Vec<Rc<Term>>,
+
+    story: DRupStory,
+}
+
+/// Checks a proof of unsatisfiability of the CNF formula given in `cnf`. The proof must be in the
+/// given format.
+pub fn check_sat_proof<R: io::BufRead>(
+    mut cnf: R,
+    proof: &[u8],
+    format: SatProofFormat,
+) -> Result<CheckedSatProof, SatProofError> {
+    let cnf = {
+        let mut text = String::new();
+        cnf.read_to_string(&mut text)?;
+        parser::parse_dimacs(&text)?
+    };
+    let proof_text = || String::from_utf8_lossy(proof);
+    let lines = match format {
+        SatProofFormat::Drat => parser::parse_drat(&proof_text())?,
+        SatProofFormat::BinaryDrat => parser::parse_binary_drat(proof)?,
+        SatProofFormat::Lrat => parser::parse_lrat(&proof_text(), &cnf)?,
+    };
+    check_lines(cnf, lines)
+}
+
+fn clause_to_term(
+    pool: &mut dyn TermPool,
+    variables: &[Rc<Term>],
+    clause: &DimacsClause,
+) -> Rc<Term> {
+    let literals: Vec<_> = clause
+        .iter()
+        .map(|&lit| {
+            let var = variables[lit.unsigned_abs() as usize - 1].clone();
+            if lit > 0 {
+                var
+            } else {
+                build_term!(pool, (not { var }))
+            }
+        })
+        .collect();
+    build_term!(pool, (cl[literals]))
+}
+
+fn check_lines(cnf: Cnf, lines: Vec<ProofLine>) -> Result<CheckedSatProof, SatProofError> {
+    let mut pool = PrimitivePool::new();
+
+    // Proofs may introduce new variables (in RAT lemmas), so we consider all variables that appear
+    // in the proof
+    let max_var = lines
+        .iter()
+        .flat_map(|line| match line {
+            ProofLine::Add(c) | ProofLine::Delete(c) => c.iter(),
+        })
+        .map(|lit| lit.unsigned_abs() as usize)
+        .fold(cnf.num_vars, usize::max);
+    let bool_sort = pool.add(Term::Sort(Sort::Bool));
+    let variables: Vec<_> = (1..=max_var)
+        .map(|i| pool.add(Term::new_var(format!("x{}", i), bool_sort.clone())))
+        .collect();
+
+    let clauses: Vec<_> = cnf
+        .clauses
+        .iter()
+        .map(|c| clause_to_term(&mut pool, &variables, c))
+        .collect();
+
+    // The engine identifies clauses by their term, so a deletion must use exactly the same term as
+    // the corresponding addition, and must only be issued when no other copy of the clause is alive
+    let mut alive: HashMap<Vec<i64>, Vec<Rc<Term>>> = HashMap::new();
+    let key = |clause: &DimacsClause| {
+        let mut key = clause.clone();
+        key.sort_unstable();
+        key.dedup();
+        key
+    };
+    for (clause, term) in cnf.clauses.iter().zip(&clauses) {
+        alive.entry(key(clause)).or_default().push(term.clone());
+    }
+
+    let mut args = Vec::new();
+    let mut derived_empty_clause = false;
+    for line in &lines {
+        match line {
+            ProofLine::Add(clause) => {
+                let term = clause_to_term(&mut pool, &variables, clause);
+                alive.entry(key(clause)).or_default().push(term.clone());
+                args.push(term);
+                if clause.is_empty() {
+                    derived_empty_clause = true;
+                    break;
+                }
+            }
+            ProofLine::Delete(clause) => {
+                // Deleting a clause that doesn't exist has no effect, so we just ignore it
+                let Some(copies) = alive.get_mut(&key(clause)) else {
+                    continue;
+                };
+                let Some(term) = copies.pop() else {
+                    continue;
+                };
+                if !copies.contains(&term) {
+                    args.push(build_term!(pool, (delete { term })));
+                }
+            }
+        }
+    }
+    // If the proof doesn't explicitly add the empty clause, it must follow from unit propagation
+    if !derived_empty_clause {
+        args.push(build_term!(pool, (cl[Vec::new()])));
+    }
+
+    let conclusion = build_term!(pool, (cl[Vec::new()]));
+    let story = check_drup(&mut pool, conclusion, &clauses, &args, true)?;
+    Ok(CheckedSatProof { pool, variables, clauses, story })
+}
+
+impl CheckedSatProof {
+    /// Converts the checked proof into an Alethe proof, together with the problem that declares the
+    /// variables and asserts the original clauses. Each clause is introduced as an `assume` command,
+    /// and each lemma is derived by `resolution` (and possibly `weakening`) steps. This fails if the
+    /// proof contains RAT lemmas, as those can't be expressed by resolution.
+    pub fn into_alethe(mut self) -> Result<(Problem, Proof, PrimitivePool), SatProofError> {
+        let pool = &mut self.pool;
+        let mut problem = Problem::new();
+        problem.prelude.logic = Some("QF_UF".to_owned());
+        let bool_sort = pool.add(Term::Sort(Sort::Bool));
+        problem.prelude.function_declarations = self
+            .variables
+            .iter()
+            .map(|v| (v.as_var().unwrap().to_owned(), bool_sort.clone()))
+            .collect();
+
+        // All clauses are assumed first, and then converted into `cl` clauses if needed
+        let mut commands: Vec<_> = self
+            .clauses
+            .iter()
+            .enumerate()
+            .map(|(i, clause)| {
+                let literals = match_term!((cl ...) = clause).unwrap();
+                let term = match literals {
+                    [] => pool.bool_false(),
+                    [l] => l.clone(),
+                    _ => build_term!(pool, (or[literals.to_vec()])),
+                };
+                problem.premises.insert(term.clone());
+                ProofCommand::Assume { id: format!("a{}", i + 1), term }
+            })
+            .collect();
+
+        let step = |id: String, clause, rule: &str, premises, args| {
+            ProofCommand::Step(ProofStep {
+                id,
+                clause,
+                rule: rule.to_owned(),
+                premises,
+                args,
+                discharge: Vec::new(),
+            })
+        };
+        let mut positions = HashMap::new();
+        for (assume, clause) in self.clauses.iter().enumerate() {
+            let id = format!("c{}", assume + 1);
+            match match_term!((cl ...) = clause).unwrap() {
+                // An empty input clause is assumed as `false`, so we derive the empty clause from it
+                [] => {
+                    let f = pool.bool_false();
+                    let not_false = build_term!(pool, (not { f.clone() }));
+                    let t = pool.bool_true();
+                    let false_step = step(
+                        format!("{}.f", id),
+                        vec![not_false],
+                        "false",
+                        vec![],
+                        vec![],
+                    );
+                    commands.push(false_step);
+                    let premises = vec![(0, assume), (0, commands.len() - 1)];
+                    commands.push(step(id, vec![], "resolution", premises, vec![f, t]));
+                }
+                [_] => {
+                    positions.insert(hash_term(pool, clause), (0, assume));
+                    continue;
+                }
+                literals => {
+                    let or_step = step(id, literals.to_vec(), "or", vec![(0, assume)], vec![]);
+                    commands.push(or_step);
+                }
+            }
+            positions.insert(hash_term(pool, clause), (0, commands.len() - 1));
+        }
+
+        let derivations = story_to_resolution(pool, &self.story, positions.keys().copied())?;
+        for (i, derivation) in derivations.into_iter().enumerate() {
+            let mut previous = positions[&derivation.conflict];
+            for (j, s) in derivation.steps.into_iter().enumerate() {
+                let premises = s
+                    .premises
+                    .iter()
+                    .map(|p| match p {
+                        DerivationPremise::Clause(hash) => positions[hash],
+                        DerivationPremise::Previous => previous,
+                    })
+                    .collect();
+                commands.push(step(
+                    format!("t{}.{}", i + 1, j + 1),
+                    s.clause,
+                    s.rule,
+                    premises,
+                    s.args,
+                ));
+                previous = (0, commands.len() - 1);
+            }
+            positions.insert(derivation.hash, previous);
+        }
+
+        let proof = Proof {
+            constant_definitions: Vec::new(),
+            commands,
+        };
+        Ok((problem, proof, self.pool))
+    }
+}
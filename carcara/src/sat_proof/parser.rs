@@ -0,0 +1,183 @@
+//! Parsers for DIMACS CNF files and for DRAT and LRAT proofs.
+
+use super::SatProofError;
+use std::collections::HashMap;
+
+/// A clause, represented as a list of DIMACS literals.
+pub type DimacsClause = Vec<i64>;
+
+/// A CNF formula read from a DIMACS file.
+#[derive(Debug, Default)]
+pub struct Cnf {
+    /// The number of variables declared in the header.
+    pub num_vars: usize,
+
+    /// The clauses of the formula, in the order they appear in the file.
+    pub clauses: Vec<DimacsClause>,
+}
+
+/// A line of a clausal proof, after clause ids (in LRAT proofs) are resolved.
+#[derive(Debug, PartialEq, Eq)]
+pub enum ProofLine {
+    Add(DimacsClause),
+    Delete(DimacsClause),
+}
+
+/// Iterates over the whitespace-separated tokens of a text file, together with their line numbers.
+/// Comment lines, which start with `c`, are skipped.
+fn tokens(text: &str) -> impl Iterator<Item = (usize, &str)> {
+    text.lines()
+        .enumerate()
+        .filter(|(_, line)| !line.trim_start().starts_with('c'))
+        .flat_map(|(i, line)| line.split_whitespace().map(move |t| (i + 1, t)))
+}
+
+fn parse_number(line: usize, token: &str) -> Result<i64, SatProofError> {
+    token
+        .parse()
+        .map_err(|_| SatProofError::InvalidToken(line, token.to_owned()))
+}
+
+/// Reads literals from `tokens` until the terminating `0`.
+fn parse_clause<'a>(
+    line: usize,
+    tokens: &mut impl Iterator<Item = (usize, &'a str)>,
+) -> Result<DimacsClause, SatProofError> {
+    let mut clause = Vec::new();
+    loop {
+        let (line, token) = tokens
+            .next()
+            .ok_or(SatProofError::UnterminatedClause(line))?;
+        match parse_number(line, token)? {
+            0 => return Ok(clause),
+            lit => clause.push(lit),
+        }
+    }
+}
+
+pub fn parse_dimacs(text: &str) -> Result<Cnf, SatProofError> {
+    let mut tokens = tokens(text).peekable();
+
+    let (num_vars, num_clauses) = match tokens.next() {
+        Some((line, "p")) => {
+            let mut header = || tokens.next().map(|(_, t)| t);
+            let invalid_header = || SatProofError::InvalidHeader(line);
+            if header() != Some("cnf") {
+                return Err(invalid_header());
+            }
+            let num_vars = header().and_then(|t| t.parse().ok());
+            let num_clauses = header().and_then(|t| t.parse().ok());
+            num_vars.zip(num_clauses).ok_or_else(invalid_header)?
+        }
+        _ => return Err(SatProofError::MissingHeader),
+    };
+
+    let mut clauses = Vec::with_capacity(num_clauses);
+    // Some benchmark sets end their files with a `%` line, after which there is only garbage
+    while let Some(&(line, token)) = tokens.peek() {
+        if token == "%" {
+            break;
+        }
+        let clause = parse_clause(line, &mut tokens)?;
+        if let Some(&lit) = clause.iter().find(|l| l.unsigned_abs() as usize > num_vars) {
+            return Err(SatProofError::VariableOutOfRange(line, lit, num_vars));
+        }
+        clauses.push(clause);
+    }
+    if clauses.len() != num_clauses {
+        return Err(SatProofError::WrongNumberOfClauses(
+            num_clauses,
+            clauses.len(),
+        ));
+    }
+    Ok(Cnf { num_vars, clauses })
+}
+
+pub fn parse_drat(text: &str) -> Result<Vec<ProofLine>, SatProofError> {
+    let mut tokens = tokens(text).peekable();
+    let mut result = Vec::new();
+    while let Some(&(line, token)) = tokens.peek() {
+        if token == "d" {
+            tokens.next();
+            result.push(ProofLine::Delete(parse_clause(line, &mut tokens)?));
+        } else {
+            result.push(ProofLine::Add(parse_clause(line, &mut tokens)?));
+        }
+    }
+    Ok(result)
+}
+
+/// Parses a proof in the binary DRAT format. In this format, each line starts with the byte `a`
+/// (for additions) or `d` (for deletions), followed by the literals encoded as variable-length
+/// integers, and terminated by a zero byte. A literal `l` is encoded as `2 * |l|`, plus one if `l`
+/// is negative.
+pub fn parse_binary_drat(bytes: &[u8]) -> Result<Vec<ProofLine>, SatProofError> {
+    let mut bytes = bytes.iter().copied();
+    let mut result = Vec::new();
+    while let Some(marker) = bytes.next() {
+        let is_addition = match marker {
+            b'a' => true,
+            b'd' => false,
+            other => return Err(SatProofError::InvalidBinaryMarker(other)),
+        };
+        let mut clause = Vec::new();
+        loop {
+            let mut encoded: u64 = 0;
+            let mut shift = 0;
+            loop {
+                let byte = bytes.next().ok_or(SatProofError::TruncatedBinaryProof)?;
+                encoded |= u64::from(byte & 0x7f) << shift;
+                shift += 7;
+                if byte & 0x80 == 0 {
+                    break;
+                }
+                if shift >= 63 {
+                    return Err(SatProofError::TruncatedBinaryProof);
+                }
+            }
+            if encoded == 0 {
+                break;
+            }
+            let var = (encoded >> 1) as i64;
+            clause.push(if encoded & 1 == 1 { -var } else { var });
+        }
+        result.push(if is_addition {
+            ProofLine::Add(clause)
+        } else {
+            ProofLine::Delete(clause)
+        });
+    }
+    Ok(result)
+}
+
+/// Parses a proof in the LRAT format. The clause ids are resolved against the clauses of the
+/// original formula, which are implicitly numbered starting from 1, and against the clauses added
+/// in the proof itself. The hints of each lemma are ignored, as lemmas are checked again using
+/// unit propagation over all the clauses.
+pub fn parse_lrat(text: &str, cnf: &Cnf) -> Result<Vec<ProofLine>, SatProofError> {
+    let mut clauses: HashMap<u64, DimacsClause> = (1..).zip(cnf.clauses.iter().cloned()).collect();
+    let mut tokens = tokens(text).peekable();
+    let mut result = Vec::new();
+
+    while let Some((line, token)) = tokens.next() {
+        let id = parse_number(line, token)?;
+        let id =
+            u64::try_from(id).map_err(|_| SatProofError::InvalidToken(line, token.to_owned()))?;
+        if tokens.peek().map(|(_, t)| *t) == Some("d") {
+            tokens.next();
+            for deleted in parse_clause(line, &mut tokens)? {
+                let deleted = deleted.unsigned_abs();
+                let clause = clauses
+                    .remove(&deleted)
+                    .ok_or(SatProofError::UnknownClauseId(deleted))?;
+                result.push(ProofLine::Delete(clause));
+            }
+        } else {
+            let clause = parse_clause(line, &mut tokens)?;
+            parse_clause(line, &mut tokens)?; // The hints are ignored
+            clauses.insert(id, clause.clone());
+            result.push(ProofLine::Add(clause));
+        }
+    }
+    Ok(result)
+}
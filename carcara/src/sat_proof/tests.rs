@@ -0,0 +1,170 @@
+use super::*;
+use crate::checker;
+
+// All eight clauses over three variables, which is unsatisfiable
+const CNF: &str = "c all clauses over three variables
+p cnf 3 8
+1 2 3 0
+1 2 -3 0
+1 -2 3 0
+1 -2 -3 0
+-1 2 3 0
+-1 2 -3 0
+-1 -2 3 0
+-1 -2 -3 0
+";
+
+const DRAT: &str = "1 2 0
+d 1 2 3 0
+1 0
+-1 2 0
+2 0
+0
+";
+
+fn check(proof: &[u8], format: SatProofFormat) -> Result<CheckedSatProof, SatProofError> {
+    check_sat_proof(CNF.as_bytes(), proof, format)
+}
+
+fn encode_binary(lines: &[(u8, &[i64])]) -> Vec<u8> {
+    let mut result = Vec::new();
+    for (marker, clause) in lines {
+        result.push(*marker);
+        for &lit in *clause {
+            let mut encoded = 2 * lit.unsigned_abs() + u64::from(lit < 0);
+            while encoded >= 0x80 {
+                result.push((encoded & 0x7f) as u8 | 0x80);
+                encoded >>= 7;
+            }
+            result.push(encoded as u8);
+        }
+        result.push(0);
+    }
+    result
+}
+
+#[test]
+fn test_parse_dimacs() {
+    let cnf = parser::parse_dimacs(CNF).unwrap();
+    assert_eq!(cnf.num_vars, 3);
+    assert_eq!(cnf.clauses.len(), 8);
+    assert_eq!(cnf.clauses[3], vec![1, -2, -3]);
+
+    let cnf = parser::parse_dimacs("p cnf 2 2\n1\n-2 0 2\n0\n%\n0\n").unwrap();
+    assert_eq!(cnf.clauses, vec![vec![1, -2], vec![2]]);
+
+    let cases = [
+        ("1 2 0\n", "sat_proof.missing_header"),
+        ("p dnf 2 1\n1 2 0\n", "sat_proof.invalid_header"),
+        ("p cnf 2 2\n1 2 0\n", "sat_proof.wrong_number_of_clauses"),
+        ("p cnf 2 1\n1 3 0\n", "sat_proof.variable_out_of_range"),
+        ("p cnf 2 1\n1 x 0\n", "sat_proof.invalid_token"),
+        ("p cnf 2 1\n1 2\n", "sat_proof.unterminated_clause"),
+    ];
+    for (input, code) in cases {
+        assert_eq!(parser::parse_dimacs(input).unwrap_err().code(), code);
+    }
+}
+
+#[test]
+fn test_parse_binary_drat() {
+    let bytes = encode_binary(&[(b'a', &[1, -70]), (b'd', &[2])]);
+    let expected = vec![ProofLine::Add(vec![1, -70]), ProofLine::Delete(vec![2])];
+    assert_eq!(parser::parse_binary_drat(&bytes).unwrap(), expected);
+    assert_eq!(
+        SatProofFormat::detect_drat(&bytes),
+        SatProofFormat::BinaryDrat
+    );
+    assert_eq!(
+        SatProofFormat::detect_drat(DRAT.as_bytes()),
+        SatProofFormat::Drat
+    );
+
+    let truncated = &bytes[..3];
+    assert!(matches!(
+        parser::parse_binary_drat(truncated),
+        Err(SatProofError::TruncatedBinaryProof)
+    ));
+}
+
+#[test]
+fn test_check_drat() {
+    assert!(check(DRAT.as_bytes(), SatProofFormat::Drat).is_ok());
+
+    // The empty clause can be omitted, if it follows by unit propagation
+    assert!(check(b"1 2 0\n1 0\n2 0\n", SatProofFormat::Drat).is_ok());
+
+    // A lemma that is neither RUP nor RAT
+    let err = check(b"1 0\n0\n", SatProofFormat::Drat);
+    assert!(matches!(err, Err(SatProofError::Check(_))));
+
+    // Deleting clauses that are needed makes the proof invalid
+    let err = check(
+        b"d 1 2 3 0\nd 1 2 -3 0\n1 2 0\n1 0\n2 0\n0\n",
+        SatProofFormat::Drat,
+    );
+    assert!(matches!(err, Err(SatProofError::Check(_))));
+
+    // Deleting a clause that doesn't exist is ignored
+    assert!(check(b"d 1 0\n1 2 0\n1 0\n2 0\n0\n", SatProofFormat::Drat).is_ok());
+
+    let binary = encode_binary(&[
+        (b'a', &[1, 2]),
+        (b'd', &[1, 2, 3]),
+        (b'a', &[1]),
+        (b'a', &[-1, 2]),
+        (b'a', &[2]),
+        (b'a', &[]),
+    ]);
+    assert!(check(&binary, SatProofFormat::BinaryDrat).is_ok());
+}
+
+#[test]
+fn test_check_lrat() {
+    let lrat = "9 1 2 0 1 2 0
+9 d 1 0
+10 1 0 9 3 4 0
+11 2 0 10 5 6 7 8 0
+12 0 10 11 0
+";
+    assert!(check(lrat.as_bytes(), SatProofFormat::Lrat).is_ok());
+
+    let err = check(b"9 d 20 0\n", SatProofFormat::Lrat);
+    assert!(matches!(err, Err(SatProofError::UnknownClauseId(20))));
+}
+
+#[test]
+fn test_into_alethe() {
+    let proofs: [&[u8]; 3] = [
+        DRAT.as_bytes(),
+        b"1 2 0\n1 0\n2 0\n",
+        // The first lemma is derived by weakening an input clause
+        b"1 2 3 4 0\n1 2 0\n1 0\n2 0\n0\n",
+    ];
+    for proof in proofs {
+        let (problem, proof, mut pool) = check(proof, SatProofFormat::Drat)
+            .unwrap()
+            .into_alethe()
+            .unwrap();
+        let config = checker::Config::new().elaborated(true);
+        let result = checker::ProofChecker::new(&mut pool, config).check(&problem, &proof);
+        assert!(matches!(result, Ok(false)));
+    }
+
+    // A formula that contains the empty clause
+    let (problem, proof, mut pool) =
+        check_sat_proof("p cnf 1 2\n1 0\n0\n".as_bytes(), b"", SatProofFormat::Drat)
+            .unwrap()
+            .into_alethe()
+            .unwrap();
+    let config = checker::Config::new().elaborated(true);
+    let result = checker::ProofChecker::new(&mut pool, config).check(&problem, &proof);
+    assert!(matches!(result, Ok(false)));
+
+    // RAT lemmas can't be converted. The unit `x4` is RAT, as `x4` is a fresh variable
+    let checked = check(b"4 0\n1 2 0\n1 0\n2 0\n0\n", SatProofFormat::Drat).unwrap();
+    assert!(matches!(
+        checked.into_alethe(),
+        Err(SatProofError::Check(DrupFormatError::RatLemmaInResolution))
+    ));
+}
@@ -0,0 +1,429 @@
+//! An exact simplex procedure over the rationals, used to find Farkas certificates for sets of
+//! linear constraints that are unsatisfiable over the reals.
+//!
+//! This follows the general simplex algorithm of Dutertre and de Moura ("A Fast Linear-Arithmetic
+//! Solver for DPLL(T)", CAV 2006). Each constraint introduces a slack variable equal to its left
+//! side, and the constraint itself becomes a bound on that variable. Strict bounds are handled by
+//! using values of the form `c + kδ`, where `δ` is an infinitesimal positive number. Pivoting
+//! follows Bland's rule, so the procedure always terminates. When a bound can't be satisfied, the
+//! row of the tableau that caused the conflict gives the coefficients of the certificate.
+
+use rug::{Integer, Rational};
+use std::cmp::Ordering;
+
+/// The relation between the left and right sides of a constraint.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Relation {
+    Greater,
+    GreaterEq,
+    Equal,
+}
+
+/// A linear constraint of the form `a_1 * x_1 + ... + a_n * x_n <relation> d`. The variables are
+/// represented by their indices.
+#[derive(Debug, Clone)]
+pub struct Constraint {
+    pub coefficients: Vec<(usize, Rational)>,
+    pub relation: Relation,
+    pub constant: Rational,
+}
+
+/// A value of the form `c + kδ`, where `δ` is an infinitesimal positive number. The derived
+/// ordering is lexicographic, which is the intended ordering for these values.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+struct DeltaRational(Rational, Rational);
+
+impl DeltaRational {
+    fn zero() -> Self {
+        Self(Rational::new(), Rational::new())
+    }
+
+    fn add_mul(&mut self, other: &Self, scalar: &Rational) {
+        self.0 += Rational::from(&other.0 * scalar);
+        self.1 += Rational::from(&other.1 * scalar);
+    }
+
+    fn sub(&self, other: &Self) -> Self {
+        Self(
+            Rational::from(&self.0 - &other.0),
+            Rational::from(&self.1 - &other.1),
+        )
+    }
+
+    fn div(&self, scalar: &Rational) -> Self {
+        Self(
+            Rational::from(&self.0 / scalar),
+            Rational::from(&self.1 / scalar),
+        )
+    }
+}
+
+struct Tableau {
+    /// For each row, the coefficients of the nonbasic variables that define the basic variable of
+    /// that row. The coefficients of basic variables are always zero.
+    rows: Vec<Vec<Rational>>,
+
+    /// The basic variable of each row.
+    basic: Vec<usize>,
+
+    /// The current value of every variable.
+    values: Vec<DeltaRational>,
+
+    lower: Vec<Option<DeltaRational>>,
+    upper: Vec<Option<DeltaRational>>,
+}
+
+impl Tableau {
+    fn new(num_vars: usize, constraints: &[Constraint]) -> Self {
+        let total = num_vars + constraints.len();
+        let mut rows = Vec::with_capacity(constraints.len());
+        let mut lower = vec![None; total];
+        let mut upper = vec![None; total];
+        for (i, c) in constraints.iter().enumerate() {
+            let mut row = vec![Rational::new(); total];
+            for (var, coeff) in &c.coefficients {
+                row[*var] += coeff;
+            }
+            rows.push(row);
+
+            let slack = num_vars + i;
+            let bound = |delta: i32| DeltaRational(c.constant.clone(), Rational::from(delta));
+            match c.relation {
+                Relation::Greater => lower[slack] = Some(bound(1)),
+                Relation::GreaterEq => lower[slack] = Some(bound(0)),
+                Relation::Equal => {
+                    lower[slack] = Some(bound(0));
+                    upper[slack] = Some(bound(0));
+                }
+            }
+        }
+        Self {
+            rows,
+            basic: (num_vars..total).collect(),
+            values: vec![DeltaRational::zero(); total],
+            lower,
+            upper,
+        }
+    }
+
+    /// Exchanges the basic variable of row `r` with the nonbasic variable `entering`.
+    fn pivot(&mut self, r: usize, entering: usize) {
+        let leaving = self.basic[r];
+        let coeff = std::mem::take(&mut self.rows[r][entering]);
+
+        // Solve the row for the entering variable
+        let row = &mut self.rows[r];
+        for c in row.iter_mut() {
+            if *c != 0 {
+                *c /= &coeff;
+                *c = -std::mem::take(c);
+            }
+        }
+        row[leaving] = Rational::from(coeff.recip_ref());
+        self.basic[r] = entering;
+
+        // Substitute the entering variable in all other rows
+        let pivot_row = std::mem::take(&mut self.rows[r]);
+        for (i, row) in self.rows.iter_mut().enumerate() {
+            if i == r || row[entering] == 0 {
+                continue;
+            }
+            let factor = std::mem::take(&mut row[entering]);
+            for (c, p) in row.iter_mut().zip(&pivot_row) {
+                if *p != 0 {
+                    *c += Rational::from(p * &factor);
+                }
+            }
+        }
+        self.rows[r] = pivot_row;
+    }
+
+    /// Recomputes the values of all basic variables from the values of the nonbasic ones.
+    fn update_basic_values(&mut self) {
+        for (row, &b) in self.rows.iter().zip(&self.basic) {
+            let mut value = DeltaRational::zero();
+            for (var, coeff) in row.iter().enumerate() {
+                if *coeff != 0 {
+                    value.add_mul(&self.values[var], coeff);
+                }
+            }
+            self.values[b] = value;
+        }
+    }
+
+    fn can_increase(&self, var: usize) -> bool {
+        self.upper[var]
+            .as_ref()
+            .is_none_or(|u| self.values[var] < *u)
+    }
+
+    fn can_decrease(&self, var: usize) -> bool {
+        self.lower[var]
+            .as_ref()
+            .is_none_or(|l| self.values[var] > *l)
+    }
+
+    /// Runs the simplex procedure. Returns `None` if the bounds are satisfiable, or the index of
+    /// the row that shows that they are unsatisfiable.
+    fn check(&mut self) -> Option<usize> {
+        loop {
+            // Following Bland's rule, we pick the violated basic variable with the smallest index
+            let violated = (0..self.rows.len())
+                .filter_map(|r| {
+                    let b = self.basic[r];
+                    let value = &self.values[b];
+                    if self.lower[b].as_ref().is_some_and(|l| value < l) {
+                        Some((b, r, Ordering::Less))
+                    } else if self.upper[b].as_ref().is_some_and(|u| value > u) {
+                        Some((b, r, Ordering::Greater))
+                    } else {
+                        None
+                    }
+                })
+                .min_by_key(|(b, _, _)| *b);
+            let (_, r, direction) = violated?;
+
+            // We then pick the nonbasic variable with the smallest index that can be changed to
+            // bring the basic variable towards its bound
+            let entering = self.rows[r].iter().enumerate().position(|(var, coeff)| {
+                let increases = match coeff.cmp0() {
+                    Ordering::Equal => return false,
+                    Ordering::Greater => direction == Ordering::Less,
+                    Ordering::Less => direction == Ordering::Greater,
+                };
+                if increases {
+                    self.can_increase(var)
+                } else {
+                    self.can_decrease(var)
+                }
+            });
+            let Some(entering) = entering else {
+                return Some(r);
+            };
+
+            let b = self.basic[r];
+            let target = match direction {
+                Ordering::Less => self.lower[b].clone().unwrap(),
+                _ => self.upper[b].clone().unwrap(),
+            };
+            let theta = target.sub(&self.values[b]).div(&self.rows[r][entering]);
+            self.values[entering].add_mul(&theta, &Rational::from(1));
+            self.values[b] = target;
+            self.pivot(r, entering);
+            self.update_basic_values();
+        }
+    }
+
+    /// Finds a concrete value for the infinitesimal `δ` such that every bound is still satisfied,
+    /// and returns the resulting value of each of the first `num_vars` variables. This assumes all
+    /// bounds are currently satisfied.
+    fn model(&self, num_vars: usize) -> Vec<Rational> {
+        // For each bound, if `c + kδ >= c' + k'δ` holds because `c > c'`, we need `δ` to be at
+        // most `(c - c') / (k' - k)`
+        let mut delta = Rational::from(1);
+        let bounds = (self.values.iter().zip(&self.lower))
+            .filter_map(|(v, l)| Some((v, l.as_ref()?)))
+            .chain(
+                (self.upper.iter().zip(&self.values)).filter_map(|(u, v)| Some((u.as_ref()?, v))),
+            );
+        for (greater, smaller) in bounds {
+            if greater.0 > smaller.0 && greater.1 < smaller.1 {
+                let limit = Rational::from(&greater.0 - &smaller.0)
+                    / Rational::from(&smaller.1 - &greater.1);
+                delta = delta.min(limit);
+            }
+        }
+        self.values[..num_vars]
+            .iter()
+            .map(|v| Rational::from(&v.1 * &delta) + &v.0)
+            .collect()
+    }
+
+    /// Builds the Farkas certificate from the row `r`, which was found to be in conflict by
+    /// `Tableau::check`. The coefficients are scaled so they are the smallest possible integers.
+    fn certificate(&self, r: usize, num_vars: usize) -> Vec<Integer> {
+        // The conflicting row expresses its basic variable `b` as a combination of nonbasic slack
+        // variables that are all at their bounds. If `b` is below its lower bound, `b - Σ a_j * s_j`
+        // is zero, and combining the lower bound of `b` with the bounds of each `s_j` gives a
+        // contradiction. The case where `b` is above its upper bound is symmetric.
+        let b = self.basic[r];
+        let sign = if self.lower[b].as_ref().is_some_and(|l| self.values[b] < *l) {
+            -1
+        } else {
+            1
+        };
+        let mut result = vec![Rational::new(); self.rows.len()];
+        result[b - num_vars] = Rational::from(-sign);
+        for (var, coeff) in self.rows[r].iter().enumerate() {
+            if *coeff != 0 {
+                debug_assert!(var >= num_vars, "original variables are unbounded");
+                result[var - num_vars] = Rational::from(coeff * sign);
+            }
+        }
+
+        let lcm = (result.iter()).fold(Integer::from(1), |acc, c| acc.lcm(c.denom()));
+        let integers: Vec<_> = result
+            .into_iter()
+            .map(|c| c.numer() * Integer::from(&lcm / c.denom()))
+            .collect();
+        let gcd = (integers.iter()).fold(Integer::new(), Integer::gcd);
+        integers.into_iter().map(|c| c / &gcd).collect()
+    }
+}
+
+/// The result of the simplex procedure.
+#[derive(Debug)]
+pub enum Solution {
+    /// The constraints are satisfiable, and this is a value for each variable that satisfies
+    /// them.
+    Sat(Vec<Rational>),
+
+    /// The constraints are unsatisfiable over the reals, and this is a Farkas certificate for
+    /// them. That is, a coefficient for each constraint such that the linear combination of their
+    /// left sides is zero, and the same combination of the constraints is a contradiction: the
+    /// combination of their right sides is positive, or it is zero and some strict constraint has
+    /// a positive coefficient. The coefficients of inequalities are never negative.
+    Unsat(Vec<Integer>),
+}
+
+/// Decides whether a set of constraints over `num_vars` variables is satisfiable over the reals.
+pub fn solve(num_vars: usize, constraints: &[Constraint]) -> Solution {
+    let mut tableau = Tableau::new(num_vars, constraints);
+    match tableau.check() {
+        Some(r) => Solution::Unsat(tableau.certificate(r, num_vars)),
+        None => Solution::Sat(tableau.model(num_vars)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn constraint(coefficients: &[(usize, i32)], relation: Relation, constant: i32) -> Constraint {
+        Constraint {
+            coefficients: coefficients
+                .iter()
+                .map(|&(var, c)| (var, Rational::from(c)))
+                .collect(),
+            relation,
+            constant: Rational::from(constant),
+        }
+    }
+
+    /// Checks that `coefficients` is a valid certificate for the given constraints.
+    fn assert_is_certificate(
+        num_vars: usize,
+        constraints: &[Constraint],
+        coefficients: &[Integer],
+    ) {
+        let mut sum = vec![Rational::new(); num_vars];
+        let mut constant = Rational::new();
+        let mut is_strict = false;
+        for (c, a) in constraints.iter().zip(coefficients) {
+            if c.relation != Relation::Equal {
+                assert!(*a >= 0, "inequality has negative coefficient");
+            }
+            for (var, coeff) in &c.coefficients {
+                sum[*var] += Rational::from(coeff * a);
+            }
+            constant += Rational::from(&c.constant * a);
+            is_strict |= c.relation == Relation::Greater && *a > 0;
+        }
+        assert!(sum.iter().all(|c| *c == 0), "left side is not zero");
+        assert!(
+            constant > 0 || (constant == 0 && is_strict),
+            "not a contradiction"
+        );
+    }
+
+    /// Checks that `model` satisfies the given constraints.
+    fn assert_is_model(constraints: &[Constraint], model: &[Rational]) {
+        for c in constraints {
+            let mut value = Rational::new();
+            for (var, coeff) in &c.coefficients {
+                value += Rational::from(coeff * &model[*var]);
+            }
+            let satisfied = match c.relation {
+                Relation::Greater => value > c.constant,
+                Relation::GreaterEq => value >= c.constant,
+                Relation::Equal => value == c.constant,
+            };
+            assert!(satisfied, "constraint is not satisfied: {:?}", c);
+        }
+    }
+
+    #[test]
+    fn test_unsat() {
+        use Relation::*;
+        let cases = [
+            // x > 0, -x >= 0
+            (
+                1,
+                vec![
+                    constraint(&[(0, 1)], Greater, 0),
+                    constraint(&[(0, -1)], GreaterEq, 0),
+                ],
+            ),
+            // 0 > 0
+            (0, vec![constraint(&[], Greater, 0)]),
+            // x + y >= 2, x - y = 0, -x >= 0
+            (
+                2,
+                vec![
+                    constraint(&[(0, 1), (1, 1)], GreaterEq, 2),
+                    constraint(&[(0, 1), (1, -1)], Equal, 0),
+                    constraint(&[(0, -1)], GreaterEq, 0),
+                ],
+            ),
+            // 2x - 3y > 1, y >= x, x >= 0, y = 5, z >= 10
+            (
+                3,
+                vec![
+                    constraint(&[(0, 2), (1, -3)], Greater, 1),
+                    constraint(&[(1, 1), (0, -1)], GreaterEq, 0),
+                    constraint(&[(0, 1)], GreaterEq, 0),
+                    constraint(&[(1, 1)], Equal, 5),
+                    constraint(&[(2, 1)], GreaterEq, 10),
+                ],
+            ),
+        ];
+        for (num_vars, constraints) in cases {
+            match solve(num_vars, &constraints) {
+                Solution::Unsat(result) => assert_is_certificate(num_vars, &constraints, &result),
+                Solution::Sat(_) => panic!("constraints should be unsatisfiable"),
+            }
+        }
+    }
+
+    #[test]
+    fn test_sat() {
+        use Relation::*;
+        let cases = [
+            (0, vec![]),
+            (0, vec![constraint(&[], GreaterEq, 0)]),
+            // x > 0, -x > -1
+            (
+                1,
+                vec![
+                    constraint(&[(0, 1)], Greater, 0),
+                    constraint(&[(0, -1)], Greater, -1),
+                ],
+            ),
+            // 2x = 1, y - x >= 0, -y > -1
+            (
+                2,
+                vec![
+                    constraint(&[(0, 2)], Equal, 1),
+                    constraint(&[(1, 1), (0, -1)], GreaterEq, 0),
+                    constraint(&[(1, -1)], Greater, -1),
+                ],
+            ),
+        ];
+        for (num_vars, constraints) in cases {
+            match solve(num_vars, &constraints) {
+                Solution::Sat(model) => assert_is_model(&constraints, &model),
+                Solution::Unsat(_) => panic!("constraints should be satisfiable"),
+            }
+        }
+    }
+}
@@ -0,0 +1,113 @@
+#[test]
+fn array_read_over_write() {
+    test_cases! {
+        definitions = "
+            (declare-const a (Array Int Int))
+            (declare-const b (Array Int Int))
+            (declare-const i Int)
+            (declare-const j Int)
+            (declare-const e Int)
+        ",
+        "Simple working examples" {
+            "(step t1 (cl (= i j) (= (select (store a i e) j) (select a j)))
+                :rule array_read_over_write)": true,
+            "(step t1 (cl (= 1 (+ j 1)) (= (select (store a 1 2) (+ j 1)) (select a (+ j 1))))
+                :rule array_read_over_write)": true,
+        }
+        "Wrong indices in the disequality" {
+            "(step t1 (cl (= j i) (= (select (store a i e) j) (select a j)))
+                :rule array_read_over_write)": false,
+            "(step t1 (cl (= i e) (= (select (store a i e) j) (select a j)))
+                :rule array_read_over_write)": false,
+        }
+        "Select on the right-hand side is wrong" {
+            "(step t1 (cl (= i j) (= (select (store a i e) j) (select b j)))
+                :rule array_read_over_write)": false,
+            "(step t1 (cl (= i j) (= (select (store a i e) j) (select a i)))
+                :rule array_read_over_write)": false,
+        }
+        "Clause of the wrong form" {
+            "(step t1 (cl (= (select (store a i e) j) (select a j)))
+                :rule array_read_over_write)": false,
+            "(step t1 (cl (= (select (store a i e) j) (select a j)) (= i j))
+                :rule array_read_over_write)": false,
+        }
+    }
+}
+
+#[test]
+fn array_read_over_write_1() {
+    test_cases! {
+        definitions = "
+            (declare-const a (Array Int Int))
+            (declare-const i Int)
+            (declare-const j Int)
+            (declare-const e Int)
+        ",
+        "Simple working examples" {
+            "(step t1 (cl (= (select (store a i e) i) e)) :rule array_read_over_write_1)": true,
+            "(step t1 (cl (= (select (store a (+ i 1) 0) (+ i 1)) 0))
+                :rule array_read_over_write_1)": true,
+        }
+        "Different indices" {
+            "(step t1 (cl (= (select (store a i e) j) e)) :rule array_read_over_write_1)": false,
+        }
+        "Wrong result" {
+            "(step t1 (cl (= (select (store a i e) i) j)) :rule array_read_over_write_1)": false,
+            "(step t1 (cl (= (select (store a i e) i) (select a i)))
+                :rule array_read_over_write_1)": false,
+        }
+    }
+}
+
+#[test]
+fn array_const() {
+    test_cases! {
+        definitions = "
+            (declare-const a (Array Int Int))
+            (declare-const i Int)
+            (declare-const v Int)
+        ",
+        "Simple working examples" {
+            "(step t1 (cl (= (select ((as const (Array Int Int)) 0) i) 0)) :rule array_const)": true,
+            "(step t1 (cl (= (select ((as const (Array Int Int)) v) 5) v)) :rule array_const)": true,
+        }
+        "Wrong result" {
+            "(step t1 (cl (= (select ((as const (Array Int Int)) 0) i) 1)) :rule array_const)": false,
+        }
+        "Array is not a constant array" {
+            "(step t1 (cl (= (select a i) v)) :rule array_const)": false,
+        }
+    }
+}
+
+#[test]
+fn array_ext() {
+    test_cases! {
+        definitions = "
+            (declare-const a (Array Int Int))
+            (declare-const b (Array Int Int))
+            (declare-const c (Array Int Int))
+            (declare-const k Int)
+        ",
+        "Simple working examples" {
+            "(step t1 (cl (= a b) (not (= (select a (choice ((x Int)) (not (= (select a x) (select b x)))))
+                                          (select b (choice ((x Int)) (not (= (select a x) (select b x))))))))
+                :rule array_ext)": true,
+        }
+        "Index is not the extensionality witness" {
+            "(step t1 (cl (= a b) (not (= (select a k) (select b k)))) :rule array_ext)": false,
+            "(step t1 (cl (= a b) (not (= (select a (choice ((x Int)) (not (= (select a x) (select c x)))))
+                                          (select b (choice ((x Int)) (not (= (select a x) (select c x))))))))
+                :rule array_ext)": false,
+            "(step t1 (cl (= a b) (not (= (select a (choice ((x Int)) (not (= (select a x) (select b k)))))
+                                          (select b (choice ((x Int)) (not (= (select a x) (select b k))))))))
+                :rule array_ext)": false,
+        }
+        "Arrays don't match" {
+            "(step t1 (cl (= a c) (not (= (select a (choice ((x Int)) (not (= (select a x) (select b x)))))
+                                          (select b (choice ((x Int)) (not (= (select a x) (select b x))))))))
+                :rule array_ext)": false,
+        }
+    }
+}
@@ -52,3 +52,211 @@ fn extract() {
         }
     }
 }
+
+#[test]
+fn var_and_const() {
+    test_cases! {
+        definitions = "
+            (declare-fun x2 () (_ BitVec 2))
+        ",
+        "Variables" {
+            "(step t1 (cl (= x2 (@bbterm ((_ @bit_of 0) x2) ((_ @bit_of 1) x2)))) :rule bitblast_var)": true,
+            "(step t1 (cl (= x2 (@bbterm ((_ @bit_of 1) x2) ((_ @bit_of 0) x2)))) :rule bitblast_var)": false,
+            "(step t1 (cl (= x2 (@bbterm ((_ @bit_of 0) x2) false))) :rule bitblast_var)": false,
+        }
+        "Constants" {
+            "(step t1 (cl (= #b01 (@bbterm true false))) :rule bitblast_const)": true,
+            "(step t1 (cl (= #b0110 (@bbterm false true true false))) :rule bitblast_const)": true,
+            "(step t1 (cl (= (_ bv2 3) (@bbterm false true false))) :rule bitblast_const)": true,
+            "(step t1 (cl (= #b01 (@bbterm false true))) :rule bitblast_const)": false,
+            "(step t1 (cl (= x2 (@bbterm false true))) :rule bitblast_const)": false,
+        }
+    }
+}
+
+#[test]
+fn equal_and_ite() {
+    test_cases! {
+        definitions = "
+            (declare-fun a () Bool)
+            (declare-fun x2 () (_ BitVec 2))
+            (declare-fun y2 () (_ BitVec 2))
+        ",
+        "Equality" {
+            "(step t1 (cl (= (= x2 y2) (and (= ((_ @bit_of 0) x2) ((_ @bit_of 0) y2)) (= ((_ @bit_of 1) x2) ((_ @bit_of 1) y2))))) :rule bitblast_equal)": true,
+            "(step t1 (cl (= (= x2 y2) (and (= ((_ @bit_of 0) x2) ((_ @bit_of 0) y2))))) :rule bitblast_equal)": false,
+            "(step t1 (cl (= (= a a) (= a a))) :rule bitblast_equal)": false,
+        }
+        "Ite" {
+            "(step t1 (cl (= (ite a x2 y2) (@bbterm (ite a ((_ @bit_of 0) x2) ((_ @bit_of 0) y2)) (ite a ((_ @bit_of 1) x2) ((_ @bit_of 1) y2))))) :rule bitblast_ite)": true,
+            "(step t1 (cl (= (ite a x2 y2) (@bbterm (ite a ((_ @bit_of 0) y2) ((_ @bit_of 0) x2)) (ite a ((_ @bit_of 1) y2) ((_ @bit_of 1) x2))))) :rule bitblast_ite)": false,
+        }
+    }
+}
+
+#[test]
+fn bitwise() {
+    test_cases! {
+        definitions = "
+            (declare-fun x1 () (_ BitVec 1))
+            (declare-fun y1 () (_ BitVec 1))
+            (declare-fun x2 () (_ BitVec 2))
+            (declare-fun y2 () (_ BitVec 2))
+        ",
+        "bvnot" {
+            "(step t1 (cl (= (bvnot x2) (@bbterm (not ((_ @bit_of 0) x2)) (not ((_ @bit_of 1) x2))))) :rule bitblast_bvnot)": true,
+            "(step t1 (cl (= (bvnot x2) (@bbterm ((_ @bit_of 0) x2) (not ((_ @bit_of 1) x2))))) :rule bitblast_bvnot)": false,
+        }
+        "bvand, bvor and bvxor" {
+            "(step t1 (cl (= (bvand x2 y2) (@bbterm (and ((_ @bit_of 0) x2) ((_ @bit_of 0) y2)) (and ((_ @bit_of 1) x2) ((_ @bit_of 1) y2))))) :rule bitblast_bvand)": true,
+            "(step t1 (cl (= (bvand x1 y1 x1) (@bbterm (and ((_ @bit_of 0) x1) ((_ @bit_of 0) y1) ((_ @bit_of 0) x1))))) :rule bitblast_bvand)": true,
+            "(step t1 (cl (= (bvor x1 y1) (@bbterm (or ((_ @bit_of 0) x1) ((_ @bit_of 0) y1))))) :rule bitblast_bvor)": true,
+            "(step t1 (cl (= (bvxor x1 y1) (@bbterm (xor ((_ @bit_of 0) x1) ((_ @bit_of 0) y1))))) :rule bitblast_bvxor)": true,
+            "(step t1 (cl (= (bvor x1 y1) (@bbterm (and ((_ @bit_of 0) x1) ((_ @bit_of 0) y1))))) :rule bitblast_bvor)": false,
+            "(step t1 (cl (= (bvand x1 y1) (@bbterm (and ((_ @bit_of 0) x1) ((_ @bit_of 0) y1))))) :rule bitblast_bvor)": false,
+        }
+        "bvnand, bvnor and bvxnor" {
+            "(step t1 (cl (= (bvnand x1 y1) (@bbterm (not (and ((_ @bit_of 0) x1) ((_ @bit_of 0) y1)))))) :rule bitblast_bvnand)": true,
+            "(step t1 (cl (= (bvnor x1 y1) (@bbterm (not (or ((_ @bit_of 0) x1) ((_ @bit_of 0) y1)))))) :rule bitblast_bvnor)": true,
+            "(step t1 (cl (= (bvxnor x1 y1) (@bbterm (= ((_ @bit_of 0) x1) ((_ @bit_of 0) y1))))) :rule bitblast_bvxnor)": true,
+            "(step t1 (cl (= (bvxnor x1 y1) (@bbterm (not (xor ((_ @bit_of 0) x1) ((_ @bit_of 0) y1)))))) :rule bitblast_bvxnor)": false,
+        }
+        "bvcomp" {
+            "(step t1 (cl (= (bvcomp x2 y2) (@bbterm (and (= ((_ @bit_of 0) x2) ((_ @bit_of 0) y2)) (= ((_ @bit_of 1) x2) ((_ @bit_of 1) y2)))))) :rule bitblast_bvcomp)": true,
+            "(step t1 (cl (= (bvcomp x2 y2) (@bbterm (or (= ((_ @bit_of 0) x2) ((_ @bit_of 0) y2)) (= ((_ @bit_of 1) x2) ((_ @bit_of 1) y2)))))) :rule bitblast_bvcomp)": false,
+        }
+    }
+}
+
+#[test]
+fn arithmetic() {
+    test_cases! {
+        definitions = "
+            (declare-fun x1 () (_ BitVec 1))
+            (declare-fun y1 () (_ BitVec 1))
+            (declare-fun x2 () (_ BitVec 2))
+            (declare-fun y2 () (_ BitVec 2))
+        ",
+        "bvneg and bvsub" {
+            "(step t1 (cl (= (bvneg x1) (@bbterm (xor (xor (not ((_ @bit_of 0) x1)) false) true)))) :rule bitblast_bvneg)": true,
+            "(step t1 (cl (= (bvsub x1 y1) (@bbterm (xor (xor ((_ @bit_of 0) x1) (not ((_ @bit_of 0) y1))) true)))) :rule bitblast_bvsub)": true,
+            "(step t1 (cl (= (bvsub x1 y1) (@bbterm (xor (xor ((_ @bit_of 0) x1) ((_ @bit_of 0) y1)) false)))) :rule bitblast_bvsub)": false,
+        }
+        "bvmul" {
+            "(step t1 (cl (= (bvmul x2 y2) (@bbterm
+                (and ((_ @bit_of 0) x2) ((_ @bit_of 0) y2))
+                (xor (xor (and ((_ @bit_of 1) x2) ((_ @bit_of 0) y2)) (and ((_ @bit_of 0) x2) ((_ @bit_of 1) y2))) false))))
+                :rule bitblast_bvmul)": true,
+            "(step t1 (cl (= (bvmul x2 y2) (@bbterm
+                (and ((_ @bit_of 0) x2) ((_ @bit_of 0) y2))
+                (xor (and ((_ @bit_of 1) x2) ((_ @bit_of 0) y2)) (and ((_ @bit_of 0) x2) ((_ @bit_of 1) y2))))))
+                :rule bitblast_bvmul)": false,
+        }
+        "bvudiv and bvurem" {
+            "(step t1 (cl (= (bvudiv x1 y1) (@bbterm
+                (or (and false true) (and (xor false true)
+                    (or (and ((_ @bit_of 0) x1) (not ((_ @bit_of 0) y1)))
+                        (and (xor ((_ @bit_of 0) x1) (not ((_ @bit_of 0) y1))) true)))))))
+                :rule bitblast_bvudiv)": true,
+            "(step t1 (cl (= (bvurem x1 y1) (@bbterm
+                (ite (or (and false true) (and (xor false true)
+                        (or (and ((_ @bit_of 0) x1) (not ((_ @bit_of 0) y1)))
+                            (and (xor ((_ @bit_of 0) x1) (not ((_ @bit_of 0) y1))) true))))
+                    (xor (xor ((_ @bit_of 0) x1) (not ((_ @bit_of 0) y1))) true)
+                    ((_ @bit_of 0) x1)))))
+                :rule bitblast_bvurem)": true,
+            "(step t1 (cl (= (bvudiv x1 y1) (@bbterm (and ((_ @bit_of 0) x1) ((_ @bit_of 0) y1)))))
+                :rule bitblast_bvudiv)": false,
+        }
+    }
+}
+
+#[test]
+fn shifts() {
+    test_cases! {
+        definitions = "
+            (declare-fun x2 () (_ BitVec 2))
+            (declare-fun y2 () (_ BitVec 2))
+        ",
+        "bvshl" {
+            "(step t1 (cl (= (bvshl x2 y2) (@bbterm
+                (ite ((_ @bit_of 1) y2) false (ite ((_ @bit_of 0) y2) false ((_ @bit_of 0) x2)))
+                (ite ((_ @bit_of 1) y2) false (ite ((_ @bit_of 0) y2) ((_ @bit_of 0) x2) ((_ @bit_of 1) x2))))))
+                :rule bitblast_bvshl)": true,
+            "(step t1 (cl (= (bvshl x2 y2) (@bbterm
+                (ite ((_ @bit_of 0) y2) false ((_ @bit_of 0) x2))
+                (ite ((_ @bit_of 0) y2) ((_ @bit_of 0) x2) ((_ @bit_of 1) x2)))))
+                :rule bitblast_bvshl)": false,
+        }
+        "bvlshr and bvashr" {
+            "(step t1 (cl (= (bvlshr x2 y2) (@bbterm
+                (ite ((_ @bit_of 1) y2) false (ite ((_ @bit_of 0) y2) ((_ @bit_of 1) x2) ((_ @bit_of 0) x2)))
+                (ite ((_ @bit_of 1) y2) false (ite ((_ @bit_of 0) y2) false ((_ @bit_of 1) x2))))))
+                :rule bitblast_bvlshr)": true,
+            "(step t1 (cl (= (bvashr x2 y2) (@bbterm
+                (ite ((_ @bit_of 1) y2) ((_ @bit_of 1) x2) (ite ((_ @bit_of 0) y2) ((_ @bit_of 1) x2) ((_ @bit_of 0) x2)))
+                (ite ((_ @bit_of 1) y2) ((_ @bit_of 1) x2) (ite ((_ @bit_of 0) y2) ((_ @bit_of 1) x2) ((_ @bit_of 1) x2))))))
+                :rule bitblast_bvashr)": true,
+            "(step t1 (cl (= (bvashr x2 y2) (@bbterm
+                (ite ((_ @bit_of 1) y2) false (ite ((_ @bit_of 0) y2) ((_ @bit_of 1) x2) ((_ @bit_of 0) x2)))
+                (ite ((_ @bit_of 1) y2) false (ite ((_ @bit_of 0) y2) false ((_ @bit_of 1) x2))))))
+                :rule bitblast_bvashr)": false,
+        }
+    }
+}
+
+#[test]
+fn comparisons() {
+    test_cases! {
+        definitions = "
+            (declare-fun x1 () (_ BitVec 1))
+            (declare-fun y1 () (_ BitVec 1))
+            (declare-fun x2 () (_ BitVec 2))
+            (declare-fun y2 () (_ BitVec 2))
+        ",
+        "Unsigned" {
+            "(step t1 (cl (= (bvule x2 y2) (or (and (= ((_ @bit_of 1) x2) ((_ @bit_of 1) y2)) (or (not ((_ @bit_of 0) x2)) ((_ @bit_of 0) y2))) (and (not ((_ @bit_of 1) x2)) ((_ @bit_of 1) y2))))) :rule bitblast_ule)": true,
+            "(step t1 (cl (= (bvugt x1 y1) (and (not ((_ @bit_of 0) y1)) ((_ @bit_of 0) x1)))) :rule bitblast_ugt)": true,
+            "(step t1 (cl (= (bvuge x1 y1) (or (not ((_ @bit_of 0) y1)) ((_ @bit_of 0) x1)))) :rule bitblast_uge)": true,
+            "(step t1 (cl (= (bvugt x1 y1) (and (not ((_ @bit_of 0) x1)) ((_ @bit_of 0) y1)))) :rule bitblast_ugt)": false,
+        }
+        "Signed" {
+            "(step t1 (cl (= (bvslt x2 y2) (or (and (= ((_ @bit_of 1) x2) ((_ @bit_of 1) y2)) (and (not ((_ @bit_of 0) x2)) ((_ @bit_of 0) y2))) (and ((_ @bit_of 1) x2) (not ((_ @bit_of 1) y2)))))) :rule bitblast_slt)": true,
+            "(step t1 (cl (= (bvsle x1 y1) (or ((_ @bit_of 0) x1) (not ((_ @bit_of 0) y1))))) :rule bitblast_sle)": true,
+            "(step t1 (cl (= (bvsgt x1 y1) (and ((_ @bit_of 0) y1) (not ((_ @bit_of 0) x1))))) :rule bitblast_sgt)": true,
+            "(step t1 (cl (= (bvsge x1 y1) (or ((_ @bit_of 0) y1) (not ((_ @bit_of 0) x1))))) :rule bitblast_sge)": true,
+            "(step t1 (cl (= (bvslt x2 y2) (or (and (= ((_ @bit_of 1) x2) ((_ @bit_of 1) y2)) (and (not ((_ @bit_of 0) x2)) ((_ @bit_of 0) y2))) (and (not ((_ @bit_of 1) x2)) ((_ @bit_of 1) y2))))) :rule bitblast_slt)": false,
+        }
+    }
+}
+
+#[test]
+fn structural() {
+    test_cases! {
+        definitions = "
+            (declare-fun x1 () (_ BitVec 1))
+            (declare-fun x2 () (_ BitVec 2))
+            (declare-fun y2 () (_ BitVec 2))
+            (declare-fun x3 () (_ BitVec 3))
+        ",
+        "concat" {
+            "(step t1 (cl (= (concat x1 y2) (@bbterm ((_ @bit_of 0) y2) ((_ @bit_of 1) y2) ((_ @bit_of 0) x1)))) :rule bitblast_concat)": true,
+            "(step t1 (cl (= (concat x1 y2) (@bbterm ((_ @bit_of 0) x1) ((_ @bit_of 0) y2) ((_ @bit_of 1) y2)))) :rule bitblast_concat)": false,
+        }
+        "Extensions" {
+            "(step t1 (cl (= ((_ zero_extend 1) x1) (@bbterm ((_ @bit_of 0) x1) false))) :rule bitblast_zero_extend)": true,
+            "(step t1 (cl (= ((_ sign_extend 2) x1) (@bbterm ((_ @bit_of 0) x1) ((_ @bit_of 0) x1) ((_ @bit_of 0) x1)))) :rule bitblast_sign_extend)": true,
+            "(step t1 (cl (= ((_ sign_extend 1) x1) (@bbterm ((_ @bit_of 0) x1) false))) :rule bitblast_sign_extend)": false,
+        }
+        "repeat" {
+            "(step t1 (cl (= ((_ repeat 2) x2) (@bbterm ((_ @bit_of 0) x2) ((_ @bit_of 1) x2) ((_ @bit_of 0) x2) ((_ @bit_of 1) x2)))) :rule bitblast_repeat)": true,
+            "(step t1 (cl (= ((_ repeat 2) x2) (@bbterm ((_ @bit_of 0) x2) ((_ @bit_of 0) x2) ((_ @bit_of 1) x2) ((_ @bit_of 1) x2)))) :rule bitblast_repeat)": false,
+        }
+        "Rotations" {
+            "(step t1 (cl (= ((_ rotate_left 1) x3) (@bbterm ((_ @bit_of 2) x3) ((_ @bit_of 0) x3) ((_ @bit_of 1) x3)))) :rule bitblast_rotate_left)": true,
+            "(step t1 (cl (= ((_ rotate_left 4) x3) (@bbterm ((_ @bit_of 2) x3) ((_ @bit_of 0) x3) ((_ @bit_of 1) x3)))) :rule bitblast_rotate_left)": true,
+            "(step t1 (cl (= ((_ rotate_right 1) x3) (@bbterm ((_ @bit_of 1) x3) ((_ @bit_of 2) x3) ((_ @bit_of 0) x3)))) :rule bitblast_rotate_right)": true,
+            "(step t1 (cl (= ((_ rotate_right 1) x3) (@bbterm ((_ @bit_of 2) x3) ((_ @bit_of 0) x3) ((_ @bit_of 1) x3)))) :rule bitblast_rotate_right)": false,
+        }
+    }
+}
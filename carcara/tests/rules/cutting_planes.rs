@@ -26,6 +26,10 @@ fn cp_addition() {
             r#"(assume c1 (>= (+ (* 1 x1) (* 2 x2)) 4))
                (assume c2 (>= (+ (* 1 (- 1 x1)) (* 2 (- 1 x2))) 0))
                (step t1 (cl (>= 0 1)) :rule cp_addition :premises (c1 c2))"#: true,
+
+            r#"(assume c1 (>= (* 1 x1) 0))
+               (assume c2 (>= (* 1 (- 1 x1)) 0))
+               (step t1 (cl (>= 0 (- 1))) :rule cp_addition :premises (c1 c2))"#: true,
         }
         "Simple working examples" {
             r#"(assume c1 (>= (* 1 x1) 1))
@@ -0,0 +1,260 @@
+#[test]
+fn dt_split() {
+    test_cases! {
+        definitions = "
+            (declare-datatype Color ((red) (green) (blue)))
+            (declare-datatypes ((Tree 0) (Forest 0)) (
+                ((leaf (val Int)) (node (children Forest)))
+                ((nil) (cons (head Tree) (tail Forest)))
+            ))
+            (declare-datatypes ((List 1)) ((par (T) ((empty) (ins (hd T) (tl (List T)))))))
+            (declare-const c Color)
+            (declare-const t Tree)
+            (declare-const f Forest)
+            (declare-const l (List Int))
+        ",
+        "Simple working examples" {
+            "(step t1 (cl ((_ is red) c) ((_ is green) c) ((_ is blue) c)) :rule dt_split)": true,
+            "(step t1 (cl ((_ is leaf) t) ((_ is node) t)) :rule dt_split)": true,
+            "(step t1 (cl ((_ is empty) l) ((_ is ins) l)) :rule dt_split)": true,
+        }
+        "Missing or extra constructors" {
+            "(step t1 (cl ((_ is red) c) ((_ is green) c)) :rule dt_split)": false,
+            "(step t1 (cl ((_ is leaf) t) ((_ is node) t) ((_ is leaf) t)) :rule dt_split)": false,
+        }
+        "Wrong order of constructors" {
+            "(step t1 (cl ((_ is green) c) ((_ is red) c) ((_ is blue) c)) :rule dt_split)": false,
+        }
+        "Testers on different terms" {
+            "(step t1 (cl ((_ is leaf) t) ((_ is node) (node f))) :rule dt_split)": false,
+        }
+        "Literal is not a tester" {
+            "(step t1 (cl (= c red) ((_ is green) c) ((_ is blue) c)) :rule dt_split)": false,
+        }
+    }
+}
+
+#[test]
+fn dt_inst() {
+    test_cases! {
+        definitions = "
+            (declare-datatype Color ((red) (green) (blue)))
+            (declare-datatype Pair ((pair (first Int) (second Int))))
+            (declare-datatypes ((Tree 0) (Forest 0)) (
+                ((leaf (val Int)) (node (children Forest)))
+                ((nil) (cons (head Tree) (tail Forest)))
+            ))
+            (declare-datatypes ((List 1)) ((par (T) ((empty) (ins (hd T) (tl (List T)))))))
+            (declare-const c Color)
+            (declare-const p Pair)
+            (declare-const f Forest)
+            (declare-const l (List Int))
+            (declare-const b Int)
+        ",
+        "Simple working examples" {
+            "(step t1 (cl (= ((_ is red) c) (= c red))) :rule dt_inst)": true,
+            "(step t1 (cl (= ((_ is pair) p) (= p (pair (first p) (second p))))) :rule dt_inst)": true,
+            "(step t1 (cl (= ((_ is cons) f) (= f (cons (head f) (tail f))))) :rule dt_inst)": true,
+            "(step t1 (cl (= ((_ is ins) l) (= l (ins (hd l) (tl l))))) :rule dt_inst)": true,
+        }
+        "Wrong constructor" {
+            "(step t1 (cl (= ((_ is red) c) (= c green))) :rule dt_inst)": false,
+            "(step t1 (cl (= ((_ is nil) f) (= f (cons (head f) (tail f))))) :rule dt_inst)": false,
+        }
+        "Wrong selectors" {
+            "(step t1 (cl (= ((_ is pair) p) (= p (pair (second p) (first p))))) :rule dt_inst)": false,
+            "(step t1 (cl (= ((_ is pair) p) (= p (pair (first p) b)))) :rule dt_inst)": false,
+        }
+        "Selectors applied to a different term" {
+            "(step t1 (cl (= ((_ is cons) f) (= f (cons (head f) (tail nil))))) :rule dt_inst)": false,
+        }
+    }
+}
+
+#[test]
+fn dt_collapse_selector() {
+    test_cases! {
+        definitions = "
+            (declare-datatype Pair ((pair (first Int) (second Int))))
+            (declare-datatypes ((Tree 0) (Forest 0)) (
+                ((leaf (val Int)) (node (children Forest)))
+                ((nil) (cons (head Tree) (tail Forest)))
+            ))
+            (declare-datatypes ((List 1)) ((par (T) ((empty) (ins (hd T) (tl (List T)))))))
+            (declare-const p Pair)
+            (declare-const t Tree)
+            (declare-const f Forest)
+            (declare-const l (List Int))
+            (declare-const a Int)
+            (declare-const b Int)
+        ",
+        "Simple working examples" {
+            "(step t1 (cl (= (first (pair a b)) a)) :rule dt_collapse_selector)": true,
+            "(step t1 (cl (= (second (pair a b)) b)) :rule dt_collapse_selector)": true,
+            "(step t1 (cl (= (tail (cons t nil)) nil)) :rule dt_collapse_selector)": true,
+            "(step t1 (cl (= (hd (ins a l)) a)) :rule dt_collapse_selector)": true,
+        }
+        "Wrong result" {
+            "(step t1 (cl (= (first (pair a b)) b)) :rule dt_collapse_selector)": false,
+        }
+        "Selector of a different constructor" {
+            "(step t1 (cl (= (val (node f)) a)) :rule dt_collapse_selector)": false,
+        }
+        "Argument is not a constructor application" {
+            "(step t1 (cl (= (first p) a)) :rule dt_collapse_selector)": false,
+        }
+    }
+}
+
+#[test]
+fn dt_collapse_tester() {
+    test_cases! {
+        definitions = "
+            (declare-datatype Color ((red) (green) (blue)))
+            (declare-datatypes ((Tree 0) (Forest 0)) (
+                ((leaf (val Int)) (node (children Forest)))
+                ((nil) (cons (head Tree) (tail Forest)))
+            ))
+            (declare-datatypes ((List 1)) ((par (T) ((empty) (ins (hd T) (tl (List T)))))))
+            (declare-const c Color)
+            (declare-const f Forest)
+            (declare-const l (List Int))
+            (declare-const a Int)
+        ",
+        "Simple working examples" {
+            "(step t1 (cl (= ((_ is red) red) true)) :rule dt_collapse_tester)": true,
+            "(step t1 (cl (= ((_ is red) blue) false)) :rule dt_collapse_tester)": true,
+            "(step t1 (cl (= ((_ is leaf) (node f)) false)) :rule dt_collapse_tester)": true,
+            "(step t1 (cl (= ((_ is ins) (ins a l)) true)) :rule dt_collapse_tester)": true,
+        }
+        "Wrong result" {
+            "(step t1 (cl (= ((_ is red) red) false)) :rule dt_collapse_tester)": false,
+            "(step t1 (cl (= ((_ is leaf) (node f)) true)) :rule dt_collapse_tester)": false,
+        }
+        "Argument is not a constructor application" {
+            "(step t1 (cl (= ((_ is red) c) true)) :rule dt_collapse_tester)": false,
+        }
+    }
+}
+
+#[test]
+fn dt_collapse_tester_singleton() {
+    test_cases! {
+        definitions = "
+            (declare-datatype Color ((red) (green) (blue)))
+            (declare-datatype Pair ((pair (first Int) (second Int))))
+            (declare-const c Color)
+            (declare-const p Pair)
+        ",
+        "Simple working examples" {
+            "(step t1 (cl (= ((_ is pair) p) true)) :rule dt_collapse_tester_singleton)": true,
+        }
+        "Datatype with more than one constructor" {
+            "(step t1 (cl (= ((_ is red) c) true)) :rule dt_collapse_tester_singleton)": false,
+        }
+        "Wrong result" {
+            "(step t1 (cl (= ((_ is pair) p) false)) :rule dt_collapse_tester_singleton)": false,
+        }
+    }
+}
+
+#[test]
+fn dt_unif() {
+    test_cases! {
+        definitions = "
+            (declare-datatype Pair ((pair (first Int) (second Int))))
+            (declare-datatypes ((Tree 0) (Forest 0)) (
+                ((leaf (val Int)) (node (children Forest)))
+                ((nil) (cons (head Tree) (tail Forest)))
+            ))
+            (declare-const t Tree)
+            (declare-const f Forest)
+            (declare-const a Int)
+            (declare-const b Int)
+        ",
+        "Simple working examples" {
+            "(step t1 (cl (not (= (pair a b) (pair b a))) (= a b)) :rule dt_unif)": true,
+            "(step t1 (cl (not (= (pair a 1) (pair b 2))) (= 1 2)) :rule dt_unif)": true,
+            "(step t1 (cl (not (= (cons t f) (cons t nil))) (= f nil)) :rule dt_unif)": true,
+        }
+        "Different constructors" {
+            "(step t1 (cl (not (= (leaf a) (node f))) (= a b)) :rule dt_unif)": false,
+        }
+        "Equality is not between corresponding arguments" {
+            "(step t1 (cl (not (= (pair a 1) (pair b 2))) (= a 2)) :rule dt_unif)": false,
+            "(step t1 (cl (not (= (pair a 1) (pair b 2))) (= b a)) :rule dt_unif)": false,
+        }
+    }
+}
+
+#[test]
+fn dt_clash() {
+    test_cases! {
+        definitions = "
+            (declare-datatype Color ((red) (green) (blue)))
+            (declare-datatypes ((Tree 0) (Forest 0)) (
+                ((leaf (val Int)) (node (children Forest)))
+                ((nil) (cons (head Tree) (tail Forest)))
+            ))
+            (declare-const c Color)
+            (declare-const t Tree)
+            (declare-const f Forest)
+            (declare-const a Int)
+            (declare-const b Int)
+        ",
+        "Simple working examples" {
+            "(step t1 (cl (not (= red blue))) :rule dt_clash)": true,
+            "(step t1 (cl (not (= (leaf a) (node f)))) :rule dt_clash)": true,
+            "(step t1 (cl (not (= nil (cons t f)))) :rule dt_clash)": true,
+        }
+        "Same constructor" {
+            "(step t1 (cl (not (= (leaf a) (leaf b)))) :rule dt_clash)": false,
+        }
+        "Term is not a constructor application" {
+            "(step t1 (cl (not (= c blue))) :rule dt_clash)": false,
+        }
+        "Constructor shadowed by a variable" {
+            "(anchor :step t1 :args ((red Color)))
+            (step t1.t1 (cl (not (= red blue))) :rule dt_clash)
+            (step t1 (cl) :rule hole)": false,
+
+            "(anchor :step t1 :args ((:= (red Color) blue)))
+            (step t1.t1 (cl (not (= red blue))) :rule dt_clash)
+            (step t1 (cl) :rule hole)": false,
+        }
+        "Constructors inside subproofs" {
+            "(anchor :step t1 :args ((blue Int)))
+            (step t1.t1 (cl (not (= red green))) :rule dt_clash)
+            (step t1 (cl) :rule hole)": true,
+        }
+    }
+}
+
+#[test]
+fn dt_cycle() {
+    test_cases! {
+        definitions = "
+            (declare-datatypes ((Tree 0) (Forest 0)) (
+                ((leaf (val Int)) (node (children Forest)))
+                ((nil) (cons (head Tree) (tail Forest)))
+            ))
+            (declare-datatypes ((List 1)) ((par (T) ((empty) (ins (hd T) (tl (List T)))))))
+            (declare-const t Tree)
+            (declare-const f Forest)
+            (declare-const l (List Int))
+            (declare-const a Int)
+        ",
+        "Simple working examples" {
+            "(step t1 (cl (not (= f (cons t f)))) :rule dt_cycle)": true,
+            "(step t1 (cl (not (= (ins a l) l))) :rule dt_cycle)": true,
+            "(step t1 (cl (not (= t (node (cons t nil))))) :rule dt_cycle)": true,
+        }
+        "Term does not occur under constructors" {
+            "(step t1 (cl (not (= f (cons t nil)))) :rule dt_cycle)": false,
+            "(step t1 (cl (not (= f (tail (cons t f))))) :rule dt_cycle)": false,
+        }
+        "Term is not a proper subterm" {
+            "(step t1 (cl (not (= f f))) :rule dt_cycle)": false,
+        }
+    }
+}
@@ -60,6 +60,21 @@ fn la_generic() {
             "(step t1 (cl (< (+ a b) 1.0) (> (+ a b c) 0.0))
                 :rule la_generic :args (1.0 (- 1.0)))": false,
         }
+        "Coefficients are omitted" {
+            "(step t1 (cl (> a 0.0) (<= a 0.0)) :rule la_generic)": true,
+            "(step t1 (cl (<= 0.0 0.0)) :rule la_generic)": true,
+            "(step t1 (cl (not (<= (- a b) (- c 1.0))) (<= (+ 1.0 (- a c)) b))
+                :rule la_generic)": true,
+            "(step t1 (cl (not (= (+ a b) 2.0)) (not (>= a 1.5)) (not (>= b 1.0)))
+                :rule la_generic)": true,
+            "(step t1 (cl (not (<= a (* 2.0 b))) (not (< b (- c 3.0))) (not (<= (* 0.5 c) 1.0))
+                (not (>= (+ a 1.0) (* 2.0 c)))) :rule la_generic)": true,
+            "(step t1 (cl (not (<= a (* 2.0 b))) (not (< b (- c 3.0))) (not (<= (* 0.5 c) 1.0))
+                (not (>= (+ a 7.0) (* 2.0 c)))) :rule la_generic)": false,
+            "(step t1 (cl (not (< m n)) (not (< n (+ m 1)))) :rule la_generic)": false,
+            "(step t1 (cl (< (+ a b) 1.0) (> (+ a b c) 0.0)) :rule la_generic)": false,
+            "(step t1 (cl (ite (= a b) false true)) :rule la_generic)": false,
+        }
         "Edge case where the strengthening rules need to be stronger" {
             "(step t1 (cl
                 (not (<= (- 1) n))
@@ -87,10 +87,12 @@ macro_rules! test_cases {
     }};
 }
 
+pub(super) mod arrays;
 pub(super) mod bitvectors;
 pub(super) mod clausification;
 pub(super) mod congruence;
 pub(super) mod cutting_planes;
+pub(super) mod datatypes;
 pub(super) mod drup;
 pub(super) mod extras;
 pub(super) mod linear_arithmetic;
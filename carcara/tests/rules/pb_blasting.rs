@@ -3852,3 +3852,772 @@ fn pbblast_bvand_ith_bit() {
         }
     }
 }
+
+#[test]
+fn pbblast_bvadd_1() {
+    test_cases! {
+        definitions = "
+            (declare-const x1 (_ BitVec 1))
+            (declare-const y1 (_ BitVec 1))
+            (define-fun s0 () Int (+ ((_ @int_of 0) x1) ((_ @int_of 0) y1) 0))
+            (define-fun c1 () Int (choice ((z Int)) (and (>= s0 (* 2 z)) (>= (+ (* 2 z) 1) s0))))
+        ",
+        "Valid 1-bit ADD" {
+            r#"(step t1 (cl (= (bvadd x1 y1)
+                (@pbbterm (choice ((z Int)) (= s0 (+ z (* 2 c1)))))
+            )) :rule pbblast_bvadd)"#: true,
+        }
+        "Invalid 1-bit ADD" {
+            // The carry in should be 0
+            r#"(step t1 (cl (= (bvadd x1 y1)
+                (@pbbterm (choice ((z Int)) (= (+ ((_ @int_of 0) x1) ((_ @int_of 0) y1) 1) (+ z (* 2 c1)))))
+            )) :rule pbblast_bvadd)"#: false,
+
+            // The sum bit is missing the carry out
+            r#"(step t1 (cl (= (bvadd x1 y1)
+                (@pbbterm (choice ((z Int)) (= s0 z)))
+            )) :rule pbblast_bvadd)"#: false,
+        }
+    }
+}
+
+#[test]
+fn pbblast_bvadd_2() {
+    test_cases! {
+        definitions = "
+            (declare-const x2 (_ BitVec 2))
+            (declare-const y2 (_ BitVec 2))
+            (define-fun s0 () Int (+ ((_ @int_of 0) x2) ((_ @int_of 0) y2) 0))
+            (define-fun c1 () Int (choice ((z Int)) (and (>= s0 (* 2 z)) (>= (+ (* 2 z) 1) s0))))
+            (define-fun s1 () Int (+ ((_ @int_of 1) x2) ((_ @int_of 1) y2) c1))
+            (define-fun c2 () Int (choice ((z Int)) (and (>= s1 (* 2 z)) (>= (+ (* 2 z) 1) s1))))
+        ",
+        "Valid 2-bit ADD" {
+            r#"(step t1 (cl (= (bvadd x2 y2)
+                (@pbbterm (choice ((z Int)) (= s0 (+ z (* 2 c1))))
+                          (choice ((z Int)) (= s1 (+ z (* 2 c2)))))
+            )) :rule pbblast_bvadd)"#: true,
+        }
+        "Invalid 2-bit ADD" {
+            // The bits are in the wrong order
+            r#"(step t1 (cl (= (bvadd x2 y2)
+                (@pbbterm (choice ((z Int)) (= s1 (+ z (* 2 c2))))
+                          (choice ((z Int)) (= s0 (+ z (* 2 c1)))))
+            )) :rule pbblast_bvadd)"#: false,
+
+            // The second column ignores the carry
+            r#"(step t1 (cl (= (bvadd x2 y2)
+                (@pbbterm (choice ((z Int)) (= s0 (+ z (* 2 c1))))
+                          (choice ((z Int)) (= (+ ((_ @int_of 1) x2) ((_ @int_of 1) y2) 0) (+ z (* 2 c2)))))
+            )) :rule pbblast_bvadd)"#: false,
+        }
+    }
+}
+
+#[test]
+fn pbblast_bvadd_2_short_circuit() {
+    test_cases! {
+        definitions = "
+            (declare-const @x0 Int)
+            (declare-const @x1 Int)
+            (declare-const @y0 Int)
+            (declare-const @y1 Int)
+            (define-fun s0 () Int (+ @x0 @y0 0))
+            (define-fun c1 () Int (choice ((z Int)) (and (>= s0 (* 2 z)) (>= (+ (* 2 z) 1) s0))))
+            (define-fun s1 () Int (+ @x1 @y1 c1))
+            (define-fun c2 () Int (choice ((z Int)) (and (>= s1 (* 2 z)) (>= (+ (* 2 z) 1) s1))))
+        ",
+        "Valid 2-bit ADD (short-circuit)" {
+            r#"(step t1 (cl (= (bvadd (@pbbterm @x0 @x1) (@pbbterm @y0 @y1))
+                (@pbbterm (choice ((z Int)) (= s0 (+ z (* 2 c1))))
+                          (choice ((z Int)) (= s1 (+ z (* 2 c2)))))
+            )) :rule pbblast_bvadd)"#: true,
+        }
+        "Invalid 2-bit ADD (short-circuit)" {
+            r#"(step t1 (cl (= (bvadd (@pbbterm @x1 @x0) (@pbbterm @y0 @y1))
+                (@pbbterm (choice ((z Int)) (= s0 (+ z (* 2 c1))))
+                          (choice ((z Int)) (= s1 (+ z (* 2 c2)))))
+            )) :rule pbblast_bvadd)"#: false,
+        }
+    }
+}
+
+#[test]
+fn pbblast_bvadd_8() {
+    test_cases! {
+        definitions = "
+            (declare-const x8 (_ BitVec 8))
+            (declare-const y8 (_ BitVec 8))
+            (define-fun s0 () Int (+ ((_ @int_of 0) x8) ((_ @int_of 0) y8) 0))
+            (define-fun c1 () Int (choice ((z Int)) (and (>= s0 (* 2 z)) (>= (+ (* 2 z) 1) s0))))
+            (define-fun s1 () Int (+ ((_ @int_of 1) x8) ((_ @int_of 1) y8) c1))
+            (define-fun c2 () Int (choice ((z Int)) (and (>= s1 (* 2 z)) (>= (+ (* 2 z) 1) s1))))
+            (define-fun s2 () Int (+ ((_ @int_of 2) x8) ((_ @int_of 2) y8) c2))
+            (define-fun c3 () Int (choice ((z Int)) (and (>= s2 (* 2 z)) (>= (+ (* 2 z) 1) s2))))
+            (define-fun s3 () Int (+ ((_ @int_of 3) x8) ((_ @int_of 3) y8) c3))
+            (define-fun c4 () Int (choice ((z Int)) (and (>= s3 (* 2 z)) (>= (+ (* 2 z) 1) s3))))
+            (define-fun s4 () Int (+ ((_ @int_of 4) x8) ((_ @int_of 4) y8) c4))
+            (define-fun c5 () Int (choice ((z Int)) (and (>= s4 (* 2 z)) (>= (+ (* 2 z) 1) s4))))
+            (define-fun s5 () Int (+ ((_ @int_of 5) x8) ((_ @int_of 5) y8) c5))
+            (define-fun c6 () Int (choice ((z Int)) (and (>= s5 (* 2 z)) (>= (+ (* 2 z) 1) s5))))
+            (define-fun s6 () Int (+ ((_ @int_of 6) x8) ((_ @int_of 6) y8) c6))
+            (define-fun c7 () Int (choice ((z Int)) (and (>= s6 (* 2 z)) (>= (+ (* 2 z) 1) s6))))
+            (define-fun s7 () Int (+ ((_ @int_of 7) x8) ((_ @int_of 7) y8) c7))
+            (define-fun c8 () Int (choice ((z Int)) (and (>= s7 (* 2 z)) (>= (+ (* 2 z) 1) s7))))
+        ",
+        "Valid 8-bit ADD" {
+            r#"(step t1 (cl (= (bvadd x8 y8)
+                (@pbbterm (choice ((z Int)) (= s0 (+ z (* 2 c1))))
+                          (choice ((z Int)) (= s1 (+ z (* 2 c2))))
+                          (choice ((z Int)) (= s2 (+ z (* 2 c3))))
+                          (choice ((z Int)) (= s3 (+ z (* 2 c4))))
+                          (choice ((z Int)) (= s4 (+ z (* 2 c5))))
+                          (choice ((z Int)) (= s5 (+ z (* 2 c6))))
+                          (choice ((z Int)) (= s6 (+ z (* 2 c7))))
+                          (choice ((z Int)) (= s7 (+ z (* 2 c8)))))
+            )) :rule pbblast_bvadd)"#: true,
+        }
+        "Invalid 8-bit ADD" {
+            // The carry out of the last column is not a bit of the result
+            r#"(step t1 (cl (= (bvadd x8 y8)
+                (@pbbterm (choice ((z Int)) (= s0 (+ z (* 2 c1))))
+                          (choice ((z Int)) (= s1 (+ z (* 2 c2))))
+                          (choice ((z Int)) (= s2 (+ z (* 2 c3))))
+                          (choice ((z Int)) (= s3 (+ z (* 2 c4))))
+                          (choice ((z Int)) (= s4 (+ z (* 2 c5))))
+                          (choice ((z Int)) (= s5 (+ z (* 2 c6))))
+                          (choice ((z Int)) (= s6 (+ z (* 2 c7))))
+                          c8)
+            )) :rule pbblast_bvadd)"#: false,
+
+            // The fourth and fifth bits are swapped
+            r#"(step t1 (cl (= (bvadd x8 y8)
+                (@pbbterm (choice ((z Int)) (= s0 (+ z (* 2 c1))))
+                          (choice ((z Int)) (= s1 (+ z (* 2 c2))))
+                          (choice ((z Int)) (= s2 (+ z (* 2 c3))))
+                          (choice ((z Int)) (= s4 (+ z (* 2 c5))))
+                          (choice ((z Int)) (= s3 (+ z (* 2 c4))))
+                          (choice ((z Int)) (= s5 (+ z (* 2 c6))))
+                          (choice ((z Int)) (= s6 (+ z (* 2 c7))))
+                          (choice ((z Int)) (= s7 (+ z (* 2 c8)))))
+            )) :rule pbblast_bvadd)"#: false,
+        }
+    }
+}
+
+#[test]
+fn pbblast_bvadd_8_short_circuit() {
+    test_cases! {
+        definitions = "
+            (declare-const @x0 Int)
+            (declare-const @x1 Int)
+            (declare-const @x2 Int)
+            (declare-const @x3 Int)
+            (declare-const @x4 Int)
+            (declare-const @x5 Int)
+            (declare-const @x6 Int)
+            (declare-const @x7 Int)
+            (declare-const @y0 Int)
+            (declare-const @y1 Int)
+            (declare-const @y2 Int)
+            (declare-const @y3 Int)
+            (declare-const @y4 Int)
+            (declare-const @y5 Int)
+            (declare-const @y6 Int)
+            (declare-const @y7 Int)
+            (define-fun s0 () Int (+ @x0 @y0 0))
+            (define-fun c1 () Int (choice ((z Int)) (and (>= s0 (* 2 z)) (>= (+ (* 2 z) 1) s0))))
+            (define-fun s1 () Int (+ @x1 @y1 c1))
+            (define-fun c2 () Int (choice ((z Int)) (and (>= s1 (* 2 z)) (>= (+ (* 2 z) 1) s1))))
+            (define-fun s2 () Int (+ @x2 @y2 c2))
+            (define-fun c3 () Int (choice ((z Int)) (and (>= s2 (* 2 z)) (>= (+ (* 2 z) 1) s2))))
+            (define-fun s3 () Int (+ @x3 @y3 c3))
+            (define-fun c4 () Int (choice ((z Int)) (and (>= s3 (* 2 z)) (>= (+ (* 2 z) 1) s3))))
+            (define-fun s4 () Int (+ @x4 @y4 c4))
+            (define-fun c5 () Int (choice ((z Int)) (and (>= s4 (* 2 z)) (>= (+ (* 2 z) 1) s4))))
+            (define-fun s5 () Int (+ @x5 @y5 c5))
+            (define-fun c6 () Int (choice ((z Int)) (and (>= s5 (* 2 z)) (>= (+ (* 2 z) 1) s5))))
+            (define-fun s6 () Int (+ @x6 @y6 c6))
+            (define-fun c7 () Int (choice ((z Int)) (and (>= s6 (* 2 z)) (>= (+ (* 2 z) 1) s6))))
+            (define-fun s7 () Int (+ @x7 @y7 c7))
+            (define-fun c8 () Int (choice ((z Int)) (and (>= s7 (* 2 z)) (>= (+ (* 2 z) 1) s7))))
+        ",
+        "Valid 8-bit ADD (short-circuit)" {
+            r#"(step t1 (cl (= (bvadd (@pbbterm @x0 @x1 @x2 @x3 @x4 @x5 @x6 @x7)
+                                      (@pbbterm @y0 @y1 @y2 @y3 @y4 @y5 @y6 @y7))
+                (@pbbterm (choice ((z Int)) (= s0 (+ z (* 2 c1))))
+                          (choice ((z Int)) (= s1 (+ z (* 2 c2))))
+                          (choice ((z Int)) (= s2 (+ z (* 2 c3))))
+                          (choice ((z Int)) (= s3 (+ z (* 2 c4))))
+                          (choice ((z Int)) (= s4 (+ z (* 2 c5))))
+                          (choice ((z Int)) (= s5 (+ z (* 2 c6))))
+                          (choice ((z Int)) (= s6 (+ z (* 2 c7))))
+                          (choice ((z Int)) (= s7 (+ z (* 2 c8)))))
+            )) :rule pbblast_bvadd)"#: true,
+        }
+        "Invalid 8-bit ADD (short-circuit)" {
+            // The bits of the first argument are in the wrong order
+            r#"(step t1 (cl (= (bvadd (@pbbterm @x7 @x6 @x5 @x4 @x3 @x2 @x1 @x0)
+                                      (@pbbterm @y0 @y1 @y2 @y3 @y4 @y5 @y6 @y7))
+                (@pbbterm (choice ((z Int)) (= s0 (+ z (* 2 c1))))
+                          (choice ((z Int)) (= s1 (+ z (* 2 c2))))
+                          (choice ((z Int)) (= s2 (+ z (* 2 c3))))
+                          (choice ((z Int)) (= s3 (+ z (* 2 c4))))
+                          (choice ((z Int)) (= s4 (+ z (* 2 c5))))
+                          (choice ((z Int)) (= s5 (+ z (* 2 c6))))
+                          (choice ((z Int)) (= s6 (+ z (* 2 c7))))
+                          (choice ((z Int)) (= s7 (+ z (* 2 c8)))))
+            )) :rule pbblast_bvadd)"#: false,
+        }
+    }
+}
+
+#[test]
+fn pbblast_bvadd_3_args() {
+    test_cases! {
+        definitions = "
+            (declare-const x2 (_ BitVec 2))
+            (declare-const y2 (_ BitVec 2))
+            (declare-const w2 (_ BitVec 2))
+            (define-fun s0 () Int (+ ((_ @int_of 0) x2) ((_ @int_of 0) y2) 0))
+            (define-fun c1 () Int (choice ((z Int)) (and (>= s0 (* 2 z)) (>= (+ (* 2 z) 1) s0))))
+            (define-fun s1 () Int (+ ((_ @int_of 1) x2) ((_ @int_of 1) y2) c1))
+            (define-fun c2 () Int (choice ((z Int)) (and (>= s1 (* 2 z)) (>= (+ (* 2 z) 1) s1))))
+            (define-fun a0 () Int (choice ((z Int)) (= s0 (+ z (* 2 c1)))))
+            (define-fun a1 () Int (choice ((z Int)) (= s1 (+ z (* 2 c2)))))
+            (define-fun t0 () Int (+ a0 ((_ @int_of 0) w2) 0))
+            (define-fun d1 () Int (choice ((z Int)) (and (>= t0 (* 2 z)) (>= (+ (* 2 z) 1) t0))))
+            (define-fun t1 () Int (+ a1 ((_ @int_of 1) w2) d1))
+            (define-fun d2 () Int (choice ((z Int)) (and (>= t1 (* 2 z)) (>= (+ (* 2 z) 1) t1))))
+        ",
+        "Valid 2-bit ADD with three arguments" {
+            // The third argument is added to the sum of the first two
+            r#"(step t1 (cl (= (bvadd x2 y2 w2)
+                (@pbbterm (choice ((z Int)) (= t0 (+ z (* 2 d1))))
+                          (choice ((z Int)) (= t1 (+ z (* 2 d2)))))
+            )) :rule pbblast_bvadd)"#: true,
+        }
+        "Invalid 2-bit ADD with three arguments" {
+            // This is the blasting of `(bvadd x2 y2)`
+            r#"(step t1 (cl (= (bvadd x2 y2 w2) (@pbbterm a0 a1))) :rule pbblast_bvadd)"#: false,
+
+            // The arguments are added in the wrong order
+            r#"(step t1 (cl (= (bvadd w2 x2 y2)
+                (@pbbterm (choice ((z Int)) (= t0 (+ z (* 2 d1))))
+                          (choice ((z Int)) (= t1 (+ z (* 2 d2)))))
+            )) :rule pbblast_bvadd)"#: false,
+        }
+    }
+}
+
+#[test]
+fn pbblast_bvsub_2() {
+    test_cases! {
+        definitions = "
+            (declare-const x2 (_ BitVec 2))
+            (declare-const y2 (_ BitVec 2))
+            (define-fun s0 () Int (+ ((_ @int_of 0) x2) (- 1 ((_ @int_of 0) y2)) 1))
+            (define-fun c1 () Int (choice ((z Int)) (and (>= s0 (* 2 z)) (>= (+ (* 2 z) 1) s0))))
+            (define-fun s1 () Int (+ ((_ @int_of 1) x2) (- 1 ((_ @int_of 1) y2)) c1))
+            (define-fun c2 () Int (choice ((z Int)) (and (>= s1 (* 2 z)) (>= (+ (* 2 z) 1) s1))))
+        ",
+        "Valid 2-bit SUB" {
+            r#"(step t1 (cl (= (bvsub x2 y2)
+                (@pbbterm (choice ((z Int)) (= s0 (+ z (* 2 c1))))
+                          (choice ((z Int)) (= s1 (+ z (* 2 c2)))))
+            )) :rule pbblast_bvsub)"#: true,
+        }
+        "Invalid 2-bit SUB" {
+            // This is the blasting of `(bvadd x2 y2)`
+            r#"(step t1 (cl (= (bvsub x2 y2)
+                (@pbbterm (choice ((z Int)) (= (+ ((_ @int_of 0) x2) ((_ @int_of 0) y2) 0) (+ z (* 2 c1))))
+                          (choice ((z Int)) (= s1 (+ z (* 2 c2)))))
+            )) :rule pbblast_bvsub)"#: false,
+
+            r#"(step t1 (cl (= (bvsub y2 x2)
+                (@pbbterm (choice ((z Int)) (= s0 (+ z (* 2 c1))))
+                          (choice ((z Int)) (= s1 (+ z (* 2 c2)))))
+            )) :rule pbblast_bvsub)"#: false,
+        }
+    }
+}
+
+#[test]
+fn pbblast_bvneg_1() {
+    test_cases! {
+        definitions = "
+            (declare-const x1 (_ BitVec 1))
+            (define-fun s0 () Int (+ (- 1 ((_ @int_of 0) x1)) 0 1))
+            (define-fun c1 () Int (choice ((z Int)) (and (>= s0 (* 2 z)) (>= (+ (* 2 z) 1) s0))))
+        ",
+        "Valid 1-bit NEG" {
+            r#"(step t1 (cl (= (bvneg x1)
+                (@pbbterm (choice ((z Int)) (= s0 (+ z (* 2 c1)))))
+            )) :rule pbblast_bvneg)"#: true,
+        }
+        "Invalid 1-bit NEG" {
+            // The negation of a single bit is the bit itself, but that is not the expected form
+            r#"(step t1 (cl (= (bvneg x1) (@pbbterm ((_ @int_of 0) x1)))) :rule pbblast_bvneg)"#: false,
+        }
+    }
+}
+
+#[test]
+fn pbblast_bvneg_2() {
+    test_cases! {
+        definitions = "
+            (declare-const x2 (_ BitVec 2))
+            (define-fun s0 () Int (+ (- 1 ((_ @int_of 0) x2)) 0 1))
+            (define-fun c1 () Int (choice ((z Int)) (and (>= s0 (* 2 z)) (>= (+ (* 2 z) 1) s0))))
+            (define-fun s1 () Int (+ (- 1 ((_ @int_of 1) x2)) 0 c1))
+            (define-fun c2 () Int (choice ((z Int)) (and (>= s1 (* 2 z)) (>= (+ (* 2 z) 1) s1))))
+        ",
+        "Valid 2-bit NEG" {
+            r#"(step t1 (cl (= (bvneg x2)
+                (@pbbterm (choice ((z Int)) (= s0 (+ z (* 2 c1))))
+                          (choice ((z Int)) (= s1 (+ z (* 2 c2)))))
+            )) :rule pbblast_bvneg)"#: true,
+        }
+        "Invalid 2-bit NEG" {
+            // The carry in should be 1
+            r#"(step t1 (cl (= (bvneg x2)
+                (@pbbterm (choice ((z Int)) (= (+ (- 1 ((_ @int_of 0) x2)) 0 0) (+ z (* 2 c1))))
+                          (choice ((z Int)) (= s1 (+ z (* 2 c2)))))
+            )) :rule pbblast_bvneg)"#: false,
+        }
+    }
+}
+
+#[test]
+fn pbblast_bvmul_1() {
+    test_cases! {
+        definitions = "
+            (declare-const x1 (_ BitVec 1))
+            (declare-const y1 (_ BitVec 1))
+        ",
+        "Valid 1-bit MUL" {
+            r#"(step t1 (cl (= (bvmul x1 y1)
+                (@pbbterm (choice ((z Int)) (and
+                    (>= ((_ @int_of 0) x1) z)
+                    (>= ((_ @int_of 0) y1) z)
+                    (>= (+ z 1) (+ ((_ @int_of 0) x1) ((_ @int_of 0) y1))))))
+            )) :rule pbblast_bvmul)"#: true,
+        }
+        "Invalid 1-bit MUL" {
+            // This is the blasting of `(bvxor x1 y1)`
+            r#"(step t1 (cl (= (bvmul x1 y1)
+                (@pbbterm (choice ((z Int)) (and
+                    (>= (+ ((_ @int_of 0) x1) ((_ @int_of 0) y1)) z)
+                    (>= (+ z ((_ @int_of 0) x1)) ((_ @int_of 0) y1))
+                    (>= (+ z ((_ @int_of 0) y1)) ((_ @int_of 0) x1))
+                    (>= 2 (+ z ((_ @int_of 0) x1) ((_ @int_of 0) y1))))))
+            )) :rule pbblast_bvmul)"#: false,
+        }
+    }
+}
+
+#[test]
+fn pbblast_bvmul_2() {
+    test_cases! {
+        definitions = "
+            (declare-const x2 (_ BitVec 2))
+            (declare-const y2 (_ BitVec 2))
+            (define-fun x0 () Int ((_ @int_of 0) x2))
+            (define-fun x1 () Int ((_ @int_of 1) x2))
+            (define-fun y0 () Int ((_ @int_of 0) y2))
+            (define-fun y1 () Int ((_ @int_of 1) y2))
+            (define-fun a00 () Int (choice ((z Int)) (and (>= x0 z) (>= y0 z) (>= (+ z 1) (+ x0 y0)))))
+            (define-fun a10 () Int (choice ((z Int)) (and (>= x1 z) (>= y0 z) (>= (+ z 1) (+ x1 y0)))))
+            (define-fun a01 () Int (choice ((z Int)) (and (>= x0 z) (>= y1 z) (>= (+ z 1) (+ x0 y1)))))
+            (define-fun s1 () Int (+ a10 a01 0))
+            (define-fun c2 () Int (choice ((z Int)) (and (>= s1 (* 2 z)) (>= (+ (* 2 z) 1) s1))))
+        ",
+        "Valid 2-bit MUL" {
+            r#"(step t1 (cl (= (bvmul x2 y2)
+                (@pbbterm a00 (choice ((z Int)) (= s1 (+ z (* 2 c2)))))
+            )) :rule pbblast_bvmul)"#: true,
+        }
+        "Invalid 2-bit MUL" {
+            // The partial products are added in the wrong order
+            r#"(step t1 (cl (= (bvmul x2 y2)
+                (@pbbterm a00 (choice ((z Int)) (= (+ a01 a10 0) (+ z (* 2 c2)))))
+            )) :rule pbblast_bvmul)"#: false,
+
+            // The second bit is not just a partial product
+            r#"(step t1 (cl (= (bvmul x2 y2) (@pbbterm a00 a10))) :rule pbblast_bvmul)"#: false,
+        }
+    }
+}
+
+#[test]
+fn pbblast_bvmul_3_args() {
+    test_cases! {
+        definitions = "
+            (declare-const x2 (_ BitVec 2))
+            (declare-const y2 (_ BitVec 2))
+            (declare-const w2 (_ BitVec 2))
+            (define-fun x0 () Int ((_ @int_of 0) x2))
+            (define-fun x1 () Int ((_ @int_of 1) x2))
+            (define-fun y0 () Int ((_ @int_of 0) y2))
+            (define-fun y1 () Int ((_ @int_of 1) y2))
+            (define-fun w0 () Int ((_ @int_of 0) w2))
+            (define-fun w1 () Int ((_ @int_of 1) w2))
+            (define-fun a00 () Int (choice ((z Int)) (and (>= x0 z) (>= y0 z) (>= (+ z 1) (+ x0 y0)))))
+            (define-fun a10 () Int (choice ((z Int)) (and (>= x1 z) (>= y0 z) (>= (+ z 1) (+ x1 y0)))))
+            (define-fun a01 () Int (choice ((z Int)) (and (>= x0 z) (>= y1 z) (>= (+ z 1) (+ x0 y1)))))
+            (define-fun s1 () Int (+ a10 a01 0))
+            (define-fun c2 () Int (choice ((z Int)) (and (>= s1 (* 2 z)) (>= (+ (* 2 z) 1) s1))))
+            (define-fun p0 () Int a00)
+            (define-fun p1 () Int (choice ((z Int)) (= s1 (+ z (* 2 c2)))))
+            (define-fun b00 () Int (choice ((z Int)) (and (>= p0 z) (>= w0 z) (>= (+ z 1) (+ p0 w0)))))
+            (define-fun b10 () Int (choice ((z Int)) (and (>= p1 z) (>= w0 z) (>= (+ z 1) (+ p1 w0)))))
+            (define-fun b01 () Int (choice ((z Int)) (and (>= p0 z) (>= w1 z) (>= (+ z 1) (+ p0 w1)))))
+            (define-fun t1 () Int (+ b10 b01 0))
+            (define-fun d2 () Int (choice ((z Int)) (and (>= t1 (* 2 z)) (>= (+ (* 2 z) 1) t1))))
+        ",
+        "Valid 2-bit MUL with three arguments" {
+            // The product of the first two arguments is multiplied by the third
+            r#"(step t1 (cl (= (bvmul x2 y2 w2)
+                (@pbbterm b00 (choice ((z Int)) (= t1 (+ z (* 2 d2)))))
+            )) :rule pbblast_bvmul)"#: true,
+        }
+        "Invalid 2-bit MUL with three arguments" {
+            // This is the blasting of `(bvmul x2 y2)`
+            r#"(step t1 (cl (= (bvmul x2 y2 w2) (@pbbterm p0 p1))) :rule pbblast_bvmul)"#: false,
+
+            // The arguments are multiplied in the wrong order
+            r#"(step t1 (cl (= (bvmul w2 x2 y2)
+                (@pbbterm b00 (choice ((z Int)) (= t1 (+ z (* 2 d2)))))
+            )) :rule pbblast_bvmul)"#: false,
+        }
+    }
+}
+
+#[test]
+fn pbblast_bvshl_1() {
+    test_cases! {
+        definitions = "
+            (declare-const x1 (_ BitVec 1))
+            (declare-const y1 (_ BitVec 1))
+            (define-fun x0 () Int ((_ @int_of 0) x1))
+            (define-fun y0 () Int ((_ @int_of 0) y1))
+        ",
+        "Valid 1-bit SHL" {
+            r#"(step t1 (cl (= (bvshl x1 y1)
+                (@pbbterm (choice ((z Int)) (and
+                    (>= (+ z 1) (+ 0 y0)) (>= (+ 0 1) (+ z y0)) (>= (+ z y0) x0) (>= (+ x0 y0) z))))
+            )) :rule pbblast_bvshl)"#: true,
+        }
+        "Invalid 1-bit SHL" {
+            // The selected bits are swapped
+            r#"(step t1 (cl (= (bvshl x1 y1)
+                (@pbbterm (choice ((z Int)) (and
+                    (>= (+ z 1) (+ x0 y0)) (>= (+ x0 1) (+ z y0)) (>= (+ z y0) 0) (>= (+ 0 y0) z))))
+            )) :rule pbblast_bvshl)"#: false,
+        }
+    }
+}
+
+#[test]
+fn pbblast_bvshl_2() {
+    test_cases! {
+        definitions = "
+            (declare-const x2 (_ BitVec 2))
+            (declare-const y2 (_ BitVec 2))
+            (define-fun x0 () Int ((_ @int_of 0) x2))
+            (define-fun x1 () Int ((_ @int_of 1) x2))
+            (define-fun y0 () Int ((_ @int_of 0) y2))
+            (define-fun y1 () Int ((_ @int_of 1) y2))
+            (define-fun m0 () Int (choice ((z Int)) (and
+                (>= (+ z 1) (+ 0 y0)) (>= (+ 0 1) (+ z y0)) (>= (+ z y0) x0) (>= (+ x0 y0) z))))
+            (define-fun m1 () Int (choice ((z Int)) (and
+                (>= (+ z 1) (+ x0 y0)) (>= (+ x0 1) (+ z y0)) (>= (+ z y0) x1) (>= (+ x1 y0) z))))
+        ",
+        "Valid 2-bit SHL" {
+            r#"(step t1 (cl (= (bvshl x2 y2)
+                (@pbbterm
+                    (choice ((z Int)) (and
+                        (>= (+ z 1) (+ 0 y1)) (>= (+ 0 1) (+ z y1)) (>= (+ z y1) m0) (>= (+ m0 y1) z)))
+                    (choice ((z Int)) (and
+                        (>= (+ z 1) (+ 0 y1)) (>= (+ 0 1) (+ z y1)) (>= (+ z y1) m1) (>= (+ m1 y1) z))))
+            )) :rule pbblast_bvshl)"#: true,
+        }
+        "Invalid 2-bit SHL" {
+            // Missing the stage for the overflow bit `y1`
+            r#"(step t1 (cl (= (bvshl x2 y2) (@pbbterm m0 m1))) :rule pbblast_bvshl)"#: false,
+
+            // This is the blasting of `(bvlshr x2 y2)`
+            r#"(step t1 (cl (= (bvshl x2 y2)
+                (@pbbterm
+                    (choice ((z Int)) (and
+                        (>= (+ z 1) (+ 0 y1)) (>= (+ 0 1) (+ z y1)) (>= (+ z y1) m1) (>= (+ m1 y1) z)))
+                    (choice ((z Int)) (and
+                        (>= (+ z 1) (+ 0 y1)) (>= (+ 0 1) (+ z y1)) (>= (+ z y1) m0) (>= (+ m0 y1) z))))
+            )) :rule pbblast_bvshl)"#: false,
+        }
+    }
+}
+
+#[test]
+fn pbblast_bvshl_3() {
+    test_cases! {
+        definitions = "
+            (declare-const x3 (_ BitVec 3))
+            (declare-const y3 (_ BitVec 3))
+            (define-fun x0 () Int ((_ @int_of 0) x3))
+            (define-fun x1 () Int ((_ @int_of 1) x3))
+            (define-fun x2 () Int ((_ @int_of 2) x3))
+            (define-fun y0 () Int ((_ @int_of 0) y3))
+            (define-fun y1 () Int ((_ @int_of 1) y3))
+            (define-fun y2 () Int ((_ @int_of 2) y3))
+            (define-fun m0 () Int (choice ((z Int)) (and
+                (>= (+ z 1) (+ 0 y0)) (>= (+ 0 1) (+ z y0)) (>= (+ z y0) x0) (>= (+ x0 y0) z))))
+            (define-fun m1 () Int (choice ((z Int)) (and
+                (>= (+ z 1) (+ x0 y0)) (>= (+ x0 1) (+ z y0)) (>= (+ z y0) x1) (>= (+ x1 y0) z))))
+            (define-fun m2 () Int (choice ((z Int)) (and
+                (>= (+ z 1) (+ x1 y0)) (>= (+ x1 1) (+ z y0)) (>= (+ z y0) x2) (>= (+ x2 y0) z))))
+            (define-fun n0 () Int (choice ((z Int)) (and
+                (>= (+ z 1) (+ 0 y1)) (>= (+ 0 1) (+ z y1)) (>= (+ z y1) m0) (>= (+ m0 y1) z))))
+            (define-fun n1 () Int (choice ((z Int)) (and
+                (>= (+ z 1) (+ 0 y1)) (>= (+ 0 1) (+ z y1)) (>= (+ z y1) m1) (>= (+ m1 y1) z))))
+            (define-fun n2 () Int (choice ((z Int)) (and
+                (>= (+ z 1) (+ m0 y1)) (>= (+ m0 1) (+ z y1)) (>= (+ z y1) m2) (>= (+ m2 y1) z))))
+        ",
+        "Valid 3-bit SHL" {
+            // Shifting by 4 or more always gives zero, so the last bit of `y3` is an overflow stage
+            r#"(step t1 (cl (= (bvshl x3 y3)
+                (@pbbterm
+                    (choice ((z Int)) (and
+                        (>= (+ z 1) (+ 0 y2)) (>= (+ 0 1) (+ z y2)) (>= (+ z y2) n0) (>= (+ n0 y2) z)))
+                    (choice ((z Int)) (and
+                        (>= (+ z 1) (+ 0 y2)) (>= (+ 0 1) (+ z y2)) (>= (+ z y2) n1) (>= (+ n1 y2) z)))
+                    (choice ((z Int)) (and
+                        (>= (+ z 1) (+ 0 y2)) (>= (+ 0 1) (+ z y2)) (>= (+ z y2) n2) (>= (+ n2 y2) z))))
+            )) :rule pbblast_bvshl)"#: true,
+        }
+        "Invalid 3-bit SHL" {
+            // Missing the overflow stage for `y2`
+            r#"(step t1 (cl (= (bvshl x3 y3) (@pbbterm n0 n1 n2))) :rule pbblast_bvshl)"#: false,
+
+            // The overflow stage shifts by 4 instead of filling every bit with zero
+            r#"(step t1 (cl (= (bvshl x3 y3)
+                (@pbbterm
+                    (choice ((z Int)) (and
+                        (>= (+ z 1) (+ 0 y2)) (>= (+ 0 1) (+ z y2)) (>= (+ z y2) n0) (>= (+ n0 y2) z)))
+                    (choice ((z Int)) (and
+                        (>= (+ z 1) (+ 0 y2)) (>= (+ 0 1) (+ z y2)) (>= (+ z y2) n1) (>= (+ n1 y2) z)))
+                    (choice ((z Int)) (and
+                        (>= (+ z 1) (+ n0 y2)) (>= (+ n0 1) (+ z y2)) (>= (+ z y2) n2) (>= (+ n2 y2) z))))
+            )) :rule pbblast_bvshl)"#: false,
+        }
+    }
+}
+
+#[test]
+fn pbblast_bvlshr_2() {
+    test_cases! {
+        definitions = "
+            (declare-const x2 (_ BitVec 2))
+            (declare-const y2 (_ BitVec 2))
+            (define-fun x0 () Int ((_ @int_of 0) x2))
+            (define-fun x1 () Int ((_ @int_of 1) x2))
+            (define-fun y0 () Int ((_ @int_of 0) y2))
+            (define-fun y1 () Int ((_ @int_of 1) y2))
+            (define-fun m0 () Int (choice ((z Int)) (and
+                (>= (+ z 1) (+ x1 y0)) (>= (+ x1 1) (+ z y0)) (>= (+ z y0) x0) (>= (+ x0 y0) z))))
+            (define-fun m1 () Int (choice ((z Int)) (and
+                (>= (+ z 1) (+ 0 y0)) (>= (+ 0 1) (+ z y0)) (>= (+ z y0) x1) (>= (+ x1 y0) z))))
+        ",
+        "Valid 2-bit LSHR" {
+            r#"(step t1 (cl (= (bvlshr x2 y2)
+                (@pbbterm
+                    (choice ((z Int)) (and
+                        (>= (+ z 1) (+ 0 y1)) (>= (+ 0 1) (+ z y1)) (>= (+ z y1) m0) (>= (+ m0 y1) z)))
+                    (choice ((z Int)) (and
+                        (>= (+ z 1) (+ 0 y1)) (>= (+ 0 1) (+ z y1)) (>= (+ z y1) m1) (>= (+ m1 y1) z))))
+            )) :rule pbblast_bvlshr)"#: true,
+        }
+        "Invalid 2-bit LSHR" {
+            // The fill bit should be 0, not the sign bit
+            r#"(step t1 (cl (= (bvlshr x2 y2)
+                (@pbbterm
+                    (choice ((z Int)) (and
+                        (>= (+ z 1) (+ x1 y1)) (>= (+ x1 1) (+ z y1)) (>= (+ z y1) m0) (>= (+ m0 y1) z)))
+                    (choice ((z Int)) (and
+                        (>= (+ z 1) (+ x1 y1)) (>= (+ x1 1) (+ z y1)) (>= (+ z y1) m1) (>= (+ m1 y1) z))))
+            )) :rule pbblast_bvlshr)"#: false,
+        }
+    }
+}
+
+#[test]
+fn pbblast_bvashr_2() {
+    test_cases! {
+        definitions = "
+            (declare-const x2 (_ BitVec 2))
+            (declare-const y2 (_ BitVec 2))
+            (define-fun x0 () Int ((_ @int_of 0) x2))
+            (define-fun x1 () Int ((_ @int_of 1) x2))
+            (define-fun y0 () Int ((_ @int_of 0) y2))
+            (define-fun y1 () Int ((_ @int_of 1) y2))
+            (define-fun m0 () Int (choice ((z Int)) (and
+                (>= (+ z 1) (+ x1 y0)) (>= (+ x1 1) (+ z y0)) (>= (+ z y0) x0) (>= (+ x0 y0) z))))
+            (define-fun m1 () Int (choice ((z Int)) (and
+                (>= (+ z 1) (+ x1 y0)) (>= (+ x1 1) (+ z y0)) (>= (+ z y0) x1) (>= (+ x1 y0) z))))
+        ",
+        "Valid 2-bit ASHR" {
+            r#"(step t1 (cl (= (bvashr x2 y2)
+                (@pbbterm
+                    (choice ((z Int)) (and
+                        (>= (+ z 1) (+ x1 y1)) (>= (+ x1 1) (+ z y1)) (>= (+ z y1) m0) (>= (+ m0 y1) z)))
+                    (choice ((z Int)) (and
+                        (>= (+ z 1) (+ x1 y1)) (>= (+ x1 1) (+ z y1)) (>= (+ z y1) m1) (>= (+ m1 y1) z))))
+            )) :rule pbblast_bvashr)"#: true,
+        }
+        "Invalid 2-bit ASHR" {
+            // The fill bit should be the sign bit, not 0
+            r#"(step t1 (cl (= (bvashr x2 y2)
+                (@pbbterm
+                    (choice ((z Int)) (and
+                        (>= (+ z 1) (+ 0 y1)) (>= (+ 0 1) (+ z y1)) (>= (+ z y1) m0) (>= (+ m0 y1) z)))
+                    (choice ((z Int)) (and
+                        (>= (+ z 1) (+ 0 y1)) (>= (+ 0 1) (+ z y1)) (>= (+ z y1) m1) (>= (+ m1 y1) z))))
+            )) :rule pbblast_bvashr)"#: false,
+        }
+    }
+}
+
+#[test]
+fn pbblast_concat() {
+    test_cases! {
+        definitions = "
+            (declare-const x1 (_ BitVec 1))
+            (declare-const x2 (_ BitVec 2))
+            (declare-const y1 (_ BitVec 1))
+            (declare-const @x0 Int)
+            (declare-const @x1 Int)
+            (declare-const @y0 Int)
+        ",
+        "Valid CONCAT" {
+            r#"(step t1 (cl (= (concat x1 y1)
+                (@pbbterm ((_ @int_of 0) y1) ((_ @int_of 0) x1))
+            )) :rule pbblast_concat)"#: true,
+
+            r#"(step t1 (cl (= (concat x2 y1)
+                (@pbbterm ((_ @int_of 0) y1) ((_ @int_of 0) x2) ((_ @int_of 1) x2))
+            )) :rule pbblast_concat)"#: true,
+
+            r#"(step t1 (cl (= (concat x1 x2 y1)
+                (@pbbterm ((_ @int_of 0) y1) ((_ @int_of 0) x2) ((_ @int_of 1) x2) ((_ @int_of 0) x1))
+            )) :rule pbblast_concat)"#: true,
+
+            r#"(step t1 (cl (= (concat (@pbbterm @x0 @x1) (@pbbterm @y0))
+                (@pbbterm @y0 @x0 @x1)
+            )) :rule pbblast_concat)"#: true,
+        }
+        "Invalid CONCAT" {
+            // The first argument holds the most significant bits
+            r#"(step t1 (cl (= (concat x2 y1)
+                (@pbbterm ((_ @int_of 0) x2) ((_ @int_of 1) x2) ((_ @int_of 0) y1))
+            )) :rule pbblast_concat)"#: false,
+
+            r#"(step t1 (cl (= (concat (@pbbterm @x0 @x1) (@pbbterm @y0))
+                (@pbbterm @y0 @x1 @x0)
+            )) :rule pbblast_concat)"#: false,
+        }
+    }
+}
+
+#[test]
+fn pbblast_extract() {
+    test_cases! {
+        definitions = "
+            (declare-const x1 (_ BitVec 1))
+            (declare-const x8 (_ BitVec 8))
+            (declare-const @x0 Int)
+            (declare-const @x1 Int)
+            (declare-const @x2 Int)
+        ",
+        "Valid EXTRACT" {
+            r#"(step t1 (cl (= ((_ extract 0 0) x1) (@pbbterm ((_ @int_of 0) x1)))) :rule pbblast_extract)"#: true,
+
+            r#"(step t1 (cl (= ((_ extract 5 2) x8)
+                (@pbbterm ((_ @int_of 2) x8) ((_ @int_of 3) x8) ((_ @int_of 4) x8) ((_ @int_of 5) x8))
+            )) :rule pbblast_extract)"#: true,
+
+            r#"(step t1 (cl (= ((_ extract 7 7) x8) (@pbbterm ((_ @int_of 7) x8)))) :rule pbblast_extract)"#: true,
+
+            r#"(step t1 (cl (= ((_ extract 2 1) (@pbbterm @x0 @x1 @x2)) (@pbbterm @x1 @x2))) :rule pbblast_extract)"#: true,
+        }
+        "Invalid EXTRACT" {
+            r#"(step t1 (cl (= ((_ extract 5 2) x8)
+                (@pbbterm ((_ @int_of 5) x8) ((_ @int_of 4) x8) ((_ @int_of 3) x8) ((_ @int_of 2) x8))
+            )) :rule pbblast_extract)"#: false,
+
+            r#"(step t1 (cl (= ((_ extract 1 0) (@pbbterm @x0 @x1 @x2)) (@pbbterm @x1 @x2))) :rule pbblast_extract)"#: false,
+        }
+    }
+}
+
+#[test]
+fn pbblast_zero_extend() {
+    test_cases! {
+        definitions = "
+            (declare-const x1 (_ BitVec 1))
+            (declare-const x2 (_ BitVec 2))
+            (declare-const @x0 Int)
+            (declare-const @x1 Int)
+        ",
+        "Valid ZERO_EXTEND" {
+            r#"(step t1 (cl (= ((_ zero_extend 1) x1) (@pbbterm ((_ @int_of 0) x1) 0))) :rule pbblast_zero_extend)"#: true,
+
+            r#"(step t1 (cl (= ((_ zero_extend 2) x2)
+                (@pbbterm ((_ @int_of 0) x2) ((_ @int_of 1) x2) 0 0)
+            )) :rule pbblast_zero_extend)"#: true,
+
+            r#"(step t1 (cl (= ((_ zero_extend 0) x2)
+                (@pbbterm ((_ @int_of 0) x2) ((_ @int_of 1) x2))
+            )) :rule pbblast_zero_extend)"#: true,
+
+            r#"(step t1 (cl (= ((_ zero_extend 1) (@pbbterm @x0 @x1)) (@pbbterm @x0 @x1 0))) :rule pbblast_zero_extend)"#: true,
+        }
+        "Invalid ZERO_EXTEND" {
+            r#"(step t1 (cl (= ((_ zero_extend 2) x2)
+                (@pbbterm 0 0 ((_ @int_of 0) x2) ((_ @int_of 1) x2))
+            )) :rule pbblast_zero_extend)"#: false,
+
+            r#"(step t1 (cl (= ((_ zero_extend 1) x1) (@pbbterm ((_ @int_of 0) x1) 1))) :rule pbblast_zero_extend)"#: false,
+        }
+    }
+}
+
+#[test]
+fn pbblast_sign_extend() {
+    test_cases! {
+        definitions = "
+            (declare-const x1 (_ BitVec 1))
+            (declare-const x8 (_ BitVec 8))
+            (declare-const @x0 Int)
+            (declare-const @x1 Int)
+        ",
+        "Valid SIGN_EXTEND" {
+            r#"(step t1 (cl (= ((_ sign_extend 2) x1)
+                (@pbbterm ((_ @int_of 0) x1) ((_ @int_of 0) x1) ((_ @int_of 0) x1))
+            )) :rule pbblast_sign_extend)"#: true,
+
+            r#"(step t1 (cl (= ((_ sign_extend 1) x8)
+                (@pbbterm ((_ @int_of 0) x8) ((_ @int_of 1) x8) ((_ @int_of 2) x8) ((_ @int_of 3) x8)
+                          ((_ @int_of 4) x8) ((_ @int_of 5) x8) ((_ @int_of 6) x8) ((_ @int_of 7) x8)
+                          ((_ @int_of 7) x8))
+            )) :rule pbblast_sign_extend)"#: true,
+
+            r#"(step t1 (cl (= ((_ sign_extend 1) (@pbbterm @x0 @x1)) (@pbbterm @x0 @x1 @x1))) :rule pbblast_sign_extend)"#: true,
+        }
+        "Invalid SIGN_EXTEND" {
+            // The sign bit is the most significant one
+            r#"(step t1 (cl (= ((_ sign_extend 1) (@pbbterm @x0 @x1)) (@pbbterm @x0 @x1 @x0))) :rule pbblast_sign_extend)"#: false,
+
+            r#"(step t1 (cl (= ((_ sign_extend 2) x1) (@pbbterm ((_ @int_of 0) x1) 0 0))) :rule pbblast_sign_extend)"#: false,
+        }
+    }
+}
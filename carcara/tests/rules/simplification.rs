@@ -466,6 +466,14 @@ fn div_simplify() {
         "Division by zero" {
             "(step t1 (cl (= (div 3 0) 1)) :rule div_simplify)": false,
             "(step t1 (cl (= (/ 3.0 0.0) 1.0)) :rule div_simplify)": false,
+            "(step t1 (cl (= (div n 0) n)) :rule div_simplify)": false,
+            "(step t1 (cl (= (/ x 0.0) x)) :rule div_simplify)": false,
+        }
+        "Non-constant operands" {
+            "(step t1 (cl (= (div n 2) n)) :rule div_simplify)": false,
+            "(step t1 (cl (= (div 4 n) 2)) :rule div_simplify)": false,
+            "(step t1 (cl (= (/ x 2.0) 0.5)) :rule div_simplify)": false,
+            "(step t1 (cl (= (/ 1.0 x) x)) :rule div_simplify)": false,
         }
         "Integer division" {
             "(step t1 (cl (= (div 8 3) 2)) :rule div_simplify)": true,
@@ -41,6 +41,10 @@ fn run_test(problem_path: &Path, proof_path: &Path) -> CarcaraResult<()> {
         elaborated: false,
         ignore_unknown_rules: false,
         allowed_rules: ["all_simplify".to_owned(), "rare_rewrite".to_owned()].into(),
+        keep_going: false,
+        prove_lia_generic: false,
+        rare_rules: None,
+        all_simplify_rewriter: None,
     };
 
     // First, we check the proof normally
@@ -51,6 +55,8 @@ fn run_test(problem_path: &Path, proof_path: &Path) -> CarcaraResult<()> {
         lia_options: None,
         hole_options: None,
         uncrowd_rotation: true,
+        num_threads: 1,
+        num_solver_processes: 1,
     };
     let node = ast::ProofNode::from_commands(proof.commands.clone());
     let elaborated_node = elaborator::Elaborator::new(&mut pool, &problem, config.clone())
@@ -61,17 +67,36 @@ fn run_test(problem_path: &Path, proof_path: &Path) -> CarcaraResult<()> {
     };
 
     // After that, we check the elaborated proof to make sure it is valid
-    checker::ProofChecker::new(&mut pool, checker_config).check(&problem, &elaborated)?;
+    checker::ProofChecker::new(&mut pool, checker_config.clone()).check(&problem, &elaborated)?;
 
     // Finally, we elaborate the already elaborated proof, to make sure the elaboration step is
     // idempotent
-    let elaborated_twice = elaborator::Elaborator::new(&mut pool, &problem, config)
+    let elaborated_twice = elaborator::Elaborator::new(&mut pool, &problem, config.clone())
         .elaborate_with_default_pipeline(&elaborated_node);
     assert!(
         elaborated.commands == elaborated_twice.into_commands(),
         "elaboration was not idempotent!"
     );
 
+    // Elaborating with multiple threads must give exactly the same proof
+    let parallel_config = elaborator::Config { num_threads: 4, ..config.clone() };
+    let elaborated_parallel = elaborator::Elaborator::new(&mut pool, &problem, parallel_config)
+        .elaborate_with_default_pipeline(&node);
+    assert!(
+        elaborated.commands == elaborated_parallel.into_commands(),
+        "parallel elaboration gave a different proof!"
+    );
+
+    // We also check the proof while streaming it
+    let premise_uses = parser::count_premise_uses(io::BufReader::new(fs::File::open(proof_path)?))?;
+    check_streaming(
+        io::BufReader::new(fs::File::open(problem_path)?),
+        io::BufReader::new(fs::File::open(proof_path)?),
+        Some(premise_uses),
+        parser::Config::new(),
+        checker_config,
+    )?;
+
     // We also test the parallel checker, with different values for the number of threads
     run_parallel_checker_test(problem_path, proof_path, 1)?;
     run_parallel_checker_test(problem_path, proof_path, 4)?;
@@ -96,7 +121,14 @@ fn test_file(proof_path: &str) {
             Error::Io(_) => "IO error".to_owned(),
             Error::Parser(_, (line, column)) => format!("parser error at {}:{}", line, column),
             Error::Checker { rule, step, .. } => format!("checker error at '{}' ({})", step, rule),
+            Error::CheckerFailures(failures) => {
+                format!("checker errors at {} steps", failures.len())
+            }
             Error::DoesNotReachEmptyClause => format!("{}", e), // This one is already pretty short
+            Error::SatProof(e) => format!("SAT proof error ({})", e.code()),
+            Error::PbProof(e) => format!("PB proof error ({})", e.code()),
+            Error::LeanExport(e) => format!("Lean export error ({})", e.code()),
+            Error::Pipeline(e) => format!("pipeline error ({})", e.code()),
         };
         panic!(
             "\"{}\" returned error: {}",
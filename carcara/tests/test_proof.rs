@@ -1,16 +1,29 @@
 use carcara::{checker::*, parser};
 
-fn run_test(problem: &str, proof: &str, expected_result: bool) {
+fn run_test(problem_text: &str, proof_text: &str, expected_result: bool) {
     let (problem, proof, mut pool) = parser::parse_instance(
-        problem.as_bytes(),
-        proof.as_bytes(),
+        problem_text.as_bytes(),
+        proof_text.as_bytes(),
         parser::Config::default(),
     )
     .unwrap();
 
     let got = ProofChecker::new(&mut pool, Config::new()).check(&problem, &proof);
-
     assert_eq!(got.is_ok(), expected_result);
+
+    // Checking the proof while streaming it must give the same result, whether the premise uses
+    // are known or not
+    let uses = parser::count_premise_uses(proof_text.as_bytes()).unwrap();
+    for premise_uses in [Some(uses), None] {
+        let got = carcara::check_streaming(
+            problem_text.as_bytes(),
+            proof_text.as_bytes(),
+            premise_uses,
+            parser::Config::default(),
+            Config::new(),
+        );
+        assert_eq!(got.is_ok(), expected_result);
+    }
 }
 
 #[test]
@@ -32,3 +45,1249 @@ fn test_reached_empty_clause() {
         true,
     );
 }
+
+#[test]
+fn test_streaming() {
+    let problem = "(declare-const p Bool) (declare-const q Bool) (assert p) (assert (not p))";
+    run_test(
+        problem,
+        "(assume h1 p)
+        (assume h2 (not p))
+        (step t1 (cl p) :rule hole :premises (h1))
+        (anchor :step t2)
+        (assume t2.h1 q)
+        (step t2.t1 (cl p) :rule hole :premises (t1 t2.h1))
+        (step t2 (cl (not q) p) :rule subproof :discharge (t2.h1))
+        (step t3 (cl p) :rule hole :premises (t2 t1))
+        (step t4 (cl) :rule resolution :premises (t3 h2))",
+        true,
+    );
+
+    // The local assumption is not discharged
+    run_test(
+        problem,
+        "(anchor :step t1)
+        (assume t1.h1 q)
+        (step t1.t1 (cl p) :rule hole)
+        (step t1 (cl p) :rule subproof)
+        (step t2 (cl) :rule hole :premises (t1))",
+        false,
+    );
+
+    // The same step id may be used in sibling subproofs
+    run_test(
+        problem,
+        "(anchor :step t1)
+        (step t1.t1 (cl p) :rule hole)
+        (step t1.t2 (cl p) :rule hole :premises (t1.t1))
+        (step t1 (cl p) :rule hole :premises (t1.t2))
+        (anchor :step t2)
+        (step t1.t1 (cl q) :rule hole)
+        (step t2 (cl q) :rule hole :premises (t1.t1))
+        (step t3 (cl) :rule hole :premises (t1 t2 t1))",
+        true,
+    );
+
+    // If the premise uses are wrong, commands are discarded too early
+    let got = carcara::check_streaming(
+        problem.as_bytes(),
+        "(step t1 (cl p) :rule hole)
+        (step t2 (cl q) :rule hole)
+        (step t3 (cl) :rule hole :premises (t1))"
+            .as_bytes(),
+        Some(Default::default()),
+        parser::Config::default(),
+        Config::new(),
+    );
+    assert_eq!(got.unwrap_err().code(), "discarded_premise");
+}
+
+/// Builds a proof with many subproofs whose steps depend on their contexts. If `valid` is false,
+/// one of the steps in the last subproof is wrong.
+fn parallel_test_proof(num_subproofs: usize, valid: bool) -> String {
+    let mut proof = String::new();
+    for i in 0..num_subproofs {
+        let wrong = !valid && i == num_subproofs - 1;
+        proof += &format!(
+            "(anchor :step s{i} :args ((y Real) (:= (x Real) y)))
+            (step s{i}.t1 (cl (= x y)) :rule refl)
+            (anchor :step s{i}.t2 :args ((w Real)))
+            (step s{i}.t2.t1 (cl (= x {})) :rule refl)
+            (step s{i}.t2 (cl (= x y)) :rule hole :premises (s{i}.t1))
+            (step s{i}.t3 (cl (= p p)) :rule hole :premises (s{i}.t2))
+            (step s{i} (cl (= (forall ((x Real)) p) (forall ((y Real)) p))) :rule bind)\n",
+            if wrong { "w" } else { "y" },
+        );
+    }
+    let premises: Vec<_> = (0..num_subproofs).map(|i| format!("s{i}")).collect();
+    proof += &format!(
+        "(step t1 (cl) :rule hole :premises ({}))",
+        premises.join(" ")
+    );
+    proof
+}
+
+#[test]
+fn test_parallel_checking() {
+    use std::sync::Arc;
+
+    let heavy_holes = StepWeights::from_csv("rule,time\nhole,1000000\n".as_bytes()).unwrap();
+    for valid in [true, false] {
+        let (problem, proof, pool) = parser::parse_instance(
+            "(declare-fun p () Bool)".as_bytes(),
+            parallel_test_proof(20, valid).as_bytes(),
+            parser::Config::default(),
+        )
+        .unwrap();
+        let pool = Arc::new(pool);
+
+        for weights in [StepWeights::new(), heavy_holes.clone()] {
+            for num_threads in [1, 2, 4, 16] {
+                let (scheduler, context_usage) =
+                    Scheduler::with_weights(num_threads, &proof, &weights);
+
+                // Every command must be checked by exactly one task
+                let mut scheduled: Vec<_> = scheduler
+                    .tasks
+                    .iter()
+                    .flat_map(|task| task.iter(&proof.commands))
+                    .filter(|c| !matches!(c, carcara::ast::ProofCommand::Subproof(_)))
+                    .map(|c| c.id())
+                    .collect();
+                let mut expected: Vec<_> = proof
+                    .iter()
+                    .filter(|c| !matches!(c, carcara::ast::ProofCommand::Subproof(_)))
+                    .map(|c| c.id())
+                    .collect();
+                scheduled.sort_unstable();
+                expected.sort_unstable();
+                assert_eq!(scheduled, expected);
+
+                let got = ParallelProofChecker::new(
+                    pool.clone(),
+                    Config::new(),
+                    &problem.prelude,
+                    &context_usage,
+                    128 * 1024 * 1024,
+                )
+                .check(&problem, &proof, &scheduler);
+                assert_eq!(got.is_ok(), valid);
+            }
+        }
+    }
+}
+
+#[test]
+fn test_parallel_keep_going() {
+    use std::sync::Arc;
+
+    // Every subproof has two failing steps, which are spread across the threads
+    let proof_text = parallel_test_proof(10, true)
+        .replace("(cl (= p p)) :rule hole", "(cl (= p (not p))) :rule refl");
+    let (problem, proof, pool) = parser::parse_instance(
+        "(declare-fun p () Bool)".as_bytes(),
+        proof_text.as_bytes(),
+        parser::Config::default(),
+    )
+    .unwrap();
+    let pool = Arc::new(pool);
+    let expected: Vec<_> = (0..10)
+        .flat_map(|i| [format!("s{i}.t3"), format!("s{i}")])
+        .collect();
+
+    let heavy_holes = StepWeights::from_csv("rule,time\nhole,1000000\n".as_bytes()).unwrap();
+    for weights in [StepWeights::new(), heavy_holes] {
+        for num_threads in [1, 2, 4, 16] {
+            let (scheduler, context_usage) = Scheduler::with_weights(num_threads, &proof, &weights);
+            let got = ParallelProofChecker::new(
+                pool.clone(),
+                Config::new().keep_going(true),
+                &problem.prelude,
+                &context_usage,
+                128 * 1024 * 1024,
+            )
+            .check(&problem, &proof, &scheduler);
+
+            // The failures must be reported in proof order, regardless of which thread found them
+            match got {
+                Err(carcara::Error::CheckerFailures(failures)) => {
+                    let steps: Vec<_> = failures.iter().map(|f| f.step.as_str()).collect();
+                    assert_eq!(steps, expected);
+                }
+                other => panic!("expected checker failures, got {:?}", other),
+            }
+        }
+    }
+}
+
+#[test]
+fn test_keep_going() {
+    let (problem, proof, mut pool) = parser::parse_instance(
+        "(declare-const p Bool) (declare-const q Bool)".as_bytes(),
+        "(step t1 (cl p) :rule hole)
+        (step t2 (cl (not p)) :rule not_not :premises (t1))
+        (step t3 (cl q) :rule hole)
+        (step t4 (cl (= q p)) :rule refl)
+        (step t5 (cl) :rule resolution :premises (t1 t2))"
+            .as_bytes(),
+        parser::Config::default(),
+    )
+    .unwrap();
+
+    // Without `keep_going`, the checker stops at the first failing step
+    let got = ProofChecker::new(&mut pool, Config::new()).check(&problem, &proof);
+    assert!(matches!(got, Err(carcara::Error::Checker { step, .. }) if step == "t2"));
+
+    // With it, all failing steps are reported, and the failing conclusions are trusted, so `t5`
+    // still checks
+    let got = ProofChecker::new(&mut pool, Config::new().keep_going(true)).check(&problem, &proof);
+    match got {
+        Err(carcara::Error::CheckerFailures(failures)) => {
+            let steps: Vec<_> = failures.iter().map(|f| f.step.as_str()).collect();
+            assert_eq!(steps, ["t2", "t4"]);
+            assert_eq!(failures[1].rule, "refl");
+        }
+        other => panic!("expected checker failures, got {:?}", other),
+    }
+
+    // If every step is valid, `keep_going` makes no difference
+    let (problem, proof, mut pool) = parser::parse_instance(
+        "".as_bytes(),
+        "(step t1 (cl) :rule hole)".as_bytes(),
+        parser::Config::default(),
+    )
+    .unwrap();
+    let got = ProofChecker::new(&mut pool, Config::new().keep_going(true)).check(&problem, &proof);
+    assert!(matches!(got, Ok(true)));
+}
+
+#[test]
+fn test_error_codes() {
+    fn check(problem: &str, proof: &str) -> carcara::Error {
+        let (problem, proof, mut pool) = parser::parse_instance(
+            problem.as_bytes(),
+            proof.as_bytes(),
+            parser::Config::default(),
+        )
+        .unwrap();
+        ProofChecker::new(&mut pool, Config::new())
+            .check(&problem, &proof)
+            .unwrap_err()
+    }
+
+    let err = check(
+        "(declare-const p Bool) (declare-const q Bool)",
+        "(step t1 (cl (= q p)) :rule refl)",
+    );
+    let carcara::Error::Checker { inner, .. } = &err else {
+        panic!("expected checker error, got {:?}", err);
+    };
+    assert_eq!(err.code(), "reflexivity_failed");
+    let terms: Vec<_> = inner.terms().iter().map(|t| t.to_string()).collect();
+    assert_eq!(terms, ["q", "p"]);
+
+    let err = check(
+        "(declare-const x Int) (declare-const y Int) (assert (>= (+ (* 1 x) y) 1))",
+        "(assume h1 (>= (+ (* 1 x) y) 1))
+        (step t1 (cl (>= (* 2 x) 2)) :rule cp_multiplication :premises (h1) :args (2))",
+    );
+    let carcara::Error::Checker { inner, .. } = &err else {
+        panic!("expected checker error, got {:?}", err);
+    };
+    assert_eq!(inner.code(), "cp.not_pseudo_boolean_literal");
+    assert_eq!(inner.terms()[0].to_string(), "y");
+
+    // `div_simplify` reports a division by zero even if the numerator is not a constant, and
+    // otherwise points to the operand that is not a constant
+    let cases = [
+        (
+            "(step t1 (cl (= (div n 0) n)) :rule div_simplify)",
+            "div_or_mod_by_zero",
+            None,
+        ),
+        (
+            "(step t1 (cl (= (div n 2) n)) :rule div_simplify)",
+            "expected_any_number",
+            Some("n"),
+        ),
+        (
+            "(step t1 (cl (= (div 4 n) 2)) :rule div_simplify)",
+            "expected_any_number",
+            Some("n"),
+        ),
+        (
+            "(step t1 (cl (= (/ 1.0 x) x)) :rule div_simplify)",
+            "expected_any_number",
+            Some("x"),
+        ),
+    ];
+    for (proof, code, term) in cases {
+        let err = check("(declare-const n Int) (declare-const x Real)", proof);
+        let carcara::Error::Checker { inner, .. } = &err else {
+            panic!("expected checker error, got {:?}", err);
+        };
+        assert_eq!(inner.code(), code);
+        let terms: Vec<_> = inner.terms().iter().map(|t| t.to_string()).collect();
+        assert_eq!(terms.first().map(String::as_str), term);
+    }
+
+    let err = parser::parse_instance(
+        "".as_bytes(),
+        "(step t1 (cl p) :rule hole)".as_bytes(),
+        parser::Config::default(),
+    )
+    .map(|_| ())
+    .unwrap_err();
+    assert_eq!(err.code(), "parser.undefined_iden");
+}
+
+#[test]
+fn test_holes_are_collected() {
+    use carcara::benchmarking::OnlineBenchmarkResults;
+
+    let mut stats = OnlineBenchmarkResults::new();
+    let got = carcara::check_with_stats(
+        "(declare-const p Bool)".as_bytes(),
+        "(step t1 (cl p) :rule hole)
+        (step t2 (cl p) :rule all_simplify)
+        (step t3 (cl) :rule hole)"
+            .as_bytes(),
+        parser::Config::default(),
+        Config {
+            allowed_rules: ["all_simplify".to_owned()].into(),
+            ..Config::new()
+        },
+        &mut stats,
+    );
+    assert!(matches!(got, Ok(true)));
+
+    let holes: Vec<_> = stats
+        .holes
+        .iter()
+        .map(|(rule, steps)| {
+            let steps: Vec<_> = steps.iter().map(|(_, id)| id.as_str()).collect();
+            (rule.as_str(), steps)
+        })
+        .collect();
+    assert_eq!(
+        holes,
+        [("hole", vec!["t1", "t3"]), ("all_simplify", vec!["t2"])]
+    );
+}
+
+#[test]
+fn test_elaborate_drup() {
+    use carcara::{ast, elaborator};
+
+    let (problem, proof, mut pool) = parser::parse_instance(
+        "(declare-const a Bool)
+        (declare-const b Bool)
+        (declare-const c Bool)
+        (declare-const d Bool)
+        (declare-const e Bool)
+        (assert (or a c))
+        (assert (or a (not c) d))
+        (assert (or (not d) e))
+        (assert (or (not d) (not e)))
+        (assert (not a))
+        (assert (not b))"
+            .as_bytes(),
+        "(assume a0 (or a c))
+        (assume a1 (or a (not c) d))
+        (assume a2 (or (not d) e))
+        (assume a3 (or (not d) (not e)))
+        (assume a4 (not a))
+        (assume a5 (not b))
+        (step t0 (cl a c) :rule or :premises (a0))
+        (step t1 (cl a (not c) d) :rule or :premises (a1))
+        (step t2 (cl (not d) e) :rule or :premises (a2))
+        (step t3 (cl (not d) (not e)) :rule or :premises (a3))
+        (step t4 (cl a b) :rule drup :premises (t0 t1 t2 t3)
+            :args ((cl (not d) a) (@d (cl (not d) e)) (cl a b)))
+        (step t5 (cl) :rule drat :premises (t4 a4 a5) :args ((cl)))"
+            .as_bytes(),
+        parser::Config::default(),
+    )
+    .unwrap();
+
+    let node = ast::ProofNode::from_commands(proof.commands.clone());
+    let elaborated = elaborator::Elaborator::new(
+        &mut pool,
+        &problem,
+        elaborator::Config {
+            lia_options: None,
+            hole_options: None,
+            uncrowd_rotation: false,
+            num_threads: 1,
+            num_solver_processes: 1,
+        },
+    )
+    .elaborate_with_default_pipeline(&node);
+    let elaborated = ast::Proof {
+        constant_definitions: Vec::new(),
+        commands: elaborated.into_commands(),
+    };
+
+    let rules: Vec<_> = elaborated
+        .iter()
+        .filter_map(|c| match c {
+            ast::ProofCommand::Step(s) => Some(s.rule.as_str()),
+            _ => None,
+        })
+        .collect();
+    assert!(!rules.contains(&"drup") && !rules.contains(&"drat"));
+    assert!(rules.contains(&"resolution"));
+
+    let got =
+        ProofChecker::new(&mut pool, Config::new().elaborated(true)).check(&problem, &elaborated);
+    assert!(matches!(got, Ok(false)));
+}
+
+#[test]
+fn test_elaborate_drup_permuted_premise() {
+    use carcara::{ast, elaborator};
+
+    // The conclusion of `t1` is a permutation of its premise, and the conclusion of `t2` adds a
+    // literal to its premise in the first position, so neither can be derived by `weakening` alone.
+    // Only the drup elaboration is used, since later steps of the pipeline remove `reordering` steps
+    let (problem, proof, mut pool) = parser::parse_instance(
+        "(declare-const a Bool)
+        (declare-const b Bool)
+        (declare-const c Bool)
+        (assert (or a b))
+        (assert (not a))
+        (assert (not b))
+        (assert (not c))"
+            .as_bytes(),
+        "(assume h0 (or a b))
+        (assume h1 (not a))
+        (assume h2 (not b))
+        (assume h3 (not c))
+        (step t0 (cl a b) :rule or :premises (h0))
+        (step t1 (cl b a) :rule drup :premises (t0) :args ((cl b a)))
+        (step t2 (cl c b a) :rule drup :premises (t1) :args ((cl c b a)))
+        (step t3 (cl) :rule resolution :premises (t2 h1 h2 h3) :args (a true b true c true))"
+            .as_bytes(),
+        parser::Config::default(),
+    )
+    .unwrap();
+
+    let node = ast::ProofNode::from_commands(proof.commands.clone());
+    let elaborated = elaborator::Elaborator::new(
+        &mut pool,
+        &problem,
+        elaborator::Config {
+            lia_options: None,
+            hole_options: None,
+            uncrowd_rotation: false,
+            num_threads: 1,
+            num_solver_processes: 1,
+        },
+    )
+    .elaborate(&node, vec![elaborator::ElaborationStep::Drup]);
+    let elaborated = ast::Proof {
+        constant_definitions: Vec::new(),
+        commands: elaborated.into_commands(),
+    };
+
+    let get_rule = |id: &str| {
+        elaborated.iter().find_map(|c| match c {
+            ast::ProofCommand::Step(s) if s.id == id => Some(s.rule.as_str()),
+            _ => None,
+        })
+    };
+    assert_eq!(get_rule("t1"), Some("reordering"));
+    assert_eq!(get_rule("t2"), Some("reordering"));
+    assert_eq!(get_rule("t2.t1"), Some("weakening"));
+
+    let got =
+        ProofChecker::new(&mut pool, Config::new().elaborated(true)).check(&problem, &elaborated);
+    assert!(matches!(got, Ok(false)), "{:?}", got);
+}
+
+#[test]
+fn test_compress() {
+    use carcara::{ast, elaborator};
+
+    fn compress(
+        problem: &str,
+        proof: &str,
+        pipeline: Vec<elaborator::ElaborationStep>,
+    ) -> (
+        ast::Proof,
+        carcara::CarcaraResult<bool>,
+        Vec<(elaborator::ElaborationStep, usize, usize)>,
+    ) {
+        let (problem, proof, mut pool) = parser::parse_instance(
+            problem.as_bytes(),
+            proof.as_bytes(),
+            parser::Config::default(),
+        )
+        .unwrap();
+        let config = elaborator::Config {
+            lia_options: None,
+            hole_options: None,
+            uncrowd_rotation: false,
+            num_threads: 1,
+            num_solver_processes: 1,
+        };
+        let node = ast::ProofNode::from_commands(proof.commands);
+        let mut elaborator = elaborator::Elaborator::new(&mut pool, &problem, config);
+        let compressed = elaborator.elaborate(&node, pipeline).into_commands();
+        let sizes = elaborator.compressions().to_vec();
+        let compressed = ast::Proof {
+            constant_definitions: Vec::new(),
+            commands: compressed,
+        };
+        let result = ProofChecker::new(&mut pool, Config::new().elaborated(true))
+            .check(&problem, &compressed);
+        (compressed, result, sizes)
+    }
+
+    let ids = |proof: &ast::Proof| -> Vec<String> {
+        proof.commands.iter().map(|c| c.id().to_owned()).collect()
+    };
+
+    // `t2` is bypassed, since it has the same clause as its premise, and then `t1` is merged into
+    // `t0`
+    let (compressed, result, sizes) = compress(
+        "(declare-const p Bool) (declare-const q Bool) (assert (or p q))",
+        "(assume a0 (or p q))
+        (step t0 (cl p q) :rule or :premises (a0))
+        (step t1 (cl p q) :rule or :premises (a0))
+        (step t2 (cl p q) :rule contraction :premises (t1))
+        (step t3 (cl) :rule hole :premises (t0 t2))",
+        vec![
+            elaborator::ElaborationStep::Prune,
+            elaborator::ElaborationStep::Merge,
+        ],
+    );
+    assert_eq!(ids(&compressed), ["a0", "t0", "t3"]);
+    assert!(result.is_ok());
+    assert_eq!(
+        sizes,
+        [
+            (elaborator::ElaborationStep::Prune, 5, 4),
+            (elaborator::ElaborationStep::Merge, 4, 3),
+        ]
+    );
+
+    // In `t4`, the premise `t5` is only used to remove `q`, which is also done by the unit `a0`
+    let (compressed, result, sizes) = compress(
+        "(declare-const p Bool)
+        (declare-const q Bool)
+        (declare-const r Bool)
+        (assert (not q))
+        (assert (or q p))
+        (assert (or (not p) q r))
+        (assert (or (not q) r))
+        (assert (not r))",
+        "(assume a0 (not q))
+        (assume a1 (or q p))
+        (assume a2 (or (not p) q r))
+        (assume a3 (not r))
+        (assume a4 (or (not q) r))
+        (step t0 (cl q p) :rule or :premises (a1))
+        (step t1 (cl p) :rule resolution :premises (a0 t0) :args (q false))
+        (step t2 (cl (not p) q r) :rule or :premises (a2))
+        (step t5 (cl (not q) r) :rule or :premises (a4))
+        (step t4 (cl (not p) r) :rule resolution :premises (t2 t5) :args (q true))
+        (step t6 (cl) :rule resolution :premises (t1 t4 a3) :args (p true r true))",
+        vec![elaborator::ElaborationStep::RecycleUnits],
+    );
+    assert_eq!(
+        ids(&compressed),
+        ["a0", "a1", "a2", "a3", "t0", "t1", "t2", "t4", "t6"]
+    );
+    assert!(matches!(result, Ok(false)));
+    assert_eq!(sizes, [(elaborator::ElaborationStep::RecycleUnits, 11, 9)]);
+
+    // `t1.t1` doesn't depend on the assumption of the subproof, so it is lifted out of it, and then
+    // merged into `t0`. The steps in `t2` can't be lifted, since its anchor has arguments
+    let (compressed, result, _) = compress(
+        "(declare-const p Bool) (declare-const q Bool) (assert (or p q))",
+        "(assume a0 (or p q))
+        (step t0 (cl p q) :rule or :premises (a0))
+        (anchor :step t1)
+        (assume t1.a0 (not p))
+        (step t1.t1 (cl p q) :rule or :premises (a0))
+        (step t1.t2 (cl q) :rule resolution :premises (t1.t1 t1.a0) :args (p true))
+        (step t1 (cl (not (not p)) q) :rule subproof :discharge (t1.a0))
+        (anchor :step t2 :args ((x Int)))
+        (step t2.t1 (cl p q) :rule or :premises (a0))
+        (step t2.t2 (cl (= (= x x) (= x x))) :rule hole :premises (t2.t1))
+        (step t2 (cl (= (forall ((x Int)) (= x x)) (forall ((x Int)) (= x x)))) :rule bind)
+        (step t3 (cl) :rule hole :premises (t0 t1 t2))",
+        vec![
+            elaborator::ElaborationStep::Lift,
+            elaborator::ElaborationStep::Merge,
+        ],
+    );
+    assert_eq!(ids(&compressed), ["a0", "t0", "t1", "t2", "t3"]);
+    let subproof_ids = |id: &str| -> Vec<String> {
+        let command = compressed.commands.iter().find(|c| c.id() == id).unwrap();
+        let ast::ProofCommand::Subproof(s) = command else {
+            panic!("'{}' is not a subproof", id);
+        };
+        s.commands.iter().map(|c| c.id().to_owned()).collect()
+    };
+    assert_eq!(subproof_ids("t1"), ["t1.a0", "t1.t2", "t1"]);
+    assert_eq!(subproof_ids("t2"), ["t2.t1", "t2.t2", "t2"]);
+    assert!(result.is_ok());
+}
+
+#[cfg(unix)]
+#[test]
+fn test_elaborate_with_multiple_solver_processes() {
+    use carcara::{ast, elaborator};
+
+    let num_steps = 8;
+    let mut proof_text = String::new();
+    for i in 0..num_steps {
+        // The built-in prover can't handle non-linear terms, so these steps need the solver
+        proof_text += &format!("(step t{i} (cl (>= (* x x) {i})) :rule lia_generic)\n");
+    }
+    let premises: Vec<_> = (0..num_steps).map(|i| format!("t{i}")).collect();
+    proof_text += &format!(
+        "(step t (cl) :rule hole :premises ({}))",
+        premises.join(" ")
+    );
+    let (problem, proof, mut pool) = parser::parse_instance(
+        "(set-logic QF_LIA) (declare-const x Int)".as_bytes(),
+        proof_text.as_bytes(),
+        parser::Config::default(),
+    )
+    .unwrap();
+    let node = ast::ProofNode::from_commands(proof.commands);
+
+    // The "solver" just discards the problem and gives a proof with a single unknown rule, which is
+    // accepted when checking the solver proof
+    let lia_options = elaborator::LiaGenericOptions {
+        solver: "sh".into(),
+        arguments: vec![
+            "-c".into(),
+            "cat > /dev/null; echo unsat; echo '(step t1 (cl) :rule trust)'".into(),
+        ],
+    };
+    let mut elaborate = |num_solver_processes| {
+        let config = elaborator::Config {
+            lia_options: Some(lia_options.clone()),
+            hole_options: None,
+            uncrowd_rotation: false,
+            num_threads: 1,
+            num_solver_processes,
+        };
+        elaborator::Elaborator::new(&mut pool, &problem, config)
+            .elaborate(&node, vec![elaborator::ElaborationStep::LiaGeneric])
+            .into_commands()
+    };
+    let sequential = elaborate(1);
+    let parallel = elaborate(4);
+    assert!(sequential == parallel);
+
+    // Each `lia_generic` step is replaced by a subproof containing the solver proof
+    let lia_generic_steps = sequential
+        .iter()
+        .filter(|c| matches!(c, ast::ProofCommand::Step(s) if s.rule == "lia_generic"))
+        .count();
+    let subproofs = sequential.iter().filter(|c| c.is_subproof()).count();
+    assert_eq!((lia_generic_steps, subproofs), (0, num_steps));
+}
+
+#[test]
+fn test_parallel_elaboration() {
+    use carcara::{ast, elaborator};
+
+    let num_blocks = 30;
+    let mut proof_text = "(assume h1 (= a b))
+        (assume h2 (= b c))\n"
+        .to_owned();
+    let mut last_steps = Vec::new();
+    for i in 0..num_blocks {
+        proof_text += &format!(
+            "(step t{i}.1 (cl (not (= a b)) (not (= b c)) (= a c)) :rule eq_transitive)
+            (step t{i}.2 (cl (= a c)) :rule resolution :premises (t{i}.1 h1 h2))
+            (step t{i}.3 (cl (= (f a) (f c))) :rule cong :premises (t{i}.2))
+            (step t{i}.4 (cl (= (f (f a)) (f (f c)))) :rule cong :premises (t{i}.3))
+            (step t{i}.5 (cl (= c a)) :rule trans :premises (h2 h1))
+            (anchor :step t{i}.6)
+            (assume t{i}.6.h1 (= a b))
+            (step t{i}.6.t1 (cl (= (f a) (f b))) :rule cong :premises (t{i}.6.h1))
+            (step t{i}.6 (cl (not (= a b)) (= (f a) (f b))) :rule subproof :discharge (t{i}.6.h1))\n"
+        );
+        last_steps.extend([4, 5, 6].map(|j| format!("t{i}.{j}")));
+    }
+    proof_text += &format!(
+        "(step t (cl) :rule hole :premises ({}))",
+        last_steps.join(" ")
+    );
+
+    let (problem, proof, mut pool) = parser::parse_instance(
+        "(declare-sort U 0)
+        (declare-fun a () U) (declare-fun b () U) (declare-fun c () U)
+        (declare-fun f (U) U)
+        (assert (= a b)) (assert (= b c))"
+            .as_bytes(),
+        proof_text.as_bytes(),
+        parser::Config::default(),
+    )
+    .unwrap();
+    let node = ast::ProofNode::from_commands(proof.commands);
+
+    let mut elaborate = |num_threads| {
+        let config = elaborator::Config {
+            lia_options: None,
+            hole_options: None,
+            uncrowd_rotation: false,
+            num_threads,
+            num_solver_processes: 1,
+        };
+        let commands = elaborator::Elaborator::new(&mut pool, &problem, config)
+            .elaborate_with_default_pipeline(&node)
+            .into_commands();
+        ast::Proof {
+            constant_definitions: Vec::new(),
+            commands,
+        }
+    };
+    let sequential = elaborate(1);
+    for num_threads in [2, 4, 16] {
+        // The terms in the elaborated proof are compared by reference, so this also checks that
+        // the terms created by each thread were correctly added back to the pool
+        assert!(sequential.commands == elaborate(num_threads).commands);
+    }
+
+    let got =
+        ProofChecker::new(&mut pool, Config::new().elaborated(true)).check(&problem, &sequential);
+    assert!(matches!(got, Ok(true)));
+}
+
+#[test]
+fn test_elaboration_errors() {
+    use carcara::{ast, elaborator};
+
+    // `h2` is not an assertion of the problem, the conclusion of `t2` is not an equality, and the
+    // premise of `t5` is not a unit clause
+    let (problem, proof, mut pool) = parser::parse_instance(
+        "(declare-const p Bool)
+        (declare-const q Bool)
+        (declare-const r Bool)
+        (assert (= p q))"
+            .as_bytes(),
+        "(assume h1 (= p q))
+        (assume h2 (= q p q))
+        (step t1 (cl (= p q)) :rule trans :premises (h1))
+        (step t2 (cl p) :rule trans :premises (h1))
+        (step t4 (cl (= p q) r) :rule trans :premises (h1))
+        (step t5 (cl (= (= p q) (= p r))) :rule cong :premises (t4))
+        (step t3 (cl) :rule hole :premises (h2 t1 t2 t5))"
+            .as_bytes(),
+        parser::Config::default(),
+    )
+    .unwrap();
+    let node = ast::ProofNode::from_commands(proof.commands);
+
+    for num_threads in [1, 2] {
+        let config = elaborator::Config {
+            lia_options: None,
+            hole_options: None,
+            uncrowd_rotation: false,
+            num_threads,
+            num_solver_processes: 1,
+        };
+        let mut elaborator = elaborator::Elaborator::new(&mut pool, &problem, config);
+        let elaborated = elaborator.elaborate_with_default_pipeline(&node);
+        let errors: Vec<_> = elaborator
+            .errors()
+            .iter()
+            .map(|e| (e.step.as_str(), e.rule.as_str(), e.code()))
+            .collect();
+        assert_eq!(
+            errors,
+            [
+                ("h2", "assume", "elaboration.assume_not_in_problem"),
+                ("t2", "trans", "term_of_wrong_form"),
+                ("t4", "trans", "wrong_length_of_clause"),
+                ("t5", "cong", "wrong_length_of_clause"),
+            ]
+        );
+
+        // The steps that failed are kept as they were
+        let commands = elaborated.into_commands();
+        let t2 = commands.iter().find(|c| c.id() == "t2").unwrap();
+        assert!(matches!(t2, ast::ProofCommand::Step(s) if s.rule == "trans"));
+        assert!(commands.iter().any(|c| c.id() == "h2"));
+    }
+}
+
+#[test]
+fn test_check_and_elaborate_with_elaboration_steps() {
+    use carcara::elaborator::{Config as ElabConfig, ElaborationStep};
+
+    // The pipeline can be given directly as elaboration steps
+    let (holey, _, proof, _, errors) = carcara::check_and_elaborate(
+        "(declare-const p Bool) (assert p) (assert (not p))".as_bytes(),
+        "(assume h1 p) (assume h2 (not p)) (step t1 (cl) :rule resolution :premises (h1 h2))"
+            .as_bytes(),
+        parser::Config::default(),
+        Config::new(),
+        ElabConfig {
+            lia_options: None,
+            hole_options: None,
+            uncrowd_rotation: false,
+            num_threads: 1,
+            num_solver_processes: 1,
+        },
+        vec![ElaborationStep::Polyeq, ElaborationStep::Local],
+        false,
+    )
+    .unwrap();
+    assert!(!holey);
+    assert!(errors.is_empty());
+    assert!(matches!(
+        proof.commands.last(),
+        Some(carcara::ast::ProofCommand::Step(s)) if s.rule == "resolution" && s.args.len() == 2
+    ));
+}
+
+#[cfg(unix)]
+#[test]
+fn test_elaboration_errors_are_returned() {
+    use carcara::elaborator::{Config as ElabConfig, ElaborationStep, PipelineStep};
+
+    // The solver used to elaborate the hole doesn't exist, so the hole can't be elaborated
+    let pipeline = vec![PipelineStep {
+        rules: Some(["hole".to_owned()].into()),
+        solver: Some("/nonexistent/solver".into()),
+        ..PipelineStep::from(ElaborationStep::Hole)
+    }];
+    let config = ElabConfig {
+        lia_options: None,
+        hole_options: None,
+        uncrowd_rotation: false,
+        num_threads: 1,
+        num_solver_processes: 1,
+    };
+    let (holey, _, proof, _, errors) = carcara::check_and_elaborate(
+        "(declare-const p Bool) (assert p)".as_bytes(),
+        "(assume h1 p) (step t1 (cl (not p)) :rule hole) (step t2 (cl) :rule resolution :premises (h1 t1))"
+            .as_bytes(),
+        parser::Config::default(),
+        Config::new(),
+        config,
+        pipeline,
+        false,
+    )
+    .unwrap();
+    assert!(holey);
+    let errors: Vec<_> = errors.iter().map(|e| (e.step.as_str(), e.code())).collect();
+    assert_eq!(errors, [("t1", "elaboration.hole_solver")]);
+    assert!(proof.commands.iter().any(|c| c.id() == "t1"));
+}
+
+#[test]
+fn test_custom_elaboration_rules() {
+    use carcara::{ast::*, checker::error::CheckerError, elaborator};
+    use std::collections::HashSet;
+
+    let (problem, proof, mut pool) = parser::parse_instance(
+        "(declare-sort U 0)
+        (declare-fun a () U) (declare-fun b () U) (declare-fun c () U)
+        (assert (= a b)) (assert (= b c)) (assert (not (= a c)))"
+            .as_bytes(),
+        "(assume h1 (= a b))
+        (assume h2 (= b c))
+        (assume h3 (not (= a c)))
+        (step t1 (cl (= a c)) :rule my_trans :premises (h1 h2))
+        (step t2 (cl (= c a)) :rule trans :premises (h2 h1))
+        (step t3 (cl) :rule hole :premises (t1 t2 h3))"
+            .as_bytes(),
+        parser::Config::default(),
+    )
+    .unwrap();
+    let node = ProofNode::from_commands(proof.commands);
+
+    // `my_trans` is just another name for `trans`
+    let my_trans = |_: &mut dyn TermPool, _: &mut ContextStack, step: &StepNode| {
+        Ok::<_, CheckerError>(Rc::new(ProofNode::Step(StepNode {
+            rule: "trans".to_owned(),
+            ..step.clone()
+        })))
+    };
+    let config = elaborator::Config {
+        lia_options: None,
+        hole_options: None,
+        uncrowd_rotation: false,
+        num_threads: 1,
+        num_solver_processes: 1,
+    };
+    let mut elaborator = elaborator::Elaborator::new(&mut pool, &problem, config);
+    elaborator.register_rule("my_trans", my_trans);
+
+    // Only the `my_trans` step is elaborated. Elaborating `t2` would add steps to reorder its
+    // premises
+    let step = elaborator::PipelineStep {
+        rules: Some(HashSet::from(["my_trans".to_owned()])),
+        ..elaborator::ElaborationStep::Local.into()
+    };
+    let elaborated = elaborator.elaborate(&node, vec![step]).into_commands();
+    let rules: Vec<_> = elaborated
+        .iter()
+        .filter_map(|c| match c {
+            ProofCommand::Step(s) => Some((s.id.as_str(), s.rule.as_str())),
+            _ => None,
+        })
+        .collect();
+    assert_eq!(rules, [("t1", "trans"), ("t2", "trans"), ("t3", "hole")]);
+    assert!(elaborator.errors().is_empty());
+}
+
+#[test]
+fn test_elaborate_lia_generic_without_solver() {
+    use carcara::{ast, elaborator};
+
+    let (problem, proof, mut pool) = parser::parse_instance(
+        "(declare-fun x () Real) (declare-fun m () Int) (declare-fun n () Int)
+        (declare-fun k () Int)"
+            .as_bytes(),
+        "(step t1 (cl (not (<= x 1.0)) (not (>= (* 2.0 x) 4.0))) :rule lia_generic)
+        (step t2 (cl (not (< m n)) (not (< n (+ m 1)))) :rule lia_generic)
+        (step t3 (cl (< x 0.0) (<= (- x) 0.0)) :rule la_generic)
+        (step t4 (cl (not (< m n))) :rule lia_generic)
+        (step t5 (cl (not (< m n)) (not (< n (+ m 1 (* 0 k))))) :rule lia_generic)
+        (step t (cl) :rule hole :premises (t1 t2 t3 t4 t5))"
+            .as_bytes(),
+        parser::Config::default(),
+    )
+    .unwrap();
+    let config = elaborator::Config {
+        lia_options: None,
+        hole_options: None,
+        uncrowd_rotation: false,
+        num_threads: 1,
+        num_solver_processes: 1,
+    };
+    let node = ast::ProofNode::from_commands(proof.commands);
+    let elaborated = ast::Proof {
+        constant_definitions: Vec::new(),
+        commands: elaborator::Elaborator::new(&mut pool, &problem, config)
+            .elaborate_with_default_pipeline(&node)
+            .into_commands(),
+    };
+
+    // The first step is valid over the reals, so it becomes a single `la_generic` step. The second
+    // step is only valid over the integers, so it is proved using more steps. The fourth step is not
+    // valid, and the `la_generic` rule can't express the `(* 0 k)` term in the fifth step, so both
+    // can only be elaborated by an external solver
+    let get_step = |id: &str| {
+        let step = elaborated.commands.iter().find(|c| c.id() == id);
+        match step {
+            Some(ast::ProofCommand::Step(s)) => (s.rule.as_str(), s.args.len()),
+            _ => panic!("step '{}' not found", id),
+        }
+    };
+    assert_eq!(get_step("t1"), ("la_generic", 2));
+    assert_ne!(get_step("t2").0, "lia_generic");
+    assert_eq!(get_step("t3"), ("la_generic", 2));
+    assert_eq!(get_step("t4"), ("lia_generic", 0));
+    assert_eq!(get_step("t5"), ("lia_generic", 0));
+
+    let got =
+        ProofChecker::new(&mut pool, Config::new().elaborated(true)).check(&problem, &elaborated);
+    assert!(matches!(got, Ok(true)));
+}
+
+#[test]
+fn test_elaborate_quantifier_steps() {
+    use carcara::{ast, elaborator};
+
+    let (problem, proof, mut pool) = parser::parse_instance(
+        "(declare-fun a () Int) (declare-fun b () Real) (declare-fun p (Int) Bool)".as_bytes(),
+        "(step t1 (cl (or (not (forall ((x Int) (y Real)) (= (p x) (> y 0.0)))) (= (p a) (> b 0.0))))
+            :rule forall_inst)
+        (step t2 (cl (or (not (forall ((x Int) (y Real)) (= (p x) (> y 0.0)))) (= (p a) (> b 0.0))))
+            :rule forall_inst :args (b a))
+        (step t3 (cl (or (not (forall ((x Int) (y Real)) (= (p x) (> y 0.0)))) (= (> b 0.0) (p a))))
+            :rule forall_inst)
+        (step t4 (cl (or (not (forall ((x Int) (z Int)) (p x))) (p 1))) :rule forall_inst)
+        (step t5 (cl (or
+            (not (forall ((x Int) (y Int)) (and (p x) (p y))))
+            (forall ((x Int)) (p x))
+        )) :rule qnt_cnf)
+        (step t6 (cl (or
+            (not (forall ((x Int)) (forall ((y Int)) (or (p x) (p y)))))
+            (forall ((x Int) (y Int)) (or (p x) (p y)))
+        )) :rule qnt_cnf)
+        (step t7 (cl (or
+            (not (forall ((x Int) (z Int)) (or (p x) (p 0))))
+            (forall ((x Int)) (or (p x) (p 0)))
+        )) :rule qnt_cnf)
+        (step t (cl) :rule hole :premises (t1 t2 t3 t4 t5 t6 t7))"
+            .as_bytes(),
+        parser::Config::default(),
+    )
+    .unwrap();
+    let config = elaborator::Config {
+        lia_options: None,
+        hole_options: None,
+        uncrowd_rotation: false,
+        num_threads: 1,
+        num_solver_processes: 1,
+    };
+    let node = ast::ProofNode::from_commands(proof.commands);
+    let elaborated = ast::Proof {
+        constant_definitions: Vec::new(),
+        commands: elaborator::Elaborator::new(&mut pool, &problem, config)
+            .elaborate_with_default_pipeline(&node)
+            .into_commands(),
+    };
+    // The rules used to derive each step of the original proof, including the ones in the
+    // elaborated subproof that replaces it
+    let rules_of = |id: &str| -> Vec<&str> {
+        let mut rules: Vec<_> = elaborated
+            .iter()
+            .filter_map(|c| match c {
+                ast::ProofCommand::Step(s)
+                    if s.id == id || s.id.starts_with(&format!("{}.", id)) =>
+                {
+                    Some(s.rule.as_str())
+                }
+                _ => None,
+            })
+            .collect();
+        rules.sort_unstable();
+        rules
+    };
+    assert_eq!(
+        rules_of("t5"),
+        ["cong", "equiv1", "qnt_cnf", "qnt_rm_unused", "resolution"]
+    );
+    let qnt_cnf_clause = elaborated.iter().find_map(|c| match c {
+        ast::ProofCommand::Step(s) if s.id.starts_with("t5.") && s.rule == "qnt_cnf" => {
+            Some(s.clause[0].to_string())
+        }
+        _ => None,
+    });
+    assert_eq!(
+        qnt_cnf_clause.as_deref(),
+        Some(
+            "(or (not (forall ((x Int) (y Int)) (and (p x) (p y)))) \
+            (forall ((x Int) (y Int)) (p x)))"
+        )
+    );
+
+    // If the body of the joined quantifier is already the selected clause, no `qnt_cnf` step is
+    // needed
+    let (t6, t7) = (rules_of("t6"), rules_of("t7"));
+    assert!(
+        t6.contains(&"qnt_join") && !t6.contains(&"qnt_cnf"),
+        "{:?}",
+        t6
+    );
+    assert_eq!(t6.iter().filter(|&&r| r == "or_neg").count(), 2);
+    assert!(
+        t7.contains(&"qnt_rm_unused") && !t7.contains(&"qnt_cnf"),
+        "{:?}",
+        t7
+    );
+    assert_eq!(t7.iter().filter(|&&r| r == "or_neg").count(), 2);
+
+    let got =
+        ProofChecker::new(&mut pool, Config::new().elaborated(true)).check(&problem, &elaborated);
+    assert!(matches!(got, Ok(true)), "{:?}", got);
+}
+
+#[test]
+fn test_prove_lia_generic() {
+    // The negation of each literal in the clause is asserted, so the proof reaches the empty clause
+    fn check(clause: &[&str]) -> Result<bool, carcara::Error> {
+        let mut problem =
+            "(declare-fun x () Int) (declare-fun y () Int) (declare-fun z () Real)".to_owned();
+        let mut proof = String::new();
+        for (i, l) in clause.iter().enumerate() {
+            problem += &format!("(assert (not {}))", l);
+            proof += &format!("(assume h{} (not {}))", i, l);
+        }
+        let premises: Vec<_> = (0..clause.len()).map(|i| format!("h{}", i)).collect();
+        proof += &format!(
+            "(step t1 (cl {}) :rule lia_generic)
+            (step t2 (cl) :rule resolution :premises (t1 {}))",
+            clause.join(" "),
+            premises.join(" ")
+        );
+        let (problem, proof, mut pool) = parser::parse_instance(
+            problem.as_bytes(),
+            proof.as_bytes(),
+            parser::Config::default(),
+        )
+        .unwrap();
+        let config = Config::new().prove_lia_generic(true);
+        ProofChecker::new(&mut pool, config).check(&problem, &proof)
+    }
+
+    let valid: &[&[&str]] = &[
+        // Valid over the reals
+        &["(not (<= x 1))", "(not (>= x 2))"],
+        // Needs a cutting plane
+        &["(not (= (* 2 x) (+ (* 2 y) 1)))"],
+        &["(not (> (* 3 x) 1))", "(not (< x 1))"],
+        // Needs branching
+        &["(not (= (+ x y) 1))", "(not (= x y))"],
+        &[
+            "(not (= (+ (* 2 x) (* 3 y)) 1))",
+            "(not (<= 0 x))",
+            "(not (<= x 1))",
+        ],
+        // Contains equalities
+        &["(= x 0)", "(< x 0)", "(> x 0)"],
+        &["(= x y)", "(not (<= x y))", "(not (<= y x))"],
+        // Mixes integers and reals
+        &[
+            "(not (< x z))",
+            "(not (< z (+ x 1)))",
+            "(not (= (* 2.0 z) 2.0))",
+        ],
+    ];
+    for clause in valid {
+        assert!(matches!(check(clause), Ok(false)), "{:?}", clause);
+    }
+
+    // If the clause is not valid, the step fails to check
+    let got = check(&["(not (< x y))", "(not (< y (+ x 2)))"]);
+    match got {
+        Err(carcara::Error::Checker { inner, .. }) => {
+            assert_eq!(inner.code(), "la.lia_generic_not_valid");
+        }
+        _ => panic!("expected checker error, got {:?}", got),
+    }
+
+    // If the prover can't prove the clause, the step is considered a hole
+    assert!(matches!(check(&["(>= (* x x) 0)"]), Ok(true)));
+}
+
+#[test]
+fn test_diff_proofs() {
+    use carcara::diff::diff_proofs;
+
+    let problem = "(declare-const p Bool)
+        (declare-const q Bool)
+        (declare-const a Int)
+        (declare-const b Int)
+        (assert (or p q))
+        (assert (not q))";
+    let old = "(assume a0 (or p q))
+        (assume a1 (not q))
+        (step t0 (cl p q) :rule or :premises (a0))
+        (step t1 (cl (= a b) (not (= a b))) :rule hole)
+        (step t2 (cl p) :rule resolution :premises (t0 a1) :args (q true))
+        (step t3 (cl) :rule hole :premises (t2 t1))";
+    let new = "(assume a0 (or p q))
+        (assume a1 (not q))
+        (step s0 (cl q p) :rule or :premises (a0))
+        (step s1 (cl (not (= b a)) (= b a)) :rule equiv_pos2)
+        (step s2 (cl p) :rule th_resolution :premises (s0 a1) :args (q true))
+        (step s3 (cl (not p)) :rule hole)
+        (step s4 (cl) :rule resolution :premises (s2 s3 s1) :args (p true))";
+
+    let mut pool = carcara::ast::PrimitivePool::new();
+    let mut parse = |proof: &str| {
+        let config = parser::Config::default();
+        parser::parse_instance_with_pool(problem.as_bytes(), proof.as_bytes(), config, &mut pool)
+            .unwrap()
+            .1
+    };
+    let (old, new) = (parse(old), parse(new));
+
+    let diff = diff_proofs(&old, &old);
+    assert!(diff.is_empty());
+    assert_eq!(diff.holes, [("hole".to_owned(), 2, 2)]);
+
+    let diff = diff_proofs(&old, &new);
+    let ids = |steps: &[carcara::diff::DiffStep]| -> Vec<String> {
+        steps.iter().map(|s| s.id.clone()).collect()
+    };
+    assert_eq!(ids(&diff.added), ["s3"]);
+    assert!(diff.removed.is_empty());
+
+    // `t0` and `s0` conclude the same clause modulo reordering, as do `t1` and `s1`
+    let mut changed: Vec<_> = (diff.changed.iter())
+        .map(|c| {
+            (
+                c.old.id.as_str(),
+                c.new.id.as_str(),
+                c.rule_changed,
+                c.premises_changed,
+            )
+        })
+        .collect();
+    changed.sort();
+    assert_eq!(
+        changed,
+        [
+            ("t1", "s1", true, false),
+            ("t2", "s2", true, false),
+            ("t3", "s4", true, true),
+        ]
+    );
+    assert_eq!(
+        diff.rule_usage,
+        [
+            ("equiv_pos2".to_owned(), 0, 1),
+            ("hole".to_owned(), 2, 1),
+            ("th_resolution".to_owned(), 0, 1),
+        ]
+    );
+    assert_eq!(diff.holes, [("hole".to_owned(), 2, 1)]);
+}
+
+#[test]
+fn test_proof_stats() {
+    use carcara::proof_stats::ProofStats;
+
+    let (_, proof, pool) = parser::parse_instance(
+        "(declare-fun f (Int) Int)
+        (declare-const p Bool)
+        (declare-const a Int)
+        (assert (forall ((x Int)) (> x 0)))
+        (assert (not (forall ((y Int)) (> y 0))))
+        (assert p)"
+            .as_bytes(),
+        "(assume h1 (forall ((x Int)) (> x 0)))
+        (assume h2 (not (forall ((y Int)) (> y 0))))
+        (assume h3 p)
+        (anchor :step t3 :args ((y Int) (:= (x Int) y)))
+        (step t3.t1 (cl (= x y)) :rule refl)
+        (step t3.t2 (cl (= (> x 0) (> y 0))) :rule cong :premises (t3.t1))
+        (step t3 (cl (= (forall ((x Int)) (> x 0)) (forall ((y Int)) (> y 0)))) :rule bind)
+        (step t4 (cl (not (forall ((x Int)) (> x 0))) (forall ((y Int)) (> y 0)))
+            :rule equiv1 :premises (t3))
+        (step t5 (cl (= (f a) (f a))) :rule lia_generic)
+        (step t6 (cl) :rule resolution :premises (t4 h1 h2))"
+            .as_bytes(),
+        parser::Config::default(),
+    )
+    .unwrap();
+    let stats = ProofStats::new(&proof, &pool);
+
+    assert_eq!(stats.num_steps(), 6);
+    assert_eq!(stats.steps_by_rule[0], ("bind".to_owned(), 1));
+    assert_eq!((stats.num_subproofs, stats.max_depth), (1, 1));
+    assert_eq!(stats.num_assumes, 3);
+    assert_eq!(stats.unused_assumes, ["h3"]);
+    assert_eq!(stats.clause_widths.get(&0), Some(&1));
+    assert_eq!(stats.clause_widths.get(&2), Some(&1));
+    assert_eq!(stats.holes, [("t5".to_owned(), "lia_generic".to_owned())]);
+
+    // Sizes are grouped in powers of two: `p` has size 1, `(= x y)` has size 3, and the conclusion
+    // of `t3` has size 9
+    let sizes: Vec<_> = stats.term_sizes.iter().map(|(k, v)| (*k, *v)).collect();
+    assert_eq!(sizes, [(1, 1), (2, 1), (4, 6), (8, 1)]);
+    assert!(stats.distinct_terms < stats.total_term_size as usize);
+    assert!(stats.sharing_ratio() > 1.0);
+
+    let mut csv = Vec::new();
+    stats.write_csv(&mut csv).unwrap();
+    let csv = String::from_utf8(csv).unwrap();
+    assert!(csv.starts_with("category,key,value\nsteps,total,6\n"));
+    assert!(csv.ends_with("hole,t5,lia_generic\n"));
+}
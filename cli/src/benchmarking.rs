@@ -24,11 +24,11 @@ fn run_job<T: CollectResults + Default + Send>(
     job: JobDescriptor,
     parser_config: parser::Config,
     checker_config: checker::Config,
-    elaborator_config: Option<(elaborator::Config, Vec<elaborator::ElaborationStep>)>,
+    elaborator_config: Option<(elaborator::Config, Vec<elaborator::PipelineStep>)>,
 ) -> Result<bool, carcara::Error> {
-    let proof_file_name = job.proof_file.to_str().unwrap();
+    let proof_file_name = job.proof_file.to_string_lossy();
     let mut checker_stats = checker::CheckerStatistics {
-        file_name: proof_file_name,
+        file_name: &proof_file_name,
         polyeq_time: Duration::ZERO,
         assume_time: Duration::ZERO,
         assume_core_time: Duration::ZERO,
@@ -88,7 +88,7 @@ fn worker_thread<T: CollectResults + Default + Send>(
     jobs_queue: &ArrayQueue<JobDescriptor>,
     parser_config: parser::Config,
     checker_config: checker::Config,
-    elaborator_config: Option<(elaborator::Config, Vec<elaborator::ElaborationStep>)>,
+    elaborator_config: Option<(elaborator::Config, Vec<elaborator::PipelineStep>)>,
 ) -> T {
     let mut results = T::default();
 
@@ -104,7 +104,7 @@ fn worker_thread<T: CollectResults + Default + Send>(
             Ok(true) => results.register_holey(),
             Err(e) => {
                 log::error!("encountered error in file '{}'", job.proof_file.display());
-                results.register_error(&e);
+                results.register_error(&job.proof_file.to_string_lossy(), &e);
             }
             _ => (),
         }
@@ -119,7 +119,7 @@ pub fn run_benchmark<T: CollectResults + Default + Send>(
     num_jobs: usize,
     parser_config: parser::Config,
     checker_config: checker::Config,
-    elaborator_config: Option<(elaborator::Config, Vec<elaborator::ElaborationStep>)>,
+    elaborator_config: Option<(elaborator::Config, Vec<elaborator::PipelineStep>)>,
 ) -> T {
     const STACK_SIZE: usize = 128 * 1024 * 1024;
 
@@ -169,7 +169,7 @@ pub fn run_csv_benchmark(
     num_jobs: usize,
     parser_config: parser::Config,
     checker_config: checker::Config,
-    elaborator_config: Option<(elaborator::Config, Vec<elaborator::ElaborationStep>)>,
+    elaborator_config: Option<(elaborator::Config, Vec<elaborator::PipelineStep>)>,
     runs_dest: &mut dyn io::Write,
     steps_dest: &mut dyn io::Write,
 ) -> io::Result<()> {
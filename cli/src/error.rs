@@ -6,6 +6,8 @@ pub enum CliError {
     CantInferProblemFile(PathBuf),
     InvalidSliceId(String),
     BothFilesStdin,
+    UnknownRewrite(String),
+    ElaborationFailed(usize),
 }
 
 pub type CliResult<T> = Result<T, CliError>;
@@ -31,6 +33,8 @@ impl fmt::Display for CliError {
             }
             CliError::BothFilesStdin => write!(f, "problem and proof files can't both be `-`"),
             CliError::InvalidSliceId(id) => write!(f, "invalid id for slice: {}", id),
+            CliError::UnknownRewrite(r) => write!(f, "unknown simplification rule: {}", r),
+            CliError::ElaborationFailed(n) => write!(f, "{} steps could not be elaborated", n),
         }
     }
 }
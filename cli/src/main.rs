@@ -2,10 +2,13 @@ mod benchmarking;
 mod error;
 mod logger;
 mod path_args;
+mod report;
 
 use carcara::{
-    ast, benchmarking::OnlineBenchmarkResults, check, check_and_elaborate, check_parallel, checker,
-    elaborator, generate_lia_smt_instances, parser, slice,
+    ast, benchmarking::OnlineBenchmarkResults, check, check_and_elaborate,
+    check_and_elaborate_with_stats, check_parallel, check_parallel_with_stats, check_streaming,
+    check_with_stats, checker, diff, elaborator, generate_lia_smt_instances, lean, parser,
+    pb_proof, proof_stats, rare, sat_proof, slice,
 };
 use clap::{AppSettings, ArgEnum, Args, Parser, Subcommand};
 use const_format::{formatcp, str_index};
@@ -72,14 +75,38 @@ enum Command {
     /// Checks and elaborates a proof file.
     Elaborate(ElaborateCommandOptions),
 
+    /// Checks and elaborates a proof file, and exports the elaborated proof as a Lean 4 proof
+    /// script.
+    ///
+    /// Steps that use the core rules of elaborated proofs are reconstructed directly. Every other
+    /// step is proved by the `alethe_step` tactic, whose default implementation in the script only
+    /// tries general-purpose tactics, so those steps may not be accepted by Lean.
+    ///
+    /// The exported script is not checked with Lean, and may fail to compile even if the proof is
+    /// valid. Run `lake env lean` on the script to check it.
+    ExportLean(ExportLeanCommandOptions),
+
     /// Checks a series of proof files and records performance statistics.
     Bench(BenchCommandOptions),
 
     /// Given a step, takes a slice of a proof consisting of its transitive premises.
     Slice(SliceCommandOptions),
 
+    /// Compares two proofs of the same problem, reporting the steps that were added, removed or
+    /// changed.
+    Diff(DiffCommandOptions),
+
+    /// Parses a proof file and prints statistics about its shape, without checking it.
+    Stats(ProofStatsCommandOptions),
+
     /// Generates the equivalent SMT instance for every `lia_generic` step in a proof.
     GenerateLiaProblems(ParseCommandOptions),
+
+    /// Checks a DRAT or LRAT proof of unsatisfiability of a CNF formula in the DIMACS format.
+    CheckSatProof(CheckSatProofCommandOptions),
+
+    /// Checks a VeriPB proof of unsatisfiability of a pseudo-Boolean instance in the OPB format.
+    CheckPbProof(CheckPbProofCommandOptions),
 }
 
 #[derive(Args)]
@@ -99,6 +126,26 @@ struct StatsOptions {
     stats: bool,
 }
 
+#[derive(ArgEnum, Clone, Copy, PartialEq, Eq)]
+enum ReportFormat {
+    Text,
+    Json,
+}
+
+#[derive(Args)]
+struct ReportOptions {
+    /// The format in which results are reported.
+    ///
+    /// If this is "json", a single JSON object is printed instead of the usual output. It contains
+    /// the verdict, the failing steps, the holes in the proof (grouped by rule) and the performance
+    /// statistics. When elaborating, it also contains the steps that could not be elaborated, and
+    /// the elaborated proof is printed after the report. For the
+    /// `diff` command, it contains the added, removed and changed steps, and the changes in rule
+    /// usage and holes.
+    #[clap(arg_enum, long, default_value = "text")]
+    report: ReportFormat,
+}
+
 #[derive(Args)]
 struct StackOptions {
     /// Defines the thread stack size for each check worker (does not include the main thread stack size, which should be set manually).
@@ -183,15 +230,68 @@ struct CheckingOptions {
     /// - the pivots for `resolution` steps must be given as arguments
     #[clap(arg_enum, long, default_value = "normal", verbatim_doc_comment)]
     check_granularity: CheckGranularity,
+
+    /// Don't stop at the first step that fails to check. Instead, trust its conclusion, keep
+    /// checking the remaining steps, and report all failing steps at the end.
+    #[clap(long)]
+    keep_going: bool,
+
+    /// Try to prove `lia_generic` steps with the built-in prover, instead of considering them as
+    /// holes.
+    #[clap(long)]
+    prove_lia_generic: bool,
+
+    /// Check `rare_rewrite` steps using the rewrite rules from these RARE files, instead of
+    /// considering `rare_rewrite` an unknown rule.
+    #[clap(long, multiple = true)]
+    rare_file: Vec<String>,
+
+    /// Check `all_simplify` steps using the built-in rewriter, instead of considering
+    /// `all_simplify` an unknown rule. Steps that the rewriter can't prove are considered holes.
+    #[clap(long)]
+    rewrite_all_simplify: bool,
+
+    /// The simplification rules used by the `all_simplify` rewriter, in the order they are tried.
+    /// By default, all supported rules are used.
+    #[clap(long, multiple = true, requires = "rewrite-all-simplify")]
+    all_simplify_rewrites: Option<Vec<String>>,
 }
 
-impl From<CheckingOptions> for checker::Config {
-    fn from(val: CheckingOptions) -> Self {
-        Self {
+impl TryFrom<CheckingOptions> for checker::Config {
+    type Error = CliError;
+
+    fn try_from(val: CheckingOptions) -> CliResult<Self> {
+        let rare_rules = if val.rare_file.is_empty() {
+            None
+        } else {
+            let mut rules = rare::RareRules::new();
+            for path in &val.rare_file {
+                rules.load(io::BufReader::new(File::open(path)?))?;
+            }
+            Some(rules.into())
+        };
+        let all_simplify_rewriter = if val.rewrite_all_simplify {
+            Some(match val.all_simplify_rewrites {
+                Some(names) => checker::Rewriter::new(
+                    names
+                        .iter()
+                        .map(|n| n.parse().map_err(|()| CliError::UnknownRewrite(n.clone())))
+                        .collect::<CliResult<Vec<checker::Rewrite>>>()?,
+                ),
+                None => checker::Rewriter::default(),
+            })
+        } else {
+            None
+        };
+        Ok(Self {
             elaborated: val.check_granularity == CheckGranularity::Elaborated,
             ignore_unknown_rules: val.ignore_unknown_rules || val.skip_unknown_rules,
             allowed_rules: val.allowed_rules.unwrap_or_default().into_iter().collect(),
-        }
+            keep_going: val.keep_going,
+            prove_lia_generic: val.prove_lia_generic,
+            rare_rules,
+            all_simplify_rewriter,
+        })
     }
 }
 
@@ -199,10 +299,15 @@ impl From<CheckingOptions> for checker::Config {
 enum ElaborationStep {
     Polyeq,
     LiaGeneric,
+    Drup,
     Local,
     Uncrowd,
     Reordering,
     Hole,
+    Prune,
+    Lift,
+    Merge,
+    RecycleUnits,
 }
 
 #[derive(Args, Clone)]
@@ -240,30 +345,62 @@ struct ElaborationOptions {
     )]
     hole_solver_args: String,
 
-    /// The pipeline of elaboration steps to use.
+    /// Number of threads to use when elaborating steps of local rules.
+    #[clap(long, default_value_t = 1)]
+    elaboration_threads: usize,
+
+    /// Maximum number of solver processes to run at the same time, when elaborating `lia_generic`
+    /// or `hole` steps.
+    #[clap(long, default_value_t = 1)]
+    solver_processes: usize,
+
+    /// The pipeline of elaboration steps to use. The `prune`, `lift`, `merge` and `recycle-units`
+    /// steps compress the proof, and are not used by default.
     #[clap(
         arg_enum,
         long,
         multiple = true,
-        default_values = &["polyeq", "lia-generic", "local", "uncrowd", "reordering", "hole"]
+        default_values = &["polyeq", "lia-generic", "drup", "local", "uncrowd", "reordering", "hole"]
     )]
     pipeline: Vec<ElaborationStep>,
+
+    /// Read the elaboration pipeline from a file. Each line of the file contains a step, optionally
+    /// followed by the rules it applies to (`rules=trans,cong`) or the external solver it uses
+    /// (`solver=cvc5 solver-args="..."`).
+    #[clap(long, conflicts_with = "pipeline")]
+    pipeline_file: Option<String>,
 }
 
-impl From<ElaborationOptions> for (elaborator::Config, Vec<elaborator::ElaborationStep>) {
-    fn from(val: ElaborationOptions) -> Self {
-        let pipeline: Vec<_> = val
-            .pipeline
-            .into_iter()
-            .map(|s| match s {
-                ElaborationStep::Polyeq => elaborator::ElaborationStep::Polyeq,
-                ElaborationStep::LiaGeneric => elaborator::ElaborationStep::LiaGeneric,
-                ElaborationStep::Local => elaborator::ElaborationStep::Local,
-                ElaborationStep::Uncrowd => elaborator::ElaborationStep::Uncrowd,
-                ElaborationStep::Reordering => elaborator::ElaborationStep::Reordering,
-                ElaborationStep::Hole => elaborator::ElaborationStep::Hole,
-            })
-            .collect();
+impl From<ElaborationStep> for elaborator::PipelineStep {
+    fn from(val: ElaborationStep) -> Self {
+        let step = match val {
+            ElaborationStep::Polyeq => elaborator::ElaborationStep::Polyeq,
+            ElaborationStep::LiaGeneric => elaborator::ElaborationStep::LiaGeneric,
+            ElaborationStep::Drup => elaborator::ElaborationStep::Drup,
+            ElaborationStep::Local => elaborator::ElaborationStep::Local,
+            ElaborationStep::Uncrowd => elaborator::ElaborationStep::Uncrowd,
+            ElaborationStep::Reordering => elaborator::ElaborationStep::Reordering,
+            ElaborationStep::Hole => elaborator::ElaborationStep::Hole,
+            ElaborationStep::Prune => elaborator::ElaborationStep::Prune,
+            ElaborationStep::Lift => elaborator::ElaborationStep::Lift,
+            ElaborationStep::Merge => elaborator::ElaborationStep::Merge,
+            ElaborationStep::RecycleUnits => elaborator::ElaborationStep::RecycleUnits,
+        };
+        step.into()
+    }
+}
+
+impl TryFrom<ElaborationOptions> for (elaborator::Config, Vec<elaborator::PipelineStep>) {
+    type Error = CliError;
+
+    fn try_from(val: ElaborationOptions) -> CliResult<Self> {
+        let pipeline: Vec<_> = match &val.pipeline_file {
+            Some(path) => {
+                let text = std::fs::read_to_string(path)?;
+                elaborator::parse_pipeline(&text).map_err(carcara::Error::from)?
+            }
+            None => val.pipeline.into_iter().map(|s| s.into()).collect(),
+        };
         let lia_options = val.lia_solver.map(|solver| elaborator::LiaGenericOptions {
             solver: solver.into(),
             arguments: val
@@ -286,8 +423,10 @@ impl From<ElaborationOptions> for (elaborator::Config, Vec<elaborator::Elaborati
             lia_options,
             uncrowd_rotation: val.uncrowd_rotate,
             hole_options,
+            num_threads: val.elaboration_threads,
+            num_solver_processes: val.solver_processes,
         };
-        (config, pipeline)
+        Ok((config, pipeline))
     }
 }
 
@@ -325,9 +464,24 @@ struct CheckCommandOptions {
     })]
     num_threads: usize,
 
+    /// A CSV file with the time taken to check each rule in previous runs, used to balance the
+    /// work between threads when checking in parallel. The `by-rule.csv` file created by the
+    /// `bench` command with `--dump-to-csv` can be used here.
+    #[clap(long)]
+    step_weights: Option<String>,
+
+    /// Checks the proof while it is being parsed, discarding each step as soon as it can no
+    /// longer be used as a premise. This keeps memory usage low when checking very large proofs.
+    /// If the proof is read from stdin, only the steps of closed subproofs are discarded.
+    #[clap(long, conflicts_with_all = &["num-threads", "stats"])]
+    streaming: bool,
+
     #[clap(flatten)]
     stats: StatsOptions,
 
+    #[clap(flatten)]
+    report: ReportOptions,
+
     #[clap(flatten)]
     stack: StackOptions,
 }
@@ -348,6 +502,28 @@ struct ElaborateCommandOptions {
 
     #[clap(flatten)]
     stats: StatsOptions,
+
+    #[clap(flatten)]
+    report: ReportOptions,
+}
+
+#[derive(Args)]
+struct ExportLeanCommandOptions {
+    #[clap(flatten)]
+    input: Input,
+
+    #[clap(flatten)]
+    parsing: ParsingOptions,
+
+    #[clap(flatten)]
+    checking: CheckingOptions,
+
+    #[clap(flatten)]
+    elaboration: ElaborationOptions,
+
+    /// Write the Lean script to this file, instead of the standard output.
+    #[clap(short, long)]
+    output: Option<String>,
 }
 
 #[derive(Args)]
@@ -381,12 +557,54 @@ struct BenchCommandOptions {
     #[clap(long = "dump-to-csv")]
     dump_to_csv: bool,
 
+    #[clap(flatten)]
+    report: ReportOptions,
+
     /// The proof files on which the benchmark will be run. If a directory is passed, the checker
     /// will recursively find all proof files in the directory. The problem files will be
     /// inferred from the proof files.
     files: Vec<String>,
 }
 
+#[derive(Args)]
+struct DiffCommandOptions {
+    /// The old proof file
+    old_proof_file: String,
+
+    /// The new proof file
+    new_proof_file: String,
+
+    /// The original problem file. If this argument is not present, it will be inferred from the
+    /// old proof file.
+    problem_file: Option<String>,
+
+    #[clap(flatten)]
+    parsing: ParsingOptions,
+
+    #[clap(flatten)]
+    report: ReportOptions,
+}
+
+#[derive(ArgEnum, Clone, Copy, PartialEq, Eq)]
+enum ProofStatsFormat {
+    Text,
+    Csv,
+}
+
+#[derive(Args)]
+struct ProofStatsCommandOptions {
+    #[clap(flatten)]
+    input: Input,
+
+    #[clap(flatten)]
+    parsing: ParsingOptions,
+
+    /// The format in which the statistics are printed. In the CSV format, each row contains a
+    /// category, a key and a value.
+    #[clap(arg_enum, long, default_value = "text")]
+    format: ProofStatsFormat,
+}
+
 #[derive(Args)]
 struct SliceCommandOptions {
     #[clap(flatten)]
@@ -422,6 +640,54 @@ struct SliceCommandOptions {
     hole_solver_args: Option<String>,
 }
 
+#[derive(ArgEnum, Clone, Copy)]
+enum SatProofFormat {
+    Drat,
+    BinaryDrat,
+    Lrat,
+}
+
+impl From<SatProofFormat> for sat_proof::SatProofFormat {
+    fn from(f: SatProofFormat) -> Self {
+        match f {
+            SatProofFormat::Drat => Self::Drat,
+            SatProofFormat::BinaryDrat => Self::BinaryDrat,
+            SatProofFormat::Lrat => Self::Lrat,
+        }
+    }
+}
+
+#[derive(Args)]
+struct CheckSatProofCommandOptions {
+    /// The CNF formula, in the DIMACS format.
+    cnf_file: String,
+
+    /// The proof of unsatisfiability of the formula.
+    proof_file: String,
+
+    /// The format of the proof. If this is not present, files with the ".lrat" extension are read
+    /// as LRAT, and other files are read as DRAT, in either the text or the binary format. The
+    /// hints of LRAT proofs are ignored, and each lemma is checked again, so LRAT proofs are not
+    /// checked any faster than DRAT proofs.
+    #[clap(arg_enum, long)]
+    proof_format: Option<SatProofFormat>,
+
+    /// After checking, converts the proof into an Alethe proof that uses only resolution, and
+    /// writes it and the corresponding SMT problem to the given files. This fails if the proof has
+    /// RAT lemmas.
+    #[clap(long, value_names = &["PROBLEM", "PROOF"])]
+    alethe_output: Option<Vec<String>>,
+}
+
+#[derive(Args)]
+struct CheckPbProofCommandOptions {
+    /// The pseudo-Boolean instance, in the OPB format.
+    opb_file: String,
+
+    /// The VeriPB proof of unsatisfiability of the instance.
+    proof_file: String,
+}
+
 #[derive(ArgEnum, Clone)]
 enum LogLevel {
     Off,
@@ -451,6 +717,7 @@ fn main() {
 
     if let Command::Check(CheckCommandOptions { checking, .. })
     | Command::Elaborate(ElaborateCommandOptions { checking, .. })
+    | Command::ExportLean(ExportLeanCommandOptions { checking, .. })
     | Command::Bench(BenchCommandOptions { checking, .. }) = &cli.command
     {
         if checking.skip_unknown_rules {
@@ -466,10 +733,20 @@ fn main() {
             ast::print_proof(&mut pool, &pb.prelude, &pf, !cli.no_print_with_sharing)?;
             Ok(())
         }),
+        Command::Check(options) if options.report.report == ReportFormat::Json => {
+            check_report_command(options)
+        }
         Command::Check(options) => {
             match check_command(options) {
                 Ok(false) => println!("valid"),
                 Ok(true) => println!("holey"),
+                Err(CliError::CarcaraError(carcara::Error::CheckerFailures(failures))) => {
+                    for f in &failures {
+                        log::error!("checking failed on {}", f);
+                    }
+                    println!("invalid");
+                    std::process::exit(1);
+                }
                 Err(e) => {
                     log::error!("{}", e);
                     println!("invalid");
@@ -478,17 +755,21 @@ fn main() {
             }
             return;
         }
+        Command::Elaborate(options) if options.report.report == ReportFormat::Json => {
+            elaborate_report_command(options, !cli.no_print_with_sharing)
+        }
         Command::Elaborate(options) => {
-            elaborate_command(options).and_then(|(res, pb, pf, mut pool)| {
+            elaborate_command(options).and_then(|(res, pb, pf, mut pool, errors)| {
                 if res {
                     println!("holey");
                 } else {
                     println!("valid");
                 }
                 ast::print_proof(&mut pool, &pb.prelude, &pf, !cli.no_print_with_sharing)?;
-                Ok(())
+                check_elaboration_errors(&errors)
             })
         }
+        Command::ExportLean(options) => export_lean_command(options),
         Command::Bench(options) => bench_command(options),
         Command::Slice(options) => {
             slice_command(options, cli.no_print_with_sharing).and_then(|(pb, pf, mut pool)| {
@@ -496,9 +777,33 @@ fn main() {
                 Ok(())
             })
         }
+        Command::Diff(options) => diff_command(options),
+        Command::Stats(options) => proof_stats_command(options),
         Command::GenerateLiaProblems(options) => {
             generate_lia_problems_command(options, !cli.no_print_with_sharing)
         }
+        Command::CheckSatProof(options) => {
+            match check_sat_proof_command(options, !cli.no_print_with_sharing) {
+                Ok(()) => println!("valid"),
+                Err(e) => {
+                    log::error!("{}", e);
+                    println!("invalid");
+                    std::process::exit(1);
+                }
+            }
+            return;
+        }
+        Command::CheckPbProof(options) => {
+            match check_pb_proof_command(options) {
+                Ok(()) => println!("valid"),
+                Err(e) => {
+                    log::error!("{}", e);
+                    println!("invalid");
+                    std::process::exit(1);
+                }
+            }
+            return;
+        }
     };
     if let Err(e) = result {
         log::error!("{}", e);
@@ -548,12 +853,31 @@ fn parse_command(
     Ok(result)
 }
 
+fn read_step_weights(path: Option<&str>) -> CliResult<checker::StepWeights> {
+    match path {
+        Some(path) => Ok(checker::StepWeights::from_csv(io::BufReader::new(
+            File::open(path)?,
+        ))?),
+        None => Ok(checker::StepWeights::new()),
+    }
+}
+
 fn check_command(options: CheckCommandOptions) -> CliResult<bool> {
     let (problem, proof) = get_instance(&options.input, options.parsing.buffer_entire_file)?;
     let parser_config = options.parsing.into();
-    let checker_config = options.checking.into();
+    let checker_config = options.checking.try_into()?;
     let collect_stats = options.stats.stats;
-    if options.num_threads == 1 {
+    if options.streaming {
+        // To know when each step can be discarded, we scan the proof once before checking it,
+        // which is only possible if it is not read from stdin
+        let premise_uses = match options.input.proof_file.as_str() {
+            "-" => None,
+            path => Some(parser::count_premise_uses(io::BufReader::new(File::open(
+                path,
+            )?))?),
+        };
+        check_streaming(problem, proof, premise_uses, parser_config, checker_config)
+    } else if options.num_threads == 1 {
         check(problem, proof, parser_config, checker_config, collect_stats)
     } else {
         check_parallel(
@@ -564,22 +888,49 @@ fn check_command(options: CheckCommandOptions) -> CliResult<bool> {
             collect_stats,
             options.num_threads,
             options.stack.stack_size,
+            &read_step_weights(options.step_weights.as_deref())?,
         )
     }
     .map_err(Into::into)
 }
 
-fn elaborate_command(
-    options: ElaborateCommandOptions,
-) -> CliResult<(bool, ast::Problem, ast::Proof, ast::PrimitivePool)> {
+/// Checks a proof file and prints a JSON report of the results. If the proof is invalid, this exits
+/// with a non-zero exit code after printing the report.
+fn check_report_command(options: CheckCommandOptions) -> CliResult<()> {
+    let (problem, proof) = get_instance(&options.input, options.parsing.buffer_entire_file)?;
+    let parser_config = options.parsing.into();
+    let checker_config = options.checking.try_into()?;
+    let mut stats = OnlineBenchmarkResults::new();
+    let result = if options.num_threads == 1 {
+        check_with_stats(problem, proof, parser_config, checker_config, &mut stats)
+    } else {
+        check_parallel_with_stats(
+            problem,
+            proof,
+            parser_config,
+            checker_config,
+            options.num_threads,
+            options.stack.stack_size,
+            &read_step_weights(options.step_weights.as_deref())?,
+            &mut stats,
+        )
+    };
+    report::write_check_report(&mut io::stdout(), &result, |&holey| holey, &[], &stats)?;
+    if result.is_err() {
+        std::process::exit(1);
+    }
+    Ok(())
+}
+
+fn elaborate_command(options: ElaborateCommandOptions) -> CliResult<carcara::ElaborationResult> {
     let (problem, proof) = get_instance(&options.input, options.parsing.buffer_entire_file)?;
 
-    let (elab_config, pipeline) = options.elaboration.into();
+    let (elab_config, pipeline) = options.elaboration.try_into()?;
     check_and_elaborate(
         problem,
         proof,
         options.parsing.into(),
-        options.checking.into(),
+        options.checking.try_into()?,
         elab_config,
         pipeline,
         options.stats.stats,
@@ -587,6 +938,86 @@ fn elaborate_command(
     .map_err(CliError::CarcaraError)
 }
 
+/// Checks and elaborates a proof file, printing a JSON report of the results followed by the
+/// elaborated proof. If the proof is invalid, this exits with a non-zero exit code after printing
+/// the report.
+fn elaborate_report_command(options: ElaborateCommandOptions, use_sharing: bool) -> CliResult<()> {
+    let (problem, proof) = get_instance(&options.input, options.parsing.buffer_entire_file)?;
+
+    let (elab_config, pipeline) = options.elaboration.try_into()?;
+    let mut stats = OnlineBenchmarkResults::new();
+    let result = check_and_elaborate_with_stats(
+        problem,
+        proof,
+        options.parsing.into(),
+        options.checking.try_into()?,
+        elab_config,
+        pipeline,
+        &mut stats,
+    );
+    let errors = result
+        .as_ref()
+        .map_or(&[][..], |(.., errors)| errors.as_slice());
+    report::write_check_report(
+        &mut io::stdout(),
+        &result,
+        |(holey, ..)| *holey,
+        errors,
+        &stats,
+    )?;
+    match result {
+        Ok((_, pb, pf, mut pool, errors)) => {
+            ast::print_proof(&mut pool, &pb.prelude, &pf, use_sharing)?;
+            if !errors.is_empty() {
+                std::process::exit(1);
+            }
+            Ok(())
+        }
+        Err(_) => std::process::exit(1),
+    }
+}
+
+/// Logs the steps that could not be elaborated, and returns an error if there are any.
+fn check_elaboration_errors(errors: &[elaborator::ElaborationError]) -> CliResult<()> {
+    for e in errors {
+        log::error!("{}", e);
+    }
+    if errors.is_empty() {
+        Ok(())
+    } else {
+        Err(CliError::ElaborationFailed(errors.len()))
+    }
+}
+
+fn export_lean_command(options: ExportLeanCommandOptions) -> CliResult<()> {
+    let (problem, proof) = get_instance(&options.input, options.parsing.buffer_entire_file)?;
+
+    let (elab_config, pipeline) = options.elaboration.try_into()?;
+    let checker_config: checker::Config = options.checking.try_into()?;
+    let (_, problem, proof, mut pool, errors) = check_and_elaborate(
+        problem,
+        proof,
+        options.parsing.into(),
+        checker_config.clone(),
+        elab_config,
+        pipeline,
+        false,
+    )?;
+    check_elaboration_errors(&errors)?;
+
+    // The exported script relies on the restrictions of elaborated proofs, so we check that the
+    // elaborated proof follows them
+    let config = checker::Config { elaborated: true, ..checker_config };
+    checker::ProofChecker::new(&mut pool, config).check(&problem, &proof)?;
+
+    let result = match options.output {
+        Some(path) => lean::write_lean_proof(&mut pool, &problem, &proof, &mut File::create(path)?),
+        None => lean::write_lean_proof(&mut pool, &problem, &proof, &mut io::stdout()),
+    };
+    result.map_err(carcara::Error::from)?;
+    Ok(())
+}
+
 fn bench_command(options: BenchCommandOptions) -> CliResult<()> {
     let instances = get_instances_from_paths(options.files.iter().map(|s| s.as_str()))?;
     if instances.is_empty() {
@@ -606,8 +1037,11 @@ fn bench_command(options: BenchCommandOptions) -> CliResult<()> {
             options.num_runs,
             options.num_jobs,
             options.parsing.into(),
-            options.checking.into(),
-            options.elaborate.then(|| options.elaboration.into()),
+            options.checking.try_into()?,
+            options
+                .elaborate
+                .then(|| options.elaboration.try_into())
+                .transpose()?,
             &mut File::create("runs.csv")?,
             &mut File::create("steps.csv")?,
         )?;
@@ -619,9 +1053,16 @@ fn bench_command(options: BenchCommandOptions) -> CliResult<()> {
         options.num_runs,
         options.num_jobs,
         options.parsing.into(),
-        options.checking.into(),
-        options.elaborate.then(|| options.elaboration.into()),
+        options.checking.try_into()?,
+        options
+            .elaborate
+            .then(|| options.elaboration.try_into())
+            .transpose()?,
     );
+    if options.report.report == ReportFormat::Json {
+        report::write_bench_report(&mut io::stdout(), &results)?;
+        return Ok(());
+    }
     if results.is_empty() {
         println!("no benchmark data collected");
         return Ok(());
@@ -638,6 +1079,92 @@ fn bench_command(options: BenchCommandOptions) -> CliResult<()> {
     Ok(())
 }
 
+fn diff_command(options: DiffCommandOptions) -> CliResult<()> {
+    let problem_path = match options.problem_file {
+        Some(path) => path.into(),
+        None => infer_problem_path(&options.old_proof_file)?,
+    };
+    let problem_text = std::fs::read_to_string(problem_path)?;
+
+    // Both proofs are parsed using the same pool, so most of their terms can be compared by identity
+    let mut pool = ast::PrimitivePool::new();
+    let mut parse = |path: &str| -> CliResult<ast::Proof> {
+        let proof_text = std::fs::read_to_string(path)?;
+        let (problem, proof) = (problem_text.as_bytes(), proof_text.as_bytes());
+        let config = options.parsing.into();
+        let (_, proof) = parser::parse_instance_with_pool(problem, proof, config, &mut pool)?;
+        Ok(proof)
+    };
+    let old = parse(&options.old_proof_file)?;
+    let new = parse(&options.new_proof_file)?;
+
+    let result = diff::diff_proofs(&old, &new);
+    match options.report.report {
+        ReportFormat::Json => report::write_diff_report(&mut io::stdout(), &result)?,
+        ReportFormat::Text => print_diff(&result),
+    }
+    Ok(())
+}
+
+fn print_diff(diff: &diff::ProofDiff) {
+    fn clause(step: &diff::DiffStep) -> String {
+        let terms: Vec<_> = step.clause.iter().map(|t| format!(" {}", t)).collect();
+        format!("(cl{})", terms.concat())
+    }
+
+    if diff.is_empty() {
+        println!("no differences");
+    }
+    for s in &diff.removed {
+        println!("- {} ({}): {}", s.id, s.rule, clause(s));
+    }
+    for s in &diff.added {
+        println!("+ {} ({}): {}", s.id, s.rule, clause(s));
+    }
+    for c in &diff.changed {
+        let mut changes = Vec::new();
+        if c.rule_changed {
+            changes.push(format!("rule {} -> {}", c.old.rule, c.new.rule));
+        }
+        if c.args_changed {
+            changes.push("arguments".to_owned());
+        }
+        if c.premises_changed {
+            changes.push("premises".to_owned());
+        }
+        println!(
+            "~ {} -> {} ({}): {}",
+            c.old.id,
+            c.new.id,
+            changes.join(", "),
+            clause(&c.new)
+        );
+    }
+    if !diff.rule_usage.is_empty() {
+        println!("rule usage:");
+        for (rule, old, new) in &diff.rule_usage {
+            println!("  {}: {} -> {}", rule, old, new);
+        }
+    }
+    if !diff.holes.is_empty() {
+        println!("holes:");
+        for (rule, old, new) in &diff.holes {
+            println!("  {}: {} -> {}", rule, old, new);
+        }
+    }
+}
+
+fn proof_stats_command(options: ProofStatsCommandOptions) -> CliResult<()> {
+    let (problem, proof) = get_instance(&options.input, options.parsing.buffer_entire_file)?;
+    let (_, proof, pool) = parser::parse_instance(problem, proof, options.parsing.into())?;
+    let stats = proof_stats::ProofStats::new(&proof, &pool);
+    match options.format {
+        ProofStatsFormat::Text => stats.write_text(&mut io::stdout())?,
+        ProofStatsFormat::Csv => stats.write_csv(&mut io::stdout())?,
+    }
+    Ok(())
+}
+
 fn slice_command(
     options: SliceCommandOptions,
     no_print_with_sharing: bool,
@@ -700,6 +1227,60 @@ fn slice_command(
     Ok((problem, sliced, pool))
 }
 
+fn check_sat_proof_command(
+    options: CheckSatProofCommandOptions,
+    use_sharing: bool,
+) -> CliResult<()> {
+    use std::fs;
+
+    let cnf = io::BufReader::new(File::open(&options.cnf_file)?);
+    let proof = fs::read(&options.proof_file)?;
+    let format = match options.proof_format {
+        Some(f) => f.into(),
+        None if options.proof_file.ends_with(".lrat") => sat_proof::SatProofFormat::Lrat,
+        None => sat_proof::SatProofFormat::detect_drat(&proof),
+    };
+    let checked = sat_proof::check_sat_proof(cnf, &proof, format).map_err(carcara::Error::from)?;
+
+    if let Some(paths) = options.alethe_output {
+        let (problem, proof, mut pool) = checked.into_alethe().map_err(carcara::Error::from)?;
+
+        let mut problem_file = fs::File::create(&paths[0])?;
+        write!(problem_file, "{}", problem.prelude)?;
+        let asserts = problem.premises.iter().cloned().collect();
+        ast::write_asserts(
+            &mut pool,
+            &problem.prelude,
+            &mut problem_file,
+            &asserts,
+            false,
+        )?;
+        problem_file.write_all(b"(check-sat)\n")?;
+        problem_file.write_all(b"(exit)\n")?;
+
+        let mut proof_file = fs::File::create(&paths[1])?;
+        ast::write_proof_to_dest(
+            &mut pool,
+            &problem.prelude,
+            &proof,
+            &mut proof_file,
+            use_sharing,
+        )?;
+        proof_file.write_all(b"\n")?;
+    }
+    Ok(())
+}
+
+fn check_pb_proof_command(options: CheckPbProofCommandOptions) -> CliResult<()> {
+    let opb = io::BufReader::new(File::open(&options.opb_file)?);
+    let proof = io::BufReader::new(File::open(&options.proof_file)?);
+    let checked = pb_proof::check_pb_proof(opb, proof).map_err(carcara::Error::from)?;
+    if !checked.derived_contradiction {
+        log::warn!("proof does not derive a contradiction");
+    }
+    Ok(())
+}
+
 fn generate_lia_problems_command(options: ParseCommandOptions, use_sharing: bool) -> CliResult<()> {
     use std::io::Write;
 
@@ -0,0 +1,346 @@
+use carcara::{
+    benchmarking::{Metrics, OnlineBenchmarkResults, OnlineMetrics},
+    checker::{error::CheckerError, CheckerFailure},
+    diff::{DiffStep, ProofDiff},
+    elaborator::ElaborationError,
+};
+use std::{collections::HashSet, fmt, io};
+
+/// A minimal JSON value, used to build machine-readable reports.
+enum Json {
+    Null,
+    Number(u128),
+    String(String),
+    Array(Vec<Json>),
+    Object(Vec<(&'static str, Json)>),
+    Map(Vec<(String, Json)>),
+}
+
+impl Json {
+    fn string(s: impl Into<String>) -> Self {
+        Self::String(s.into())
+    }
+}
+
+fn write_json_string(f: &mut fmt::Formatter, s: &str) -> fmt::Result {
+    write!(f, "\"")?;
+    for c in s.chars() {
+        match c {
+            '"' => write!(f, "\\\"")?,
+            '\\' => write!(f, "\\\\")?,
+            '\n' => write!(f, "\\n")?,
+            '\r' => write!(f, "\\r")?,
+            '\t' => write!(f, "\\t")?,
+            c if (c as u32) < 0x20 => write!(f, "\\u{:04x}", c as u32)?,
+            c => write!(f, "{}", c)?,
+        }
+    }
+    write!(f, "\"")
+}
+
+impl fmt::Display for Json {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        fn write_entries<'a, K: AsRef<str> + 'a>(
+            f: &mut fmt::Formatter,
+            entries: impl Iterator<Item = (&'a K, &'a Json)>,
+        ) -> fmt::Result {
+            write!(f, "{{")?;
+            for (i, (key, value)) in entries.enumerate() {
+                if i > 0 {
+                    write!(f, ",")?;
+                }
+                write_json_string(f, key.as_ref())?;
+                write!(f, ":{}", value)?;
+            }
+            write!(f, "}}")
+        }
+
+        match self {
+            Json::Null => write!(f, "null"),
+            Json::Number(n) => write!(f, "{}", n),
+            Json::String(s) => write_json_string(f, s),
+            Json::Array(values) => {
+                write!(f, "[")?;
+                for (i, v) in values.iter().enumerate() {
+                    if i > 0 {
+                        write!(f, ",")?;
+                    }
+                    write!(f, "{}", v)?;
+                }
+                write!(f, "]")
+            }
+            Json::Object(entries) => write_entries(f, entries.iter().map(|(k, v)| (k, v))),
+            Json::Map(entries) => write_entries(f, entries.iter().map(|(k, v)| (k, v))),
+        }
+    }
+}
+
+fn failure_to_json(step: &str, rule: &str, inner: &CheckerError) -> Json {
+    Json::Object(vec![
+        ("step", Json::string(step)),
+        ("rule", Json::string(rule)),
+        ("code", Json::string(inner.code())),
+        ("message", Json::String(inner.to_string())),
+        (
+            "terms",
+            Json::Array(
+                inner
+                    .terms()
+                    .iter()
+                    .map(|t| Json::String(t.to_string()))
+                    .collect(),
+            ),
+        ),
+    ])
+}
+
+fn metrics_to_json<K: Clone>(metrics: &OnlineMetrics<K>) -> Json {
+    if metrics.is_empty() {
+        return Json::Null;
+    }
+    Json::Object(vec![
+        ("count", Json::Number(metrics.count() as u128)),
+        ("total_ns", Json::Number(metrics.total().as_nanos())),
+        ("mean_ns", Json::Number(metrics.mean().as_nanos())),
+        (
+            "std_dev_ns",
+            Json::Number(metrics.standard_deviation().as_nanos()),
+        ),
+    ])
+}
+
+fn statistics_to_json(stats: &OnlineBenchmarkResults) -> Json {
+    let by_rule = stats
+        .step_time_by_rule()
+        .iter()
+        .map(|(rule, m)| (rule.clone(), metrics_to_json(m)))
+        .collect();
+    Json::Object(vec![
+        ("parsing", metrics_to_json(stats.parsing())),
+        ("checking", metrics_to_json(stats.checking())),
+        ("elaborating", metrics_to_json(stats.elaborating())),
+        ("scheduling", metrics_to_json(stats.scheduling())),
+        (
+            "total_accounted_for",
+            metrics_to_json(stats.total_accounted_for()),
+        ),
+        ("total", metrics_to_json(stats.total())),
+        ("polyeq", metrics_to_json(&stats.polyeq_time)),
+        ("assume", metrics_to_json(&stats.assume_time)),
+        ("assume_core", metrics_to_json(&stats.assume_core_time)),
+        ("num_assumes", Json::Number(stats.num_assumes as u128)),
+        (
+            "num_easy_assumes",
+            Json::Number(stats.num_easy_assumes as u128),
+        ),
+        ("by_rule", Json::Map(by_rule)),
+    ])
+}
+
+/// Converts the holes collected in `stats` to JSON. If `with_file` is `true`, each hole also
+/// includes the file it was found in.
+fn holes_to_json(stats: &OnlineBenchmarkResults, with_file: bool) -> Json {
+    let holes = stats
+        .holes
+        .iter()
+        .map(|(rule, steps)| {
+            // When a benchmark is run multiple times, the same hole is recorded once per run
+            let mut seen = HashSet::new();
+            let steps = steps
+                .iter()
+                .filter(|s| seen.insert(*s))
+                .map(|(file, step)| {
+                    if with_file {
+                        Json::Object(vec![
+                            ("file", Json::string(file)),
+                            ("step", Json::string(step)),
+                        ])
+                    } else {
+                        Json::string(step)
+                    }
+                })
+                .collect();
+            (rule.clone(), Json::Array(steps))
+        })
+        .collect();
+    Json::Map(holes)
+}
+
+/// Writes a JSON report of a single checking run, given its result, the steps that could not be
+/// elaborated (if the proof was elaborated) and the statistics collected.
+pub fn write_check_report<T>(
+    dest: &mut dyn io::Write,
+    result: &Result<T, carcara::Error>,
+    is_holey: impl FnOnce(&T) -> bool,
+    elaboration_errors: &[ElaborationError],
+    stats: &OnlineBenchmarkResults,
+) -> io::Result<()> {
+    let (verdict, failures, error) = match result {
+        Ok(r) if is_holey(r) => ("holey", Vec::new(), Json::Null),
+        Ok(_) => ("valid", Vec::new(), Json::Null),
+        Err(carcara::Error::Checker { inner, rule, step }) => (
+            "invalid",
+            vec![failure_to_json(step, rule, inner)],
+            Json::Null,
+        ),
+        Err(carcara::Error::CheckerFailures(failures)) => (
+            "invalid",
+            failures
+                .iter()
+                .map(|CheckerFailure { step, rule, inner }| failure_to_json(step, rule, inner))
+                .collect(),
+            Json::Null,
+        ),
+        Err(e) => (
+            "invalid",
+            Vec::new(),
+            Json::Object(vec![
+                ("code", Json::string(e.code())),
+                ("message", Json::String(e.to_string())),
+            ]),
+        ),
+    };
+    let report = Json::Object(vec![
+        ("verdict", Json::string(verdict)),
+        ("error", error),
+        ("failures", Json::Array(failures)),
+        (
+            "elaboration_errors",
+            Json::Array(
+                elaboration_errors
+                    .iter()
+                    .map(|e| {
+                        Json::Object(vec![
+                            ("step", Json::string(&e.step)),
+                            ("rule", Json::string(&e.rule)),
+                            ("code", Json::string(e.code())),
+                            ("message", Json::String(e.inner.to_string())),
+                        ])
+                    })
+                    .collect(),
+            ),
+        ),
+        ("holes", holes_to_json(stats, false)),
+        ("statistics", statistics_to_json(stats)),
+    ]);
+    writeln!(dest, "{}", report)
+}
+
+/// Writes a JSON report of a benchmark run.
+pub fn write_bench_report(
+    dest: &mut dyn io::Write,
+    results: &OnlineBenchmarkResults,
+) -> io::Result<()> {
+    let verdict = if results.had_error {
+        "invalid"
+    } else if results.is_holey {
+        "holey"
+    } else {
+        "valid"
+    };
+    let errors = results
+        .errors
+        .iter()
+        .map(|(file, code, message)| {
+            Json::Object(vec![
+                ("file", Json::string(file)),
+                ("code", Json::string(*code)),
+                ("message", Json::string(message)),
+            ])
+        })
+        .collect();
+
+    // Group the errors by their code, so failures can be triaged by cause. As with holes, an error
+    // that happens in every run of a benchmark is only counted once
+    let mut seen = HashSet::new();
+    let mut by_code: Vec<(String, u128)> = Vec::new();
+    for (_, code, _) in results.errors.iter().filter(|e| seen.insert(*e)) {
+        match by_code.iter_mut().find(|(c, _)| c == code) {
+            Some((_, count)) => *count += 1,
+            None => by_code.push((code.to_string(), 1)),
+        }
+    }
+    let by_code = by_code
+        .into_iter()
+        .map(|(code, count)| (code, Json::Number(count)))
+        .collect();
+
+    let report = Json::Object(vec![
+        ("verdict", Json::string(verdict)),
+        ("errors", Json::Array(errors)),
+        ("errors_by_code", Json::Map(by_code)),
+        ("holes", holes_to_json(results, true)),
+        ("statistics", statistics_to_json(results)),
+    ]);
+    writeln!(dest, "{}", report)
+}
+
+fn diff_step_to_json(step: &DiffStep) -> Json {
+    Json::Object(vec![
+        ("id", Json::string(&step.id)),
+        ("depth", Json::Number(step.depth as u128)),
+        ("rule", Json::string(&step.rule)),
+        (
+            "clause",
+            Json::Array(
+                step.clause
+                    .iter()
+                    .map(|t| Json::String(t.to_string()))
+                    .collect(),
+            ),
+        ),
+    ])
+}
+
+fn rule_counts_to_json(counts: &[(String, usize, usize)]) -> Json {
+    let counts = counts
+        .iter()
+        .map(|(rule, old, new)| {
+            let value = Json::Object(vec![
+                ("old", Json::Number(*old as u128)),
+                ("new", Json::Number(*new as u128)),
+            ]);
+            (rule.clone(), value)
+        })
+        .collect();
+    Json::Map(counts)
+}
+
+/// Writes a JSON report of the differences between two proofs.
+pub fn write_diff_report(dest: &mut dyn io::Write, diff: &ProofDiff) -> io::Result<()> {
+    let changed = diff
+        .changed
+        .iter()
+        .map(|c| {
+            let mut changes = Vec::new();
+            if c.rule_changed {
+                changes.push(Json::string("rule"));
+            }
+            if c.args_changed {
+                changes.push(Json::string("args"));
+            }
+            if c.premises_changed {
+                changes.push(Json::string("premises"));
+            }
+            Json::Object(vec![
+                ("old", diff_step_to_json(&c.old)),
+                ("new", diff_step_to_json(&c.new)),
+                ("changes", Json::Array(changes)),
+            ])
+        })
+        .collect();
+    let report = Json::Object(vec![
+        (
+            "added",
+            Json::Array(diff.added.iter().map(diff_step_to_json).collect()),
+        ),
+        (
+            "removed",
+            Json::Array(diff.removed.iter().map(diff_step_to_json).collect()),
+        ),
+        ("changed", Json::Array(changed)),
+        ("rule_usage", rule_counts_to_json(&diff.rule_usage)),
+        ("holes", rule_counts_to_json(&diff.holes)),
+    ]);
+    writeln!(dest, "{}", report)
+}